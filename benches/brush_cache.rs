@@ -0,0 +1,101 @@
+//! # Brush Cache Benchmark
+//!
+//! Draws a scene of 1,000 rectangles, each in its own color, so every rectangle takes
+//! its brush from the `BrushCache`. The first frame fills the cache; after that, every
+//! frame only hits it. The benchmark checks that such a frame makes no heap
+//! allocations, counted with a global allocator, and then measures how long it takes.
+//!
+//! Run it on Windows with `cargo bench --bench brush_cache`.
+
+#[cfg(windows)]
+mod bench {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::hint::black_box;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use criterion::Criterion;
+
+    use my_gui::core::{
+        render::{
+            color::Color, direct2d_context::Direct2DContext, objects::primitives::Rectangle,
+            scene::Scene,
+        },
+        window::config::WindowConfig,
+    };
+
+    /// The system allocator, counting the allocations made through it.
+    struct CountingAllocator;
+
+    /// The number of allocations made so far.
+    static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            unsafe { System.alloc(layout) }
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            unsafe { System.dealloc(ptr, layout) }
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            unsafe { System.realloc(ptr, layout, new_size) }
+        }
+    }
+
+    #[global_allocator]
+    static GLOBAL: CountingAllocator = CountingAllocator;
+
+    /// The number of rectangles, and of colors, in the scene.
+    const COUNT: usize = 1_000;
+
+    /// Creates a scene of `COUNT` small rectangles in a 40 by 25 grid, each in its own
+    /// color.
+    fn scene() -> Scene {
+        let mut scene = Scene::with_capacity(COUNT);
+        for index in 0..COUNT {
+            let (row, column) = (index / 40, index % 40);
+            let color = Color::from_rgb8(index as u8, (index >> 8) as u8 * 64, 200);
+            scene.add_object(Box::new(Rectangle::new(
+                column as f32 * 10.0,
+                row as f32 * 10.0,
+                8.0,
+                8.0,
+                color,
+            )));
+        }
+        scene
+    }
+
+    pub fn brush_cache(c: &mut Criterion) {
+        let scene = scene();
+        let context = Direct2DContext::new(&WindowConfig::default()).unwrap();
+        context
+            .render_offscreen(400, 250, 96.0, |drawing_context| {
+                scene.draw_all(drawing_context)?;
+                assert_eq!(drawing_context.brushes.len(), COUNT);
+
+                let before = ALLOCATIONS.load(Ordering::Relaxed);
+                scene.draw_all(drawing_context)?;
+                let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+                assert_eq!(allocations, 0, "a frame with every brush cached allocated");
+
+                c.bench_function("draw 1,000 colors, brushes cached", |b| {
+                    b.iter(|| scene.draw_all(black_box(drawing_context)).unwrap())
+                });
+                Ok(())
+            })
+            .unwrap();
+    }
+}
+
+#[cfg(windows)]
+criterion::criterion_group!(benches, bench::brush_cache);
+#[cfg(windows)]
+criterion::criterion_main!(benches);
+
+/// Direct2D only exists on Windows.
+#[cfg(not(windows))]
+fn main() {}
@@ -0,0 +1,68 @@
+//! # Scene Construction Benchmark
+//!
+//! Builds a synthetic scene of 100,000 rectangles three ways: with `Scene::new` and
+//! one `add_object` call per rectangle, which grows the content layer as it goes; with
+//! `Scene::with_capacity` and the same calls; and by collecting the boxed rectangles
+//! into a scene, which adds them with `Extend` in one pass, as `scene!` does. Dropping
+//! the scene isn't measured.
+//!
+//! Run it on Windows with `cargo bench --bench construction`.
+
+#[cfg(windows)]
+mod bench {
+    use criterion::Criterion;
+
+    use my_gui::core::render::{
+        color::Color, drawable::Drawable, objects::primitives::Rectangle, scene::Scene,
+    };
+
+    /// The number of rectangles in the scene.
+    const COUNT: usize = 100_000;
+
+    /// Creates the `index`th rectangle of the scene.
+    fn rectangle(index: usize) -> Box<dyn Drawable> {
+        let (row, column) = (index / 316, index % 316);
+        Box::new(Rectangle::new(
+            column as f32 * 10.0,
+            row as f32 * 10.0,
+            8.0,
+            8.0,
+            Color::BLUE,
+        ))
+    }
+
+    pub fn construction(c: &mut Criterion) {
+        let mut group = c.benchmark_group("build 100k objects");
+        group.bench_function("add_object", |b| {
+            b.iter_with_large_drop(|| {
+                let mut scene = Scene::new();
+                for index in 0..COUNT {
+                    scene.add_object(rectangle(index));
+                }
+                scene
+            })
+        });
+        group.bench_function("with_capacity", |b| {
+            b.iter_with_large_drop(|| {
+                let mut scene = Scene::with_capacity(COUNT);
+                for index in 0..COUNT {
+                    scene.add_object(rectangle(index));
+                }
+                scene
+            })
+        });
+        group.bench_function("collect", |b| {
+            b.iter_with_large_drop(|| (0..COUNT).map(rectangle).collect::<Scene>())
+        });
+        group.finish();
+    }
+}
+
+#[cfg(windows)]
+criterion::criterion_group!(benches, bench::construction);
+#[cfg(windows)]
+criterion::criterion_main!(benches);
+
+/// The scene only exists on Windows.
+#[cfg(not(windows))]
+fn main() {}
@@ -0,0 +1,106 @@
+//! # Culling Benchmark
+//!
+//! Draws a synthetic scene of 50,176 rectangles spread over a canvas about 4.4 times
+//! as wide and tall as the bitmap, so only about 5% of them are visible, viewed through
+//! a camera panned to the middle of the canvas. `Scene::draw_all` skips the objects
+//! outside the visible area; the baseline draws every object, as `draw_all` did before
+//! culling. Each frame is flushed, so the time includes rasterizing.
+//!
+//! Run it on Windows with `cargo bench --bench culling`.
+
+#[cfg(windows)]
+mod bench {
+    use std::hint::black_box;
+
+    use criterion::Criterion;
+
+    use my_gui::core::{
+        render::{
+            camera::Camera,
+            color::Color,
+            direct2d_context::Direct2DContext,
+            drawable::Drawable,
+            drawing_context::DrawingContext,
+            objects::primitives::Rectangle,
+            scene::{Layer, Scene},
+        },
+        window::config::WindowConfig,
+    };
+
+    /// The width and height of the bitmap drawn into, in pixels.
+    const SIZE: u32 = 512;
+
+    /// The number of rectangles along each side of the canvas.
+    const COLUMNS: usize = 224;
+
+    /// The distance between the corners of neighbouring rectangles.
+    const SPACING: f32 = 10.0;
+
+    /// Creates the scene, with its camera showing the middle of the canvas.
+    fn scene() -> Scene {
+        let mut scene = Scene::with_capacity(COLUMNS * COLUMNS);
+        for row in 0..COLUMNS {
+            for column in 0..COLUMNS {
+                let color = Color::from_rgb8(column as u8, row as u8, 128);
+                scene.add_object(Box::new(Rectangle::new(
+                    column as f32 * SPACING,
+                    row as f32 * SPACING,
+                    8.0,
+                    8.0,
+                    color,
+                )));
+            }
+        }
+        let middle = (COLUMNS as f32 * SPACING - SIZE as f32) / 2.0;
+        scene.set_camera(Camera::new().with_position(middle, middle));
+        scene
+    }
+
+    /// Draws every object in the scene through its camera, without culling.
+    fn draw_every_object(scene: &Scene, context: &DrawingContext) -> windows::core::Result<()> {
+        context.with_transform(&scene.camera().view_matrix(), || {
+            for (_, object) in scene.layer(Layer::Content).iter() {
+                object.draw(context)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Submits the frame drawn so far, so its rasterization is measured too.
+    fn flush(context: &DrawingContext) {
+        unsafe { context.render_target.Flush(None, None).unwrap() };
+    }
+
+    pub fn culling(c: &mut Criterion) {
+        let scene = scene();
+        let context = Direct2DContext::new(&WindowConfig::default()).unwrap();
+        context
+            .render_offscreen(SIZE, SIZE, 96.0, |drawing_context| {
+                let mut group = c.benchmark_group("draw 50k objects, 5% visible");
+                group.bench_function("culled", |b| {
+                    b.iter(|| {
+                        scene.draw_all(black_box(drawing_context)).unwrap();
+                        flush(drawing_context);
+                    })
+                });
+                group.bench_function("every object", |b| {
+                    b.iter(|| {
+                        draw_every_object(&scene, black_box(drawing_context)).unwrap();
+                        flush(drawing_context);
+                    })
+                });
+                group.finish();
+                Ok(())
+            })
+            .unwrap();
+    }
+}
+
+#[cfg(windows)]
+criterion::criterion_group!(benches, bench::culling);
+#[cfg(windows)]
+criterion::criterion_main!(benches);
+
+/// The scene and Direct2D only exist on Windows.
+#[cfg(not(windows))]
+fn main() {}
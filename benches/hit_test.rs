@@ -0,0 +1,91 @@
+//! # Hit Test Benchmark
+//!
+//! Hit tests synthetic scenes of 10,000 and 100,000 rectangles laid out in a square
+//! grid, with and without a spatial index covering the grid. Each iteration tests a
+//! fixed set of points spread over the grid, so the indexed scene doesn't just hit one
+//! cell's cached results.
+//!
+//! Run it on Windows with `cargo bench --bench hit_test`.
+
+#[cfg(windows)]
+mod bench {
+    use std::hint::black_box;
+
+    use criterion::{BenchmarkId, Criterion};
+    use windows_numerics::Vector2;
+
+    use my_gui::core::render::{
+        color::Color, objects::primitives::Rectangle, rect::Rect, scene::Scene,
+    };
+
+    /// The distance between the corners of neighbouring rectangles.
+    const SPACING: f32 = 10.0;
+
+    /// Creates a scene of `count` rectangles in a square grid, with a spatial index if
+    /// `indexed`.
+    fn scene(count: usize, indexed: bool) -> Scene {
+        let columns = (count as f32).sqrt().ceil() as usize;
+        let mut scene = Scene::with_capacity(count);
+        if indexed {
+            let side = columns as f32 * SPACING;
+            scene = scene.with_spatial_index(Rect::new(0.0, 0.0, side, side));
+        }
+        for index in 0..count {
+            let (row, column) = (index / columns, index % columns);
+            scene.add_object(Box::new(Rectangle::new(
+                column as f32 * SPACING,
+                row as f32 * SPACING,
+                8.0,
+                8.0,
+                Color::BLUE,
+            )));
+        }
+        scene
+    }
+
+    /// Returns 64 points spread over a grid of `count` rectangles, half of them on a
+    /// rectangle and half in the gaps between them.
+    fn points(count: usize) -> Vec<Vector2> {
+        let columns = (count as f32).sqrt().ceil() as usize;
+        (0..64)
+            .map(|index| {
+                let column = (index * 37) % 64 * columns / 64;
+                let row = index * columns / 64;
+                let offset = if index % 2 == 0 { 4.0 } else { 9.0 };
+                Vector2 {
+                    X: column as f32 * SPACING + offset,
+                    Y: row as f32 * SPACING + offset,
+                }
+            })
+            .collect()
+    }
+
+    pub fn hit_test(c: &mut Criterion) {
+        let mut group = c.benchmark_group("hit test");
+        for count in [10_000, 100_000] {
+            let points = points(count);
+            for (name, indexed) in [("linear", false), ("indexed", true)] {
+                let scene = scene(count, indexed);
+                // The first query builds the index, which isn't what is measured.
+                scene.hit_test(Vector2::default());
+                group.bench_with_input(BenchmarkId::new(name, count), &points, |b, points| {
+                    b.iter(|| {
+                        for point in points {
+                            black_box(scene.hit_test(*point));
+                        }
+                    })
+                });
+            }
+        }
+        group.finish();
+    }
+}
+
+#[cfg(windows)]
+criterion::criterion_group!(benches, bench::hit_test);
+#[cfg(windows)]
+criterion::criterion_main!(benches);
+
+/// The scene only exists on Windows.
+#[cfg(not(windows))]
+fn main() {}
@@ -0,0 +1,116 @@
+//! # MyGui Camera
+//!
+//! This example shows a grid of tiles that is larger than the window, viewed through
+//! the scene's `Camera`. Dragging with the middle mouse button pans the view and the
+//! mouse wheel zooms around the cursor. Clicking a tile toggles its border, which still
+//! hits the right tile while the view is panned or zoomed. The help text is in the
+//! overlay, so it stays in place.
+use windows::core::*;
+use windows_numerics::Vector2;
+
+use my_gui::{
+    app::App,
+    core::{
+        event::{
+            camera_controller::CameraController, event_handler::EventHandler,
+            render_event_handler::RenderEventHandler, root_event_handler::RootEventHandler,
+        },
+        render::{
+            color::Color,
+            objects::{primitives::Rectangle, text_object::TextObject},
+            scene::Layer,
+        },
+        window::{WindowBuilder, config::WindowConfig},
+    },
+};
+
+/// The number of tiles along each side of the grid.
+const TILES: usize = 20;
+
+/// The size of one tile, in world units.
+const TILE_SIZE: f32 = 60.0;
+
+/// The gap between tiles, in world units.
+const GAP: f32 = 8.0;
+
+/// An event handler that toggles the border of the tile under the cursor when clicked.
+struct SelectHandler;
+
+impl EventHandler for SelectHandler {
+    fn on_lbutton_down(&mut self, app: &mut App, x: i32, y: i32) {
+        // `hit_test` takes the position in the window and applies the camera itself.
+        let cursor = Vector2 {
+            X: x as f32,
+            Y: y as f32,
+        };
+        let Some(&id) = app.scene.hit_test(cursor).first() else {
+            return;
+        };
+        let Some(tile) = app.scene.get_object_mut::<Rectangle>(id) else {
+            return;
+        };
+        tile.stroke = match tile.stroke {
+            Some(_) => None,
+            None => Some((Color::WHITE, 4.0)),
+        };
+        app.request_redraw();
+    }
+}
+
+fn main() -> Result<()> {
+    // Create the application state.
+    let mut app = App::new();
+
+    // Fill the world with a grid of tiles shaded by their position.
+    for row in 0..TILES {
+        for column in 0..TILES {
+            let color = Color::from_rgb8(
+                40 + (column * 200 / TILES) as u8,
+                60,
+                40 + (row * 200 / TILES) as u8,
+            );
+            app.scene.add_object(Box::new(Rectangle::new(
+                column as f32 * (TILE_SIZE + GAP),
+                row as f32 * (TILE_SIZE + GAP),
+                TILE_SIZE,
+                TILE_SIZE,
+                color,
+            )));
+        }
+    }
+
+    // The overlay isn't moved by the camera.
+    app.scene
+        .layer_mut(Layer::Overlay)
+        .add_object(Box::new(TextObject::new(
+            "Middle-drag to pan, wheel to zoom, click to select",
+            10.0,
+            10.0,
+        )));
+
+    // Create the event handlers.
+    let mut event_handler = RootEventHandler::new();
+    event_handler.add_handler(Box::new(CameraController::new()));
+    event_handler.add_handler(Box::new(SelectHandler));
+    event_handler.add_handler(Box::new(RenderEventHandler::new()));
+
+    // Create the window configuration.
+    let config = WindowConfig {
+        title: "Camera".to_string(),
+        width: 800,
+        height: 600,
+        ..Default::default()
+    };
+
+    // Create the window.
+    let window = WindowBuilder::from_config(config).build(event_handler, app)?;
+
+    // Run the application.
+    let result = window.run();
+
+    // The window is intentionally "leaked" using `std::mem::forget` because its
+    // lifetime is managed by the Windows API.
+    std::mem::forget(window);
+
+    result
+}
@@ -1,23 +1,76 @@
 //! # MyGui Hello World
 //!
 //! This is a simple example of how to use the `my_gui` framework to create a
-//! "Hello, World!" application.
+//! "Hello, World!" application. The arrow keys move the blue rectangle, which shows
+//! the retained-mode loop end to end: an event handler changes the scene and requests
+//! a redraw, and the window repaints the changed scene. Print Screen saves the window
+//! as a numbered PNG file in the current directory.
 use windows::core::*;
 
 use my_gui::{
     app::App,
     core::{
-        event::{render_event_handler::RenderEventHandler, root_event_handler::RootEventHandler},
+        event::{
+            event_handler::EventHandler, key_id::KeyId, render_event_handler::RenderEventHandler,
+            root_event_handler::RootEventHandler,
+        },
+        render::{objects::primitives::Rectangle, scene::ObjectId},
         window::{WindowBuilder, config::WindowConfig},
     },
 };
 
+/// How far one arrow key press moves the rectangle, in DIPs.
+const STEP: f32 = 10.0;
+
+/// An event handler that moves the blue rectangle with the arrow keys.
+struct MoveHandler {
+    rectangle: ObjectId,
+}
+
+impl EventHandler for MoveHandler {
+    fn on_key_down(&mut self, app: &mut App, key: KeyId) {
+        let Some(rectangle) = app.scene.get_object_mut::<Rectangle>(self.rectangle) else {
+            return;
+        };
+        match key {
+            KeyId::Left => rectangle.x -= STEP,
+            KeyId::Right => rectangle.x += STEP,
+            KeyId::Up => rectangle.y -= STEP,
+            KeyId::Down => rectangle.y += STEP,
+            _ => return,
+        }
+        app.request_redraw();
+    }
+}
+
+/// An event handler that saves a screenshot of the window when Print Screen is pressed.
+struct ScreenshotHandler {
+    count: usize,
+}
+
+impl EventHandler for ScreenshotHandler {
+    // Windows only reports the release of Print Screen to the window.
+    fn on_key_up(&mut self, app: &mut App, key: KeyId) {
+        if key != KeyId::PrintScreen {
+            return;
+        }
+        self.count += 1;
+        app.request_capture(format!("screenshot-{}.png", self.count));
+    }
+}
+
 fn main() -> Result<()> {
     // Create the application state.
-    let app = App::new();
+    let mut app = App::new();
+
+    // Add a blue rectangle with a border and a dashed selection marquee around it to
+    // the scene.
+    let rectangle = app.add_sample_shapes();
 
     // Create the event handlers.
     let mut event_handler = RootEventHandler::new();
+    event_handler.add_handler(Box::new(MoveHandler { rectangle }));
+    event_handler.add_handler(Box::new(ScreenshotHandler { count: 0 }));
     event_handler.add_handler(Box::new(RenderEventHandler::new()));
 
     // Create the window configuration.
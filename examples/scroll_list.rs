@@ -0,0 +1,119 @@
+//! # MyGui Scroll List
+//!
+//! This example shows a scrolling list built from a `Canvas`. The canvas clips a long
+//! column of rows, and the mouse wheel scrolls it while the cursor is over the canvas.
+//! A `ScrollBar` beside the list follows the scrolling and can be dragged.
+use windows::core::*;
+use windows_numerics::Vector2;
+
+use my_gui::{
+    app::App,
+    core::{
+        event::{
+            event_handler::EventHandler, render_event_handler::RenderEventHandler,
+            root_event_handler::RootEventHandler, scroll_bar_handler::ScrollBarHandler,
+        },
+        render::{
+            color::Color,
+            objects::{
+                canvas::Canvas,
+                primitives::Rectangle,
+                scroll_bar::{ScrollBar, ScrollOrientation},
+                text_object::TextObject,
+            },
+            scene::ObjectId,
+        },
+        window::{WindowBuilder, config::WindowConfig},
+    },
+};
+
+/// The number of rows in the list.
+const ROWS: usize = 50;
+
+/// The height of one row, in DIPs.
+const ROW_HEIGHT: f32 = 32.0;
+
+/// How far one wheel notch scrolls the list, in DIPs.
+const SCROLL_STEP: f32 = 3.0 * ROW_HEIGHT;
+
+/// An event handler that scrolls a canvas with the mouse wheel.
+struct ScrollHandler {
+    canvas: ObjectId,
+}
+
+impl EventHandler for ScrollHandler {
+    fn on_mouse_wheel(&mut self, app: &mut App, x: i32, y: i32, delta: i32) {
+        let Some(canvas) = app.scene.get_object_mut::<Canvas>(self.canvas) else {
+            return;
+        };
+        let cursor = Vector2 {
+            X: x as f32,
+            Y: y as f32,
+        };
+        if canvas.map_to_local(cursor).is_none() {
+            return;
+        }
+        // Rotating the wheel away from the user scrolls up, toward the first row.
+        canvas.scroll_by(0.0, -(delta as f32 / 120.0) * SCROLL_STEP);
+        app.request_redraw();
+    }
+}
+
+fn main() -> Result<()> {
+    // Create the application state.
+    let mut app = App::new();
+
+    // Build a list of rows that is much taller than the canvas showing it.
+    let mut list =
+        Canvas::new(40.0, 60.0, 320.0, 400.0).with_content_size(320.0, ROWS as f32 * ROW_HEIGHT);
+    for row in 0..ROWS {
+        let y = row as f32 * ROW_HEIGHT;
+        let color = if row % 2 == 0 {
+            Color::from_rgb8(40, 40, 60)
+        } else {
+            Color::from_rgb8(60, 60, 90)
+        };
+        list.add_child(Box::new(Rectangle::new(0.0, y, 320.0, ROW_HEIGHT, color)));
+        list.add_child(Box::new(TextObject::new(
+            &format!("Row {}", row + 1),
+            10.0,
+            y + 4.0,
+        )));
+    }
+    let canvas = app.scene.add_object(Box::new(list));
+
+    // Add a scroll bar to the right of the list, covering its full height.
+    let scroll_bar = app.scene.add_object(Box::new(ScrollBar::new(
+        364.0,
+        60.0,
+        12.0,
+        400.0,
+        ScrollOrientation::Vertical,
+    )));
+
+    // Create the event handlers.
+    let mut event_handler = RootEventHandler::new();
+    event_handler.add_handler(Box::new(ScrollHandler { canvas }));
+    event_handler.add_handler(Box::new(ScrollBarHandler::new(scroll_bar, canvas)));
+    event_handler.add_handler(Box::new(RenderEventHandler::new()));
+
+    // Create the window configuration.
+    let config = WindowConfig {
+        title: "Scroll List".to_string(),
+        width: 420,
+        height: 540,
+        ..Default::default()
+    };
+
+    // Create the window.
+    let window = WindowBuilder::from_config(config).build(event_handler, app)?;
+
+    // Run the application.
+    let result = window.run();
+
+    // The window is intentionally "leaked" using `std::mem::forget` because its
+    // lifetime is managed by the Windows API.
+    std::mem::forget(window);
+
+    result
+}
@@ -0,0 +1,123 @@
+//! # MyGui Settings Panel
+//!
+//! This example shows a settings panel laid out with stacks instead of positioning
+//! every object by hand. A `VStack` places a heading and a row per setting one below
+//! the other, and each row is an `HStack` that places a label next to a bar showing the
+//! setting's value. Pressing F12 outlines the bounds the stacks laid out.
+use windows::core::*;
+
+use my_gui::{
+    app::App,
+    core::{
+        event::{
+            debug_overlay_handler::DebugOverlayHandler, render_event_handler::RenderEventHandler,
+            root_event_handler::RootEventHandler,
+        },
+        render::{
+            color::Color,
+            objects::{
+                label::Label,
+                primitives::Rectangle,
+                stack::{HStack, StackAlignment, VStack},
+                text_object::TextObject,
+            },
+            text_rendering::TextRendering,
+        },
+        window::{WindowBuilder, config::WindowConfig},
+    },
+};
+
+/// The settings shown in the panel, with their values between 0 and 1.
+const SETTINGS: [(&str, f32); 4] = [
+    ("Volume", 0.8),
+    ("Brightness", 0.55),
+    ("Contrast", 0.4),
+    ("Mouse speed", 0.3),
+];
+
+/// The width of the panel, in DIPs.
+const PANEL_WIDTH: f32 = 420.0;
+
+/// The height of one setting row, in DIPs.
+const ROW_HEIGHT: f32 = 36.0;
+
+/// The width of the label column, in DIPs.
+const LABEL_WIDTH: f32 = 140.0;
+
+/// The width of a bar for a value of 1, in DIPs.
+const BAR_WIDTH: f32 = 240.0;
+
+/// Builds a row with a setting's name next to a bar showing its value.
+fn setting_row(name: &str, value: f32) -> HStack {
+    let mut row = HStack::new(0.0, 0.0, PANEL_WIDTH - 20.0, ROW_HEIGHT)
+        .with_spacing(10.0)
+        .with_alignment(StackAlignment::Center);
+
+    // The label column has a fixed width, so the bars line up.
+    let mut column =
+        VStack::new(0.0, 0.0, LABEL_WIDTH, ROW_HEIGHT).with_alignment(StackAlignment::Start);
+    column.add_child(Box::new(TextObject::new(name, 0.0, 0.0)));
+    row.add_child(Box::new(column));
+
+    row.add_child(Box::new(Rectangle::new(
+        0.0,
+        0.0,
+        BAR_WIDTH * value,
+        12.0,
+        Color::from_rgb8(90, 150, 240),
+    )));
+    row
+}
+
+fn main() -> Result<()> {
+    // Create the application state.
+    let mut app = App::new();
+
+    // Stack the heading and a row per setting, with a gap between them.
+    let mut panel = VStack::new(40.0, 40.0, PANEL_WIDTH, 400.0)
+        .with_spacing(8.0)
+        .with_alignment(StackAlignment::Center);
+    panel.canvas_mut().background = Some(Color::from_rgb8(30, 30, 40));
+    panel.canvas_mut().border = Some((Color::from_rgb8(90, 90, 120), 2.0));
+
+    panel.add_child(Box::new(
+        Label::new(
+            TextObject::new("Settings", 0.0, 0.0),
+            Color::from_rgb8(60, 60, 90),
+        )
+        .with_padding(8.0),
+    ));
+    for (name, value) in SETTINGS {
+        panel.add_child(Box::new(setting_row(name, value)));
+    }
+    app.scene.add_object(Box::new(panel));
+
+    // Create the event handlers.
+    let mut event_handler = RootEventHandler::new();
+    event_handler.add_handler(Box::new(RenderEventHandler::new()));
+    // Press F12 to outline the layout on top of the panel.
+    event_handler.add_handler(Box::new(DebugOverlayHandler::new()));
+
+    // Create the window configuration.
+    let config = WindowConfig {
+        title: "Settings Panel".to_string(),
+        width: 520,
+        height: 520,
+        background_color: Color::from_rgb8(18, 18, 24),
+        pixel_snap: true,
+        text_rendering: TextRendering::LightOnDark,
+        ..Default::default()
+    };
+
+    // Create the window.
+    let window = WindowBuilder::from_config(config).build(event_handler, app)?;
+
+    // Run the application.
+    let result = window.run();
+
+    // The window is intentionally "leaked" using `std::mem::forget` because its
+    // lifetime is managed by the Windows API.
+    std::mem::forget(window);
+
+    result
+}
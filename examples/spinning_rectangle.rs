@@ -0,0 +1,96 @@
+//! # MyGui Spinning Rectangle
+//!
+//! This example rotates a rectangle around its center using a per-object `Transform`,
+//! while an enclosing drawable translates it. It shows that object transforms compose
+//! with the transform already active on the render target, and that a drawable can
+//! animate itself through `Drawable::update`.
+//!
+//! Run it with `--immediate` to present frames without waiting for the vertical blank.
+//! The edges of the rectangle may then tear as it spins, which they never do with the
+//! default `PresentMode::VSync`.
+use std::time::Duration;
+
+use windows::core::*;
+use windows_numerics::Matrix3x2;
+
+use my_gui::{
+    app::App,
+    core::{
+        event::{
+            animation_handler::AnimationHandler, render_event_handler::RenderEventHandler,
+            root_event_handler::RootEventHandler,
+        },
+        render::{
+            color::Color, drawable::Drawable, drawing_context::DrawingContext,
+            objects::primitives::Rectangle, transform::Transform,
+        },
+        window::{
+            WindowBuilder,
+            config::{PresentMode, WindowConfig},
+        },
+    },
+};
+
+/// A rectangle that spins at a fixed rate, drawn inside a translated coordinate space.
+struct SpinningRectangle {
+    rectangle: Rectangle,
+    offset: Matrix3x2,
+    degrees: f32,
+}
+
+impl Drawable for SpinningRectangle {
+    fn draw(&self, context: &DrawingContext) -> Result<()> {
+        context.with_transform(&self.offset, || self.rectangle.draw(context))
+    }
+
+    fn update(&mut self, dt: Duration) {
+        // Rotate a quarter turn per second.
+        self.degrees = (self.degrees + dt.as_secs_f32() * 90.0) % 360.0;
+        self.rectangle.transform = Some(Transform::rotation(self.degrees));
+    }
+}
+
+fn main() -> Result<()> {
+    // Create the application state.
+    let mut app = App::new();
+
+    // Add a rectangle that spins around its own center, offset into the window.
+    app.scene.add_object(Box::new(SpinningRectangle {
+        rectangle: Rectangle::new(-75.0, -50.0, 150.0, 100.0, Color::BLUE)
+            .with_stroke(Color::WHITE, 4.0),
+        offset: Matrix3x2::translation(450.0, 300.0),
+        degrees: 0.0,
+    }));
+
+    // Create the event handlers. The animation handler must run before the renderer
+    // so the scene is updated before it is drawn.
+    let mut event_handler = RootEventHandler::new();
+    event_handler.add_handler(Box::new(AnimationHandler::new()));
+    event_handler.add_handler(Box::new(RenderEventHandler::new()));
+
+    // Create the window configuration.
+    let present_mode = if std::env::args().any(|arg| arg == "--immediate") {
+        PresentMode::Immediate
+    } else {
+        PresentMode::VSync
+    };
+    let config = WindowConfig {
+        title: format!("Spinning Rectangle ({present_mode:?})"),
+        width: 900,
+        height: 600,
+        present_mode,
+        ..Default::default()
+    };
+
+    // Create the window.
+    let window = WindowBuilder::from_config(config).build(event_handler, app)?;
+
+    // Run the application.
+    let result = window.run();
+
+    // The window is intentionally "leaked" using `std::mem::forget` because its
+    // lifetime is managed by the Windows API.
+    std::mem::forget(window);
+
+    result
+}
@@ -0,0 +1,38 @@
+//! # MyGui Thumbnail
+//!
+//! This example draws a scene without showing a window. It renders the scene into a
+//! bitmap with `Direct2DContext::render_to_bitmap`, checks the pixel in the middle, and
+//! saves the bitmap as `thumbnail.png` in the current directory.
+use windows::core::*;
+
+use my_gui::core::{
+    render::{
+        color::Color, direct2d_context::Direct2DContext, objects::primitives::Rectangle,
+        scene::Scene,
+    },
+    window::config::WindowConfig,
+};
+
+/// The width of the thumbnail, in pixels.
+const WIDTH: u32 = 160;
+
+/// The height of the thumbnail, in pixels.
+const HEIGHT: u32 = 120;
+
+fn main() -> Result<()> {
+    // The context only needs the font settings of the configuration; no window is
+    // created.
+    let context = Direct2DContext::new(&WindowConfig::default())?;
+
+    // A red rectangle covering the middle of the thumbnail.
+    let mut scene = Scene::new();
+    scene.add_object(Box::new(Rectangle::new(40.0, 30.0, 80.0, 60.0, Color::RED)));
+
+    let pixels = context.render_to_bitmap(&scene, WIDTH, HEIGHT)?;
+    let center = ((HEIGHT / 2 * WIDTH + WIDTH / 2) * 4) as usize;
+    println!("Center pixel: {:?}", &pixels[center..center + 4]);
+
+    context.save_png("thumbnail.png", WIDTH, HEIGHT, &pixels)?;
+    println!("Saved thumbnail.png");
+    Ok(())
+}
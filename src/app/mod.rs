@@ -4,7 +4,18 @@
 //! drawable objects and other application-wide data. The `App` struct is the
 //! primary container for this state.
 
-use crate::core::render::{objects::text_object::TextObject, scene::Scene};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use windows_numerics::Vector2;
+
+use crate::core::event::mouse_button::MouseButton;
+use crate::core::render::{
+    color::Color,
+    objects::{primitives::Rectangle, text_object::TextObject},
+    scene::{ObjectId, Scene},
+    stroke_style::StrokeStyle,
+};
 
 /// Represents the central state of the application.
 ///
@@ -17,6 +28,25 @@ pub struct App {
     pub scene: Scene,
     /// The text string to be displayed in the window.
     pub display_text: String,
+    /// The size of the window's client area in DIPs.
+    window_size: Vector2,
+    /// Whether the window should be repainted after the current message.
+    redraw_requested: bool,
+    /// Timers to start after the current message, with their ids and intervals.
+    timer_requests: Vec<(usize, Duration)>,
+    /// Files to save a capture of the window to after the current message.
+    capture_requests: Vec<PathBuf>,
+    /// The background color to switch to after the current message.
+    background_color_request: Option<Color>,
+    /// Whether to capture (`true`) or release (`false`) the mouse after the current
+    /// message.
+    mouse_capture_request: Option<bool>,
+    /// The `MK_*` flags of the last mouse message.
+    mouse_key_state: usize,
+    /// The cursor position of the last mouse message, in DIPs.
+    mouse_position: Vector2,
+    /// Whether the frame is being painted again for `Window::capture_frame`.
+    capturing_frame: bool,
 }
 
 impl App {
@@ -30,8 +60,173 @@ impl App {
         Self {
             scene,
             display_text,
+            window_size: Vector2::default(),
+            redraw_requested: false,
+            timer_requests: Vec::new(),
+            capture_requests: Vec::new(),
+            background_color_request: None,
+            mouse_capture_request: None,
+            mouse_key_state: 0,
+            mouse_position: Vector2::default(),
+            capturing_frame: false,
         }
     }
+
+    /// Adds the shapes of the `hello_world` example to the scene: a blue rectangle with
+    /// a white border, and a dashed yellow selection marquee around it that stays on
+    /// top even after the rectangle is moved.
+    ///
+    /// Returns the id of the rectangle, which the example moves with the arrow keys.
+    pub fn add_sample_shapes(&mut self) -> ObjectId {
+        let rectangle = self.scene.add_object(Box::new(
+            Rectangle::new(10.0, 50.0, 200.0, 100.0, Color::BLUE).with_stroke(Color::WHITE, 4.0),
+        ));
+        self.scene.add_object_with_z_index(
+            Box::new(
+                Rectangle::outlined(0.0, 40.0, 220.0, 120.0, Color::YELLOW, 1.0)
+                    .with_stroke_style(StrokeStyle::Dash),
+            ),
+            1,
+        );
+        rectangle
+    }
+
+    /// Returns the size of the window's client area in DIPs, the area the scene is
+    /// drawn in.
+    ///
+    /// The window keeps this up to date when it is resized, before calling
+    /// `EventHandler::on_resize`, so layout code can read it at any time. It is zero
+    /// until the window has been created.
+    pub fn window_size(&self) -> Vector2 {
+        self.window_size
+    }
+
+    /// Records the size of the window's client area in DIPs.
+    pub(crate) fn set_window_size(&mut self, size: Vector2) {
+        self.window_size = size;
+    }
+
+    /// Requests that the window be repainted.
+    ///
+    /// The window only repaints when Windows asks it to (for example after a resize)
+    /// or when a redraw has been requested. Event handlers that change the scene
+    /// should call this so the change becomes visible.
+    pub fn request_redraw(&mut self) {
+        self.redraw_requested = true;
+    }
+
+    /// Returns whether a redraw was requested, clearing the request.
+    pub(crate) fn take_redraw_request(&mut self) -> bool {
+        std::mem::take(&mut self.redraw_requested)
+    }
+
+    /// Requests a timer that calls `EventHandler::on_timer` with `id` every `interval`.
+    ///
+    /// The timer is started after the current message has been handled, and runs until
+    /// the window is destroyed. Requesting a timer with the id of a running one
+    /// replaces its interval. Windows doesn't fire timers more often than about every
+    /// 10 ms.
+    pub fn set_timer(&mut self, id: usize, interval: Duration) {
+        self.timer_requests.push((id, interval));
+    }
+
+    /// Returns the timers requested since the last call, clearing the requests.
+    pub(crate) fn take_timer_requests(&mut self) -> Vec<(usize, Duration)> {
+        std::mem::take(&mut self.timer_requests)
+    }
+
+    /// Requests that the window's frame be saved as a PNG file at `path`.
+    ///
+    /// The capture is taken after the current message has been handled, with
+    /// `Window::capture_frame`. Failures are logged.
+    pub fn request_capture(&mut self, path: impl Into<PathBuf>) {
+        self.capture_requests.push(path.into());
+    }
+
+    /// Returns the captures requested since the last call, clearing the requests.
+    pub(crate) fn take_capture_requests(&mut self) -> Vec<PathBuf> {
+        std::mem::take(&mut self.capture_requests)
+    }
+
+    /// Returns `true` while `EventHandler::on_paint` is called to capture the frame
+    /// with `Window::capture_frame` rather than to show it.
+    ///
+    /// The captured frame has already been shown, so handlers that change the app
+    /// when painting, such as to advance an animation, should skip that while this
+    /// is set and only draw.
+    pub fn is_capturing_frame(&self) -> bool {
+        self.capturing_frame
+    }
+
+    /// Records whether the frame is being painted again to capture it.
+    pub(crate) fn set_capturing_frame(&mut self, capturing: bool) {
+        self.capturing_frame = capturing;
+    }
+
+    /// Changes the color the window is cleared to before the scene is drawn.
+    ///
+    /// The window switches to the new color after the current message has been
+    /// handled, and repaints with it.
+    pub fn set_background_color(&mut self, color: Color) {
+        self.background_color_request = Some(color);
+    }
+
+    /// Returns the background color requested since the last call, clearing the
+    /// request.
+    pub(crate) fn take_background_color_request(&mut self) -> Option<Color> {
+        self.background_color_request.take()
+    }
+
+    /// Requests that the window capture the mouse, so it keeps receiving mouse
+    /// messages while the cursor is outside it.
+    ///
+    /// The capture is taken after the current message has been handled, and lasts until
+    /// `release_mouse` is called or another window takes it, at which point
+    /// `EventHandler::on_capture_lost` is called. Handlers should capture the mouse
+    /// when a drag starts, so they see the button being released.
+    pub fn capture_mouse(&mut self) {
+        self.mouse_capture_request = Some(true);
+    }
+
+    /// Requests that the window release the mouse capture taken with `capture_mouse`.
+    pub fn release_mouse(&mut self) {
+        self.mouse_capture_request = Some(false);
+    }
+
+    /// Returns the mouse capture change requested since the last call, clearing the
+    /// request.
+    pub(crate) fn take_mouse_capture_request(&mut self) -> Option<bool> {
+        self.mouse_capture_request.take()
+    }
+
+    /// Returns `true` if `button` was down when the current mouse message was sent.
+    ///
+    /// Handlers tracking a drag should check this in `EventHandler::on_mouse_move`, as
+    /// the button may have been released while the window didn't have the capture.
+    pub fn is_mouse_button_down(&self, button: MouseButton) -> bool {
+        button.is_down_in(self.mouse_key_state)
+    }
+
+    /// Returns the cursor position of the current mouse message in the client area, in
+    /// DIPs.
+    ///
+    /// Event handlers receive mouse positions rounded to whole DIPs. At scales other
+    /// than 100%, a pixel is a fraction of a DIP, so handlers that follow the cursor
+    /// closely, such as for dragging, can read the exact position here. It is negative
+    /// left of or above the client area, while the mouse is captured.
+    pub fn mouse_position(&self) -> Vector2 {
+        self.mouse_position
+    }
+
+    /// Records the cursor position of the current mouse message, in DIPs.
+    pub(crate) fn set_mouse_position(&mut self, position: Vector2) {
+        self.mouse_position = position;
+    }
+
+    /// Records the `MK_*` flags of the current mouse message.
+    pub(crate) fn set_mouse_key_state(&mut self, key_state: usize) {
+        self.mouse_key_state = key_state;
+    }
 }
 
 impl Default for App {
@@ -39,3 +234,77 @@ impl Default for App {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::backend::{DrawCommand, Paint, RecordingRenderer};
+    use crate::core::render::rect::Rect;
+
+    #[test]
+    fn hello_world_scene_renders_the_text_then_the_rectangle_then_the_marquee() {
+        // The scene built by `examples/hello_world.rs`.
+        let mut app = App::new();
+        app.add_sample_shapes();
+
+        let mut renderer = RecordingRenderer::new();
+        app.scene.render(&mut renderer);
+
+        assert_eq!(
+            renderer.commands(),
+            [
+                DrawCommand::Text {
+                    text: app.display_text.clone(),
+                    origin: Vector2::new(10.0, 10.0),
+                    color: Color::WHITE,
+                },
+                DrawCommand::Rect {
+                    rect: Rect::new(10.0, 50.0, 200.0, 100.0),
+                    paint: Paint::Fill(Color::BLUE),
+                },
+                DrawCommand::Rect {
+                    rect: Rect::new(10.0, 50.0, 200.0, 100.0),
+                    paint: Paint::Stroke {
+                        color: Color::WHITE,
+                        width: 4.0,
+                    },
+                },
+                DrawCommand::Rect {
+                    rect: Rect::new(0.0, 40.0, 220.0, 120.0),
+                    paint: Paint::Stroke {
+                        color: Color::YELLOW,
+                        width: 1.0,
+                    },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn mouse_capture_requests_are_taken_once_and_the_last_one_wins() {
+        let mut app = App::new();
+        assert_eq!(app.take_mouse_capture_request(), None);
+
+        app.capture_mouse();
+        assert_eq!(app.take_mouse_capture_request(), Some(true));
+        assert_eq!(app.take_mouse_capture_request(), None);
+
+        app.capture_mouse();
+        app.release_mouse();
+        assert_eq!(app.take_mouse_capture_request(), Some(false));
+    }
+
+    #[test]
+    fn mouse_buttons_follow_the_key_state_of_the_last_mouse_message() {
+        let mut app = App::new();
+        assert!(!app.is_mouse_button_down(MouseButton::Left));
+
+        // MK_LBUTTON
+        app.set_mouse_key_state(0x0001);
+        assert!(app.is_mouse_button_down(MouseButton::Left));
+        assert!(!app.is_mouse_button_down(MouseButton::Middle));
+
+        app.set_mouse_key_state(0);
+        assert!(!app.is_mouse_button_down(MouseButton::Left));
+    }
+}
@@ -0,0 +1,515 @@
+use windows::{
+    core::{Error, Result, HSTRING},
+    Win32::Foundation::{COLORREF, E_FAIL, POINT, RECT},
+    Win32::Graphics::Gdi::*,
+};
+use windows_numerics::{Matrix3x2, Vector2};
+
+use crate::core::backend::{Paint, Renderer};
+use crate::core::render::color::Color;
+use crate::core::render::rect::Rect;
+use crate::core::render::transform::invert;
+
+/// The number of GDI logical units in one unit drawn through the `Renderer` trait.
+///
+/// GDI only takes integer coordinates, so everything is drawn at this finer scale and
+/// the world transform scales it back down, keeping shapes within a sixteenth of a
+/// unit of where they were asked to be.
+const UNITS_PER_DIP: f32 = 16.0;
+
+/// Converts a coordinate or length to GDI logical units.
+fn units(value: f32) -> i32 {
+    (value * UNITS_PER_DIP).round() as i32
+}
+
+/// Converts a color to a GDI `COLORREF`. Alpha is ignored.
+pub(crate) fn colorref(color: Color) -> COLORREF {
+    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u32;
+    COLORREF(channel(color.r) | (channel(color.g) << 8) | (channel(color.b) << 16))
+}
+
+/// A `Renderer` that draws with GDI into an offscreen bitmap, for machines where
+/// Direct2D can't be initialized.
+///
+/// Transforms are applied with `SetWorldTransform`, so rotated and scaled shapes and
+/// text are drawn as with Direct2D, and clips are intersected as paths, so rotated
+/// clips are exact too. Clipping and drawing order match the other backends.
+///
+/// Fidelity is lower: edges aren't antialiased, and GDI can't blend, so colors are
+/// drawn opaque and only a color or opacity of zero hides a shape. Text is drawn in
+/// the font the renderer was created with.
+///
+/// Windows configured with `RendererConfig::Gdi`, or whose Direct2D renderer fails to
+/// initialize, draw with it and copy the bitmap to the window with `present`.
+pub struct GdiRenderer {
+    /// The memory device context everything is drawn on.
+    hdc: HDC,
+    /// The DIB section selected into `hdc`, with top-down 32-bit BGRX rows.
+    bitmap: HBITMAP,
+    /// The bitmap that was selected into `hdc` when it was created.
+    previous_bitmap: HGDIOBJ,
+    /// The pixels of `bitmap`.
+    bits: *const u8,
+    /// The font text is drawn in.
+    font: HFONT,
+    /// The width of the bitmap, in pixels.
+    width: u32,
+    /// The height of the bitmap, in pixels.
+    height: u32,
+    /// The current transform.
+    transform: Matrix3x2,
+    /// The current opacity.
+    opacity: f32,
+    /// The number of clips pushed and not yet popped. Each push saves the DC state.
+    clip_depth: usize,
+}
+
+impl GdiRenderer {
+    /// Creates a new `GdiRenderer` with a black bitmap of `width` by `height` pixels,
+    /// at least one pixel in each direction, drawing text in `font_face_name` at
+    /// `font_size`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create the device context,
+    /// the bitmap, or the font.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for creating and setting up the device
+    /// context, the bitmap, and the font.
+    pub fn new(width: u32, height: u32, font_face_name: &str, font_size: f32) -> Result<Self> {
+        let hdc = unsafe { CreateCompatibleDC(None) };
+        if hdc.is_invalid() {
+            return Err(Error::new(E_FAIL, "failed to create a GDI device context"));
+        }
+
+        let mut face_name = [0u16; 32];
+        for (slot, unit) in face_name
+            .iter_mut()
+            .zip(HSTRING::from(font_face_name).as_wide().iter().take(31))
+        {
+            *slot = *unit;
+        }
+        let font = unsafe {
+            CreateFontIndirectW(&LOGFONTW {
+                // A negative height selects the font by its character height, as
+                // DirectWrite's font size does.
+                lfHeight: -units(font_size),
+                lfWeight: FW_NORMAL.0 as i32,
+                lfCharSet: DEFAULT_CHARSET,
+                lfQuality: CLEARTYPE_QUALITY,
+                lfFaceName: face_name,
+                ..Default::default()
+            })
+        };
+
+        let mut renderer = Self {
+            hdc,
+            bitmap: HBITMAP::default(),
+            previous_bitmap: HGDIOBJ::default(),
+            bits: std::ptr::null(),
+            font,
+            width: 0,
+            height: 0,
+            transform: Matrix3x2::identity(),
+            opacity: 1.0,
+            clip_depth: 0,
+        };
+        if font.is_invalid() {
+            return Err(Error::new(E_FAIL, "failed to create the GDI font"));
+        }
+        unsafe {
+            SetGraphicsMode(hdc, GM_ADVANCED);
+            SetBkMode(hdc, TRANSPARENT);
+            SelectObject(hdc, font.into());
+        }
+        renderer.resize(width, height)?;
+        renderer.set_transform(Matrix3x2::identity());
+        Ok(renderer)
+    }
+
+    /// Returns the width of the bitmap, in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height of the bitmap, in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Changes the size of the bitmap, at least one pixel in each direction, making it
+    /// black if the size changes.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create the new bitmap, in
+    /// which case the old one is kept.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for creating the bitmap and selecting it
+    /// into the device context.
+    pub fn resize(&mut self, width: u32, height: u32) -> Result<()> {
+        let (width, height) = (width.max(1), height.max(1));
+        if (width, height) == (self.width, self.height) {
+            return Ok(());
+        }
+        let info = BITMAPINFO {
+            bmiHeader: BITMAPINFOHEADER {
+                biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                biWidth: width as i32,
+                // A negative height makes the rows top-down.
+                biHeight: -(height as i32),
+                biPlanes: 1,
+                biBitCount: 32,
+                biCompression: BI_RGB.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut bits = std::ptr::null_mut();
+        let bitmap =
+            unsafe { CreateDIBSection(Some(self.hdc), &info, DIB_RGB_COLORS, &mut bits, None, 0)? };
+
+        let replaced = unsafe { SelectObject(self.hdc, bitmap.into()) };
+        if self.bitmap.is_invalid() {
+            self.previous_bitmap = replaced;
+        } else {
+            let _ = unsafe { DeleteObject(replaced) };
+        }
+        self.bitmap = bitmap;
+        self.bits = bits as *const u8;
+        self.width = width;
+        self.height = height;
+        Ok(())
+    }
+
+    /// Returns the bitmap as rows of RGBA8 pixels, top row first, in the same layout as
+    /// `SoftwareRenderer::pixels`. Every pixel is opaque.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for finishing pending drawing and reading
+    /// the bitmap's pixels.
+    pub fn pixels(&self) -> Vec<u8> {
+        let length = self.width as usize * self.height as usize * 4;
+        let bgrx = unsafe {
+            let _ = GdiFlush();
+            std::slice::from_raw_parts(self.bits, length)
+        };
+        bgrx.chunks_exact(4)
+            .flat_map(|pixel| [pixel[2], pixel[1], pixel[0], 255])
+            .collect()
+    }
+
+    /// Copies the bitmap to `target` at its top-left corner.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the copy fails.
+    ///
+    /// # Safety
+    ///
+    /// This function contains an `unsafe` block for copying the bitmap. The caller
+    /// must ensure that `target` is a valid device context.
+    pub fn present(&self, target: HDC) -> Result<()> {
+        unsafe {
+            BitBlt(
+                target,
+                0,
+                0,
+                self.width as i32,
+                self.height as i32,
+                Some(self.hdc),
+                0,
+                0,
+                SRCCOPY,
+            )
+        }
+    }
+
+    /// Returns `true` if something drawn in `color` would be seen: the color and the
+    /// opacity aren't zero and the transform doesn't collapse shapes to nothing.
+    fn is_visible(&self, color: Color) -> bool {
+        color.a * self.opacity > 0.0 && invert(&self.transform).is_some()
+    }
+
+    /// Creates a solid geometric pen `width` units wide, with flat caps, so lines end
+    /// where they were asked to as they do with Direct2D.
+    ///
+    /// # Safety
+    ///
+    /// This function contains an `unsafe` block for creating the pen.
+    fn create_pen(color: Color, width: f32) -> HPEN {
+        let brush = LOGBRUSH {
+            lbStyle: BS_SOLID,
+            lbColor: colorref(color),
+            lbHatch: 0,
+        };
+        unsafe {
+            ExtCreatePen(
+                PS_GEOMETRIC | PS_SOLID | PS_ENDCAP_FLAT | PS_JOIN_MITER,
+                units(width).max(1) as u32,
+                &brush,
+                None,
+            )
+        }
+    }
+
+    /// Selects a pen and brush for `paint` into the device context, runs `draw`, and
+    /// deletes them again. Nothing is drawn if the paint wouldn't be seen.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for creating, selecting, and deleting
+    /// the pen and brush.
+    fn paint_with(&self, paint: Paint, draw: impl FnOnce(HDC)) {
+        let (color, stroke_width) = match paint {
+            Paint::Fill(color) => (color, None),
+            Paint::Stroke { color, width } => (color, Some(width)),
+        };
+        if !self.is_visible(color) || stroke_width.is_some_and(|width| width <= 0.0) {
+            return;
+        }
+        unsafe {
+            let (pen, brush): (HGDIOBJ, HGDIOBJ) = match stroke_width {
+                None => (
+                    GetStockObject(NULL_PEN),
+                    CreateSolidBrush(colorref(color)).into(),
+                ),
+                Some(width) => (
+                    Self::create_pen(color, width).into(),
+                    GetStockObject(NULL_BRUSH),
+                ),
+            };
+            let previous_pen = SelectObject(self.hdc, pen);
+            let previous_brush = SelectObject(self.hdc, brush);
+            draw(self.hdc);
+            SelectObject(self.hdc, previous_brush);
+            SelectObject(self.hdc, previous_pen);
+            // Deleting the stock object is harmless, so both can be deleted.
+            let _ = DeleteObject(pen);
+            let _ = DeleteObject(brush);
+        }
+    }
+}
+
+impl Drop for GdiRenderer {
+    /// Deletes the device context, the bitmap, and the font.
+    fn drop(&mut self) {
+        unsafe {
+            if !self.previous_bitmap.is_invalid() {
+                SelectObject(self.hdc, self.previous_bitmap);
+            }
+            let _ = DeleteDC(self.hdc);
+            if !self.bitmap.is_invalid() {
+                let _ = DeleteObject(self.bitmap.into());
+            }
+            let _ = DeleteObject(self.font.into());
+        }
+    }
+}
+
+impl Renderer for GdiRenderer {
+    fn clear(&mut self, color: Color) {
+        let rect = RECT {
+            left: 0,
+            top: 0,
+            right: self.width as i32,
+            bottom: self.height as i32,
+        };
+        unsafe {
+            let saved = SaveDC(self.hdc);
+            let _ = ModifyWorldTransform(self.hdc, None, MWT_IDENTITY);
+            SelectClipRgn(self.hdc, None);
+            let brush = CreateSolidBrush(colorref(color));
+            FillRect(self.hdc, &rect, brush);
+            let _ = DeleteObject(brush.into());
+            let _ = RestoreDC(self.hdc, saved);
+        }
+    }
+
+    fn draw_rect(&mut self, rect: Rect, paint: Paint) {
+        self.paint_with(paint, |hdc| unsafe {
+            let _ = Rectangle(
+                hdc,
+                units(rect.x),
+                units(rect.y),
+                units(rect.right()),
+                units(rect.bottom()),
+            );
+        });
+    }
+
+    fn draw_ellipse(&mut self, center: Vector2, radius: Vector2, paint: Paint) {
+        self.paint_with(paint, |hdc| unsafe {
+            let _ = Ellipse(
+                hdc,
+                units(center.X - radius.X),
+                units(center.Y - radius.Y),
+                units(center.X + radius.X),
+                units(center.Y + radius.Y),
+            );
+        });
+    }
+
+    fn draw_line(&mut self, start: Vector2, end: Vector2, color: Color, width: f32) {
+        let paint = Paint::Stroke { color, width };
+        self.paint_with(paint, |hdc| unsafe {
+            let _ = MoveToEx(hdc, units(start.X), units(start.Y), None);
+            let _ = LineTo(hdc, units(end.X), units(end.Y));
+        });
+    }
+
+    fn draw_text(&mut self, text: &str, origin: Vector2, color: Color) {
+        if !self.is_visible(color) {
+            return;
+        }
+        let text: Vec<u16> = text.encode_utf16().collect();
+        unsafe {
+            SetTextColor(self.hdc, colorref(color));
+            let _ = TextOutW(self.hdc, units(origin.X), units(origin.Y), &text);
+        }
+    }
+
+    /// Saves the device context, then intersects its clip region with `rect` as a path,
+    /// so the clip follows a rotated transform exactly.
+    fn push_clip(&mut self, rect: Rect) {
+        let corners = [
+            POINT {
+                x: units(rect.x),
+                y: units(rect.y),
+            },
+            POINT {
+                x: units(rect.right()),
+                y: units(rect.y),
+            },
+            POINT {
+                x: units(rect.right()),
+                y: units(rect.bottom()),
+            },
+            POINT {
+                x: units(rect.x),
+                y: units(rect.bottom()),
+            },
+        ];
+        unsafe {
+            SaveDC(self.hdc);
+            let _ = BeginPath(self.hdc);
+            let _ = Polygon(self.hdc, &corners);
+            let _ = EndPath(self.hdc);
+            let _ = SelectClipPath(self.hdc, RGN_AND);
+        }
+        self.clip_depth += 1;
+    }
+
+    /// Restores the device context saved by the matching `push_clip`, then reapplies
+    /// the current transform, which restoring would otherwise undo.
+    fn pop_clip(&mut self) {
+        if self.clip_depth == 0 {
+            return;
+        }
+        self.clip_depth -= 1;
+        unsafe {
+            let _ = RestoreDC(self.hdc, -1);
+        }
+        self.set_transform(self.transform);
+    }
+
+    fn transform(&self) -> Matrix3x2 {
+        self.transform
+    }
+
+    /// Sets the world transform to `transform`, scaled down from the finer logical
+    /// units shapes are drawn in.
+    fn set_transform(&mut self, transform: Matrix3x2) {
+        self.transform = transform;
+        if invert(&transform).is_none() {
+            // GDI rejects singular transforms, and nothing is drawn with one anyway.
+            return;
+        }
+        let world = XFORM {
+            eM11: transform.M11 / UNITS_PER_DIP,
+            eM12: transform.M12 / UNITS_PER_DIP,
+            eM21: transform.M21 / UNITS_PER_DIP,
+            eM22: transform.M22 / UNITS_PER_DIP,
+            eDx: transform.M31,
+            eDy: transform.M32,
+        };
+        unsafe {
+            let _ = SetWorldTransform(self.hdc, &world);
+        }
+    }
+
+    fn opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the RGBA8 pixel at `(x, y)` of the renderer's bitmap.
+    fn pixel(renderer: &GdiRenderer, x: u32, y: u32) -> [u8; 4] {
+        let start = (y * renderer.width() + x) as usize * 4;
+        let pixels = renderer.pixels();
+        [
+            pixels[start],
+            pixels[start + 1],
+            pixels[start + 2],
+            pixels[start + 3],
+        ]
+    }
+
+    #[test]
+    fn clips_restore_when_popped_and_later_shapes_draw_on_top() {
+        let mut renderer = GdiRenderer::new(16, 16, "Segoe UI", 12.0).unwrap();
+        renderer.clear(Color::BLACK);
+        renderer.push_clip(Rect::new(0.0, 0.0, 8.0, 8.0));
+        renderer.draw_rect(Rect::new(0.0, 0.0, 16.0, 16.0), Paint::Fill(Color::RED));
+        renderer.pop_clip();
+        renderer.draw_rect(Rect::new(4.0, 4.0, 8.0, 8.0), Paint::Fill(Color::BLUE));
+
+        assert_eq!(pixel(&renderer, 2, 2), [255, 0, 0, 255]);
+        assert_eq!(pixel(&renderer, 6, 6), [0, 0, 255, 255]);
+        assert_eq!(pixel(&renderer, 14, 2), [0, 0, 0, 255]);
+        assert_eq!(pixel(&renderer, 10, 10), [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn transforms_move_shapes_and_clips_alike() {
+        let mut bitmap = GdiRenderer::new(16, 16, "Segoe UI", 12.0).unwrap();
+        bitmap.clear(Color::BLACK);
+        let renderer: &mut dyn Renderer = &mut bitmap;
+        renderer.with_transform(&Matrix3x2::translation(8.0, 8.0), |renderer| {
+            renderer.with_clip(Rect::new(0.0, 0.0, 4.0, 4.0), |renderer| {
+                renderer.draw_rect(Rect::new(-8.0, -8.0, 16.0, 16.0), Paint::Fill(Color::GREEN));
+            });
+        });
+
+        assert_eq!(bitmap.transform(), Matrix3x2::identity());
+        assert_eq!(pixel(&bitmap, 10, 10), [0, 255, 0, 255]);
+        assert_eq!(pixel(&bitmap, 6, 6), [0, 0, 0, 255]);
+        assert_eq!(pixel(&bitmap, 13, 13), [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn invisible_colors_draw_nothing() {
+        let mut renderer = GdiRenderer::new(4, 4, "Segoe UI", 12.0).unwrap();
+        renderer.clear(Color::WHITE);
+        renderer.draw_rect(
+            Rect::new(0.0, 0.0, 4.0, 4.0),
+            Paint::Fill(Color::TRANSPARENT),
+        );
+        renderer.set_opacity(0.0);
+        renderer.draw_rect(Rect::new(0.0, 0.0, 4.0, 4.0), Paint::Fill(Color::RED));
+        assert_eq!(pixel(&renderer, 1, 1), [255, 255, 255, 255]);
+    }
+}
@@ -0,0 +1,824 @@
+use std::ops::Range;
+
+use raw_window_handle::{RawDisplayHandle, RawWindowHandle};
+use wgpu::util::DeviceExt;
+use windows_numerics::{Matrix3x2, Vector2};
+
+use crate::core::backend::font::{self, GLYPH_COUNT, GLYPH_HEIGHT, GLYPH_WIDTH};
+use crate::core::backend::{Paint, Renderer, stroke_bands};
+use crate::core::render::color::Color;
+use crate::core::render::rect::Rect;
+
+/// The kinds of instance the shader draws, matching the `KIND_` constants in
+/// `gpu.wgsl`.
+const KIND_RECT: u32 = 0;
+const KIND_ELLIPSE: u32 = 1;
+const KIND_RING: u32 = 2;
+const KIND_SEGMENT: u32 = 3;
+const KIND_GLYPH: u32 = 4;
+
+/// The size of the push constants: the transform and the size of the surface.
+const PUSH_CONSTANT_SIZE: u32 = 32;
+
+/// An error creating a `WgpuRenderer` or presenting a frame.
+#[derive(Debug, thiserror::Error)]
+pub enum WgpuError {
+    /// The surface couldn't be created for the window.
+    #[error("failed to create the surface: {0}")]
+    Surface(#[from] wgpu::CreateSurfaceError),
+    /// No adapter can draw to the surface.
+    #[error("no suitable adapter: {0}")]
+    Adapter(#[from] wgpu::RequestAdapterError),
+    /// The adapter couldn't create a device, such as one without push constants.
+    #[error("failed to create the device: {0}")]
+    Device(#[from] wgpu::RequestDeviceError),
+    /// The adapter can't present to the surface.
+    #[error("the adapter can't present to the surface")]
+    UnsupportedSurface,
+    /// The next frame of the surface couldn't be acquired.
+    #[error("failed to get the next frame: {0}")]
+    Frame(#[from] wgpu::SurfaceError),
+}
+
+/// One shape or glyph, as the vertex shader reads it.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+struct Instance {
+    /// The quad covering the shape, as its left, top, right, and bottom edges.
+    bounds: [f32; 4],
+    /// The shape, whose meaning depends on `kind`.
+    params: [f32; 4],
+    /// More of the shape, or the glyph's corners in the atlas.
+    extra: [f32; 4],
+    /// The color, with straight alpha and the opacity applied.
+    color: [f32; 4],
+    /// Which shape this is, one of the `KIND_` constants.
+    kind: u32,
+    /// Pads the instance to a multiple of 16 bytes.
+    padding: [u32; 3],
+}
+
+/// A run of instances drawn with the same transform and scissor rect.
+#[derive(Debug, Clone, PartialEq)]
+struct Batch {
+    /// The transform, passed as push constants.
+    transform: Matrix3x2,
+    /// The scissor rect, as its left, top, width, and height in pixels.
+    scissor: [u32; 4],
+    /// The instances drawn.
+    instances: Range<u32>,
+}
+
+/// The instances and batches of the frame being drawn, and the state of the renderer.
+///
+/// This is kept apart from the device, so batching can be tested without a GPU.
+#[derive(Debug, Clone)]
+struct Frame {
+    /// The size of the surface, in pixels.
+    size: (u32, u32),
+    /// The color the frame is cleared to.
+    clear_color: Color,
+    /// The instances drawn so far, in order.
+    instances: Vec<Instance>,
+    /// The batches the instances are drawn in, in order.
+    batches: Vec<Batch>,
+    /// The current transform.
+    transform: Matrix3x2,
+    /// The current opacity.
+    opacity: f32,
+    /// The bounds in pixels of each clip pushed and not yet popped, each intersected
+    /// with the ones before it, innermost last.
+    clips: Vec<Rect>,
+}
+
+impl Frame {
+    /// Creates an empty frame for a surface of `width` by `height` pixels.
+    fn new(width: u32, height: u32) -> Self {
+        Self {
+            size: (width, height),
+            clear_color: Color::TRANSPARENT,
+            instances: Vec::new(),
+            batches: Vec::new(),
+            transform: Matrix3x2::identity(),
+            opacity: 1.0,
+            clips: Vec::new(),
+        }
+    }
+
+    /// Returns the scissor rect of the innermost clip, or of the whole surface, or
+    /// `None` if the clips leave nothing to draw.
+    fn scissor(&self) -> Option<[u32; 4]> {
+        let surface = Rect::new(0.0, 0.0, self.size.0 as f32, self.size.1 as f32);
+        let visible = match self.clips.last() {
+            Some(clip) => clip.intersection(&surface)?,
+            None => surface,
+        };
+        let left = visible.x.floor() as u32;
+        let top = visible.y.floor() as u32;
+        let right = visible.right().ceil() as u32;
+        let bottom = visible.bottom().ceil() as u32;
+        (right > left && bottom > top).then_some([left, top, right - left, bottom - top])
+    }
+
+    /// Adds a shape or glyph in `color` to the frame, starting a new batch if the
+    /// transform or clip changed since the last one.
+    fn push(&mut self, kind: u32, bounds: Rect, params: [f32; 4], extra: [f32; 4], color: Color) {
+        let alpha = color.a * self.opacity;
+        let Some(scissor) = self.scissor() else {
+            return;
+        };
+        if alpha <= 0.0 {
+            return;
+        }
+        let index = self.instances.len() as u32;
+        self.instances.push(Instance {
+            bounds: [bounds.x, bounds.y, bounds.right(), bounds.bottom()],
+            params,
+            extra,
+            color: [color.r, color.g, color.b, alpha],
+            kind,
+            padding: [0; 3],
+        });
+        match self.batches.last_mut() {
+            Some(batch) if batch.transform == self.transform && batch.scissor == scissor => {
+                batch.instances.end = index + 1;
+            }
+            _ => self.batches.push(Batch {
+                transform: self.transform,
+                scissor,
+                instances: index..index + 1,
+            }),
+        }
+    }
+
+    /// Adds a filled rectangle, skipping empty ones.
+    fn push_rect(&mut self, rect: Rect, color: Color) {
+        if rect.width > 0.0 && rect.height > 0.0 {
+            let edges = [rect.x, rect.y, rect.right(), rect.bottom()];
+            self.push(KIND_RECT, rect, edges, [0.0; 4], color);
+        }
+    }
+}
+
+impl Renderer for Frame {
+    /// Discards everything drawn so far, which the clear would cover, and clears the
+    /// frame to `color` when it is presented.
+    fn clear(&mut self, color: Color) {
+        self.clear_color = color;
+        self.instances.clear();
+        self.batches.clear();
+    }
+
+    fn draw_rect(&mut self, rect: Rect, paint: Paint) {
+        match paint {
+            Paint::Fill(color) => self.push_rect(rect, color),
+            Paint::Stroke { color, width } => {
+                for band in stroke_bands(rect, width) {
+                    self.push_rect(band, color);
+                }
+            }
+        }
+    }
+
+    fn draw_ellipse(&mut self, center: Vector2, radius: Vector2, paint: Paint) {
+        let (outer, inner, color) = match paint {
+            Paint::Fill(color) => (radius, None, color),
+            Paint::Stroke { color, width } => {
+                if width <= 0.0 {
+                    return;
+                }
+                let half = width / 2.0;
+                let outer = Vector2 {
+                    X: radius.X + half,
+                    Y: radius.Y + half,
+                };
+                let inner = Vector2 {
+                    X: radius.X - half,
+                    Y: radius.Y - half,
+                };
+                (outer, Some(inner), color)
+            }
+        };
+        if outer.X <= 0.0 || outer.Y <= 0.0 {
+            return;
+        }
+        let bounds = Rect::new(
+            center.X - outer.X,
+            center.Y - outer.Y,
+            outer.X * 2.0,
+            outer.Y * 2.0,
+        );
+        let params = [center.X, center.Y, outer.X, outer.Y];
+        match inner {
+            // An inner radius of zero would divide by zero in the shader, and a ring
+            // that thick is a filled ellipse anyway.
+            Some(inner) if inner.X > 0.0 && inner.Y > 0.0 => self.push(
+                KIND_RING,
+                bounds,
+                params,
+                [inner.X, inner.Y, 0.0, 0.0],
+                color,
+            ),
+            _ => self.push(KIND_ELLIPSE, bounds, params, [0.0; 4], color),
+        }
+    }
+
+    fn draw_line(&mut self, start: Vector2, end: Vector2, color: Color, width: f32) {
+        if width <= 0.0 || (start.X == end.X && start.Y == end.Y) {
+            return;
+        }
+        let half_width = width / 2.0;
+        let Some(bounds) = Rect::from_points([start, end]) else {
+            return;
+        };
+        let params = [start.X, start.Y, end.X, end.Y];
+        self.push(
+            KIND_SEGMENT,
+            bounds.inflate(half_width),
+            params,
+            [half_width, 0.0, 0.0, 0.0],
+            color,
+        );
+    }
+
+    /// Draws the text with the built-in bitmap font, as `SoftwareRenderer` does, with
+    /// each glyph a quad sampling the font's atlas. Line feeds start a new line.
+    fn draw_text(&mut self, text: &str, origin: Vector2, color: Color) {
+        let glyph_size = (
+            GLYPH_WIDTH as f32 * font::SCALE,
+            GLYPH_HEIGHT as f32 * font::SCALE,
+        );
+        for (character, corner) in font::layout(text, origin) {
+            if character == ' ' {
+                continue;
+            }
+            let bounds = Rect::new(corner.X, corner.Y, glyph_size.0, glyph_size.1);
+            let index = font::glyph_index(character) as f32;
+            let uv = [
+                index / GLYPH_COUNT as f32,
+                0.0,
+                (index + 1.0) / GLYPH_COUNT as f32,
+                1.0,
+            ];
+            self.push(KIND_GLYPH, bounds, [0.0; 4], uv, color);
+        }
+    }
+
+    /// Clips to the bounds of `rect` in pixels. Scissor rects are axis-aligned, so
+    /// under a rotated transform the clip covers the corners of its bounding box too.
+    fn push_clip(&mut self, rect: Rect) {
+        let bounds = rect.transform(&self.transform);
+        let clip = match self.clips.last() {
+            Some(outer) => outer.intersection(&bounds).unwrap_or_default(),
+            None => bounds,
+        };
+        self.clips.push(clip);
+    }
+
+    fn pop_clip(&mut self) {
+        self.clips.pop();
+    }
+
+    fn transform(&self) -> Matrix3x2 {
+        self.transform
+    }
+
+    fn set_transform(&mut self, transform: Matrix3x2) {
+        self.transform = transform;
+    }
+
+    fn opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity;
+    }
+}
+
+/// An experimental `Renderer` that draws with wgpu, on Direct3D 12, Vulkan, or
+/// Metal, whichever the platform offers.
+///
+/// Rectangles, ellipses, and lines are drawn as instanced quads shaded with signed
+/// distance functions, so their edges are antialiased at any transform. Text is drawn
+/// with the built-in bitmap font of `SoftwareRenderer`, from a glyph atlas. Each run
+/// of shapes with the same transform and clip is one draw call: the transform is
+/// passed as push constants and the clip as a scissor rect, so rotated clips cover
+/// their whole bounding box.
+///
+/// Drawing only records the frame. `present` draws it on the GPU and shows it.
+///
+/// It needs the `wgpu` feature, and an adapter that supports push constants. Windows
+/// configured with `RendererConfig::Wgpu` draw with it.
+pub struct WgpuRenderer {
+    /// The surface of the window.
+    surface: wgpu::Surface<'static>,
+    /// The device everything is drawn with.
+    device: wgpu::Device,
+    /// The queue frames are submitted to.
+    queue: wgpu::Queue,
+    /// The configuration of the surface, including its size.
+    config: wgpu::SurfaceConfiguration,
+    /// The pipeline that draws the instances.
+    pipeline: wgpu::RenderPipeline,
+    /// The glyph atlas and its sampler.
+    atlas: wgpu::BindGroup,
+    /// The frame being drawn.
+    frame: Frame,
+}
+
+impl WgpuRenderer {
+    /// Creates a new `WgpuRenderer` drawing to the window `window` on the display
+    /// `display`, with a surface of `width` by `height` pixels.
+    ///
+    /// On Windows, `window` is the `RawWindowHandle::Win32` of the window, such as the
+    /// one `Window` returns from `HasWindowHandle::window_handle`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the surface can't be created, if no
+    /// adapter can present to it, or if the adapter can't create a device with push
+    /// constants.
+    ///
+    /// # Safety
+    ///
+    /// The window must stay valid for as long as the renderer exists.
+    pub unsafe fn new(
+        display: RawDisplayHandle,
+        window: RawWindowHandle,
+        width: u32,
+        height: u32,
+    ) -> Result<Self, WgpuError> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let surface = unsafe {
+            instance.create_surface_unsafe(wgpu::SurfaceTargetUnsafe::RawHandle {
+                raw_display_handle: display,
+                raw_window_handle: window,
+            })?
+        };
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::default(),
+            force_fallback_adapter: false,
+            compatible_surface: Some(&surface),
+        }))?;
+        let (device, queue) =
+            pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+                label: Some("my_gui"),
+                required_features: wgpu::Features::PUSH_CONSTANTS,
+                required_limits: wgpu::Limits {
+                    max_push_constant_size: PUSH_CONSTANT_SIZE,
+                    ..wgpu::Limits::default()
+                },
+                ..Default::default()
+            }))?;
+
+        let mut config = surface
+            .get_default_config(&adapter, width.max(1), height.max(1))
+            .ok_or(WgpuError::UnsupportedSurface)?;
+        // Colors are blended as they are given, as Direct2D does, not in linear space.
+        let capabilities = surface.get_capabilities(&adapter);
+        if let Some(format) = capabilities.formats.iter().find(|format| !format.is_srgb()) {
+            config.format = *format;
+        }
+        surface.configure(&device, &config);
+
+        let (atlas_layout, atlas) = create_atlas(&device, &queue);
+        let pipeline = create_pipeline(&device, &atlas_layout, config.format);
+
+        Ok(Self {
+            surface,
+            device,
+            queue,
+            config,
+            pipeline,
+            atlas,
+            frame: Frame::new(width.max(1), height.max(1)),
+        })
+    }
+
+    /// Returns the width of the surface, in pixels.
+    pub fn width(&self) -> u32 {
+        self.config.width
+    }
+
+    /// Returns the height of the surface, in pixels.
+    pub fn height(&self) -> u32 {
+        self.config.height
+    }
+
+    /// Changes the size of the surface, at least one pixel in each direction.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        let (width, height) = (width.max(1), height.max(1));
+        if (width, height) == (self.config.width, self.config.height) {
+            return;
+        }
+        self.config.width = width;
+        self.config.height = height;
+        self.surface.configure(&self.device, &self.config);
+        self.frame.size = (width, height);
+    }
+
+    /// Draws the frame recorded since the last `present` on the GPU and shows it, then
+    /// starts a new, empty frame.
+    ///
+    /// If the surface was lost or is out of date, such as after the window was resized,
+    /// it is configured again and the frame is drawn on the new surface.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the next frame of the surface can't be
+    /// acquired.
+    pub fn present(&mut self) -> Result<(), WgpuError> {
+        let output = match self.surface.get_current_texture() {
+            Ok(output) => output,
+            Err(wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated) => {
+                self.surface.configure(&self.device, &self.config);
+                self.surface.get_current_texture()?
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let view = output
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        let frame = std::mem::replace(
+            &mut self.frame,
+            Frame::new(self.config.width, self.config.height),
+        );
+        let instances = (!frame.instances.is_empty()).then(|| {
+            self.device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("instances"),
+                    contents: bytemuck::cast_slice(&frame.instances),
+                    usage: wgpu::BufferUsages::VERTEX,
+                })
+        });
+
+        let clear = frame.clear_color;
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("frame"),
+            });
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("frame"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    depth_slice: None,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color {
+                            r: clear.r as f64,
+                            g: clear.g as f64,
+                            b: clear.b as f64,
+                            a: clear.a as f64,
+                        }),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                ..Default::default()
+            });
+            if let Some(instances) = &instances {
+                pass.set_pipeline(&self.pipeline);
+                pass.set_bind_group(0, &self.atlas, &[]);
+                pass.set_vertex_buffer(0, instances.slice(..));
+                for batch in &frame.batches {
+                    let [x, y, width, height] = batch.scissor;
+                    pass.set_scissor_rect(x, y, width, height);
+                    let constants = push_constants(&batch.transform, frame.size);
+                    pass.set_push_constants(
+                        wgpu::ShaderStages::VERTEX,
+                        0,
+                        bytemuck::cast_slice(&constants),
+                    );
+                    pass.draw(0..4, batch.instances.clone());
+                }
+            }
+        }
+        self.queue.submit([encoder.finish()]);
+        output.present();
+        Ok(())
+    }
+}
+
+impl Renderer for WgpuRenderer {
+    fn clear(&mut self, color: Color) {
+        self.frame.clear(color);
+    }
+
+    fn draw_rect(&mut self, rect: Rect, paint: Paint) {
+        self.frame.draw_rect(rect, paint);
+    }
+
+    fn draw_ellipse(&mut self, center: Vector2, radius: Vector2, paint: Paint) {
+        self.frame.draw_ellipse(center, radius, paint);
+    }
+
+    fn draw_line(&mut self, start: Vector2, end: Vector2, color: Color, width: f32) {
+        self.frame.draw_line(start, end, color, width);
+    }
+
+    fn draw_text(&mut self, text: &str, origin: Vector2, color: Color) {
+        self.frame.draw_text(text, origin, color);
+    }
+
+    fn push_clip(&mut self, rect: Rect) {
+        self.frame.push_clip(rect);
+    }
+
+    fn pop_clip(&mut self) {
+        self.frame.pop_clip();
+    }
+
+    fn transform(&self) -> Matrix3x2 {
+        self.frame.transform()
+    }
+
+    fn set_transform(&mut self, transform: Matrix3x2) {
+        self.frame.set_transform(transform);
+    }
+
+    fn opacity(&self) -> f32 {
+        self.frame.opacity()
+    }
+
+    fn set_opacity(&mut self, opacity: f32) {
+        self.frame.set_opacity(opacity);
+    }
+}
+
+/// Returns the push constants of a batch: the transform, in the layout of
+/// `Matrix3x2`, and the size of the surface.
+fn push_constants(transform: &Matrix3x2, size: (u32, u32)) -> [f32; 8] {
+    [
+        transform.M11,
+        transform.M12,
+        transform.M21,
+        transform.M22,
+        transform.M31,
+        transform.M32,
+        size.0 as f32,
+        size.1 as f32,
+    ]
+}
+
+/// Uploads the glyphs of the built-in font side by side into a texture, and returns
+/// the layout of its bind group and the bind group.
+fn create_atlas(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+) -> (wgpu::BindGroupLayout, wgpu::BindGroup) {
+    let width = (GLYPH_COUNT * GLYPH_WIDTH) as u32;
+    let height = GLYPH_HEIGHT as u32;
+    let mut texels = vec![0u8; (width * height) as usize];
+    for index in 0..GLYPH_COUNT {
+        let glyph = font::glyph_at(index);
+        for column in 0..GLYPH_WIDTH {
+            for row in 0..GLYPH_HEIGHT {
+                if font::is_set(&glyph, column, row) {
+                    texels[row * width as usize + index * GLYPH_WIDTH + column] = 255;
+                }
+            }
+        }
+    }
+
+    let size = wgpu::Extent3d {
+        width,
+        height,
+        depth_or_array_layers: 1,
+    };
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("glyph atlas"),
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::R8Unorm,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    });
+    queue.write_texture(
+        wgpu::TexelCopyTextureInfo {
+            texture: &texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        &texels,
+        wgpu::TexelCopyBufferLayout {
+            offset: 0,
+            bytes_per_row: Some(width),
+            rows_per_image: Some(height),
+        },
+        size,
+    );
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    // The font's pixels are magnified into crisp squares, as `SoftwareRenderer` draws
+    // them.
+    let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+        label: Some("glyph atlas"),
+        mag_filter: wgpu::FilterMode::Nearest,
+        min_filter: wgpu::FilterMode::Nearest,
+        ..Default::default()
+    });
+
+    let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("glyph atlas"),
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            },
+        ],
+    });
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("glyph atlas"),
+        layout: &layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(&view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(&sampler),
+            },
+        ],
+    });
+    (layout, bind_group)
+}
+
+/// Creates the pipeline that draws the instances as triangle strips of four vertices,
+/// blending with straight alpha.
+fn create_pipeline(
+    device: &wgpu::Device,
+    atlas_layout: &wgpu::BindGroupLayout,
+    format: wgpu::TextureFormat,
+) -> wgpu::RenderPipeline {
+    let shader = device.create_shader_module(wgpu::include_wgsl!("gpu.wgsl"));
+    let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("shapes"),
+        bind_group_layouts: &[atlas_layout],
+        push_constant_ranges: &[wgpu::PushConstantRange {
+            stages: wgpu::ShaderStages::VERTEX,
+            range: 0..PUSH_CONSTANT_SIZE,
+        }],
+    });
+    let attributes = wgpu::vertex_attr_array![
+        0 => Float32x4,
+        1 => Float32x4,
+        2 => Float32x4,
+        3 => Float32x4,
+        4 => Uint32,
+    ];
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("shapes"),
+        layout: Some(&layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: Some("vs_main"),
+            compilation_options: Default::default(),
+            buffers: &[wgpu::VertexBufferLayout {
+                array_stride: std::mem::size_of::<Instance>() as wgpu::BufferAddress,
+                step_mode: wgpu::VertexStepMode::Instance,
+                attributes: &attributes,
+            }],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: Some("fs_main"),
+            compilation_options: Default::default(),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleStrip,
+            ..Default::default()
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        multiview: None,
+        cache: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batches_split_where_the_transform_or_clip_changes() {
+        let mut frame = Frame::new(100, 100);
+        let renderer: &mut dyn Renderer = &mut frame;
+        renderer.draw_rect(Rect::new(0.0, 0.0, 10.0, 10.0), Paint::Fill(Color::RED));
+        renderer.draw_ellipse(
+            Vector2 { X: 5.0, Y: 5.0 },
+            Vector2 { X: 5.0, Y: 5.0 },
+            Paint::Fill(Color::RED),
+        );
+        renderer.with_transform(&Matrix3x2::translation(10.0, 20.0), |renderer| {
+            renderer.with_clip(Rect::new(0.0, 0.0, 30.0, 40.0), |renderer| {
+                renderer.draw_line(
+                    Vector2::default(),
+                    Vector2 { X: 5.0, Y: 0.0 },
+                    Color::BLUE,
+                    1.0,
+                );
+            });
+        });
+        renderer.draw_text("a", Vector2::default(), Color::WHITE);
+
+        assert_eq!(frame.instances.len(), 4);
+        assert_eq!(
+            frame.batches,
+            [
+                Batch {
+                    transform: Matrix3x2::identity(),
+                    scissor: [0, 0, 100, 100],
+                    instances: 0..2,
+                },
+                Batch {
+                    transform: Matrix3x2::translation(10.0, 20.0),
+                    scissor: [10, 20, 30, 40],
+                    instances: 2..3,
+                },
+                Batch {
+                    transform: Matrix3x2::identity(),
+                    scissor: [0, 0, 100, 100],
+                    instances: 3..4,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_clips_intersect_and_fully_clipped_shapes_are_dropped() {
+        let mut frame = Frame::new(100, 100);
+        frame.push_clip(Rect::new(10.0, 10.0, 50.0, 50.0));
+        frame.push_clip(Rect::new(40.0, 40.0, 50.0, 50.0));
+        assert_eq!(frame.scissor(), Some([40, 40, 20, 20]));
+
+        frame.push_clip(Rect::new(0.0, 0.0, 5.0, 5.0));
+        frame.draw_rect(Rect::new(0.0, 0.0, 100.0, 100.0), Paint::Fill(Color::RED));
+        assert!(frame.instances.is_empty());
+
+        frame.pop_clip();
+        frame.pop_clip();
+        frame.pop_clip();
+        assert_eq!(frame.scissor(), Some([0, 0, 100, 100]));
+    }
+
+    #[test]
+    fn opacity_is_applied_and_clear_discards_the_frame() {
+        let mut frame = Frame::new(10, 10);
+        frame.set_opacity(0.5);
+        frame.draw_rect(
+            Rect::new(0.0, 0.0, 4.0, 4.0),
+            Paint::Stroke {
+                color: Color::GREEN,
+                width: 2.0,
+            },
+        );
+        assert_eq!(frame.instances.len(), 4);
+        assert!(
+            frame
+                .instances
+                .iter()
+                .all(|instance| instance.color[3] == 0.5)
+        );
+
+        frame.clear(Color::BLACK);
+        assert!(frame.instances.is_empty());
+        assert!(frame.batches.is_empty());
+        assert_eq!(frame.clear_color, Color::BLACK);
+    }
+
+    #[test]
+    fn glyphs_sample_their_own_cell_of_the_atlas() {
+        let mut frame = Frame::new(10, 10);
+        frame.draw_text("A \u{e9}", Vector2::default(), Color::WHITE);
+        let cells: Vec<f32> = frame
+            .instances
+            .iter()
+            .map(|instance| instance.extra[0] * GLYPH_COUNT as f32)
+            .collect();
+        assert_eq!(cells.len(), 2);
+        assert!((cells[0] - font::glyph_index('A') as f32).abs() < 1e-3);
+        assert!((cells[1] - (GLYPH_COUNT - 1) as f32).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,119 @@
+// Shapes and glyphs for `WgpuRenderer`, drawn as instanced quads.
+//
+// Shapes are shaded with signed distance functions in the coordinates they were
+// drawn in, so their edges are antialiased at any transform. Glyphs are sampled from
+// the atlas of the built-in bitmap font.
+
+const KIND_RECT: u32 = 0u;
+const KIND_ELLIPSE: u32 = 1u;
+const KIND_RING: u32 = 2u;
+const KIND_SEGMENT: u32 = 3u;
+const KIND_GLYPH: u32 = 4u;
+
+// The transform of a batch, in the layout of `Matrix3x2`, and the size of the surface
+// in pixels.
+struct PushConstants {
+    linear: vec4<f32>,
+    translation_and_size: vec4<f32>,
+};
+
+var<push_constant> constants: PushConstants;
+
+@group(0) @binding(0) var atlas: texture_2d<f32>;
+@group(0) @binding(1) var atlas_sampler: sampler;
+
+struct Instance {
+    // The quad covering the shape, as its left, top, right, and bottom edges.
+    @location(0) bounds: vec4<f32>,
+    // The shape, whose meaning depends on `kind`.
+    @location(1) params: vec4<f32>,
+    // More of the shape, or the glyph's corners in the atlas.
+    @location(2) extra: vec4<f32>,
+    // The color, with straight alpha.
+    @location(3) color: vec4<f32>,
+    @location(4) kind: u32,
+};
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) local: vec2<f32>,
+    @location(1) uv: vec2<f32>,
+    @location(2) @interpolate(flat) params: vec4<f32>,
+    @location(3) @interpolate(flat) extra: vec4<f32>,
+    @location(4) @interpolate(flat) color: vec4<f32>,
+    @location(5) @interpolate(flat) kind: u32,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex: u32, instance: Instance) -> VertexOutput {
+    let corner = vec2<f32>(f32(vertex & 1u), f32(vertex >> 1u));
+
+    // Shapes are grown by a pixel, so their antialiased edges aren't cut off.
+    let scale = max(length(constants.linear.xy), length(constants.linear.zw));
+    var margin = 1.0 / max(scale, 1e-6);
+    if instance.kind == KIND_GLYPH {
+        margin = 0.0;
+    }
+    let local = mix(instance.bounds.xy - margin, instance.bounds.zw + margin, corner);
+
+    let device = vec2<f32>(
+        local.x * constants.linear.x + local.y * constants.linear.z + constants.translation_and_size.x,
+        local.x * constants.linear.y + local.y * constants.linear.w + constants.translation_and_size.y,
+    );
+    let size = constants.translation_and_size.zw;
+
+    var out: VertexOutput;
+    out.position = vec4<f32>(device.x / size.x * 2.0 - 1.0, 1.0 - device.y / size.y * 2.0, 0.0, 1.0);
+    out.local = local;
+    out.uv = mix(instance.extra.xy, instance.extra.zw, corner);
+    out.params = instance.params;
+    out.extra = instance.extra;
+    out.color = instance.color;
+    out.kind = instance.kind;
+    return out;
+}
+
+// The signed distance from `p` to the edge of an ellipse, approximated by scaling the
+// distance of the unit circle.
+fn ellipse_distance(p: vec2<f32>, center: vec2<f32>, radius: vec2<f32>) -> f32 {
+    return (length((p - center) / radius) - 1.0) * min(radius.x, radius.y);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    var distance = -1e4;
+    var sampled = 1.0;
+    switch in.kind {
+        case KIND_RECT: {
+            let center = (in.params.xy + in.params.zw) * 0.5;
+            let half_size = (in.params.zw - in.params.xy) * 0.5;
+            let q = abs(in.local - center) - half_size;
+            distance = length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0);
+        }
+        case KIND_ELLIPSE: {
+            distance = ellipse_distance(in.local, in.params.xy, in.params.zw);
+        }
+        case KIND_RING: {
+            let outer = ellipse_distance(in.local, in.params.xy, in.params.zw);
+            let inner = ellipse_distance(in.local, in.params.xy, in.extra.xy);
+            distance = max(outer, -inner);
+        }
+        case KIND_SEGMENT: {
+            let start = in.params.xy;
+            let span = in.params.zw - start;
+            let segment_length = max(length(span), 1e-6);
+            let direction = span / segment_length;
+            let offset = in.local - start;
+            let along = dot(offset, direction);
+            let across = abs(offset.x * direction.y - offset.y * direction.x);
+            distance = max(across - in.extra.x, max(-along, along - segment_length));
+        }
+        default: {
+            // Sampling at an explicit level is allowed outside uniform control flow.
+            sampled = textureSampleLevel(atlas, atlas_sampler, in.uv, 0.0).r;
+        }
+    }
+    let width = max(fwidth(distance), 1e-6);
+    let coverage = clamp(0.5 - distance / width, 0.0, 1.0) * sampled;
+    return vec4<f32>(in.color.rgb, in.color.a * coverage);
+}
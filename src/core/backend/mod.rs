@@ -0,0 +1,219 @@
+//! # Rendering Backends
+//!
+//! This module defines the `Renderer` trait, a small set of drawing operations that
+//! doesn't depend on Direct2D or any other platform API, and the backends that
+//! implement it. Drawables implement `Drawable::render` to draw through it, and
+//! `Scene::render` draws a whole scene.
+//!
+//! Unlike the rest of `core`, this module compiles on every platform, so rendering
+//! can be tested without a window.
+//!
+//! ## Key Components
+//!
+//! - **`Renderer`**: The drawing operations every backend implements.
+//! - **`DrawCommand`**: One call to a `Renderer`, as recorded by `RecordingRenderer`.
+//! - **`Paint`**: Whether a shape is filled or outlined, and with which color.
+//! - **`RecordingRenderer`**: A backend that records draw commands instead of drawing,
+//!   for tests.
+//! - **`SoftwareRenderer`**: A backend that rasterizes into an RGBA framebuffer on the
+//!   CPU, producing the same pixels on every platform.
+//! - **`GdiRenderer`**: A Windows-only backend that draws with GDI, for machines where
+//!   Direct2D can't be initialized.
+//! - **`WgpuRenderer`**: An experimental backend that draws with wgpu on the GPU,
+//!   behind the `wgpu` feature.
+
+mod font;
+#[cfg(windows)]
+pub mod gdi;
+#[cfg(feature = "wgpu")]
+pub mod gpu;
+pub mod recording;
+pub mod software;
+
+#[cfg(windows)]
+pub use gdi::GdiRenderer;
+#[cfg(feature = "wgpu")]
+pub use gpu::{WgpuError, WgpuRenderer};
+pub use recording::RecordingRenderer;
+pub use software::SoftwareRenderer;
+
+use windows_numerics::{Matrix3x2, Vector2};
+
+use crate::core::render::color::Color;
+use crate::core::render::rect::Rect;
+
+/// How a shape is painted: filled with a color, or outlined with a stroke.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Paint {
+    /// The interior of the shape is filled with the color.
+    Fill(Color),
+    /// The outline of the shape is stroked with the color, centered on its edge.
+    Stroke {
+        /// The stroke color.
+        color: Color,
+        /// The width of the stroke.
+        width: f32,
+    },
+}
+
+/// One call to a `Renderer`.
+///
+/// Colors are recorded as passed, before the current opacity is applied, and
+/// coordinates are recorded before the current transform is applied.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DrawCommand {
+    /// The whole target was cleared to the color, ignoring clips and transforms.
+    ClearColor(Color),
+    /// An axis-aligned rectangle was drawn.
+    Rect {
+        /// The rectangle.
+        rect: Rect,
+        /// How the rectangle was painted.
+        paint: Paint,
+    },
+    /// An ellipse was drawn.
+    Ellipse {
+        /// The center of the ellipse.
+        center: Vector2,
+        /// The horizontal and vertical radii.
+        radius: Vector2,
+        /// How the ellipse was painted.
+        paint: Paint,
+    },
+    /// A straight line was drawn.
+    Line {
+        /// The start point.
+        start: Vector2,
+        /// The end point.
+        end: Vector2,
+        /// The stroke color.
+        color: Color,
+        /// The width of the stroke.
+        width: f32,
+    },
+    /// A single line of text was drawn.
+    Text {
+        /// The text.
+        text: String,
+        /// The top-left corner of the text.
+        origin: Vector2,
+        /// The text color.
+        color: Color,
+    },
+    /// Drawing was clipped to the rectangle, in the coordinates of the current
+    /// transform, until the matching `PopClip`.
+    PushClip(Rect),
+    /// The innermost clip was removed.
+    PopClip,
+    /// The transform applied to everything drawn after it was replaced.
+    SetTransform(Matrix3x2),
+    /// The opacity applied to everything drawn after it was replaced.
+    SetOpacity(f32),
+}
+
+/// A target that shapes and text can be drawn to, independent of the platform's
+/// graphics API.
+///
+/// A renderer keeps a current transform and opacity, which apply to everything drawn
+/// until they are changed, and a stack of clip rectangles. Callers that change the
+/// transform or opacity restore the previous value when they are done, usually with
+/// `with_transform` and `with_opacity`, and pop every clip they push, usually with
+/// `with_clip`.
+pub trait Renderer {
+    /// Fills the whole target with `color`, ignoring the clips and the transform.
+    fn clear(&mut self, color: Color);
+
+    /// Draws an axis-aligned rectangle, in the coordinates of the current transform.
+    fn draw_rect(&mut self, rect: Rect, paint: Paint);
+
+    /// Draws an ellipse with the given center and radii.
+    fn draw_ellipse(&mut self, center: Vector2, radius: Vector2, paint: Paint);
+
+    /// Draws a straight line from `start` to `end`.
+    fn draw_line(&mut self, start: Vector2, end: Vector2, color: Color, width: f32);
+
+    /// Draws a single line of text with its top-left corner at `origin`, in the
+    /// backend's default font.
+    fn draw_text(&mut self, text: &str, origin: Vector2, color: Color);
+
+    /// Clips everything drawn until the matching `pop_clip` to `rect`, in the
+    /// coordinates of the current transform, and to any clips already pushed.
+    fn push_clip(&mut self, rect: Rect);
+
+    /// Removes the innermost clip.
+    fn pop_clip(&mut self);
+
+    /// Returns the current transform.
+    fn transform(&self) -> Matrix3x2;
+
+    /// Replaces the current transform.
+    fn set_transform(&mut self, transform: Matrix3x2);
+
+    /// Returns the current opacity, from `0.0` (transparent) to `1.0` (opaque).
+    fn opacity(&self) -> f32;
+
+    /// Replaces the current opacity, which multiplies the alpha of every color drawn.
+    fn set_opacity(&mut self, opacity: f32);
+}
+
+/// Returns the four bands a stroke of `width` around `rect` is made of, centered on its
+/// edge: the top and bottom bands span the full width, and the sides fit between them,
+/// so the bands meet without overlapping. Bands of a stroke too wide for the rectangle
+/// have a negative size and contain nothing.
+///
+/// It is used by the backends that draw strokes as filled rectangles.
+pub(crate) fn stroke_bands(rect: Rect, width: f32) -> [Rect; 4] {
+    if width <= 0.0 {
+        return [Rect::default(); 4];
+    }
+    let outer = rect.inflate(width / 2.0);
+    let side_height = outer.height - width * 2.0;
+    [
+        Rect::new(outer.x, outer.y, outer.width, width),
+        Rect::new(outer.x, outer.bottom() - width, outer.width, width),
+        Rect::new(outer.x, outer.y + width, width, side_height),
+        Rect::new(outer.right() - width, outer.y + width, width, side_height),
+    ]
+}
+
+impl dyn Renderer + '_ {
+    /// Runs `f` with `local` composed onto the current transform, restoring the
+    /// current transform afterwards.
+    ///
+    /// As with `DrawingContext::with_transform`, `local` is applied before the current
+    /// transform.
+    pub fn with_transform<R>(
+        &mut self,
+        local: &Matrix3x2,
+        f: impl FnOnce(&mut dyn Renderer) -> R,
+    ) -> R {
+        let original = self.transform();
+        self.set_transform(*local * original);
+        let result = f(self);
+        self.set_transform(original);
+        result
+    }
+
+    /// Runs `f` with drawing clipped to `rect`, popping the clip afterwards.
+    pub fn with_clip<R>(&mut self, rect: Rect, f: impl FnOnce(&mut dyn Renderer) -> R) -> R {
+        self.push_clip(rect);
+        let result = f(self);
+        self.pop_clip();
+        result
+    }
+
+    /// Runs `f` with the current opacity multiplied by `opacity`, restoring the current
+    /// opacity afterwards.
+    ///
+    /// An opacity of `1.0` leaves the renderer unchanged.
+    pub fn with_opacity<R>(&mut self, opacity: f32, f: impl FnOnce(&mut dyn Renderer) -> R) -> R {
+        if opacity >= 1.0 {
+            return f(self);
+        }
+        let original = self.opacity();
+        self.set_opacity(original * opacity.max(0.0));
+        let result = f(self);
+        self.set_opacity(original);
+        result
+    }
+}
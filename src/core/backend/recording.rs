@@ -0,0 +1,229 @@
+use windows_numerics::{Matrix3x2, Vector2};
+
+use crate::core::backend::{DrawCommand, Paint, Renderer};
+use crate::core::render::color::Color;
+use crate::core::render::rect::Rect;
+
+/// A `Renderer` that records the calls made to it as `DrawCommand`s instead of
+/// drawing anything.
+///
+/// It is meant for tests: render a scene to it, then check the commands, their order,
+/// and the transforms and clips in effect. It also counts the clips still pushed, so
+/// tests can check that every push was matched by a pop.
+///
+/// # Examples
+///
+/// ```rust
+/// use my_gui::core::backend::{DrawCommand, Paint, RecordingRenderer, Renderer};
+/// use my_gui::core::render::{color::Color, rect::Rect};
+///
+/// let mut renderer = RecordingRenderer::new();
+/// renderer.draw_rect(Rect::new(0.0, 0.0, 10.0, 10.0), Paint::Fill(Color::RED));
+/// assert_eq!(
+///     renderer.commands(),
+///     [DrawCommand::Rect {
+///         rect: Rect::new(0.0, 0.0, 10.0, 10.0),
+///         paint: Paint::Fill(Color::RED),
+///     }]
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct RecordingRenderer {
+    /// The commands recorded so far, in order.
+    commands: Vec<DrawCommand>,
+    /// The current transform.
+    transform: Matrix3x2,
+    /// The current opacity.
+    opacity: f32,
+    /// The number of clips pushed and not yet popped.
+    clip_depth: usize,
+}
+
+impl RecordingRenderer {
+    /// Creates a new `RecordingRenderer` with no commands, the identity transform, and
+    /// full opacity.
+    pub fn new() -> Self {
+        Self {
+            commands: Vec::new(),
+            transform: Matrix3x2::identity(),
+            opacity: 1.0,
+            clip_depth: 0,
+        }
+    }
+
+    /// Returns the commands recorded so far, in order.
+    pub fn commands(&self) -> &[DrawCommand] {
+        &self.commands
+    }
+
+    /// Removes and returns the commands recorded so far, keeping the current transform,
+    /// opacity, and clips.
+    pub fn take_commands(&mut self) -> Vec<DrawCommand> {
+        std::mem::take(&mut self.commands)
+    }
+
+    /// Returns the number of clips pushed and not yet popped.
+    pub fn clip_depth(&self) -> usize {
+        self.clip_depth
+    }
+
+    /// Returns each drawing command (shapes and text) with the transform that was
+    /// current when it was recorded.
+    ///
+    /// Clears and the commands that change the state of the renderer are skipped.
+    pub fn drawn(&self) -> Vec<(Matrix3x2, &DrawCommand)> {
+        let mut transform = Matrix3x2::identity();
+        let mut drawn = Vec::new();
+        for command in &self.commands {
+            match command {
+                DrawCommand::SetTransform(matrix) => transform = *matrix,
+                DrawCommand::ClearColor(_)
+                | DrawCommand::PushClip(_)
+                | DrawCommand::PopClip
+                | DrawCommand::SetOpacity(_) => {}
+                _ => drawn.push((transform, command)),
+            }
+        }
+        drawn
+    }
+}
+
+impl Default for RecordingRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Renderer for RecordingRenderer {
+    fn clear(&mut self, color: Color) {
+        self.commands.push(DrawCommand::ClearColor(color));
+    }
+
+    fn draw_rect(&mut self, rect: Rect, paint: Paint) {
+        self.commands.push(DrawCommand::Rect { rect, paint });
+    }
+
+    fn draw_ellipse(&mut self, center: Vector2, radius: Vector2, paint: Paint) {
+        self.commands.push(DrawCommand::Ellipse {
+            center,
+            radius,
+            paint,
+        });
+    }
+
+    fn draw_line(&mut self, start: Vector2, end: Vector2, color: Color, width: f32) {
+        self.commands.push(DrawCommand::Line {
+            start,
+            end,
+            color,
+            width,
+        });
+    }
+
+    fn draw_text(&mut self, text: &str, origin: Vector2, color: Color) {
+        self.commands.push(DrawCommand::Text {
+            text: text.to_string(),
+            origin,
+            color,
+        });
+    }
+
+    fn push_clip(&mut self, rect: Rect) {
+        self.clip_depth += 1;
+        self.commands.push(DrawCommand::PushClip(rect));
+    }
+
+    /// Records the pop even without a matching push, so tests can detect it, but
+    /// leaves the clip depth at zero.
+    fn pop_clip(&mut self) {
+        self.clip_depth = self.clip_depth.saturating_sub(1);
+        self.commands.push(DrawCommand::PopClip);
+    }
+
+    fn transform(&self) -> Matrix3x2 {
+        self.transform
+    }
+
+    fn set_transform(&mut self, transform: Matrix3x2) {
+        self.transform = transform;
+        self.commands.push(DrawCommand::SetTransform(transform));
+    }
+
+    fn opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity;
+        self.commands.push(DrawCommand::SetOpacity(opacity));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn helpers_restore_the_transform_and_opacity_and_pop_their_clips() {
+        let mut recorder = RecordingRenderer::new();
+        let renderer: &mut dyn Renderer = &mut recorder;
+        renderer.with_transform(&Matrix3x2::translation(5.0, 5.0), |renderer| {
+            renderer.with_transform(&Matrix3x2::translation(1.0, 2.0), |renderer| {
+                assert_eq!(renderer.transform(), Matrix3x2::translation(6.0, 7.0));
+            });
+            renderer.with_clip(Rect::new(0.0, 0.0, 4.0, 4.0), |renderer| {
+                renderer.with_opacity(0.5, |renderer| {
+                    renderer.with_opacity(0.5, |renderer| assert_eq!(renderer.opacity(), 0.25));
+                });
+            });
+        });
+
+        assert_eq!(recorder.transform(), Matrix3x2::identity());
+        assert_eq!(recorder.opacity(), 1.0);
+        assert_eq!(recorder.clip_depth(), 0);
+        assert_eq!(
+            recorder.commands(),
+            [
+                DrawCommand::SetTransform(Matrix3x2::translation(5.0, 5.0)),
+                DrawCommand::SetTransform(Matrix3x2::translation(6.0, 7.0)),
+                DrawCommand::SetTransform(Matrix3x2::translation(5.0, 5.0)),
+                DrawCommand::PushClip(Rect::new(0.0, 0.0, 4.0, 4.0)),
+                DrawCommand::SetOpacity(0.5),
+                DrawCommand::SetOpacity(0.25),
+                DrawCommand::SetOpacity(0.5),
+                DrawCommand::SetOpacity(1.0),
+                DrawCommand::PopClip,
+                DrawCommand::SetTransform(Matrix3x2::identity()),
+            ]
+        );
+    }
+
+    #[test]
+    fn drawn_commands_carry_the_transform_they_were_drawn_with() {
+        let mut recorder = RecordingRenderer::new();
+        recorder.clear(Color::BLACK);
+        recorder.draw_text("a", Vector2::default(), Color::WHITE);
+        recorder.set_transform(Matrix3x2::translation(3.0, 4.0));
+        recorder.draw_line(
+            Vector2::default(),
+            Vector2 { X: 1.0, Y: 0.0 },
+            Color::RED,
+            1.0,
+        );
+
+        let drawn = recorder.drawn();
+        assert_eq!(drawn.len(), 2);
+        assert_eq!(drawn[0].0, Matrix3x2::identity());
+        assert_eq!(drawn[1].0, Matrix3x2::translation(3.0, 4.0));
+        assert!(matches!(drawn[1].1, DrawCommand::Line { .. }));
+    }
+
+    #[test]
+    fn unmatched_pops_are_recorded_without_underflowing() {
+        let mut recorder = RecordingRenderer::new();
+        recorder.pop_clip();
+        assert_eq!(recorder.clip_depth(), 0);
+        assert_eq!(recorder.take_commands(), [DrawCommand::PopClip]);
+        assert!(recorder.commands().is_empty());
+    }
+}
@@ -0,0 +1,593 @@
+use windows_numerics::{Matrix3x2, Vector2};
+
+use crate::core::backend::font::{self, GLYPH_HEIGHT, GLYPH_WIDTH};
+use crate::core::backend::{Paint, Renderer, stroke_bands};
+use crate::core::render::color::Color;
+use crate::core::render::rect::Rect;
+use crate::core::render::transform::{invert, transform_point};
+
+/// The number of samples taken along each axis of a pixel a shape's edge passes
+/// through, so edges are antialiased with 16 levels of coverage.
+const SAMPLES: u32 = 4;
+
+/// A shape to rasterize, in the coordinates it was drawn in.
+#[derive(Debug, Clone, Copy)]
+enum Shape {
+    /// The inside of a rectangle.
+    Rect(Rect),
+    /// The inside of an ellipse.
+    Ellipse { center: Vector2, radius: Vector2 },
+    /// The points between two ellipses with the same center, the outline of an
+    /// ellipse.
+    Ring {
+        center: Vector2,
+        inner: Vector2,
+        outer: Vector2,
+    },
+    /// The points within `half_width` of a line segment, a line with flat caps.
+    Segment {
+        start: Vector2,
+        end: Vector2,
+        half_width: f32,
+    },
+}
+
+impl Shape {
+    /// Returns `true` if `point` lies inside the shape.
+    fn contains(&self, point: Vector2) -> bool {
+        match *self {
+            Shape::Rect(rect) => rect.contains(point),
+            Shape::Ellipse { center, radius } => in_ellipse(point, center, radius),
+            Shape::Ring {
+                center,
+                inner,
+                outer,
+            } => in_ellipse(point, center, outer) && !in_ellipse(point, center, inner),
+            Shape::Segment {
+                start,
+                end,
+                half_width,
+            } => {
+                let (dx, dy) = (end.X - start.X, end.Y - start.Y);
+                let length_squared = dx * dx + dy * dy;
+                if length_squared == 0.0 {
+                    return false;
+                }
+                let (px, py) = (point.X - start.X, point.Y - start.Y);
+                let along = (px * dx + py * dy) / length_squared;
+                let across = (px * dy - py * dx).abs() / length_squared.sqrt();
+                (0.0..=1.0).contains(&along) && across <= half_width
+            }
+        }
+    }
+
+    /// Returns `true` if the shape is convex, so a pixel whose corners all lie inside
+    /// it is covered entirely.
+    fn is_convex(&self) -> bool {
+        !matches!(self, Shape::Ring { .. })
+    }
+
+    /// Returns a rectangle containing the shape.
+    fn bounds(&self) -> Rect {
+        match *self {
+            Shape::Rect(rect) => rect,
+            Shape::Ellipse { center, radius }
+            | Shape::Ring {
+                center,
+                outer: radius,
+                ..
+            } => Rect::new(
+                center.X - radius.X,
+                center.Y - radius.Y,
+                radius.X * 2.0,
+                radius.Y * 2.0,
+            ),
+            Shape::Segment {
+                start,
+                end,
+                half_width,
+            } => Rect::from_points([start, end])
+                .unwrap_or_default()
+                .inflate(half_width),
+        }
+    }
+}
+
+/// Returns `true` if `point` lies inside the ellipse with the given center and radii.
+fn in_ellipse(point: Vector2, center: Vector2, radius: Vector2) -> bool {
+    if radius.X <= 0.0 || radius.Y <= 0.0 {
+        return false;
+    }
+    let x = (point.X - center.X) / radius.X;
+    let y = (point.Y - center.Y) / radius.Y;
+    x * x + y * y <= 1.0
+}
+
+/// A clip rectangle pushed with `push_clip`.
+#[derive(Debug, Clone, Copy)]
+struct Clip {
+    /// The clip rectangle, in the coordinates of the transform it was pushed with.
+    rect: Rect,
+    /// The inverse of the transform the clip was pushed with, or `None` if it can't be
+    /// inverted, which clips everything.
+    inverse: Option<Matrix3x2>,
+    /// A rectangle in pixels containing the clip.
+    bounds: Rect,
+}
+
+impl Clip {
+    /// Returns `true` if the point, in pixels, lies inside the clip.
+    fn contains(&self, point: Vector2) -> bool {
+        self.inverse
+            .is_some_and(|inverse| self.rect.contains(transform_point(&inverse, point)))
+    }
+}
+
+/// A `Renderer` that rasterizes on the CPU into an RGBA framebuffer, without any
+/// platform graphics API.
+///
+/// Rectangles, ellipses, and lines are drawn with antialiased edges, and text with a
+/// built-in 5×7 bitmap font covering printable ASCII, each font pixel two units
+/// across. Other characters are drawn as boxes. Transforms and clips are applied
+/// exactly as by the other backends, including rotated clips, so a scene is laid out
+/// the same way whichever backend draws it.
+///
+/// Since it has no dependencies, it produces the same pixels on every platform, which
+/// makes it suitable for golden-image tests. Windows configured with
+/// `RendererConfig::Software` draw with it.
+///
+/// # Examples
+///
+/// ```rust
+/// use my_gui::core::backend::{Paint, Renderer, SoftwareRenderer};
+/// use my_gui::core::render::{color::Color, rect::Rect};
+///
+/// let mut renderer = SoftwareRenderer::new(4, 4);
+/// renderer.clear(Color::WHITE);
+/// renderer.draw_rect(Rect::new(0.0, 0.0, 2.0, 2.0), Paint::Fill(Color::RED));
+/// assert_eq!(renderer.pixel(1, 1), [255, 0, 0, 255]);
+/// assert_eq!(renderer.pixel(2, 2), [255, 255, 255, 255]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SoftwareRenderer {
+    /// The width of the framebuffer, in pixels.
+    width: u32,
+    /// The height of the framebuffer, in pixels.
+    height: u32,
+    /// The framebuffer, as rows of RGBA8 pixels with straight alpha, top row first.
+    pixels: Vec<u8>,
+    /// The current transform.
+    transform: Matrix3x2,
+    /// The current opacity.
+    opacity: f32,
+    /// The clips pushed and not yet popped, innermost last.
+    clips: Vec<Clip>,
+}
+
+impl SoftwareRenderer {
+    /// Creates a new `SoftwareRenderer` with a transparent framebuffer of `width` by
+    /// `height` pixels, the identity transform, and full opacity.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0; width as usize * height as usize * 4],
+            transform: Matrix3x2::identity(),
+            opacity: 1.0,
+            clips: Vec::new(),
+        }
+    }
+
+    /// Returns the width of the framebuffer, in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height of the framebuffer, in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Changes the size of the framebuffer, making it transparent if the size changes.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        if (width, height) != (self.width, self.height) {
+            *self = Self {
+                transform: self.transform,
+                opacity: self.opacity,
+                clips: std::mem::take(&mut self.clips),
+                ..Self::new(width, height)
+            };
+        }
+    }
+
+    /// Returns the framebuffer as rows of RGBA8 pixels with straight alpha, top row
+    /// first, in the same layout as `Direct2DContext::render_to_bitmap`.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Returns the framebuffer, consuming the renderer.
+    pub fn into_pixels(self) -> Vec<u8> {
+        self.pixels
+    }
+
+    /// Returns the RGBA8 pixel at `(x, y)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pixel lies outside the framebuffer.
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        assert!(
+            x < self.width && y < self.height,
+            "pixel ({x}, {y}) is out of bounds"
+        );
+        let start = (y as usize * self.width as usize + x as usize) * 4;
+        [
+            self.pixels[start],
+            self.pixels[start + 1],
+            self.pixels[start + 2],
+            self.pixels[start + 3],
+        ]
+    }
+
+    /// Fills `shape` with `color`, through the current transform, opacity, and clips.
+    fn fill(&mut self, shape: Shape, color: Color) {
+        let alpha = color.a * self.opacity;
+        let Some(inverse) = invert(&self.transform) else {
+            return;
+        };
+        if alpha <= 0.0 {
+            return;
+        }
+
+        let mut area = Some(shape.bounds().transform(&self.transform));
+        for clip in &self.clips {
+            area = area.and_then(|area| area.intersection(&clip.bounds));
+        }
+        let Some(area) = area else {
+            return;
+        };
+        let columns =
+            area.x.floor().max(0.0) as u32..area.right().ceil().min(self.width as f32) as u32;
+        let rows =
+            area.y.floor().max(0.0) as u32..area.bottom().ceil().min(self.height as f32) as u32;
+
+        for y in rows {
+            for x in columns.clone() {
+                let coverage = self.coverage(&shape, &inverse, x, y);
+                if coverage > 0.0 {
+                    self.blend(x, y, color, alpha * coverage);
+                }
+            }
+        }
+    }
+
+    /// Returns the fraction of the pixel at `(x, y)` that `shape`, mapped to pixels by
+    /// the inverse of `inverse`, covers inside the clips.
+    fn coverage(&self, shape: &Shape, inverse: &Matrix3x2, x: u32, y: u32) -> f32 {
+        let inside = |point: Vector2| {
+            shape.contains(transform_point(inverse, point))
+                && self.clips.iter().all(|clip| clip.contains(point))
+        };
+        let (x, y) = (x as f32, y as f32);
+
+        // Clips are convex too, so if the shape is, a pixel whose corners are all
+        // inside is covered entirely.
+        let corners = [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (1.0, 1.0)];
+        if shape.is_convex()
+            && corners.iter().all(|(dx, dy)| {
+                inside(Vector2 {
+                    X: x + dx,
+                    Y: y + dy,
+                })
+            })
+        {
+            return 1.0;
+        }
+
+        let step = 1.0 / SAMPLES as f32;
+        let mut covered = 0;
+        for row in 0..SAMPLES {
+            for column in 0..SAMPLES {
+                let sample = Vector2 {
+                    X: x + (column as f32 + 0.5) * step,
+                    Y: y + (row as f32 + 0.5) * step,
+                };
+                if inside(sample) {
+                    covered += 1;
+                }
+            }
+        }
+        covered as f32 / (SAMPLES * SAMPLES) as f32
+    }
+
+    /// Blends `color` with the given alpha over the pixel at `(x, y)`.
+    fn blend(&mut self, x: u32, y: u32, color: Color, alpha: f32) {
+        let start = (y as usize * self.width as usize + x as usize) * 4;
+        let pixel = &mut self.pixels[start..start + 4];
+        let below = pixel[3] as f32 / 255.0;
+        let combined = alpha + below * (1.0 - alpha);
+        if combined <= 0.0 {
+            return;
+        }
+        let mix = |source: f32, destination: u8| {
+            (source * alpha + destination as f32 / 255.0 * below * (1.0 - alpha)) / combined
+        };
+        pixel[0] = to_u8(mix(color.r, pixel[0]));
+        pixel[1] = to_u8(mix(color.g, pixel[1]));
+        pixel[2] = to_u8(mix(color.b, pixel[2]));
+        pixel[3] = to_u8(combined);
+    }
+}
+
+/// Converts a channel from `0.0..=1.0` to `0..=255`.
+fn to_u8(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+impl Renderer for SoftwareRenderer {
+    fn clear(&mut self, color: Color) {
+        let pixel = [
+            to_u8(color.r),
+            to_u8(color.g),
+            to_u8(color.b),
+            to_u8(color.a),
+        ];
+        for chunk in self.pixels.chunks_exact_mut(4) {
+            chunk.copy_from_slice(&pixel);
+        }
+    }
+
+    /// Draws the rectangle, with a stroke made of four bands that meet without
+    /// overlapping, so translucent borders are even.
+    fn draw_rect(&mut self, rect: Rect, paint: Paint) {
+        match paint {
+            Paint::Fill(color) => self.fill(Shape::Rect(rect), color),
+            Paint::Stroke { color, width } => {
+                for band in stroke_bands(rect, width) {
+                    self.fill(Shape::Rect(band), color);
+                }
+            }
+        }
+    }
+
+    fn draw_ellipse(&mut self, center: Vector2, radius: Vector2, paint: Paint) {
+        match paint {
+            Paint::Fill(color) => self.fill(Shape::Ellipse { center, radius }, color),
+            Paint::Stroke { color, width } => {
+                if width <= 0.0 {
+                    return;
+                }
+                let half = width / 2.0;
+                let ring = Shape::Ring {
+                    center,
+                    inner: Vector2 {
+                        X: radius.X - half,
+                        Y: radius.Y - half,
+                    },
+                    outer: Vector2 {
+                        X: radius.X + half,
+                        Y: radius.Y + half,
+                    },
+                };
+                self.fill(ring, color);
+            }
+        }
+    }
+
+    fn draw_line(&mut self, start: Vector2, end: Vector2, color: Color, width: f32) {
+        if width <= 0.0 {
+            return;
+        }
+        let segment = Shape::Segment {
+            start,
+            end,
+            half_width: width / 2.0,
+        };
+        self.fill(segment, color);
+    }
+
+    /// Draws the text with the built-in bitmap font. Line feeds start a new line.
+    fn draw_text(&mut self, text: &str, origin: Vector2, color: Color) {
+        for (character, corner) in font::layout(text, origin) {
+            let glyph = font::glyph(character);
+            for column in 0..GLYPH_WIDTH {
+                for row in 0..GLYPH_HEIGHT {
+                    if font::is_set(&glyph, column, row) {
+                        let dot = Rect::new(
+                            corner.X + column as f32 * font::SCALE,
+                            corner.Y + row as f32 * font::SCALE,
+                            font::SCALE,
+                            font::SCALE,
+                        );
+                        self.fill(Shape::Rect(dot), color);
+                    }
+                }
+            }
+        }
+    }
+
+    fn push_clip(&mut self, rect: Rect) {
+        let inverse = invert(&self.transform);
+        let bounds = match inverse {
+            Some(_) => rect.transform(&self.transform),
+            None => Rect::default(),
+        };
+        self.clips.push(Clip {
+            rect,
+            inverse,
+            bounds,
+        });
+    }
+
+    fn pop_clip(&mut self) {
+        self.clips.pop();
+    }
+
+    fn transform(&self) -> Matrix3x2 {
+        self.transform
+    }
+
+    fn set_transform(&mut self, transform: Matrix3x2) {
+        self.transform = transform;
+    }
+
+    fn opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::render::transform::Transform;
+
+    /// Returns the framebuffer as text, one character per pixel: `.` for transparent
+    /// pixels, `R`, `G`, `B`, `W`, or `K` for opaque red, green, blue, white, or black
+    /// ones, `?` for other opaque colors, and `+` for partly transparent ones.
+    fn picture(renderer: &SoftwareRenderer) -> String {
+        let mut picture = String::new();
+        for y in 0..renderer.height() {
+            for x in 0..renderer.width() {
+                picture.push(match renderer.pixel(x, y) {
+                    [_, _, _, 0] => '.',
+                    [255, 0, 0, 255] => 'R',
+                    [0, 255, 0, 255] => 'G',
+                    [0, 0, 255, 255] => 'B',
+                    [255, 255, 255, 255] => 'W',
+                    [0, 0, 0, 255] => 'K',
+                    [_, _, _, 255] => '?',
+                    _ => '+',
+                });
+            }
+            picture.push('\n');
+        }
+        picture
+    }
+
+    /// Asserts that the framebuffer matches the golden image `name` in the `golden`
+    /// directory next to this file, printing both on mismatch. Set `UPDATE_GOLDEN` to
+    /// write the framebuffer as the new golden image instead.
+    fn assert_golden(renderer: &SoftwareRenderer, name: &str) {
+        let path = format!(
+            "{}/src/core/backend/golden/{name}.txt",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let actual = picture(renderer);
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            std::fs::write(&path, &actual).unwrap();
+            return;
+        }
+        let golden = std::fs::read_to_string(&path)
+            .unwrap()
+            .replace("\r\n", "\n");
+        assert!(
+            actual == golden,
+            "{name} doesn't match its golden image\nexpected:\n{golden}\nactual:\n{actual}"
+        );
+    }
+
+    #[test]
+    fn rectangles_are_filled_and_stroked_on_pixel_boundaries() {
+        let mut renderer = SoftwareRenderer::new(12, 9);
+        renderer.draw_rect(Rect::new(1.0, 1.0, 4.0, 3.0), Paint::Fill(Color::RED));
+        renderer.draw_rect(
+            Rect::new(6.5, 1.5, 4.0, 6.0),
+            Paint::Stroke {
+                color: Color::BLUE,
+                width: 1.0,
+            },
+        );
+        renderer.draw_rect(Rect::new(1.5, 5.0, 3.0, 3.0), Paint::Fill(Color::GREEN));
+        assert_golden(&renderer, "rectangles");
+    }
+
+    #[test]
+    fn ellipses_and_lines_are_antialiased() {
+        let mut renderer = SoftwareRenderer::new(16, 16);
+        renderer.draw_ellipse(
+            Vector2 { X: 8.0, Y: 7.0 },
+            Vector2 { X: 6.0, Y: 5.0 },
+            Paint::Fill(Color::GREEN),
+        );
+        renderer.draw_ellipse(
+            Vector2 { X: 8.0, Y: 7.0 },
+            Vector2 { X: 6.0, Y: 5.0 },
+            Paint::Stroke {
+                color: Color::WHITE,
+                width: 2.0,
+            },
+        );
+        renderer.draw_line(
+            Vector2 { X: 1.0, Y: 14.0 },
+            Vector2 { X: 15.0, Y: 14.0 },
+            Color::RED,
+            2.0,
+        );
+        renderer.draw_line(
+            Vector2 { X: 1.0, Y: 1.0 },
+            Vector2 { X: 5.0, Y: 11.0 },
+            Color::BLUE,
+            2.0,
+        );
+        assert_golden(&renderer, "ellipses_and_lines");
+    }
+
+    #[test]
+    fn text_is_drawn_with_the_bitmap_font() {
+        let mut renderer = SoftwareRenderer::new(38, 34);
+        renderer.draw_text("Hi!\n\u{e9}", Vector2 { X: 1.0, Y: 1.0 }, Color::WHITE);
+        assert_golden(&renderer, "text");
+    }
+
+    #[test]
+    fn clips_and_transforms_are_applied_like_the_other_backends() {
+        let mut framebuffer = SoftwareRenderer::new(20, 20);
+        framebuffer.clear(Color::BLACK);
+        let renderer: &mut dyn Renderer = &mut framebuffer;
+        renderer.with_transform(&Matrix3x2::translation(2.0, 2.0), |renderer| {
+            renderer.with_clip(Rect::new(0.0, 0.0, 12.0, 12.0), |renderer| {
+                let rotation = Transform::rotation(45.0).to_matrix(Vector2 { X: 8.0, Y: 8.0 });
+                renderer.with_transform(&rotation, |renderer| {
+                    renderer.with_clip(Rect::new(4.0, 4.0, 8.0, 8.0), |renderer| {
+                        let everything = Rect::new(-20.0, -20.0, 60.0, 60.0);
+                        renderer.draw_rect(everything, Paint::Fill(Color::RED));
+                    });
+                });
+            });
+            renderer.draw_rect(Rect::new(13.0, 13.0, 3.0, 3.0), Paint::Fill(Color::BLUE));
+        });
+        assert_golden(&framebuffer, "clips_and_transforms");
+    }
+
+    #[test]
+    fn opacity_blends_over_what_is_already_drawn() {
+        let mut renderer = SoftwareRenderer::new(2, 1);
+        renderer.clear(Color::RED);
+        renderer.set_opacity(0.5);
+        renderer.draw_rect(Rect::new(0.0, 0.0, 1.0, 1.0), Paint::Fill(Color::BLUE));
+        assert_eq!(renderer.pixel(0, 0), [128, 0, 128, 255]);
+        assert_eq!(renderer.pixel(1, 0), [255, 0, 0, 255]);
+
+        renderer.clear(Color::TRANSPARENT);
+        renderer.draw_rect(Rect::new(0.0, 0.0, 1.0, 1.0), Paint::Fill(Color::BLUE));
+        assert_eq!(renderer.pixel(0, 0), [0, 0, 255, 128]);
+    }
+
+    #[test]
+    fn clear_ignores_clips_and_popped_clips_stop_clipping() {
+        let mut renderer = SoftwareRenderer::new(4, 1);
+        renderer.push_clip(Rect::new(0.0, 0.0, 1.0, 1.0));
+        renderer.clear(Color::WHITE);
+        renderer.draw_rect(Rect::new(0.0, 0.0, 4.0, 1.0), Paint::Fill(Color::BLACK));
+        assert_eq!(picture(&renderer), "KWWW\n");
+
+        renderer.pop_clip();
+        renderer.draw_rect(Rect::new(0.0, 0.0, 4.0, 1.0), Paint::Fill(Color::GREEN));
+        assert_eq!(picture(&renderer), "GGGG\n");
+    }
+}
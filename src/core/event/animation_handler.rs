@@ -0,0 +1,101 @@
+use std::time::{Duration, Instant};
+
+use crate::{app::App, core::render::drawing_context::DrawingContext};
+use super::event_handler::EventHandler;
+
+/// An event handler that drives per-frame animation of the application's scene.
+///
+/// On every paint, this handler measures the time elapsed since the previous paint
+/// with a monotonic clock, advances the scene with `Scene::update_all`, and requests
+/// another redraw so the animation keeps running.
+///
+/// It must be added to the `RootEventHandler` before the `RenderEventHandler`, so the
+/// scene is updated before it is drawn.
+pub struct AnimationHandler {
+    /// The time of the previous frame, or `None` before the first frame.
+    last_frame: Option<Instant>,
+    /// The largest time step passed to `update`.
+    max_dt: Duration,
+}
+
+impl AnimationHandler {
+    /// The default largest time step, which keeps animations from jumping after the
+    /// window has been stalled (for example while being dragged).
+    const DEFAULT_MAX_DT: Duration = Duration::from_millis(100);
+
+    /// Creates a new `AnimationHandler`.
+    pub fn new() -> Self {
+        Self {
+            last_frame: None,
+            max_dt: Self::DEFAULT_MAX_DT,
+        }
+    }
+
+    /// Sets the largest time step passed to `update`. Longer gaps between frames are
+    /// clamped to this value.
+    pub fn with_max_dt(mut self, max_dt: Duration) -> Self {
+        self.max_dt = max_dt;
+        self
+    }
+}
+
+impl Default for AnimationHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventHandler for AnimationHandler {
+    /// Updates the scene by the time elapsed since the previous frame.
+    ///
+    /// The first frame is updated with a time step of zero. Frames painted again for
+    /// `Window::capture_frame` leave the scene as it is.
+    fn on_paint(&mut self, app: &mut App, _drawing_context: &DrawingContext) {
+        if app.is_capturing_frame() {
+            return;
+        }
+        let now = Instant::now();
+        let dt = self
+            .last_frame
+            .map_or(Duration::ZERO, |last| now.saturating_duration_since(last))
+            .min(self.max_dt);
+        self.last_frame = Some(now);
+
+        app.scene.update_all(dt);
+        app.request_redraw();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::render::direct2d_context::Direct2DContext;
+    use crate::core::window::config::WindowConfig;
+
+    #[test]
+    fn frames_painted_for_a_capture_leave_the_animation_alone() {
+        let context = Direct2DContext::new(&WindowConfig::default()).unwrap();
+        let mut handler = AnimationHandler::new();
+        let mut app = App::new();
+
+        app.set_capturing_frame(true);
+        context
+            .render_offscreen(1, 1, 96.0, |drawing_context| {
+                handler.on_paint(&mut app, drawing_context);
+                Ok(())
+            })
+            .unwrap();
+        assert!(!app.take_redraw_request());
+        assert_eq!(handler.last_frame, None);
+
+        app.set_capturing_frame(false);
+        context
+            .render_offscreen(1, 1, 96.0, |drawing_context| {
+                handler.on_paint(&mut app, drawing_context);
+                Ok(())
+            })
+            .unwrap();
+        assert!(app.take_redraw_request());
+        assert!(handler.last_frame.is_some());
+    }
+}
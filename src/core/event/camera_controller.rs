@@ -0,0 +1,142 @@
+use windows_numerics::Vector2;
+
+use crate::app::App;
+use super::event_handler::EventHandler;
+use super::mouse_button::MouseButton;
+
+/// An event handler that pans and zooms the scene's `Camera` with the mouse.
+///
+/// Dragging with the middle button held pans the view, so the world follows the
+/// cursor. The mouse is captured during the drag, so it continues outside the window. Rotating the wheel zooms in or out by `zoom_step` per notch, keeping the
+/// point under the cursor in place. The zoom is kept between `min_zoom` and
+/// `max_zoom`.
+///
+/// Other handlers receive mouse events in screen coordinates as before. Use
+/// `Scene::screen_to_world` to convert them, or `Scene::hit_test`, which does so
+/// itself.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use my_gui::core::event::{
+///     camera_controller::CameraController, render_event_handler::RenderEventHandler,
+///     root_event_handler::RootEventHandler,
+/// };
+///
+/// let mut event_handler = RootEventHandler::new();
+/// event_handler.add_handler(Box::new(CameraController::new().with_zoom_range(0.25, 8.0)));
+/// event_handler.add_handler(Box::new(RenderEventHandler::new()));
+/// ```
+pub struct CameraController {
+    /// How much one notch of the wheel zooms by, such as 1.1 for 10%.
+    pub zoom_step: f32,
+    /// The smallest zoom factor.
+    pub min_zoom: f32,
+    /// The largest zoom factor.
+    pub max_zoom: f32,
+    /// While the view is dragged, the last cursor position.
+    drag: Option<Vector2>,
+}
+
+impl CameraController {
+    /// The wheel delta of one notch of a standard wheel (`WHEEL_DELTA`).
+    const WHEEL_DELTA: f32 = 120.0;
+
+    /// Creates a new `CameraController` that zooms by 10% per notch, between a tenth
+    /// and ten times the actual size.
+    pub fn new() -> Self {
+        Self {
+            zoom_step: 1.1,
+            min_zoom: 0.1,
+            max_zoom: 10.0,
+            drag: None,
+        }
+    }
+
+    /// Sets how much one notch of the wheel zooms by.
+    pub fn with_zoom_step(mut self, zoom_step: f32) -> Self {
+        self.zoom_step = zoom_step;
+        self
+    }
+
+    /// Sets the smallest and largest zoom factors.
+    pub fn with_zoom_range(mut self, min_zoom: f32, max_zoom: f32) -> Self {
+        self.min_zoom = min_zoom;
+        self.max_zoom = max_zoom;
+        self
+    }
+
+    /// Returns `true` while the view is being dragged.
+    pub fn is_panning(&self) -> bool {
+        self.drag.is_some()
+    }
+}
+
+impl Default for CameraController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventHandler for CameraController {
+    /// Pans the view while the middle button is held, or stops panning if it was
+    /// released without the handler seeing it.
+    fn on_mouse_move(&mut self, app: &mut App, x: i32, y: i32) {
+        let Some(last) = self.drag else {
+            return;
+        };
+        if !app.is_mouse_button_down(MouseButton::Middle) {
+            self.drag = None;
+            app.release_mouse();
+            return;
+        }
+        let point = Vector2 {
+            X: x as f32,
+            Y: y as f32,
+        };
+        app.scene
+            .camera_mut()
+            .pan_by(point.X - last.X, point.Y - last.Y);
+        self.drag = Some(point);
+        app.request_redraw();
+    }
+
+    /// Starts panning the view and captures the mouse.
+    fn on_mbutton_down(&mut self, app: &mut App, x: i32, y: i32) {
+        self.drag = Some(Vector2 {
+            X: x as f32,
+            Y: y as f32,
+        });
+        app.capture_mouse();
+    }
+
+    /// Stops panning the view and releases the mouse capture.
+    fn on_mbutton_up(&mut self, app: &mut App, _x: i32, _y: i32) {
+        if self.drag.take().is_some() {
+            app.release_mouse();
+        }
+    }
+
+    /// Stops panning the view when another window takes the mouse capture.
+    fn on_capture_lost(&mut self, _app: &mut App) {
+        self.drag = None;
+    }
+
+    /// Zooms in or out, keeping the point under the cursor in place.
+    fn on_mouse_wheel(&mut self, app: &mut App, x: i32, y: i32, delta: i32) {
+        let camera = app.scene.camera_mut();
+        let zoom = (camera.zoom * self.zoom_step.powf(delta as f32 / Self::WHEEL_DELTA))
+            .clamp(self.min_zoom, self.max_zoom);
+        if zoom == camera.zoom {
+            return;
+        }
+        camera.zoom_at(
+            Vector2 {
+                X: x as f32,
+                Y: y as f32,
+            },
+            zoom,
+        );
+        app.request_redraw();
+    }
+}
@@ -0,0 +1,179 @@
+use windows::Win32::Graphics::Direct2D::Common::D2D_RECT_F;
+
+use crate::{
+    app::App,
+    core::render::{
+        color::Color,
+        drawable::Drawable,
+        drawing_context::DrawingContext,
+        objects::{
+            canvas::Canvas,
+            group::Group,
+            stack::{HStack, VStack, slot_child},
+            text_object::TextObject,
+        },
+        rect::Rect,
+        scene::{Layer, SceneLayer},
+    },
+};
+use super::{event_handler::EventHandler, key_id::KeyId};
+
+/// An event handler that outlines the bounds of every object in the scene, for
+/// debugging layout.
+///
+/// The overlay is off until the toggle key, F12 by default, is pressed. While it is
+/// on, every paint strokes each object's `Drawable::bounds` in one color and the
+/// rectangle of each `Canvas` in another, including the children of canvases,
+/// stacks, and groups, and shows the number of objects in the top-left corner.
+///
+/// It must be added to the `RootEventHandler` after the `RenderEventHandler`, so the
+/// outlines are drawn on top of the scene. The overlay is drawn straight to the render
+/// target, so the scene itself is never changed.
+pub struct DebugOverlayHandler {
+    /// Whether the overlay is shown.
+    enabled: bool,
+    /// The key that shows and hides the overlay.
+    toggle_key: KeyId,
+    /// The color of the object bounds and the object count.
+    bounds_color: Color,
+    /// The color of the canvas rectangles.
+    canvas_color: Color,
+}
+
+impl DebugOverlayHandler {
+    /// The distance between the object count and the edges of the window.
+    const MARGIN: f32 = 10.0;
+
+    /// Creates a new, hidden `DebugOverlayHandler` toggled with F12, which outlines
+    /// objects in magenta and canvases in cyan.
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            toggle_key: KeyId::F12,
+            bounds_color: Color::new(1.0, 0.0, 1.0, 1.0),
+            canvas_color: Color::new(0.0, 1.0, 1.0, 1.0),
+        }
+    }
+
+    /// Sets the key that shows and hides the overlay.
+    pub fn with_toggle_key(mut self, key: KeyId) -> Self {
+        self.toggle_key = key;
+        self
+    }
+
+    /// Sets the colors of the object bounds and the canvas rectangles.
+    pub fn with_colors(mut self, bounds_color: Color, canvas_color: Color) -> Self {
+        self.bounds_color = bounds_color;
+        self.canvas_color = canvas_color;
+        self
+    }
+
+    /// Returns `true` if the overlay is shown.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Shows or hides the overlay.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Outlines every object of a layer, recursing into containers.
+    fn outline_layer(&self, context: &DrawingContext, layer: &SceneLayer) {
+        for (_, object) in layer.iter() {
+            self.outline_object(context, object);
+        }
+    }
+
+    /// Outlines an object and, if it is a container, its children in their local
+    /// coordinates.
+    fn outline_object(&self, context: &DrawingContext, object: &dyn Drawable) {
+        // The children of stacks are wrapped in slots that only move them into place.
+        if let Some((offset, child)) = slot_child(object) {
+            context.with_transform(&offset, || self.outline_object(context, child));
+            return;
+        }
+
+        if let Some(bounds) = object.bounds() {
+            self.stroke(context, &bounds, &self.bounds_color);
+        }
+
+        let object = object.as_any();
+        let canvas = object.downcast_ref::<Canvas>().or_else(|| {
+            object
+                .downcast_ref::<VStack>()
+                .map(VStack::canvas)
+                .or_else(|| object.downcast_ref::<HStack>().map(HStack::canvas))
+        });
+        if let Some(canvas) = canvas {
+            context.with_transform(&canvas.frame_transform(), || {
+                self.stroke(context, &canvas.rect, &self.canvas_color);
+            });
+            context.with_transform(&canvas.local_transform(), || {
+                self.outline_layer(context, canvas.children());
+            });
+        } else if let Some(group) = object.downcast_ref::<Group>() {
+            context.with_transform(&group.local_transform(), || {
+                self.outline_layer(context, group.children());
+            });
+        }
+    }
+
+    /// Strokes a one DIP outline around `rect`.
+    ///
+    /// # Safety
+    ///
+    /// This function contains an `unsafe` block for calling a Direct2D drawing method.
+    /// The caller must ensure that the `drawing_context` contains valid Direct2D
+    /// resources.
+    fn stroke(&self, context: &DrawingContext, rect: &Rect, color: &Color) {
+        let rect: D2D_RECT_F = (*rect).into();
+        context.with_brush_color(color, |brush| unsafe {
+            context.render_target.DrawRectangle(&rect, brush, 1.0, None);
+        });
+    }
+}
+
+impl Default for DebugOverlayHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventHandler for DebugOverlayHandler {
+    /// Draws the outlines and the object count on top of the scene while the overlay is
+    /// shown.
+    fn on_paint(&mut self, app: &mut App, drawing_context: &DrawingContext) {
+        if !self.enabled {
+            return;
+        }
+        let view = app.scene.camera().view_matrix();
+        for layer in Layer::ALL {
+            if layer.follows_camera() {
+                drawing_context.with_transform(&view, || {
+                    self.outline_layer(drawing_context, app.scene.layer(layer));
+                });
+            } else {
+                self.outline_layer(drawing_context, app.scene.layer(layer));
+            }
+        }
+
+        let count = TextObject::new(
+            &format!("Objects: {}", app.scene.len()),
+            Self::MARGIN,
+            Self::MARGIN,
+        )
+        .with_color(self.bounds_color);
+        if let Err(e) = count.draw(drawing_context) {
+            println!("Failed to draw the debug overlay: {:?}", e);
+        }
+    }
+
+    /// Shows or hides the overlay when the toggle key is pressed.
+    fn on_key_down(&mut self, app: &mut App, key: KeyId) {
+        if key == self.toggle_key {
+            self.enabled = !self.enabled;
+            app.request_redraw();
+        }
+    }
+}
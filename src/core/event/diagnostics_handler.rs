@@ -0,0 +1,97 @@
+use crate::{
+    app::App,
+    core::render::{
+        color::Color,
+        drawing_context::DrawingContext,
+        objects::text_object::{ParagraphAlignment, TextObject},
+        scene::{Layer, ObjectId, SceneStats},
+    },
+};
+use super::event_handler::EventHandler;
+
+/// An event handler that shows the scene's statistics in the bottom-left corner of the
+/// window.
+///
+/// On every paint, this handler reads `Scene::stats` and writes the object count and
+/// the time taken by the previous `Scene::draw_all` into a `TextObject` in the scene's
+/// overlay layer. It enables draw timing on the scene the first time it runs.
+///
+/// It must be added to the `RootEventHandler` before the `RenderEventHandler`, so the
+/// text is updated before the scene is drawn. The text itself is counted as one of
+/// the scene's objects.
+pub struct DiagnosticsHandler {
+    /// The text showing the statistics, or `None` before the first paint.
+    text: Option<ObjectId>,
+    /// The color of the text.
+    color: Color,
+}
+
+impl DiagnosticsHandler {
+    /// The distance between the text and the edges of the window.
+    const MARGIN: f32 = 10.0;
+
+    /// Creates a new `DiagnosticsHandler` that shows yellow text.
+    pub fn new() -> Self {
+        Self {
+            text: None,
+            color: Color::YELLOW,
+        }
+    }
+
+    /// Sets the color of the text.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Formats the statistics for display.
+    fn format(stats: &SceneStats) -> String {
+        match stats.draw_time {
+            Some(draw_time) => format!(
+                "Objects: {}\nDraw: {:.2} ms",
+                stats.object_count,
+                draw_time.as_secs_f64() * 1000.0
+            ),
+            None => format!("Objects: {}\nDraw: -", stats.object_count),
+        }
+    }
+}
+
+impl Default for DiagnosticsHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventHandler for DiagnosticsHandler {
+    /// Updates the statistics text, adding it to the overlay layer on the first paint
+    /// or if it was removed from the scene.
+    ///
+    /// Frames painted again for `Window::capture_frame` keep the statistics of the
+    /// frame shown.
+    fn on_paint(&mut self, app: &mut App, _drawing_context: &DrawingContext) {
+        if app.is_capturing_frame() {
+            return;
+        }
+        let text = Self::format(&app.scene.stats());
+        let existing = self
+            .text
+            .and_then(|id| app.scene.get_object_mut::<TextObject>(id));
+        match existing {
+            Some(object) => object.set_text(text),
+            None => {
+                app.scene.set_draw_timing(true);
+                // The layout box extends to the bottom of the window, so aligning the
+                // text to its far edge keeps it in the corner as the window is resized.
+                let object = TextObject::new(&text, Self::MARGIN, -Self::MARGIN)
+                    .with_paragraph_alignment(ParagraphAlignment::Far)
+                    .with_color(self.color);
+                self.text = Some(
+                    app.scene
+                        .layer_mut(Layer::Overlay)
+                        .add_object(Box::new(object)),
+                );
+            }
+        }
+    }
+}
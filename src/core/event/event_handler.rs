@@ -11,14 +11,21 @@ use super::key_id::KeyId;
 ///
 /// Implementors of this trait can be composed to create more complex event
 /// handling logic (see `RootEventHandler`).
+///
+/// Sizes and positions are in DIPs, the same units drawables are positioned in, so
+/// hit testing works at any display scale.
 pub trait EventHandler {
     /// Called when the window needs to be repainted (in response to `WM_PAINT`).
+    ///
+    /// The render target's frame is open for the whole call, between `BeginDraw` and
+    /// `EndDraw`, so handlers can draw with `drawing_context` directly.
     fn on_paint(&mut self, _app: &mut App, _drawing_context: &DrawingContext) {}
 
     /// Called when the window is being destroyed (in response to `WM_DESTROY`).
     fn on_destroy(&mut self, _app: &mut App) {}
 
-    /// Called when the window is resized (in response to `WM_SIZE`).
+    /// Called when the window is resized (in response to `WM_SIZE`), with the new size
+    /// of the client area.
     fn on_resize(&mut self, _app: &mut App, _width: i32, _height: i32) {}
 
     /// Called when the mouse moves over the window client area.
@@ -30,12 +37,47 @@ pub trait EventHandler {
     /// Called when the left mouse button is released.
     fn on_lbutton_up(&mut self, _app: &mut App, _x: i32, _y: i32) {}
 
+    /// Called when the middle mouse button is pressed.
+    fn on_mbutton_down(&mut self, _app: &mut App, _x: i32, _y: i32) {}
+
+    /// Called when the middle mouse button is released.
+    fn on_mbutton_up(&mut self, _app: &mut App, _x: i32, _y: i32) {}
+
+    /// Called when the mouse wheel is rotated over the window.
+    ///
+    /// `x` and `y` are the cursor position in client coordinates. `delta` is positive
+    /// when the wheel is rotated away from the user, in multiples of 120 (`WHEEL_DELTA`)
+    /// for a standard notched wheel.
+    fn on_mouse_wheel(&mut self, _app: &mut App, _x: i32, _y: i32, _delta: i32) {}
+
+    /// Called when the window loses the mouse capture taken with `App::capture_mouse`,
+    /// whether it was released or taken by another window (`WM_CAPTURECHANGED`).
+    ///
+    /// Handlers tracking a drag should end it here, as the button-up message may never
+    /// arrive.
+    fn on_capture_lost(&mut self, _app: &mut App) {}
+
     /// Called when a non-system key is pressed.
     fn on_key_down(&mut self, _app: &mut App, _key: KeyId) {}
 
     /// Called when a non-system key is released.
     fn on_key_up(&mut self, _app: &mut App, _key: KeyId) {}
 
+    /// Called when a timer started with `App::set_timer` fires (in response to
+    /// `WM_TIMER`).
+    fn on_timer(&mut self, _app: &mut App, _timer_id: usize) {}
+
+    /// Called when the rendering device was lost, such as after a driver update or
+    /// when the GPU was reset, and its resources were released.
+    ///
+    /// Bitmaps, layouts, and anything else derived from the render target are stale
+    /// from now on, so handlers that cache them should drop their caches.
+    fn on_device_lost(&mut self, _app: &mut App) {}
+
+    /// Called when the device-dependent resources were recreated after the device was
+    /// lost, just before the window is painted with them.
+    fn on_device_restored(&mut self, _app: &mut App) {}
+
     /// A catch-all method for handling any other window messages.
     ///
     /// If this method handles the message, it should return `Some(result)`.
@@ -14,7 +14,7 @@ pub enum KeyId {
     Up, Down, Left, Right,
 
     // Other keys
-    Space, Enter, Escape, Backspace, Tab, Shift, Control, Alt,
+    Space, Enter, Escape, Backspace, Tab, Shift, Control, Alt, PrintScreen,
 
     /// An unknown key with the given virtual key code.
     Unknown(u16),
@@ -73,6 +73,7 @@ impl KeyId {
             // Other keys
             0x20 => KeyId::Space, 0x0D => KeyId::Enter, 0x1B => KeyId::Escape, 0x08 => KeyId::Backspace,
             0x09 => KeyId::Tab, 0x10 => KeyId::Shift, 0x11 => KeyId::Control, 0x12 => KeyId::Alt,
+            0x2C => KeyId::PrintScreen,
 
             _ => KeyId::Unknown(vkey),
         }
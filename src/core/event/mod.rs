@@ -4,7 +4,15 @@
 //! `EventHandler` trait, which provides a structured way to respond to window
 //! messages, and the `RootEventHandler`, which composes multiple event handlers.
 
+pub mod animation_handler;
+pub mod camera_controller;
+pub mod debug_overlay_handler;
+pub mod diagnostics_handler;
 pub mod event_handler;
 pub mod key_id;
+pub mod mouse_button;
 pub mod render_event_handler;
-pub mod root_event_handler;
\ No newline at end of file
+pub mod root_event_handler;
+#[cfg(feature = "serde")]
+pub mod scene_watcher;
+pub mod scroll_bar_handler;
\ No newline at end of file
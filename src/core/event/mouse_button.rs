@@ -0,0 +1,40 @@
+/// Represents a button on the mouse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+}
+
+impl MouseButton {
+    /// Returns `true` if the button is pressed in `key_state`, the `MK_*` flags in the
+    /// `wparam` of a mouse message.
+    pub fn is_down_in(self, key_state: usize) -> bool {
+        const MK_LBUTTON: usize = 0x0001;
+        const MK_RBUTTON: usize = 0x0002;
+        const MK_MBUTTON: usize = 0x0010;
+
+        let flag = match self {
+            MouseButton::Left => MK_LBUTTON,
+            MouseButton::Right => MK_RBUTTON,
+            MouseButton::Middle => MK_MBUTTON,
+        };
+        key_state & flag != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_down_in_reads_each_buttons_flag() {
+        // MK_LBUTTON | MK_SHIFT | MK_MBUTTON
+        let key_state = 0x0001 | 0x0004 | 0x0010;
+
+        assert!(MouseButton::Left.is_down_in(key_state));
+        assert!(MouseButton::Middle.is_down_in(key_state));
+        assert!(!MouseButton::Right.is_down_in(key_state));
+        assert!(!MouseButton::Left.is_down_in(0));
+    }
+}
@@ -3,8 +3,8 @@ use super::event_handler::EventHandler;
 
 /// An event handler that is responsible for rendering the application's scene.
 ///
-/// This handler implements the `on_paint` method to draw the contents of the
-/// `App`'s `Scene` to the window.
+/// This handler implements the `on_paint` method to clear the window to its
+/// background color and draw the contents of the `App`'s `Scene` to it.
 pub struct RenderEventHandler;
 
 impl RenderEventHandler {
@@ -20,10 +20,14 @@ impl Default for RenderEventHandler {
     }
 }
 
-use windows::Win32::Graphics::Direct2D::{Common::D2D1_COLOR_F, ID2D1RenderTarget};
+use windows::Win32::Graphics::Direct2D::ID2D1RenderTarget;
 
 impl EventHandler for RenderEventHandler {
-    /// Handles the `WM_PAINT` message by clearing the render target and drawing the scene.
+    /// Handles the `WM_PAINT` message by clearing the render target to
+    /// `WindowConfig::background_color` and drawing the scene.
+    ///
+    /// The window opens the frame before `on_paint` and presents it afterwards, so
+    /// handlers added after this one draw on top of the scene.
     ///
     /// # Safety
     ///
@@ -32,17 +36,12 @@ impl EventHandler for RenderEventHandler {
     /// resources.
     fn on_paint(&mut self, app: &mut App, drawing_context: &DrawingContext) {
         unsafe {
-            drawing_context.render_target.BeginDraw();
             let rt: &ID2D1RenderTarget = drawing_context.render_target;
-            rt.Clear(Some(&D2D1_COLOR_F { r: 0.0, g: 0.0, b: 0.0, a: 1.0 }));
-
-            if let Err(e) = app.scene.draw_all(drawing_context) {
-                println!("Failed to draw scene: {:?}", e);
-            }
+            rt.Clear(Some(&drawing_context.background_color.into()));
+        }
 
-            if let Err(e) = drawing_context.render_target.EndDraw(None, None) {
-                println!("EndDraw failed: {:?}", e);
-            }
+        if let Err(e) = app.scene.draw_all(drawing_context) {
+            println!("Failed to draw scene: {:?}", e);
         }
     }
 }
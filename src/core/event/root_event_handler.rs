@@ -78,6 +78,34 @@ impl EventHandler for RootEventHandler {
         }
     }
 
+    /// Delegates the `on_mbutton_down` call to all registered handlers.
+    fn on_mbutton_down(&mut self, app: &mut App, x: i32, y: i32) {
+        for handler in &mut self.handlers {
+            handler.on_mbutton_down(app, x, y);
+        }
+    }
+
+    /// Delegates the `on_mbutton_up` call to all registered handlers.
+    fn on_mbutton_up(&mut self, app: &mut App, x: i32, y: i32) {
+        for handler in &mut self.handlers {
+            handler.on_mbutton_up(app, x, y);
+        }
+    }
+
+    /// Delegates the `on_mouse_wheel` call to all registered handlers.
+    fn on_mouse_wheel(&mut self, app: &mut App, x: i32, y: i32, delta: i32) {
+        for handler in &mut self.handlers {
+            handler.on_mouse_wheel(app, x, y, delta);
+        }
+    }
+
+    /// Delegates the `on_capture_lost` call to all registered handlers.
+    fn on_capture_lost(&mut self, app: &mut App) {
+        for handler in &mut self.handlers {
+            handler.on_capture_lost(app);
+        }
+    }
+
     /// Delegates the `on_key_down` call to all registered handlers.
     fn on_key_down(&mut self, app: &mut App, key: KeyId) {
         for handler in &mut self.handlers {
@@ -92,6 +120,27 @@ impl EventHandler for RootEventHandler {
         }
     }
 
+    /// Delegates the `on_timer` call to all registered handlers.
+    fn on_timer(&mut self, app: &mut App, timer_id: usize) {
+        for handler in &mut self.handlers {
+            handler.on_timer(app, timer_id);
+        }
+    }
+
+    /// Delegates the `on_device_lost` call to all registered handlers.
+    fn on_device_lost(&mut self, app: &mut App) {
+        for handler in &mut self.handlers {
+            handler.on_device_lost(app);
+        }
+    }
+
+    /// Delegates the `on_device_restored` call to all registered handlers.
+    fn on_device_restored(&mut self, app: &mut App) {
+        for handler in &mut self.handlers {
+            handler.on_device_restored(app);
+        }
+    }
+
     /// Delegates the `handle_message` call to all registered handlers.
     ///
     /// It returns the result from the first handler that returns `Some`.
@@ -0,0 +1,166 @@
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use crate::{
+    app::App,
+    core::render::{
+        color::Color,
+        drawing_context::DrawingContext,
+        objects::text_object::TextObject,
+        scene::{Layer, ObjectId, Scene},
+        scene_description::SceneDescription,
+    },
+};
+use super::event_handler::EventHandler;
+
+/// Parses the contents of a scene file into a `SceneDescription`, or describes why it
+/// couldn't.
+type Parser = Box<dyn Fn(&str) -> Result<SceneDescription, String>>;
+
+/// An event handler that loads the app's scene from a file and reloads it whenever the
+/// file changes, for iterating on a layout without restarting.
+///
+/// The file is loaded on the first paint, and then checked for changes on a timer. The
+/// parser is supplied by the caller, so any serde format works, such as
+/// `serde_json::from_str` for JSON.
+///
+/// When the file can't be read or parsed, the previous scene stays on screen with the
+/// error shown in red in its overlay layer, until the file is fixed.
+///
+/// Reloading replaces `App::scene`, so objects added to the scene by other handlers
+/// are discarded on every reload.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use my_gui::core::event::{
+///     render_event_handler::RenderEventHandler, root_event_handler::RootEventHandler,
+///     scene_watcher::SceneWatcher,
+/// };
+///
+/// let mut event_handler = RootEventHandler::new();
+/// event_handler.add_handler(Box::new(SceneWatcher::new("scene.json", serde_json::from_str)));
+/// event_handler.add_handler(Box::new(RenderEventHandler::new()));
+/// ```
+pub struct SceneWatcher {
+    /// The file the scene is loaded from.
+    path: PathBuf,
+    parse: Parser,
+    /// How often the file is checked for changes.
+    interval: Duration,
+    /// The id of the timer used to check the file.
+    timer_id: usize,
+    /// The modification time of the file when it was last loaded, or `None` before
+    /// the first load.
+    modified: Option<SystemTime>,
+    /// Whether the timer has been requested.
+    started: bool,
+    /// The text showing the last error and the error message, or `None` if the last
+    /// load succeeded.
+    error: Option<(ObjectId, String)>,
+}
+
+impl SceneWatcher {
+    /// The default id of the timer used to check the file.
+    pub const DEFAULT_TIMER_ID: usize = 0x5CE7E;
+
+    /// Creates a new `SceneWatcher` for the file at `path`, checking it twice a second.
+    pub fn new<E: Display>(
+        path: impl Into<PathBuf>,
+        parse: impl Fn(&str) -> Result<SceneDescription, E> + 'static,
+    ) -> Self {
+        Self {
+            path: path.into(),
+            parse: Box::new(move |text| parse(text).map_err(|error| error.to_string())),
+            interval: Duration::from_millis(500),
+            timer_id: Self::DEFAULT_TIMER_ID,
+            modified: None,
+            started: false,
+            error: None,
+        }
+    }
+
+    /// Sets how often the file is checked for changes.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Sets the id of the timer used to check the file, to avoid clashing with other
+    /// timers.
+    pub fn with_timer_id(mut self, timer_id: usize) -> Self {
+        self.timer_id = timer_id;
+        self
+    }
+
+    /// Reloads the scene if the file has changed since it was last loaded.
+    fn check(&mut self, app: &mut App) {
+        let modified = std::fs::metadata(&self.path).and_then(|metadata| metadata.modified());
+        if let Ok(modified) = modified {
+            if self.modified == Some(modified) {
+                return;
+            }
+            self.modified = Some(modified);
+        }
+
+        let changed = match self.load() {
+            Ok(scene) => {
+                app.scene = scene;
+                self.error = None;
+                true
+            }
+            Err(error) => {
+                let message = format!("Failed to load {}: {error}", self.path.display());
+                self.show_error(app, message)
+            }
+        };
+        if changed {
+            app.request_redraw();
+        }
+    }
+
+    /// Shows an error message over the current scene, replacing any previous one.
+    ///
+    /// Returns `false` if the same message is already shown, such as while the file is
+    /// missing.
+    fn show_error(&mut self, app: &mut App, message: String) -> bool {
+        if let Some((id, previous)) = self.error.take() {
+            if previous == message && app.scene.contains(id) {
+                self.error = Some((id, previous));
+                return false;
+            }
+            app.scene.remove_object(id);
+        }
+        let id = app.scene.layer_mut(Layer::Overlay).add_object(Box::new(
+            TextObject::new(&message, 10.0, 10.0).with_color(Color::RED),
+        ));
+        self.error = Some((id, message));
+        true
+    }
+
+    /// Reads and parses the file.
+    fn load(&self) -> Result<Scene, String> {
+        let text = std::fs::read_to_string(&self.path).map_err(|error| error.to_string())?;
+        let description = (self.parse)(&text)?;
+        Ok(Scene::from_description(description))
+    }
+}
+
+impl EventHandler for SceneWatcher {
+    /// Loads the file and starts the timer on the first paint.
+    fn on_paint(&mut self, app: &mut App, _drawing_context: &DrawingContext) {
+        if !self.started {
+            self.started = true;
+            app.set_timer(self.timer_id, self.interval);
+            self.check(app);
+        }
+    }
+
+    /// Reloads the scene if the file has changed.
+    fn on_timer(&mut self, app: &mut App, timer_id: usize) {
+        if timer_id == self.timer_id {
+            self.check(app);
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use windows_numerics::Vector2;
+
+use crate::{
+    app::App,
+    core::render::{
+        drawing_context::DrawingContext,
+        objects::{
+            canvas::Canvas,
+            scroll_bar::{ScrollBar, ScrollOrientation},
+        },
+        scene::ObjectId,
+    },
+};
+use super::event_handler::EventHandler;
+use super::mouse_button::MouseButton;
+
+/// An event handler that connects a `ScrollBar` to the `Canvas` it scrolls.
+///
+/// Dragging the thumb scrolls the canvas, with the mouse captured so the drag follows
+/// the cursor outside the window, and clicking the track on either side of the
+/// thumb scrolls by one page towards the click. On every paint, the scroll bar is
+/// updated from the canvas, so it also follows scrolling done elsewhere, such as with
+/// the mouse wheel.
+///
+/// Both objects must be in `App::scene`. The handler must be added to the
+/// `RootEventHandler` before the `RenderEventHandler`, so the scroll bar is updated
+/// before the scene is drawn.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use my_gui::{
+///     app::App,
+///     core::{
+///         event::{
+///             render_event_handler::RenderEventHandler, root_event_handler::RootEventHandler,
+///             scroll_bar_handler::ScrollBarHandler,
+///         },
+///         render::objects::{
+///             canvas::Canvas,
+///             scroll_bar::{ScrollBar, ScrollOrientation},
+///         },
+///     },
+/// };
+///
+/// let mut app = App::new();
+/// let list = Canvas::new(40.0, 60.0, 320.0, 400.0).with_content_size(320.0, 1600.0);
+/// let canvas = app.scene.add_object(Box::new(list));
+/// let scroll_bar = app.scene.add_object(Box::new(ScrollBar::new(
+///     364.0,
+///     60.0,
+///     12.0,
+///     400.0,
+///     ScrollOrientation::Vertical,
+/// )));
+///
+/// let mut event_handler = RootEventHandler::new();
+/// event_handler.add_handler(Box::new(ScrollBarHandler::new(scroll_bar, canvas)));
+/// event_handler.add_handler(Box::new(RenderEventHandler::new()));
+/// ```
+pub struct ScrollBarHandler {
+    /// The scroll bar in the scene.
+    scroll_bar: ObjectId,
+    /// The canvas the scroll bar scrolls.
+    canvas: ObjectId,
+    /// While the thumb is dragged, the distance from the start of the thumb to the
+    /// point where it was grabbed.
+    grab: Option<f32>,
+}
+
+impl ScrollBarHandler {
+    /// Creates a new `ScrollBarHandler` for the given scroll bar and canvas.
+    pub fn new(scroll_bar: ObjectId, canvas: ObjectId) -> Self {
+        Self {
+            scroll_bar,
+            canvas,
+            grab: None,
+        }
+    }
+
+    /// Scrolls the canvas to `offset` in the scroll bar's orientation, keeping the
+    /// offset in the other direction, and updates the scroll bar to match.
+    fn scroll_to(&self, app: &mut App, offset: f32) {
+        let Some(orientation) = app
+            .scene
+            .get_object::<ScrollBar>(self.scroll_bar)
+            .map(|scroll_bar| scroll_bar.orientation)
+        else {
+            return;
+        };
+        let Some(canvas) = app.scene.get_object_mut::<Canvas>(self.canvas) else {
+            return;
+        };
+        let current = canvas.scroll_offset();
+        canvas.scroll_to(match orientation {
+            ScrollOrientation::Vertical => Vector2 {
+                X: current.X,
+                Y: offset,
+            },
+            ScrollOrientation::Horizontal => Vector2 {
+                X: offset,
+                Y: current.Y,
+            },
+        });
+        self.sync(app);
+        app.request_redraw();
+    }
+
+    /// Updates the scroll bar's range from the canvas.
+    fn sync(&self, app: &mut App) {
+        let (Some(canvas), Some(scroll_bar)) = (
+            app.scene.get_object::<Canvas>(self.canvas),
+            app.scene.get_object::<ScrollBar>(self.scroll_bar),
+        ) else {
+            return;
+        };
+        let (viewport_length, content_length, offset) = scroll_bar.range_of(canvas);
+        if let Some(scroll_bar) = app.scene.get_object_mut::<ScrollBar>(self.scroll_bar) {
+            scroll_bar.set_range(viewport_length, content_length, offset);
+        }
+    }
+}
+
+impl EventHandler for ScrollBarHandler {
+    /// Updates the scroll bar from the canvas before the scene is drawn.
+    fn on_paint(&mut self, app: &mut App, _drawing_context: &DrawingContext) {
+        self.sync(app);
+    }
+
+    /// Grabs the thumb, or scrolls by a page if the track is clicked beside it.
+    fn on_lbutton_down(&mut self, app: &mut App, x: i32, y: i32) {
+        let point = Vector2 {
+            X: x as f32,
+            Y: y as f32,
+        };
+        let Some(scroll_bar) = app.scene.get_object::<ScrollBar>(self.scroll_bar) else {
+            return;
+        };
+        if !scroll_bar.rect.contains(point) {
+            return;
+        }
+        let Some(thumb) = scroll_bar.thumb_rect() else {
+            return;
+        };
+
+        let along = scroll_bar.along(point);
+        let thumb_start = scroll_bar.along(Vector2 {
+            X: thumb.x,
+            Y: thumb.y,
+        });
+        if thumb.contains(point) {
+            self.grab = Some(along - thumb_start);
+            app.capture_mouse();
+            return;
+        }
+
+        let page = match scroll_bar.orientation {
+            ScrollOrientation::Vertical => thumb.height,
+            ScrollOrientation::Horizontal => thumb.width,
+        };
+        let direction = if along < thumb_start { -1.0 } else { 1.0 };
+        let offset = scroll_bar.offset_at(thumb_start + direction * page);
+        self.scroll_to(app, offset);
+    }
+
+    /// Scrolls the canvas while the thumb is dragged, or ends the drag if the left
+    /// button was released without the handler seeing it.
+    fn on_mouse_move(&mut self, app: &mut App, x: i32, y: i32) {
+        let Some(grab) = self.grab else {
+            return;
+        };
+        if !app.is_mouse_button_down(MouseButton::Left) {
+            self.grab = None;
+            app.release_mouse();
+            return;
+        }
+        let Some(scroll_bar) = app.scene.get_object::<ScrollBar>(self.scroll_bar) else {
+            return;
+        };
+        let along = scroll_bar.along(Vector2 {
+            X: x as f32,
+            Y: y as f32,
+        });
+        let offset = scroll_bar.offset_at(along - grab);
+        self.scroll_to(app, offset);
+    }
+
+    /// Releases the thumb and the mouse capture.
+    fn on_lbutton_up(&mut self, app: &mut App, _x: i32, _y: i32) {
+        if self.grab.take().is_some() {
+            app.release_mouse();
+        }
+    }
+
+    /// Ends the drag when another window takes the mouse capture.
+    fn on_capture_lost(&mut self, _app: &mut App) {
+        self.grab = None;
+    }
+}
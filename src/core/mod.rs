@@ -1,3 +1,6 @@
+pub mod backend;
+#[cfg(windows)]
 pub mod event;
 pub mod render;
+#[cfg(windows)]
 pub mod window;
@@ -0,0 +1,36 @@
+use windows::Win32::Graphics::Direct2D::{
+    D2D1_ANTIALIAS_MODE, D2D1_ANTIALIAS_MODE_ALIASED, D2D1_ANTIALIAS_MODE_PER_PRIMITIVE,
+};
+
+/// How the edges of shapes, clips, and masks are drawn.
+///
+/// Text is antialiased separately and isn't affected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AntialiasMode {
+    /// Edges are smoothed by blending partially covered pixels.
+    #[default]
+    PerPrimitive,
+    /// Pixels are either fully covered or not at all, giving crisp edges and one-pixel
+    /// hairlines, as pixel art needs.
+    Aliased,
+}
+
+impl From<AntialiasMode> for D2D1_ANTIALIAS_MODE {
+    fn from(mode: AntialiasMode) -> Self {
+        match mode {
+            AntialiasMode::PerPrimitive => D2D1_ANTIALIAS_MODE_PER_PRIMITIVE,
+            AntialiasMode::Aliased => D2D1_ANTIALIAS_MODE_ALIASED,
+        }
+    }
+}
+
+impl From<D2D1_ANTIALIAS_MODE> for AntialiasMode {
+    fn from(mode: D2D1_ANTIALIAS_MODE) -> Self {
+        if mode == D2D1_ANTIALIAS_MODE_ALIASED {
+            AntialiasMode::Aliased
+        } else {
+            AntialiasMode::PerPrimitive
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use windows::{
+    Win32::Graphics::Direct2D::{ID2D1RenderTarget, ID2D1SolidColorBrush},
+    core::Result,
+};
+
+use crate::core::render::color::Color;
+
+/// A cache of solid color brushes, one per color, used by
+/// `DrawingContext::with_brush_color`.
+///
+/// Brushes are device-dependent resources. The cache remembers the render target its
+/// brushes were created for and starts over when the render target changes, and it is
+/// cleared by `Direct2DContext::release_device_dependent_resources`. Once it holds
+/// `MAX_BRUSHES` brushes it is cleared before the next one is added, so a scene with
+/// an unbounded number of colors can't grow it without limit. It is owned by the
+/// `Direct2DContext`.
+pub struct BrushCache {
+    /// The brushes, keyed by the bits of their color, and the render target they
+    /// belong to.
+    brushes: RefCell<Option<(ID2D1RenderTarget, HashMap<[u32; 4], ID2D1SolidColorBrush>)>>,
+}
+
+impl BrushCache {
+    /// The largest number of brushes the cache holds.
+    pub const MAX_BRUSHES: usize = 1024;
+
+    /// Creates a new, empty `BrushCache`.
+    pub fn new() -> Self {
+        Self {
+            brushes: RefCell::new(None),
+        }
+    }
+
+    /// Returns the number of cached brushes.
+    pub fn len(&self) -> usize {
+        self.brushes
+            .borrow()
+            .as_ref()
+            .map_or(0, |(_, brushes)| brushes.len())
+    }
+
+    /// Returns `true` if no brushes are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops every cached brush.
+    pub fn clear(&self) {
+        self.brushes.replace(None);
+    }
+
+    /// Returns the brush of `color` for `render_target`, creating and caching it if
+    /// necessary.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create the brush.
+    ///
+    /// # Safety
+    ///
+    /// This function contains an `unsafe` block for creating the brush. The caller
+    /// must provide a valid render target.
+    pub(crate) fn get(
+        &self,
+        render_target: &ID2D1RenderTarget,
+        color: &Color,
+    ) -> Result<ID2D1SolidColorBrush> {
+        let key = [
+            color.r.to_bits(),
+            color.g.to_bits(),
+            color.b.to_bits(),
+            color.a.to_bits(),
+        ];
+
+        let mut cache = self.brushes.borrow_mut();
+        let stale = cache.as_ref().is_some_and(|(target, brushes)| {
+            target != render_target
+                || (brushes.len() >= Self::MAX_BRUSHES && !brushes.contains_key(&key))
+        });
+        if stale {
+            *cache = None;
+        }
+        let (_, brushes) = cache.get_or_insert_with(|| (render_target.clone(), HashMap::new()));
+        if let Some(brush) = brushes.get(&key) {
+            return Ok(brush.clone());
+        }
+
+        let brush = unsafe { render_target.CreateSolidColorBrush(&(*color).into(), None)? };
+        brushes.insert(key, brush.clone());
+        Ok(brush)
+    }
+}
+
+impl Default for BrushCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,119 @@
+use windows_numerics::{Matrix3x2, Vector2};
+
+use crate::core::render::transform::{invert, transform_point};
+
+/// The view of a `Scene`: which part of the world is shown in the window, and how
+/// large.
+///
+/// Objects are positioned in world coordinates. The camera maps them to screen
+/// coordinates, the DIPs of the window's client area, by moving `position` to the
+/// top-left corner of the window and scaling by `zoom`. The default camera shows the
+/// world unchanged, so world and screen coordinates are the same.
+///
+/// Mouse events report screen coordinates. Use `screen_to_world` to find the point of
+/// the world under the cursor.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use my_gui::core::render::camera::Camera;
+/// use windows_numerics::Vector2;
+///
+/// // Show the world from (100, 50) at twice its size.
+/// let camera = Camera::new().with_position(100.0, 50.0).with_zoom(2.0);
+/// let world = camera.screen_to_world(Vector2 { X: 20.0, Y: 40.0 });
+/// assert_eq!((world.X, world.Y), (110.0, 70.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Camera {
+    /// The point of the world shown at the top-left corner of the window.
+    #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2"))]
+    pub position: Vector2,
+    /// How many DIPs on the screen one unit of the world covers.
+    pub zoom: f32,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Camera {
+    /// Creates a new `Camera` showing the world from the origin at its actual size.
+    pub fn new() -> Self {
+        Self {
+            position: Vector2::default(),
+            zoom: 1.0,
+        }
+    }
+
+    /// Sets the point of the world shown at the top-left corner of the window.
+    pub fn with_position(mut self, x: f32, y: f32) -> Self {
+        self.position = Vector2 { X: x, Y: y };
+        self
+    }
+
+    /// Sets the zoom factor.
+    pub fn with_zoom(mut self, zoom: f32) -> Self {
+        self.zoom = zoom;
+        self
+    }
+
+    /// Returns `true` if the camera shows the world unchanged.
+    pub fn is_identity(&self) -> bool {
+        self.position.X == 0.0 && self.position.Y == 0.0 && self.zoom == 1.0
+    }
+
+    /// Returns the matrix that maps world coordinates to screen coordinates.
+    pub fn view_matrix(&self) -> Matrix3x2 {
+        Matrix3x2::translation(-self.position.X, -self.position.Y)
+            * Matrix3x2 {
+                M11: self.zoom,
+                M12: 0.0,
+                M21: 0.0,
+                M22: self.zoom,
+                M31: 0.0,
+                M32: 0.0,
+            }
+    }
+
+    /// Returns the point of the world shown at `point` in the window.
+    ///
+    /// A camera with a zoom of 0 shows a single point of the world everywhere, which is
+    /// returned for every `point`.
+    pub fn screen_to_world(&self, point: Vector2) -> Vector2 {
+        match invert(&self.view_matrix()) {
+            Some(inverse) => transform_point(&inverse, point),
+            None => self.position,
+        }
+    }
+
+    /// Returns the point in the window where `point` of the world is shown.
+    pub fn world_to_screen(&self, point: Vector2) -> Vector2 {
+        transform_point(&self.view_matrix(), point)
+    }
+
+    /// Moves the view so the world follows a drag of `dx` and `dy` screen DIPs.
+    pub fn pan_by(&mut self, dx: f32, dy: f32) {
+        if self.zoom == 0.0 {
+            return;
+        }
+        self.position.X -= dx / self.zoom;
+        self.position.Y -= dy / self.zoom;
+    }
+
+    /// Changes the zoom to `zoom`, keeping the point of the world under `anchor`, a
+    /// point in the window such as the cursor, in place.
+    pub fn zoom_at(&mut self, anchor: Vector2, zoom: f32) {
+        let world = self.screen_to_world(anchor);
+        self.zoom = zoom;
+        if zoom != 0.0 {
+            self.position = Vector2 {
+                X: world.X - anchor.X / zoom,
+                Y: world.Y - anchor.Y / zoom,
+            };
+        }
+    }
+}
@@ -0,0 +1,414 @@
+use std::str::FromStr;
+
+#[cfg(windows)]
+use windows::Win32::Graphics::Direct2D::Common::D2D1_COLOR_F;
+
+/// An error produced when parsing a hex color string.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ColorParseError {
+    /// The string did not have 3, 6, or 8 hex digits.
+    #[error("expected 3, 6, or 8 hex digits, found {0}")]
+    InvalidLength(usize),
+    /// A character that is not a hex digit was found.
+    #[error("invalid hex digit '{character}' at offset {offset}")]
+    InvalidDigit {
+        /// The invalid character.
+        character: char,
+        /// The byte offset of the character in the string.
+        offset: usize,
+    },
+}
+
+/// Represents an RGBA color.
+///
+/// Each channel is a floating-point value in the range `0.0..=1.0`. The alpha
+/// channel is straight (not pre-multiplied), matching what Direct2D brushes expect.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Color {
+    /// The red channel.
+    pub r: f32,
+    /// The green channel.
+    pub g: f32,
+    /// The blue channel.
+    pub b: f32,
+    /// The alpha (opacity) channel.
+    pub a: f32,
+}
+
+impl Color {
+    /// Opaque black.
+    pub const BLACK: Color = Color::new(0.0, 0.0, 0.0, 1.0);
+    /// Opaque white.
+    pub const WHITE: Color = Color::new(1.0, 1.0, 1.0, 1.0);
+    /// Opaque red.
+    pub const RED: Color = Color::new(1.0, 0.0, 0.0, 1.0);
+    /// Opaque green.
+    pub const GREEN: Color = Color::new(0.0, 1.0, 0.0, 1.0);
+    /// Opaque blue.
+    pub const BLUE: Color = Color::new(0.0, 0.0, 1.0, 1.0);
+    /// Opaque yellow.
+    pub const YELLOW: Color = Color::new(1.0, 1.0, 0.0, 1.0);
+    /// Opaque mid-gray.
+    pub const GRAY: Color = Color::new(0.5, 0.5, 0.5, 1.0);
+    /// Fully transparent black.
+    pub const TRANSPARENT: Color = Color::new(0.0, 0.0, 0.0, 0.0);
+
+    /// Creates a new `Color` from its red, green, blue, and alpha channels.
+    pub const fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// Creates a new opaque `Color` from 8-bit red, green, and blue channels.
+    pub const fn from_rgb8(r: u8, g: u8, b: u8) -> Self {
+        Self::from_rgba8(r, g, b, 255)
+    }
+
+    /// Creates a new `Color` from 8-bit red, green, blue, and alpha channels.
+    pub const fn from_rgba8(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self::new(
+            r as f32 / 255.0,
+            g as f32 / 255.0,
+            b as f32 / 255.0,
+            a as f32 / 255.0,
+        )
+    }
+
+    /// Parses a color from a hex string such as `#3A7BD5`.
+    ///
+    /// The `#RGB`, `#RRGGBB`, and `#RRGGBBAA` forms are accepted, with or without the
+    /// leading `#`. Digits are case-insensitive.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the string contains a character that is
+    /// not a hex digit or does not have 3, 6, or 8 digits.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use my_gui::core::render::color::Color;
+    ///
+    /// assert_eq!(Color::from_hex("#F00").unwrap(), Color::RED);
+    /// assert_eq!(Color::from_hex("3A7BD5").unwrap(), Color::from_rgb8(0x3A, 0x7B, 0xD5));
+    /// ```
+    pub fn from_hex(hex: &str) -> Result<Self, ColorParseError> {
+        let (prefix, digits) = match hex.strip_prefix('#') {
+            Some(digits) => (1, digits),
+            None => (0, hex),
+        };
+
+        if let Some((offset, character)) = digits
+            .char_indices()
+            .find(|(_, character)| !character.is_ascii_hexdigit())
+        {
+            return Err(ColorParseError::InvalidDigit {
+                character,
+                offset: prefix + offset,
+            });
+        }
+
+        // Every character is an ASCII hex digit, so each one is a single byte.
+        let digit = |index: usize| (digits.as_bytes()[index] as char).to_digit(16).unwrap() as u8;
+        let byte = |index: usize| digit(index) * 16 + digit(index + 1);
+
+        match digits.len() {
+            3 => Ok(Self::from_rgb8(digit(0) * 17, digit(1) * 17, digit(2) * 17)),
+            6 => Ok(Self::from_rgb8(byte(0), byte(2), byte(4))),
+            8 => Ok(Self::from_rgba8(byte(0), byte(2), byte(4), byte(6))),
+            length => Err(ColorParseError::InvalidLength(length)),
+        }
+    }
+
+    /// Converts the color to hue, saturation, and value.
+    ///
+    /// The hue is in degrees in the range `0.0..360.0`, and the saturation and value are
+    /// in the range `0.0..=1.0`. Grays have a hue and saturation of `0.0`. The alpha
+    /// channel is not included.
+    pub fn to_hsv(self) -> (f32, f32, f32) {
+        let (hue, max, chroma) = self.hue_max_chroma();
+        let saturation = if max > 0.0 { chroma / max } else { 0.0 };
+        (hue, saturation, max)
+    }
+
+    /// Creates a new `Color` from hue, saturation, value, and alpha.
+    ///
+    /// The hue is in degrees and wraps around, so `-60.0` and `300.0` are the same
+    /// hue. The other components are clamped to `0.0..=1.0`.
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32, alpha: f32) -> Self {
+        let saturation = saturation.clamp(0.0, 1.0);
+        let value = value.clamp(0.0, 1.0);
+        let chroma = value * saturation;
+        Self::from_hue_chroma(hue, chroma, value - chroma, alpha)
+    }
+
+    /// Converts the color to hue, saturation, and lightness.
+    ///
+    /// The hue is in degrees in the range `0.0..360.0`, and the saturation and lightness
+    /// are in the range `0.0..=1.0`. Grays have a hue and saturation of `0.0`. The alpha
+    /// channel is not included.
+    pub fn to_hsl(self) -> (f32, f32, f32) {
+        let (hue, max, chroma) = self.hue_max_chroma();
+        let lightness = max - chroma / 2.0;
+        let saturation = if lightness > 0.0 && lightness < 1.0 {
+            chroma / (1.0 - (2.0 * lightness - 1.0).abs())
+        } else {
+            0.0
+        };
+        (hue, saturation, lightness)
+    }
+
+    /// Creates a new `Color` from hue, saturation, lightness, and alpha.
+    ///
+    /// The hue is in degrees and wraps around, so `-60.0` and `300.0` are the same
+    /// hue. The other components are clamped to `0.0..=1.0`.
+    pub fn from_hsl(hue: f32, saturation: f32, lightness: f32, alpha: f32) -> Self {
+        let saturation = saturation.clamp(0.0, 1.0);
+        let lightness = lightness.clamp(0.0, 1.0);
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        Self::from_hue_chroma(hue, chroma, lightness - chroma / 2.0, alpha)
+    }
+
+    /// Linearly interpolates between this color and `other`, including alpha.
+    ///
+    /// `t` is clamped to `0.0..=1.0`, where `0.0` returns this color and `1.0` returns
+    /// `other`.
+    pub fn lerp(self, other: Color, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |from: f32, to: f32| from + (to - from) * t;
+        Self::new(
+            mix(self.r, other.r),
+            mix(self.g, other.g),
+            mix(self.b, other.b),
+            mix(self.a, other.a),
+        )
+    }
+
+    /// Returns the color with its HSL lightness increased by `amount`.
+    pub fn lighten(self, amount: f32) -> Self {
+        let (hue, saturation, lightness) = self.to_hsl();
+        Self::from_hsl(hue, saturation, lightness + amount, self.a)
+    }
+
+    /// Returns the color with its HSL lightness decreased by `amount`.
+    pub fn darken(self, amount: f32) -> Self {
+        self.lighten(-amount)
+    }
+
+    /// Returns the hue in degrees, the largest channel, and the chroma of the color.
+    fn hue_max_chroma(&self) -> (f32, f32, f32) {
+        let max = self.r.max(self.g).max(self.b);
+        let min = self.r.min(self.g).min(self.b);
+        let chroma = max - min;
+
+        let hue = if chroma <= 0.0 {
+            0.0
+        } else if max == self.r {
+            60.0 * ((self.g - self.b) / chroma).rem_euclid(6.0)
+        } else if max == self.g {
+            60.0 * ((self.b - self.r) / chroma + 2.0)
+        } else {
+            60.0 * ((self.r - self.g) / chroma + 4.0)
+        };
+
+        // Rounding can produce exactly 360.0 for hues just below red.
+        (if hue >= 360.0 { 0.0 } else { hue }, max, chroma)
+    }
+
+    /// Creates a color from a hue, a chroma, and the amount added to every channel.
+    fn from_hue_chroma(hue: f32, chroma: f32, offset: f32, alpha: f32) -> Self {
+        let sector = hue.rem_euclid(360.0) / 60.0;
+        let x = chroma * (1.0 - (sector.rem_euclid(2.0) - 1.0).abs());
+        let (r, g, b) = match sector as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        Self::new(r + offset, g + offset, b + offset, alpha.clamp(0.0, 1.0))
+    }
+}
+
+impl FromStr for Color {
+    type Err = ColorParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(s)
+    }
+}
+
+#[cfg(windows)]
+impl From<Color> for D2D1_COLOR_F {
+    fn from(color: Color) -> Self {
+        D2D1_COLOR_F {
+            r: color.r,
+            g: color.g,
+            b: color.b,
+            a: color.a,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_short_hex_colors() {
+        assert_eq!(Color::from_hex("#F00"), Ok(Color::RED));
+        assert_eq!(Color::from_hex("#fff"), Ok(Color::WHITE));
+        assert_eq!(
+            Color::from_hex("#3a7"),
+            Ok(Color::from_rgb8(0x33, 0xAA, 0x77))
+        );
+    }
+
+    #[test]
+    fn parses_long_hex_colors() {
+        assert_eq!(
+            Color::from_hex("#3A7BD5"),
+            Ok(Color::from_rgb8(0x3A, 0x7B, 0xD5))
+        );
+        assert_eq!(
+            Color::from_hex("3a7bd5"),
+            Ok(Color::from_rgb8(0x3A, 0x7B, 0xD5))
+        );
+        assert_eq!(
+            Color::from_hex("#3A7BD580"),
+            Ok(Color::from_rgba8(0x3A, 0x7B, 0xD5, 0x80))
+        );
+        assert_eq!(Color::from_hex("#00000000"), Ok(Color::TRANSPARENT));
+    }
+
+    #[test]
+    fn hex_round_trips_every_channel_value() {
+        for value in 0..=255u8 {
+            let color = Color::from_rgba8(value, 255 - value, value / 2, value);
+            let hex = format!(
+                "#{value:02x}{:02X}{:02x}{value:02X}",
+                255 - value,
+                value / 2
+            );
+            assert_eq!(Color::from_hex(&hex), Ok(color));
+        }
+    }
+
+    #[test]
+    fn from_str_matches_from_hex() {
+        for hex in ["#F00", "#3A7BD5", "#3A7BD580", "#12", "#GGG"] {
+            assert_eq!(hex.parse::<Color>(), Color::from_hex(hex));
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_hex_colors() {
+        assert_eq!(Color::from_hex(""), Err(ColorParseError::InvalidLength(0)));
+        assert_eq!(Color::from_hex("#"), Err(ColorParseError::InvalidLength(0)));
+        assert_eq!(
+            Color::from_hex("#1234"),
+            Err(ColorParseError::InvalidLength(4))
+        );
+        assert_eq!(
+            Color::from_hex("#123456789"),
+            Err(ColorParseError::InvalidLength(9))
+        );
+        assert_eq!(
+            Color::from_hex("#12G"),
+            Err(ColorParseError::InvalidDigit {
+                character: 'G',
+                offset: 3
+            })
+        );
+        assert_eq!(
+            Color::from_hex("1 2"),
+            Err(ColorParseError::InvalidDigit {
+                character: ' ',
+                offset: 1
+            })
+        );
+        assert_eq!(
+            Color::from_hex("##123"),
+            Err(ColorParseError::InvalidDigit {
+                character: '#',
+                offset: 1
+            })
+        );
+    }
+
+    /// Asserts that two colors are equal to within rounding error.
+    fn assert_close(actual: Color, expected: Color) {
+        let close = |a: f32, b: f32| (a - b).abs() < 1e-5;
+        assert!(
+            close(actual.r, expected.r)
+                && close(actual.g, expected.g)
+                && close(actual.b, expected.b)
+                && close(actual.a, expected.a),
+            "{actual:?} != {expected:?}"
+        );
+    }
+
+    #[test]
+    fn zero_saturation_gives_grays_for_any_hue() {
+        for hue in [0.0, 90.0, 200.0, 359.0] {
+            assert_close(
+                Color::from_hsv(hue, 0.0, 0.25, 1.0),
+                Color::new(0.25, 0.25, 0.25, 1.0),
+            );
+            assert_close(
+                Color::from_hsl(hue, 0.0, 0.75, 1.0),
+                Color::new(0.75, 0.75, 0.75, 1.0),
+            );
+        }
+        assert_eq!(Color::GRAY.to_hsv(), (0.0, 0.0, 0.5));
+        assert_eq!(Color::GRAY.to_hsl(), (0.0, 0.0, 0.5));
+        assert_eq!(Color::WHITE.to_hsl(), (0.0, 0.0, 1.0));
+        assert_eq!(Color::BLACK.to_hsv(), (0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn full_saturation_gives_pure_hues() {
+        let primaries = [
+            (0.0, Color::RED),
+            (60.0, Color::YELLOW),
+            (120.0, Color::GREEN),
+            (180.0, Color::new(0.0, 1.0, 1.0, 1.0)),
+            (240.0, Color::BLUE),
+            (300.0, Color::new(1.0, 0.0, 1.0, 1.0)),
+        ];
+        for (hue, color) in primaries {
+            assert_close(Color::from_hsv(hue, 1.0, 1.0, 1.0), color);
+            assert_close(Color::from_hsl(hue, 1.0, 0.5, 1.0), color);
+            assert_eq!(color.to_hsv(), (hue, 1.0, 1.0));
+            assert_eq!(color.to_hsl(), (hue, 1.0, 0.5));
+        }
+    }
+
+    #[test]
+    fn hue_wraps_around_at_360_degrees() {
+        assert_close(Color::from_hsv(360.0, 1.0, 1.0, 1.0), Color::RED);
+        assert_close(Color::from_hsl(360.0, 1.0, 0.5, 1.0), Color::RED);
+        assert_close(
+            Color::from_hsv(-60.0, 1.0, 1.0, 1.0),
+            Color::from_hsv(300.0, 1.0, 1.0, 1.0),
+        );
+        assert_close(
+            Color::from_hsl(480.0, 0.5, 0.5, 1.0),
+            Color::from_hsl(120.0, 0.5, 0.5, 1.0),
+        );
+
+        // Hues just below red stay below 360 when converted back.
+        let (hue, _, _) = Color::new(1.0, 0.0, 1e-7, 1.0).to_hsv();
+        assert!((0.0..360.0).contains(&hue));
+    }
+
+    #[test]
+    fn hsv_and_hsl_round_trip() {
+        let color = Color::from_rgb8(0x3A, 0x7B, 0xD5);
+        let (hue, saturation, value) = color.to_hsv();
+        assert_close(Color::from_hsv(hue, saturation, value, 1.0), color);
+        let (hue, saturation, lightness) = color.to_hsl();
+        assert_close(Color::from_hsl(hue, saturation, lightness, 1.0), color);
+    }
+}
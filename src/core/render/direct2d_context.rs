@@ -4,16 +4,44 @@ use windows::{
     Win32::Graphics::Direct2D::*,
     Win32::Graphics::Direct2D::Common::*,
     Win32::Graphics::DirectWrite::*,
-    Win32::System::Com::*,
+    Win32::Globalization::GetUserDefaultLocaleName,
+    Win32::Graphics::Dxgi::Common::DXGI_FORMAT_B8G8R8A8_UNORM,
+    Win32::Graphics::Imaging::{GUID_WICPixelFormat32bppPBGRA, WICBitmapCacheOnLoad},
+    Win32::System::SystemServices::LOCALE_NAME_MAX_LENGTH,
+    Win32::UI::HiDpi::GetDpiForWindow,
     Win32::UI::WindowsAndMessaging::GetClientRect,
 };
 
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use windows_numerics::Vector2;
+
 use windows::core::HSTRING;
 
+use crate::core::render::objects::text_object::{
+    CaretPosition, FontMetrics, LineMetrics, TextHit, TextMetrics, TextObject,
+};
+use crate::core::render::antialias::AntialiasMode;
+use crate::core::render::brush_cache::BrushCache;
+use crate::core::render::color::Color;
+use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::graphics_context::GraphicsContext;
+use crate::core::render::image_source::{encode_png, unpremultiply_bgra};
+use crate::core::render::layer::LayerPool;
+use crate::core::render::rect::Rect;
+use crate::core::render::scene::Scene;
+use crate::core::render::stroke_style::StrokeStyleCache;
+use crate::core::render::swap_chain::SwapChainTarget;
+use crate::core::render::transform::TransformStack;
+use crate::core::window::config::{PresentMode, RendererConfig, WindowConfig};
+
 /// Manages all Direct2D and DirectWrite resources.
 ///
-/// This struct encapsulates the factories, render targets, and other resources
-/// required for drawing. It separates resource creation into two categories:
+/// This struct encapsulates the render targets and other resources required for
+/// drawing, and holds the factories shared with other windows through a
+/// `GraphicsContext`. It separates resource creation into two categories:
 /// - **Device-independent resources**: These resources (like `IDWriteTextFormat`)
 ///   do not depend on the specific rendering device and can be created once.
 /// - **Device-dependent resources**: These resources (like `ID2D1HwndRenderTarget`
@@ -21,93 +49,197 @@ use windows::core::HSTRING;
 ///   recreated if the device is lost.
 pub struct Direct2DContext {
     // Device-independent resources
-    pub d2d_factory: ID2D1Factory1,
-    pub dwrite_factory: IDWriteFactory,
+    pub graphics: Rc<GraphicsContext>,
     pub text_format: Option<IDWriteTextFormat>,
+    pub font_collection: Option<IDWriteFontCollection>,
+    pub text_options: D2D1_DRAW_TEXT_OPTIONS,
+    pub text_rendering_params: Option<IDWriteRenderingParams>,
+    pub stroke_styles: StrokeStyleCache,
+    pub renderer: RendererConfig,
+    pub present_mode: PresentMode,
+    pub retain_contents: bool,
+    pub antialias_mode: AntialiasMode,
+    pub background_color: Color,
+    pub pixel_snap: bool,
 
     // Device-dependent resources
-    pub render_target: Option<ID2D1HwndRenderTarget>,
+    pub render_target: Option<ID2D1RenderTarget>,
+    pub swap_chain: Option<SwapChainTarget>,
     pub brush: Option<ID2D1SolidColorBrush>,
+    pub brushes: BrushCache,
+    pub layers: LayerPool,
+    pub transforms: TransformStack,
 }
 
 impl Direct2DContext {
-    /// Creates a new `Direct2DContext` and initializes device-independent resources.
+    /// Creates a new `Direct2DContext` and initializes device-independent resources
+    /// from the font settings in `config`.
+    ///
+    /// Color fonts are only enabled if `config.color_fonts` is set and DirectWrite
+    /// supports them (Windows 8.1 and later); otherwise color glyphs such as emoji are
+    /// drawn in the brush color. The text format uses `config.locale`, or the user's
+    /// default locale if it is `None`.
     ///
     /// # Errors
     ///
-    /// This function will return an error if it fails to initialize COM, create the
-    /// Direct2D or DirectWrite factories, or create the device-independent resources.
+    /// This function will return an error if it fails to get the thread's
+    /// `GraphicsContext`, load the font files, or create the device-independent
+    /// resources.
     ///
     /// # Safety
     ///
-    /// This function contains `unsafe` blocks for initializing COM and creating the
-    /// Direct2D and DirectWrite factories. The caller must ensure that it is safe
-    /// to initialize COM and create these factories.
-    pub fn new(font_face_name: &str, font_size: f32) -> Result<Self> {
-        unsafe {
-            CoInitializeEx(None, COINIT_APARTMENTTHREADED).ok()?;
-        }
-
-        let d2d_factory_options = D2D1_FACTORY_OPTIONS {
-            debugLevel: if cfg!(debug_assertions) {
-                D2D1_DEBUG_LEVEL_INFORMATION
-            } else {
-                D2D1_DEBUG_LEVEL_NONE
-            },
-        };
-
-        let d2d_factory: ID2D1Factory1 = unsafe {
-            D2D1CreateFactory(
-                D2D1_FACTORY_TYPE_SINGLE_THREADED,
-                Some(&d2d_factory_options),
-            )?
-        };
+    /// The factories come from `GraphicsContext::shared`, which initializes COM for the
+    /// thread if no other window on it has. The caller must ensure that it is safe to
+    /// initialize COM as a single-threaded apartment.
+    pub fn new(config: &WindowConfig) -> Result<Self> {
+        let graphics = GraphicsContext::shared()?;
 
-        let dwrite_factory: IDWriteFactory = unsafe {
-            DWriteCreateFactory(DWRITE_FACTORY_TYPE_SHARED)?
+        // Color glyph rendering arrived alongside `IDWriteFactory2`.
+        let color_fonts_supported = graphics.dwrite_factory.cast::<IDWriteFactory2>().is_ok();
+        let text_options = if config.color_fonts && color_fonts_supported {
+            D2D1_DRAW_TEXT_OPTIONS_ENABLE_COLOR_FONT
+        } else {
+            D2D1_DRAW_TEXT_OPTIONS_NONE
         };
 
         let mut context = Self {
-            d2d_factory,
-            dwrite_factory,
+            graphics,
             render_target: None,
+            swap_chain: None,
             text_format: None,
+            font_collection: None,
+            text_options,
+            text_rendering_params: None,
+            stroke_styles: StrokeStyleCache::new(),
+            renderer: config.renderer,
+            present_mode: config.present_mode,
+            retain_contents: config.retain_contents,
+            antialias_mode: config.antialias_mode,
+            background_color: config.background_color,
+            pixel_snap: config.pixel_snap,
             brush: None,
+            brushes: BrushCache::new(),
+            layers: LayerPool::new(),
+            transforms: TransformStack::new(),
         };
 
-        context.create_device_independent_resources(font_face_name, font_size)?;
+        if !config.font_files.is_empty() {
+            context.font_collection = Some(context.create_font_collection(&config.font_files)?);
+        }
+        context.create_device_independent_resources(config)?;
 
         Ok(context)
     }
 
+    /// Creates a font collection containing the system fonts and the fonts in
+    /// `font_files`, so text formats can use bundled fonts by their family name.
+    ///
+    /// Custom font sets require `IDWriteFactory5`, which is available on Windows 10
+    /// version 1703 and later.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the factory does not support custom font
+    /// sets, or if a font file is missing or is not a supported font.
+    fn create_font_collection(&self, font_files: &[PathBuf]) -> Result<IDWriteFontCollection> {
+        let factory: IDWriteFactory5 = self.graphics.dwrite_factory.cast().map_err(|error| {
+            Error::new(
+                error.code(),
+                "loading font files requires Windows 10 version 1703 or later",
+            )
+        })?;
+
+        unsafe {
+            let builder = factory.CreateFontSetBuilder()?;
+            builder.AddFontSet(&factory.GetSystemFontSet()?)?;
+            for path in font_files {
+                let font_file = self.load_font_file(path)?;
+                builder.AddFontFile(&font_file).map_err(|error| {
+                    font_file_error(path, error.code(), &error.message())
+                })?;
+            }
+            let font_set = builder.CreateFontSet()?;
+            factory.CreateFontCollectionFromFontSet(&font_set)?.cast()
+        }
+    }
+
+    /// Opens the font file at `path` and checks that DirectWrite can use it.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file cannot be opened or is not a
+    /// supported font.
+    fn load_font_file(&self, path: &Path) -> Result<IDWriteFontFile> {
+        let font_file = unsafe {
+            self.graphics
+                .dwrite_factory
+                .CreateFontFileReference(&HSTRING::from(path), None)
+                .map_err(|error| font_file_error(path, error.code(), &error.message()))?
+        };
+
+        let mut is_supported = BOOL::default();
+        let mut file_type = DWRITE_FONT_FILE_TYPE::default();
+        let mut face_count = 0;
+        unsafe {
+            font_file
+                .Analyze(&mut is_supported, &mut file_type, None, &mut face_count)
+                .map_err(|error| font_file_error(path, error.code(), &error.message()))?;
+        }
+        if !is_supported.as_bool() || face_count == 0 {
+            return Err(font_file_error(
+                path,
+                DWRITE_E_FILEFORMAT,
+                "the file is not a supported font",
+            ));
+        }
+
+        Ok(font_file)
+    }
+
     /// Creates resources that are not tied to a specific rendering device.
     ///
     /// # Errors
     ///
     /// This function will return an error if it fails to create the `IDWriteTextFormat`.
-    fn create_device_independent_resources(&mut self, font_face_name: &str, font_size: f32) -> Result<()> {
+    /// If the locale is rejected, the text format falls back to `en-us`.
+    fn create_device_independent_resources(&mut self, config: &WindowConfig) -> Result<()> {
+        let locale = config.locale.clone().unwrap_or_else(user_default_locale);
+
         // Create a DirectWrite text format object.
-        let text_format = unsafe {
-            self.dwrite_factory.CreateTextFormat(
-                &HSTRING::from(font_face_name),
-                None,
-                DWRITE_FONT_WEIGHT_NORMAL,
-                DWRITE_FONT_STYLE_NORMAL,
-                DWRITE_FONT_STRETCH_NORMAL,
-                font_size,
-                &HSTRING::from("en-us"),
-            )?
+        let create_text_format = |locale: &str| unsafe {
+            self.graphics.dwrite_factory.CreateTextFormat(
+                &HSTRING::from(config.font_face_name.as_str()),
+                self.font_collection.as_ref(),
+                config.font_weight.into(),
+                config.font_style.into(),
+                config.font_stretch.into(),
+                config.font_size as f32,
+                &HSTRING::from(locale),
+            )
         };
+        let text_format = create_text_format(&locale)
+            .or_else(|_| create_text_format(FALLBACK_LOCALE))?;
         self.text_format = Some(text_format);
+
+        // The parameters are set on every render target created from now on, including
+        // those recreated after the device is lost.
+        self.text_rendering_params = config
+            .text_rendering
+            .create_params(&self.graphics.dwrite_factory)?;
         Ok(())
     }
 
     /// Creates resources that are tied to a specific rendering device (the `HWND`).
     ///
+    /// The render target is an `ID2D1HwndRenderTarget`, or the device context of a
+    /// `SwapChainTarget` with `RendererConfig::SwapChain`.
+    ///
     /// # Errors
     ///
-    /// This function will return an error if it fails to get the client rect, create
-    /// the render target, or create the brush.
+    /// This function will return an error if the window renders with
+    /// `RendererConfig::Software`, `RendererConfig::Gdi`, or `RendererConfig::Wgpu`,
+    /// which draw without Direct2D, or if it fails to get the client rect, create the render target or
+    /// swap chain, or create the brush.
     ///
     /// # Safety
     ///
@@ -117,34 +249,577 @@ impl Direct2DContext {
     pub fn create_device_dependent_resources(&mut self, hwnd: HWND) -> Result<()> {
         let mut rect = RECT::default();
         unsafe { GetClientRect(hwnd, &mut rect)? };
+        let width = (rect.right - rect.left) as u32;
+        let height = (rect.bottom - rect.top) as u32;
 
-        let render_target_properties = D2D1_RENDER_TARGET_PROPERTIES::default();
+        let (render_target, swap_chain) = match self.renderer {
+            RendererConfig::SwapChain => {
+                let target = SwapChainTarget::new(&self.graphics.d2d_factory, hwnd, width, height)?;
+                (target.device_context.cast::<ID2D1RenderTarget>()?, Some(target))
+            }
+            RendererConfig::Direct2D => {
+                (self.create_hwnd_render_target(hwnd, width, height)?, None)
+            }
+            RendererConfig::Software | RendererConfig::Gdi => {
+                return Err(Error::new(
+                    E_INVALIDARG,
+                    "the software and GDI renderers have no Direct2D render target",
+                ));
+            }
+            #[cfg(feature = "wgpu")]
+            RendererConfig::Wgpu => {
+                return Err(Error::new(
+                    E_INVALIDARG,
+                    "the wgpu renderer has no Direct2D render target",
+                ));
+            }
+        };
+
+        let brush = unsafe {
+            render_target.SetAntialiasMode(self.antialias_mode.into());
+            if let Some(params) = &self.text_rendering_params {
+                render_target.SetTextRenderingParams(params);
+            }
+            render_target.CreateSolidColorBrush(&D2D1_COLOR_F { r: 1.0, g: 1.0, b: 1.0, a: 1.0 }, None)?
+        };
+
+        self.render_target = Some(render_target);
+        self.swap_chain = swap_chain;
+        self.brush = Some(brush);
+
+        Ok(())
+    }
+
+    /// Creates an `ID2D1HwndRenderTarget` of `width` by `height` pixels for `hwnd`, at
+    /// the window's DPI.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the window doesn't render with
+    /// `RendererConfig::Direct2D`, or if it fails to create the render target.
+    fn create_hwnd_render_target(
+        &self,
+        hwnd: HWND,
+        width: u32,
+        height: u32,
+    ) -> Result<ID2D1RenderTarget> {
+        let r#type = match self.renderer {
+            RendererConfig::Direct2D => D2D1_RENDER_TARGET_TYPE_DEFAULT,
+            // The device context of a swap chain is created by `SwapChainTarget`.
+            RendererConfig::SwapChain => {
+                return Err(Error::new(
+                    E_INVALIDARG,
+                    "a swap chain renderer has no HWND render target",
+                ));
+            }
+            RendererConfig::Software | RendererConfig::Gdi => {
+                return Err(Error::new(
+                    E_INVALIDARG,
+                    "the software and GDI renderers have no Direct2D render target",
+                ));
+            }
+            #[cfg(feature = "wgpu")]
+            RendererConfig::Wgpu => {
+                return Err(Error::new(
+                    E_INVALIDARG,
+                    "the wgpu renderer has no Direct2D render target",
+                ));
+            }
+        };
+        let dpi = unsafe { GetDpiForWindow(hwnd) } as f32;
+        let render_target_properties = D2D1_RENDER_TARGET_PROPERTIES {
+            r#type,
+            dpiX: dpi,
+            dpiY: dpi,
+            ..Default::default()
+        };
 
         let hwnd_render_target_properties = D2D1_HWND_RENDER_TARGET_PROPERTIES {
             hwnd,
-            pixelSize: D2D_SIZE_U {
-                width: (rect.right - rect.left) as u32,
-                height: (rect.bottom - rect.top) as u32,
-            },
-            presentOptions: D2D1_PRESENT_OPTIONS_NONE,
+            pixelSize: D2D_SIZE_U { width, height },
+            presentOptions: self.present_options(),
         };
 
         let render_target = unsafe {
-            let factory = self.d2d_factory.cast::<ID2D1Factory>()?;
+            let factory = self.graphics.d2d_factory.cast::<ID2D1Factory>()?;
             factory.CreateHwndRenderTarget(
                 &render_target_properties,
                 &hwnd_render_target_properties,
             )?
         };
+        render_target.cast()
+    }
+
+    /// Returns the presentation options of an HWND render target for the configured
+    /// present mode and `retain_contents`.
+    fn present_options(&self) -> D2D1_PRESENT_OPTIONS {
+        let mut options = match self.present_mode {
+            PresentMode::VSync => D2D1_PRESENT_OPTIONS_NONE,
+            PresentMode::Immediate => D2D1_PRESENT_OPTIONS_IMMEDIATELY,
+        };
+        if self.retain_contents {
+            options |= D2D1_PRESENT_OPTIONS_RETAIN_CONTENTS;
+        }
+        options
+    }
+
+    /// Returns the DPI of the render target, or 96 if it hasn't been created.
+    ///
+    /// Drawables are positioned in DIPs, device-independent pixels of 1/96 inch; the
+    /// render target scales them by `dpi() / 96.0` to get physical pixels.
+    pub fn dpi(&self) -> f32 {
+        let Some(render_target) = &self.render_target else {
+            return 96.0;
+        };
+        let (mut dpi_x, mut dpi_y) = (0.0, 0.0);
+        unsafe { render_target.GetDpi(&mut dpi_x, &mut dpi_y) };
+        dpi_x
+    }
+
+    /// Returns the size of the render target in DIPs, or `None` if it hasn't been
+    /// created.
+    ///
+    /// This is the area drawables can cover, in the units they are positioned in.
+    ///
+    /// # Safety
+    ///
+    /// This function contains an `unsafe` block for reading the size of the render
+    /// target.
+    pub fn size_dips(&self) -> Option<Vector2> {
+        let size = unsafe { self.render_target.as_ref()?.GetSize() };
+        Some(Vector2::new(size.width, size.height))
+    }
+
+    /// Sets the DPI of the render target, such as after the window moved to a monitor
+    /// with a different scale.
+    ///
+    /// The render target's size in pixels is unchanged; the window resizes it when its
+    /// size changes.
+    ///
+    /// # Safety
+    ///
+    /// This function contains an `unsafe` block for setting the DPI of the render
+    /// target.
+    pub fn set_dpi(&self, dpi: f32) {
+        if let Some(render_target) = &self.render_target {
+            unsafe { render_target.SetDpi(dpi, dpi) };
+        }
+    }
+
+    /// Resizes the render target to `width` by `height` pixels after the window has
+    /// been resized.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to resize the render target or
+    /// the swap chain's buffers.
+    ///
+    /// # Safety
+    ///
+    /// This function contains an `unsafe` block for resizing the HWND render target.
+    pub fn resize(&self, width: u32, height: u32) -> Result<()> {
+        if let Some(swap_chain) = &self.swap_chain {
+            return swap_chain.resize(width, height);
+        }
+        let Some(render_target) = &self.render_target else {
+            return Ok(());
+        };
+        let render_target: ID2D1HwndRenderTarget = render_target.cast()?;
+        unsafe { render_target.Resize(&D2D_SIZE_U { width, height }) }
+    }
+
+    /// Shows the frame drawn since the last call, if the render target doesn't show it
+    /// itself.
+    ///
+    /// This is called by the window after `EndDraw`. HWND render targets present in
+    /// `EndDraw`, so only swap chains have anything to do.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if presenting fails, such as when the
+    /// device is lost.
+    pub fn present(&self) -> Result<()> {
+        match &self.swap_chain {
+            Some(swap_chain) => swap_chain.present(self.present_mode),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the number of stroke style objects in the cache shared by drawables.
+    #[cfg(any(test, debug_assertions))]
+    pub fn stroke_style_cache_len(&self) -> usize {
+        self.stroke_styles.len()
+    }
+
+    /// Releases the resources tied to the rendering device, such as after the device
+    /// is lost, so `create_device_dependent_resources` can recreate them.
+    ///
+    /// This drops the render target, the swap chain and its device, the shared brush,
+    /// and the cached brushes.
+    pub fn release_device_dependent_resources(&mut self) {
+        self.render_target = None;
+        self.swap_chain = None;
+        self.brush = None;
+        self.brushes.clear();
+    }
+
+    /// Draws `scene` into a new bitmap of `width` by `height` pixels and returns its
+    /// pixels as RGBA8 with straight alpha, row by row from the top.
+    ///
+    /// This draws with a WIC bitmap render target of its own, so it needs no window
+    /// and doesn't touch the window's render target: a `Direct2DContext` created with
+    /// `new` can render thumbnails or exports without ever showing a window. The
+    /// bitmap starts out transparent and is drawn at 96 DPI, so one DIP is one pixel.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the text format has not been created, if
+    /// it fails to create the bitmap or its render target, if the scene fails to draw,
+    /// or if it fails to read the pixels back.
+    pub fn render_to_bitmap(&self, scene: &Scene, width: u32, height: u32) -> Result<Vec<u8>> {
+        self.render_offscreen(width, height, 96.0, |context| scene.draw_all(context))
+    }
 
+    /// Runs `draw` with a `DrawingContext` for a new, transparent bitmap of `width` by
+    /// `height` pixels at `dpi`, and returns its pixels as RGBA8 with straight alpha,
+    /// row by row from the top.
+    ///
+    /// This is what `render_to_bitmap` draws with, for callers that draw more than a
+    /// scene, such as `Window::capture_frame`. Like it, this needs no window.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the text format has not been created, if
+    /// it fails to create the bitmap or its render target, if `draw` fails, or if it
+    /// fails to read the pixels back.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for creating the bitmap and its render
+    /// target, drawing, and reading the pixels back.
+    pub fn render_offscreen(
+        &self,
+        width: u32,
+        height: u32,
+        dpi: f32,
+        draw: impl FnOnce(&DrawingContext) -> Result<()>,
+    ) -> Result<Vec<u8>> {
+        let text_format = self.require_text_format()?;
+        let bitmap = unsafe {
+            self.graphics.wic_factory.CreateBitmap(
+                width,
+                height,
+                &GUID_WICPixelFormat32bppPBGRA,
+                WICBitmapCacheOnLoad,
+            )?
+        };
+        let properties = D2D1_RENDER_TARGET_PROPERTIES {
+            pixelFormat: D2D1_PIXEL_FORMAT {
+                format: DXGI_FORMAT_B8G8R8A8_UNORM,
+                alphaMode: D2D1_ALPHA_MODE_PREMULTIPLIED,
+            },
+            dpiX: dpi,
+            dpiY: dpi,
+            ..Default::default()
+        };
+        let render_target = unsafe {
+            let factory: &ID2D1Factory = &self.graphics.d2d_factory;
+            factory.CreateWicBitmapRenderTarget(&bitmap, &properties)?
+        };
         let brush = unsafe {
-            let rt: &ID2D1RenderTarget = &render_target;
-            rt.CreateSolidColorBrush(&D2D1_COLOR_F { r: 1.0, g: 1.0, b: 1.0, a: 1.0 }, None)?
+            render_target.SetAntialiasMode(self.antialias_mode.into());
+            if let Some(params) = &self.text_rendering_params {
+                render_target.SetTextRenderingParams(params);
+            }
+            render_target.CreateSolidColorBrush(
+                &D2D1_COLOR_F {
+                    r: 1.0,
+                    g: 1.0,
+                    b: 1.0,
+                    a: 1.0,
+                },
+                None,
+            )?
         };
 
-        self.render_target = Some(render_target);
-        self.brush = Some(brush);
+        // The layers, transforms, and brushes belong to the window's frames, so the
+        // bitmap gets its own.
+        let brushes = BrushCache::new();
+        let layers = LayerPool::new();
+        let transforms = TransformStack::new();
+        let drawing_context = DrawingContext {
+            render_target: &render_target,
+            brush: &brush,
+            brushes: &brushes,
+            text_format,
+            dwrite_factory: &self.graphics.dwrite_factory,
+            d2d_factory: &self.graphics.d2d_factory,
+            stroke_styles: &self.stroke_styles,
+            layers: &layers,
+            transforms: &transforms,
+            wic_factory: &self.graphics.wic_factory,
+            text_options: self.text_options,
+            background_color: self.background_color,
+            d3d_device: None,
+            pixel_snap: self.pixel_snap,
+        };
 
-        Ok(())
+        unsafe {
+            render_target.BeginDraw();
+            render_target.Clear(Some(&D2D1_COLOR_F::default()));
+        }
+        let drawn = draw(&drawing_context);
+        drawing_context.end_frame();
+        unsafe { render_target.EndDraw(None, None)? };
+        drawn?;
+
+        let mut pixels = vec![0; width as usize * height as usize * 4];
+        unsafe { bitmap.CopyPixels(std::ptr::null(), width * 4, &mut pixels)? };
+        Ok(unpremultiply_bgra(&pixels))
+    }
+
+    /// Saves RGBA8 pixels with straight alpha, such as those returned by
+    /// `render_to_bitmap`, as a PNG file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `pixels` is not `width * height * 4` bytes
+    /// long, or, naming the file, if it fails to create the file or encode the image.
+    pub fn save_png(
+        &self,
+        path: impl AsRef<Path>,
+        width: u32,
+        height: u32,
+        pixels: &[u8],
+    ) -> Result<()> {
+        let expected = width as usize * height as usize * 4;
+        if pixels.len() != expected {
+            return Err(Error::new(
+                E_INVALIDARG,
+                format!(
+                    "expected {expected} bytes of {width}x{height} RGBA8 pixels, got {}",
+                    pixels.len()
+                ),
+            ));
+        }
+        encode_png(&self.graphics.wic_factory, path.as_ref(), width, height, pixels)
+    }
+
+    /// Measures `text` using the window's text format.
+    ///
+    /// This only needs device-independent resources, so it works before the render
+    /// target has been created or drawing has begun. Unset dimensions of the layout box
+    /// take the render target size if it exists, and are unbounded otherwise.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the text format has not been created, or
+    /// if it fails to create the text layout or to read its metrics.
+    pub fn measure_text(&self, text: &TextObject) -> Result<TextMetrics> {
+        let text_format = self.require_text_format()?;
+        text.measure(&self.graphics.dwrite_factory, text_format, self.layout_size())
+    }
+
+    /// Returns the bounding rectangle of `text` laid out with the window's text format,
+    /// in the same coordinates as the text's position.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the text format has not been created, or
+    /// if it fails to create the text layout or to read its metrics.
+    pub fn text_bounds(&self, text: &TextObject) -> Result<Rect> {
+        let text_format = self.require_text_format()?;
+        text.bounds(&self.graphics.dwrite_factory, text_format, self.layout_size())
     }
-}
\ No newline at end of file
+
+    /// Returns the position of each line of `text` laid out with the window's text
+    /// format.
+    ///
+    /// Like `measure_text`, this works before the render target has been created.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the text format has not been created, or
+    /// if it fails to create the text layout or to read its metrics.
+    pub fn text_line_metrics(&self, text: &TextObject) -> Result<Vec<LineMetrics>> {
+        let text_format = self.require_text_format()?;
+        text.line_metrics(&self.graphics.dwrite_factory, text_format, self.layout_size())
+    }
+
+    /// Finds the character of `text` nearest to `point`, which is relative to the
+    /// text's origin, using the window's text format.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the text format has not been created, or
+    /// if it fails to create or hit-test the text layout.
+    pub fn hit_test_point(&self, text: &TextObject, point: Vector2) -> Result<TextHit> {
+        let text_format = self.require_text_format()?;
+        text.hit_test_point(&self.graphics.dwrite_factory, text_format, self.layout_size(), point)
+    }
+
+    /// Returns where a caret placed before byte `index` of `text` is drawn, relative to
+    /// the text's origin, using the window's text format.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the text format has not been created, if
+    /// `index` is not a character boundary of the text, or if it fails to create or
+    /// hit-test the text layout.
+    pub fn hit_test_index(&self, text: &TextObject, index: usize) -> Result<CaretPosition> {
+        let text_format = self.require_text_format()?;
+        text.hit_test_index(&self.graphics.dwrite_factory, text_format, self.layout_size(), index)
+    }
+
+    /// Returns the rectangles covered by a byte range of `text`, relative to the text's
+    /// origin, using the window's text format.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the text format has not been created, if
+    /// the range is not a valid range of the text, or if it fails to create or hit-test
+    /// the text layout.
+    pub fn text_range_rects(&self, text: &TextObject, range: Range<usize>) -> Result<Vec<Rect>> {
+        let text_format = self.require_text_format()?;
+        text.range_rects(&self.graphics.dwrite_factory, text_format, self.layout_size(), range)
+    }
+
+    /// Returns the ascent, descent, and line gap of the window's font.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the text format has not been created, or
+    /// if the font family is not installed.
+    pub fn font_metrics(&self) -> Result<FontMetrics> {
+        FontMetrics::from_text_format(self.require_text_format()?)
+    }
+
+    /// Returns the window's text format.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the text format has not been created.
+    fn require_text_format(&self) -> Result<&IDWriteTextFormat> {
+        self.text_format
+            .as_ref()
+            .ok_or_else(|| Error::new(E_FAIL, "the text format has not been created"))
+    }
+
+    /// Returns the size used for unset dimensions of a text layout box: the render
+    /// target size if it exists, and unbounded otherwise.
+    fn layout_size(&self) -> D2D_SIZE_F {
+        match &self.render_target {
+            Some(render_target) => unsafe { render_target.GetSize() },
+            None => D2D_SIZE_F {
+                width: f32::MAX,
+                height: f32::MAX,
+            },
+        }
+    }
+}
+
+/// The locale used when the requested or user default locale is unavailable.
+const FALLBACK_LOCALE: &str = "en-us";
+
+/// Returns the user's default locale name, such as `ja-JP`, or `en-us` if it can't be
+/// read.
+///
+/// # Safety
+///
+/// This function contains an `unsafe` block for querying the user's locale.
+fn user_default_locale() -> String {
+    let mut buffer = [0u16; LOCALE_NAME_MAX_LENGTH as usize];
+    let length = unsafe { GetUserDefaultLocaleName(&mut buffer) };
+    if length <= 1 {
+        return FALLBACK_LOCALE.to_string();
+    }
+    // The length includes the terminating null character.
+    String::from_utf16_lossy(&buffer[..length as usize - 1])
+}
+
+/// Creates an error describing why the font file at `path` could not be loaded.
+fn font_file_error(path: &Path, code: HRESULT, reason: &str) -> Error {
+    Error::new(
+        code,
+        format!("failed to load font file {}: {reason}", path.display()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::render::objects::primitives::{Polyline, Rectangle};
+    use crate::core::render::stroke_style::StrokeStyle;
+
+    /// Returns the RGBA8 pixel at `(x, y)` of a `width`-pixel-wide bitmap.
+    fn pixel(pixels: &[u8], width: u32, x: u32, y: u32) -> [u8; 4] {
+        let start = (y * width + x) as usize * 4;
+        [
+            pixels[start],
+            pixels[start + 1],
+            pixels[start + 2],
+            pixels[start + 3],
+        ]
+    }
+
+    #[test]
+    fn renders_a_scene_without_a_window() {
+        let context = Direct2DContext::new(&WindowConfig::default()).unwrap();
+        let scene = crate::scene![Rectangle::new(16.0, 16.0, 32.0, 32.0, Color::RED)];
+
+        let pixels = context.render_to_bitmap(&scene, 64, 64).unwrap();
+        assert_eq!(pixels.len(), 64 * 64 * 4);
+        assert_eq!(pixel(&pixels, 64, 32, 32), [255, 0, 0, 255]);
+        // The bitmap starts out transparent.
+        assert_eq!(pixel(&pixels, 64, 4, 4), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn save_png_checks_the_pixel_count() {
+        let context = Direct2DContext::new(&WindowConfig::default()).unwrap();
+        let path = std::env::temp_dir().join("my_gui_save_png_test.png");
+
+        let error = context.save_png(&path, 4, 4, &[0; 15]).unwrap_err();
+        assert_eq!(error.code(), E_INVALIDARG);
+
+        let pixels = [255, 0, 0, 255, 0, 0, 255, 128];
+        context.save_png(&path, 2, 1, &pixels).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert!(bytes.starts_with(b"\x89PNG\r\n\x1a\n"));
+    }
+
+    #[test]
+    fn drawing_reuses_cached_stroke_styles() {
+        let context = Direct2DContext::new(&WindowConfig::default()).unwrap();
+        let mut border = Rectangle::outlined(8.0, 8.0, 16.0, 16.0, Color::RED, 2.0);
+        border.stroke_style = StrokeStyle::Dash;
+        let scene = crate::scene![border.clone(), border];
+
+        context.render_to_bitmap(&scene, 32, 32).unwrap();
+        assert_eq!(context.stroke_style_cache_len(), 1);
+        context.render_to_bitmap(&scene, 32, 32).unwrap();
+        assert_eq!(context.stroke_style_cache_len(), 1);
+    }
+
+    #[test]
+    fn culling_keeps_a_miter_joined_corner_reaching_into_view() {
+        let context = Direct2DContext::new(&WindowConfig::default()).unwrap();
+        // Every point lies left of the bitmap, and so does the box around them grown by
+        // half the stroke width, but the sharp miter at the middle point reaches about
+        // 6.6 DIPs into it.
+        let corner = Polyline::new(
+            vec![
+                Vector2 { X: -42.0, Y: 20.0 },
+                Vector2 { X: -6.0, Y: 32.0 },
+                Vector2 { X: -42.0, Y: 44.0 },
+            ],
+            8.0,
+            Color::RED,
+        );
+        let scene = crate::scene![corner];
+
+        let pixels = context.render_to_bitmap(&scene, 64, 64).unwrap();
+        assert!(pixel(&pixels, 64, 3, 32)[3] > 0);
+    }
+}
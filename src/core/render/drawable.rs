@@ -1,5 +1,9 @@
+use std::any::Any;
+use std::time::Duration;
 
+use crate::core::backend::Renderer;
 use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::rect::Rect;
 use windows::core::Result;
 
 /// A trait for objects that can be drawn to a `DrawingContext`.
@@ -9,11 +13,110 @@ use windows::core::Result;
 /// rendering pipeline. This abstraction allows the `Scene` to manage a heterogeneous
 /// collection of different drawable types (e.g., text, shapes, images) without knowing
 /// their concrete implementations.
-pub trait Drawable {
+///
+/// Every `Drawable` can be converted to `&dyn Any`, through the blanket `AsAny`
+/// implementation, so a `Scene` can hand its objects back as their concrete types.
+pub trait Drawable: AsAny {
     /// Draws the object to the given `DrawingContext`.
     ///
     /// # Arguments
     ///
     /// * `context` - The `DrawingContext` to draw to.
     fn draw(&self, context: &DrawingContext) -> Result<()>;
+
+    /// Draws the object through a backend-independent `Renderer`, such as the
+    /// `RecordingRenderer` used in tests.
+    ///
+    /// `Renderer` only covers solid shapes, lines, and plain text, so this may draw a
+    /// simplified version of what `draw` draws. The built-in shapes, `TextObject`,
+    /// `Canvas`, and `Group` implement it. The default implementation draws nothing, so
+    /// objects that only implement `draw` are skipped by the other backends.
+    fn render(&self, _renderer: &mut dyn Renderer) {}
+
+    /// Advances the object's animation state by `dt`.
+    ///
+    /// This is called once per frame, before the scene is drawn, when an
+    /// `AnimationHandler` is installed. The default implementation does nothing, so
+    /// static objects don't need to implement it.
+    ///
+    /// # Arguments
+    ///
+    /// * `dt` - The time elapsed since the previous frame.
+    fn update(&mut self, _dt: Duration) {}
+
+    /// Returns the rectangle the object covers when drawn, in the same coordinates as
+    /// its position, or `None` if it has no known extent.
+    ///
+    /// The bounds are used by `Scene::hit_test` to find the objects under a point, and
+    /// by `Scene::draw_all` to skip objects outside the window, so they must contain
+    /// everything the object draws. They include strokes and transforms, and may be
+    /// larger than the drawn shape. The default implementation returns `None`, which
+    /// makes the object impossible to hit and always drawn.
+    fn bounds(&self) -> Option<Rect> {
+        None
+    }
+
+    /// Returns an independent copy of the object, or `None` if it can't be copied.
+    ///
+    /// This is used by `Scene::snapshot` to copy a whole scene, for example to
+    /// implement undo. Every built-in drawable can be copied. Types that implement
+    /// `Clone` can implement this method with the `impl_clone_box!` macro. The default
+    /// implementation returns `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use my_gui::core::render::{drawable::Drawable, drawing_context::DrawingContext};
+    /// use windows::core::Result;
+    ///
+    /// #[derive(Clone)]
+    /// struct Blank;
+    ///
+    /// impl Drawable for Blank {
+    ///     fn draw(&self, _context: &DrawingContext) -> Result<()> {
+    ///         Ok(())
+    ///     }
+    ///
+    ///     my_gui::impl_clone_box!();
+    /// }
+    /// ```
+    fn clone_box(&self) -> Option<Box<dyn Drawable>> {
+        None
+    }
+}
+
+/// Implements `Drawable::clone_box` inside an `impl Drawable` block for a type that
+/// implements `Clone`.
+#[macro_export]
+macro_rules! impl_clone_box {
+    () => {
+        fn clone_box(
+            &self,
+        ) -> ::std::option::Option<::std::boxed::Box<dyn $crate::core::render::drawable::Drawable>>
+        {
+            ::std::option::Option::Some(::std::boxed::Box::new(::std::clone::Clone::clone(self)))
+        }
+    };
+}
+
+/// Converts a value to `&dyn Any` so it can be downcast to its concrete type.
+///
+/// This is implemented for every `'static` type, so implementors of `Drawable` never
+/// need to implement it themselves.
+pub trait AsAny {
+    /// Returns the value as `&dyn Any`.
+    fn as_any(&self) -> &dyn Any;
+
+    /// Returns the value as `&mut dyn Any`.
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
@@ -1,10 +1,48 @@
 
 use windows::{
+    Win32::Foundation::{E_NOTIMPL, HANDLE},
+    Win32::Graphics::Direct2D::Common::{
+        D2D1_ALPHA_MODE_PREMULTIPLIED, D2D1_PIXEL_FORMAT, D2D_RECT_F,
+    },
+    Win32::Graphics::Direct2D::ID2D1Bitmap,
+    Win32::Graphics::Direct2D::ID2D1Bitmap1,
+    Win32::Graphics::Direct2D::ID2D1DeviceContext,
+    Win32::Graphics::Direct2D::ID2D1Factory1,
+    Win32::Graphics::Direct2D::ID2D1Geometry,
     Win32::Graphics::Direct2D::ID2D1RenderTarget,
     Win32::Graphics::Direct2D::ID2D1SolidColorBrush,
+    Win32::Graphics::Direct2D::ID2D1StrokeStyle,
+    Win32::Graphics::Direct2D::D2D1_BITMAP_PROPERTIES1,
+    Win32::Graphics::Direct2D::D2D1_DRAW_TEXT_OPTIONS,
+    Win32::Graphics::Direct2D::D2D1_LAYER_OPTIONS_NONE,
+    Win32::Graphics::Direct2D::D2D1_LAYER_PARAMETERS,
+    Win32::Graphics::Direct3D11::ID3D11Device,
     Win32::Graphics::DirectWrite::{IDWriteFactory, IDWriteTextFormat},
+    Win32::Graphics::Dxgi::IDXGISurface,
+    Win32::Graphics::Imaging::IWICImagingFactory,
 };
 
+use std::mem::ManuallyDrop;
+use std::ops::Range;
+
+use windows::core::{Error, Interface, Result};
+use windows_numerics::{Matrix3x2, Vector2};
+
+use crate::core::render::antialias::AntialiasMode;
+use crate::core::render::brush_cache::BrushCache;
+use crate::core::render::color::Color;
+use crate::core::render::geometry::{Geometry, GeometryStroke};
+use crate::core::render::image_source::{ImageSource, Interpolation};
+use crate::core::render::layer::{LayerOptions, LayerPool, Pushed};
+use crate::core::render::objects::text_object::{
+    CaretPosition, FontMetrics, LineMetrics, TextHit, TextMetrics, TextObject,
+};
+use crate::core::render::rect::Rect;
+use crate::core::render::stroke_style::{
+    LineCap, LineJoin, StrokeStyle, StrokeStyleCache, DEFAULT_MITER_LIMIT,
+};
+use crate::core::render::transform::{invert, TransformStack};
+
 /// A context for drawing operations.
 ///
 /// This struct bundles the necessary Direct2D resources for drawing, making it
@@ -14,8 +52,857 @@ pub struct DrawingContext<'a> {
     pub render_target: &'a ID2D1RenderTarget,
     /// The brush to use for drawing.
     pub brush: &'a ID2D1SolidColorBrush,
+    /// The cache of solid color brushes used by `with_brush_color`.
+    pub brushes: &'a BrushCache,
     /// The text format to use for drawing text.
     pub text_format: &'a IDWriteTextFormat,
     // The DirectWrite factory for creating text layouts.
     pub dwrite_factory: &'a IDWriteFactory,
+    /// The Direct2D factory for creating device-independent resources such as geometries.
+    pub d2d_factory: &'a ID2D1Factory1,
+    /// The cache of stroke style objects shared by all drawables.
+    pub stroke_styles: &'a StrokeStyleCache,
+    /// The layers pushed with `push_layer`, and a pool of layers to reuse.
+    pub layers: &'a LayerPool,
+    /// The transforms replaced by `push_transform`.
+    pub transforms: &'a TransformStack,
+    /// The WIC factory for decoding images.
+    pub wic_factory: &'a IWICImagingFactory,
+    /// The options used when drawing text, such as whether color fonts are enabled.
+    pub text_options: D2D1_DRAW_TEXT_OPTIONS,
+    /// The color the window is cleared to before the scene is drawn.
+    pub background_color: Color,
+    /// The Direct3D device of the render target, if it is drawn with
+    /// `RendererConfig::SwapChain`.
+    pub d3d_device: Option<&'a ID3D11Device>,
+    /// Whether strokes and fills are aligned to device pixels. See `snap_stroke_width`.
+    pub pixel_snap: bool,
+}
+
+impl DrawingContext<'_> {
+    /// Runs `f` with a brush of `color`.
+    ///
+    /// The brush comes from the `BrushCache`, so drawing the same color again doesn't
+    /// create a brush. It is given the shared brush's opacity, so `with_opacity`
+    /// applies to it. If the brush can't be created, the shared brush is temporarily
+    /// set to `color` instead, and its previous color is restored afterwards, so
+    /// drawables that rely on the default brush color (such as `TextObject`) are
+    /// unaffected.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for reading and setting the brush color
+    /// and opacity. The brushes are guaranteed to be valid for the lifetime of the
+    /// `DrawingContext`.
+    pub fn with_brush_color<R>(
+        &self,
+        color: &Color,
+        f: impl FnOnce(&ID2D1SolidColorBrush) -> R,
+    ) -> R {
+        if let Ok(brush) = self.brushes.get(self.render_target, color) {
+            unsafe { brush.SetOpacity(self.brush.GetOpacity()) };
+            return f(&brush);
+        }
+
+        let previous = unsafe { self.brush.GetColor() };
+        unsafe { self.brush.SetColor(&(*color).into()) };
+        let result = f(self.brush);
+        unsafe { self.brush.SetColor(&previous) };
+        result
+    }
+
+    /// Runs `f` with the shared brush's opacity multiplied by `opacity`.
+    ///
+    /// The opacity is clamped to `[0, 1]`. A fully transparent drawable is skipped
+    /// without calling `f`. The brush's previous opacity is restored afterwards.
+    ///
+    /// # Errors
+    ///
+    /// This function will return any error returned by `f`.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for reading and setting the brush opacity.
+    /// The brush is guaranteed to be valid for the lifetime of the `DrawingContext`.
+    pub fn with_opacity(&self, opacity: f32, f: impl FnOnce() -> Result<()>) -> Result<()> {
+        let opacity = opacity.clamp(0.0, 1.0);
+        if opacity <= 0.0 {
+            return Ok(());
+        }
+        if opacity >= 1.0 {
+            return f();
+        }
+
+        let previous = unsafe { self.brush.GetOpacity() };
+        unsafe { self.brush.SetOpacity(previous * opacity) };
+        let result = f();
+        unsafe { self.brush.SetOpacity(previous) };
+        result
+    }
+
+    /// Measures `text` as it will be laid out when drawn with this context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create the text layout or to
+    /// read its metrics.
+    pub fn measure_text(&self, text: &TextObject) -> Result<TextMetrics> {
+        let size = unsafe { self.render_target.GetSize() };
+        text.measure(self.dwrite_factory, self.text_format, size)
+    }
+
+    /// Returns the bounding rectangle of `text` as it will be laid out when drawn with
+    /// this context, in the same coordinates as the text's position.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create the text layout or to
+    /// read its metrics.
+    pub fn text_bounds(&self, text: &TextObject) -> Result<Rect> {
+        let size = unsafe { self.render_target.GetSize() };
+        text.bounds(self.dwrite_factory, self.text_format, size)
+    }
+
+    /// Returns the position of each line of `text` as it will be laid out when drawn
+    /// with this context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create the text layout or to
+    /// read its metrics.
+    pub fn text_line_metrics(&self, text: &TextObject) -> Result<Vec<LineMetrics>> {
+        let size = unsafe { self.render_target.GetSize() };
+        text.line_metrics(self.dwrite_factory, self.text_format, size)
+    }
+
+    /// Finds the character of `text` nearest to `point`, which is relative to the
+    /// text's origin.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create or hit-test the text
+    /// layout.
+    pub fn hit_test_point(&self, text: &TextObject, point: Vector2) -> Result<TextHit> {
+        let size = unsafe { self.render_target.GetSize() };
+        text.hit_test_point(self.dwrite_factory, self.text_format, size, point)
+    }
+
+    /// Returns where a caret placed before byte `index` of `text` is drawn, relative to
+    /// the text's origin.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `index` is not a character boundary of
+    /// the text, or if it fails to create or hit-test the text layout.
+    pub fn hit_test_index(&self, text: &TextObject, index: usize) -> Result<CaretPosition> {
+        let size = unsafe { self.render_target.GetSize() };
+        text.hit_test_index(self.dwrite_factory, self.text_format, size, index)
+    }
+
+    /// Returns the rectangles covered by a byte range of `text`, relative to the text's
+    /// origin.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the range is not a valid range of the
+    /// text, or if it fails to create or hit-test the text layout.
+    pub fn text_range_rects(&self, text: &TextObject, range: Range<usize>) -> Result<Vec<Rect>> {
+        let size = unsafe { self.render_target.GetSize() };
+        text.range_rects(self.dwrite_factory, self.text_format, size, range)
+    }
+
+    /// Returns the part of the render target that is visible, in the coordinates of the
+    /// current transform.
+    ///
+    /// Drawing entirely outside this rectangle has no visible effect. Returns `None` if
+    /// the current transform can't be inverted, such as a scale of zero.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for reading the render target's size and
+    /// transform. The render target is guaranteed to be valid for the lifetime of the
+    /// `DrawingContext`.
+    pub fn visible_rect(&self) -> Option<Rect> {
+        let size = unsafe { self.render_target.GetSize() };
+        let inverse = invert(&self.transform())?;
+        Some(Rect::new(0.0, 0.0, size.width, size.height).transform(&inverse))
+    }
+
+    /// Returns the DPI of the render target.
+    ///
+    /// Coordinates passed to the context are DIPs, 1/96 inch each, so a drawable only
+    /// needs the DPI to work in physical pixels, such as to align lines to them.
+    ///
+    /// # Safety
+    ///
+    /// This function contains an `unsafe` block for reading the render target's DPI.
+    /// The render target is guaranteed to be valid for the lifetime of the
+    /// `DrawingContext`.
+    pub fn dpi(&self) -> f32 {
+        let (mut dpi_x, mut dpi_y) = (0.0, 0.0);
+        unsafe { self.render_target.GetDpi(&mut dpi_x, &mut dpi_y) };
+        dpi_x
+    }
+
+    /// Returns `stroke_width` rounded to whole device pixels if pixel snapping is on.
+    ///
+    /// The width is rounded to the nearest number of device pixels, with halves rounded
+    /// down, and is at least one pixel, so a 1 DIP line is a hairline exactly one pixel
+    /// wide at 100% and 150% scale alike. Drawables that snap their strokes pass the
+    /// snapped width on to `snap_stroke_point` or `snap_stroke_rect`.
+    ///
+    /// Without `WindowConfig::pixel_snap`, or under a transform that rotates or skews,
+    /// nothing is snapped and the width is returned unchanged.
+    pub fn snap_stroke_width(&self, stroke_width: f32) -> f32 {
+        let Some(grid) = self.pixel_grid() else {
+            return stroke_width;
+        };
+        let scale = grid.scale.X.abs();
+        stroke_width_pixels(stroke_width * scale) / scale
+    }
+
+    /// Returns `point` moved so a stroke `stroke_width` wide through it covers whole
+    /// device pixels if pixel snapping is on.
+    ///
+    /// Strokes an odd number of pixels wide are centered on a pixel, half a pixel off
+    /// the pixel grid, and even ones on a grid line, so neither is smeared over a
+    /// partially covered pixel on each side.
+    pub fn snap_stroke_point(&self, point: Vector2, stroke_width: f32) -> Vector2 {
+        let Some(grid) = self.pixel_grid() else {
+            return point;
+        };
+        grid.snap(point, |pixel, scale| {
+            if stroke_width_pixels(stroke_width * scale.abs()) % 2.0 == 1.0 {
+                pixel.floor() + 0.5
+            } else {
+                pixel.round()
+            }
+        })
+    }
+
+    /// Returns `rect` with its edges moved to the nearest device pixel boundaries if
+    /// pixel snapping is on, so a fill doesn't blend into its neighbors at the edges.
+    pub fn snap_rect(&self, rect: &Rect) -> Rect {
+        let Some(grid) = self.pixel_grid() else {
+            return *rect;
+        };
+        let round = |pixel: f32, _: f32| pixel.round();
+        let top_left = grid.snap(Vector2::new(rect.x, rect.y), round);
+        let bottom_right = grid.snap(Vector2::new(rect.right(), rect.bottom()), round);
+        Rect::new(
+            top_left.X,
+            top_left.Y,
+            bottom_right.X - top_left.X,
+            bottom_right.Y - top_left.Y,
+        )
+    }
+
+    /// Returns `rect` with its corners snapped with `snap_stroke_point`, for outlining
+    /// it with a stroke `stroke_width` wide.
+    pub fn snap_stroke_rect(&self, rect: &Rect, stroke_width: f32) -> Rect {
+        let top_left = self.snap_stroke_point(Vector2::new(rect.x, rect.y), stroke_width);
+        let bottom_right =
+            self.snap_stroke_point(Vector2::new(rect.right(), rect.bottom()), stroke_width);
+        Rect::new(
+            top_left.X,
+            top_left.Y,
+            bottom_right.X - top_left.X,
+            bottom_right.Y - top_left.Y,
+        )
+    }
+
+    /// Returns how coordinates map to device pixels, or `None` if pixel snapping is off
+    /// or the current transform rotates, skews, or collapses them.
+    fn pixel_grid(&self) -> Option<PixelGrid> {
+        if !self.pixel_snap {
+            return None;
+        }
+        let transform = self.transform();
+        if transform.M12 != 0.0
+            || transform.M21 != 0.0
+            || transform.M11 == 0.0
+            || transform.M22 == 0.0
+        {
+            return None;
+        }
+        let scale = self.dpi() / 96.0;
+        Some(PixelGrid {
+            scale: Vector2::new(transform.M11 * scale, transform.M22 * scale),
+            offset: Vector2::new(transform.M31 * scale, transform.M32 * scale),
+        })
+    }
+
+    /// Returns the ascent, descent, and line gap of the context's font.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the font family is not installed.
+    pub fn font_metrics(&self) -> Result<FontMetrics> {
+        FontMetrics::from_text_format(self.text_format)
+    }
+
+    /// Returns the Direct2D stroke style object for the given dash pattern, caps, and
+    /// joins with the default miter limit, or `None` for a plain solid stroke.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create the stroke style.
+    pub fn stroke_style(
+        &self,
+        style: &StrokeStyle,
+        cap: LineCap,
+        join: LineJoin,
+    ) -> Result<Option<ID2D1StrokeStyle>> {
+        self.stroke_style_with_miter_limit(style, cap, join, DEFAULT_MITER_LIMIT)
+    }
+
+    /// Returns the Direct2D stroke style object for the given dash pattern, caps,
+    /// joins, and miter limit, or `None` for a plain solid stroke.
+    ///
+    /// The miter limit is a multiple of half the stroke width; miter joins that would
+    /// extend further are beveled.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create the stroke style.
+    pub fn stroke_style_with_miter_limit(
+        &self,
+        style: &StrokeStyle,
+        cap: LineCap,
+        join: LineJoin,
+        miter_limit: f32,
+    ) -> Result<Option<ID2D1StrokeStyle>> {
+        self.stroke_styles.get(self.d2d_factory, style, cap, join, miter_limit)
+    }
+
+    /// Fills and then strokes `geometry`, each only if given.
+    ///
+    /// The fill and stroke are drawn with the shared brush, whose opacity applies. The
+    /// stroke's dash pattern, caps, and joins are taken from the stroke style cache. The
+    /// geometry's Direct2D path geometry is built on the first call and reused until
+    /// its figures change. Empty geometries and strokes of zero width are skipped.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to build the path geometry or
+    /// to create the stroke style.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for calling Direct2D drawing methods. The
+    /// render target is guaranteed to be valid for the lifetime of the `DrawingContext`.
+    pub fn draw_geometry(
+        &self,
+        geometry: &Geometry,
+        fill: Option<&Color>,
+        stroke: Option<GeometryStroke<'_>>,
+    ) -> Result<()> {
+        let stroke = stroke.filter(|stroke| stroke.width > 0.0);
+        if geometry.is_empty() || (fill.is_none() && stroke.is_none()) {
+            return Ok(());
+        }
+
+        let path_geometry = geometry.path_geometry(self)?;
+        if let Some(fill) = fill {
+            self.with_brush_color(fill, |brush| unsafe {
+                self.render_target.FillGeometry(&path_geometry, brush, None);
+            });
+        }
+        if let Some(stroke) = stroke {
+            let stroke_style = self.stroke_style(stroke.style, stroke.line_cap, stroke.line_join)?;
+            self.with_brush_color(stroke.color, |brush| unsafe {
+                self.render_target.DrawGeometry(
+                    &path_geometry,
+                    brush,
+                    stroke.width,
+                    stroke_style.as_ref(),
+                );
+            });
+        }
+        Ok(())
+    }
+
+    /// Draws `source`, or the `source_rect` region of it in image pixels, stretched to
+    /// fill `destination`.
+    ///
+    /// The opacity is clamped to `[0, 1]`, and a fully transparent image is skipped.
+    /// The source's bitmap is created for the render target on first use and recreated
+    /// if the render target changes. See `ImageSource`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the image file cannot be decoded or if it
+    /// fails to create the Direct2D bitmap.
+    ///
+    /// # Safety
+    ///
+    /// This function contains an `unsafe` block for calling `DrawBitmap`. The render
+    /// target is guaranteed to be valid for the lifetime of the `DrawingContext`.
+    pub fn draw_bitmap(
+        &self,
+        source: &ImageSource,
+        destination: &Rect,
+        source_rect: Option<&Rect>,
+        opacity: f32,
+        interpolation: Interpolation,
+    ) -> Result<()> {
+        let opacity = opacity.clamp(0.0, 1.0);
+        if opacity <= 0.0 {
+            return Ok(());
+        }
+
+        let bitmap = source.bitmap(self.wic_factory, self.render_target)?;
+        self.draw_d2d_bitmap(&bitmap, destination, source_rect, opacity, interpolation);
+        Ok(())
+    }
+
+    /// Draws a Direct2D bitmap created for the render target, or the `source_rect`
+    /// region of it in pixels, stretched to fill `destination`.
+    ///
+    /// This is what `draw_bitmap` draws with, for drawables that create and update
+    /// their own bitmaps instead of decoding an `ImageSource`, such as `PixelBuffer`.
+    /// The opacity is clamped to `[0, 1]`, and a fully transparent bitmap is skipped.
+    ///
+    /// # Safety
+    ///
+    /// This function contains an `unsafe` block for calling `DrawBitmap`. The caller
+    /// must ensure that `bitmap` was created for this context's render target.
+    pub(crate) fn draw_d2d_bitmap(
+        &self,
+        bitmap: &ID2D1Bitmap,
+        destination: &Rect,
+        source_rect: Option<&Rect>,
+        opacity: f32,
+        interpolation: Interpolation,
+    ) {
+        let opacity = opacity.clamp(0.0, 1.0);
+        if opacity <= 0.0 {
+            return;
+        }
+
+        let destination: D2D_RECT_F = (*destination).into();
+        let source_rect: Option<D2D_RECT_F> = source_rect.map(|rect| (*rect).into());
+        unsafe {
+            self.render_target.DrawBitmap(
+                bitmap,
+                Some(&destination),
+                opacity,
+                interpolation.into(),
+                source_rect.as_ref().map(|rect| rect as *const _),
+            );
+        }
+    }
+
+    /// Opens a texture shared by another Direct3D device, such as an application's own
+    /// 3D renderer, by the handle from its `IDXGIResource::GetSharedHandle`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the render target isn't drawn with
+    /// `RendererConfig::SwapChain`, which is the only renderer with a Direct3D device,
+    /// or if the handle can't be opened.
+    ///
+    /// # Safety
+    ///
+    /// This function contains an `unsafe` block for opening the shared resource. The
+    /// caller must provide a valid shared handle.
+    pub fn open_shared_surface(&self, handle: HANDLE) -> Result<IDXGISurface> {
+        let Some(device) = self.d3d_device else {
+            return Err(Error::new(
+                E_NOTIMPL,
+                "shared surfaces require RendererConfig::SwapChain",
+            ));
+        };
+        unsafe { device.OpenSharedResource(handle) }
+    }
+
+    /// Creates a bitmap that draws the contents of `surface`, without copying them.
+    ///
+    /// The surface must belong to the render target's Direct3D device, such as one
+    /// returned by `open_shared_surface`. The bitmap is at 96 DPI, so one of its pixels
+    /// is one DIP unless it is stretched.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the render target isn't a device context,
+    /// which requires `RendererConfig::SwapChain`, or if the bitmap can't be created.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for reading the surface's format and
+    /// creating the bitmap.
+    pub fn create_bitmap_from_dxgi_surface(&self, surface: &IDXGISurface) -> Result<ID2D1Bitmap1> {
+        let device_context: ID2D1DeviceContext = self.render_target.cast()?;
+        let description = unsafe { surface.GetDesc()? };
+        let properties = D2D1_BITMAP_PROPERTIES1 {
+            pixelFormat: D2D1_PIXEL_FORMAT {
+                format: description.Format,
+                alphaMode: D2D1_ALPHA_MODE_PREMULTIPLIED,
+            },
+            dpiX: 96.0,
+            dpiY: 96.0,
+            ..Default::default()
+        };
+        unsafe { device_context.CreateBitmapFromDxgiSurface(surface, Some(&properties)) }
+    }
+
+    /// Returns how the edges of shapes, clips, and masks are currently drawn.
+    ///
+    /// # Safety
+    ///
+    /// This function contains an `unsafe` block for reading the render target's
+    /// antialiasing mode. The render target is guaranteed to be valid for the lifetime
+    /// of the `DrawingContext`.
+    pub fn antialias_mode(&self) -> AntialiasMode {
+        unsafe { self.render_target.GetAntialiasMode() }.into()
+    }
+
+    /// Sets how the edges of shapes, clips, and masks are drawn from now on.
+    ///
+    /// The window's render target starts each frame with `WindowConfig::antialias_mode`
+    /// unless this is called, and keeps the last mode set between frames, so prefer
+    /// `with_antialias_mode` for changes that should only apply to some drawables.
+    ///
+    /// # Safety
+    ///
+    /// This function contains an `unsafe` block for setting the render target's
+    /// antialiasing mode. The render target is guaranteed to be valid for the lifetime
+    /// of the `DrawingContext`.
+    pub fn set_antialias_mode(&self, mode: AntialiasMode) {
+        unsafe { self.render_target.SetAntialiasMode(mode.into()) };
+    }
+
+    /// Runs `f` with the antialiasing mode set to `mode`, restoring the previous mode
+    /// afterwards.
+    pub fn with_antialias_mode<R>(&self, mode: AntialiasMode, f: impl FnOnce() -> R) -> R {
+        let previous = self.antialias_mode();
+        self.set_antialias_mode(mode);
+        let result = f();
+        self.set_antialias_mode(previous);
+        result
+    }
+
+    /// Returns the render target's current transform, from the coordinates drawables
+    /// draw in to window DIPs.
+    ///
+    /// # Safety
+    ///
+    /// This function contains an `unsafe` block for reading the render target
+    /// transform. The render target is guaranteed to be valid for the lifetime of the
+    /// `DrawingContext`.
+    pub fn transform(&self) -> Matrix3x2 {
+        let mut transform = Matrix3x2::default();
+        unsafe { self.render_target.GetTransform(&mut transform) };
+        transform
+    }
+
+    /// Runs `f` with `local` composed onto the render target's current transform.
+    ///
+    /// The local transform is applied before the current one, so coordinates drawn by
+    /// `f` are first transformed locally and then by any enclosing transform. The
+    /// original transform is restored afterwards. See `push_transform`.
+    ///
+    /// If `f` leaves transforms of its own pushed, the mismatch is reported, and they
+    /// are popped together with this one.
+    pub fn with_transform<R>(&self, local: &Matrix3x2, f: impl FnOnce() -> R) -> R {
+        let depth = self.transforms.depth();
+        self.push_transform(local);
+        let result = f();
+        if self.transforms.depth() != depth + 1 {
+            report_unbalanced("a transform pushed inside `with_transform` was not popped by it");
+        }
+        while self.transforms.depth() > depth {
+            self.pop_transform();
+        }
+        result
+    }
+
+    /// Composes `local` onto the render target's current transform until the matching
+    /// `pop_transform`.
+    ///
+    /// The local transform is applied before the current one, as in `with_transform`,
+    /// which is usually more convenient. Pushes nest, and each pop restores the
+    /// transform from before its push. In debug builds, a pop without a matching push
+    /// panics, as does a frame that ends with transforms still pushed. In release
+    /// builds, the mismatch is logged and recovered from.
+    ///
+    /// # Safety
+    ///
+    /// This function contains an `unsafe` block for setting the render target
+    /// transform. The render target is guaranteed to be valid for the lifetime of the
+    /// `DrawingContext`.
+    pub fn push_transform(&self, local: &Matrix3x2) {
+        let original = self.transform();
+        self.transforms.push(original);
+        unsafe { self.render_target.SetTransform(&(*local * original)) };
+    }
+
+    /// Restores the transform from before the innermost unpopped `push_transform`.
+    ///
+    /// A pop without a matching push panics in debug builds and is logged and ignored
+    /// in release builds.
+    ///
+    /// # Safety
+    ///
+    /// This function contains an `unsafe` block for setting the render target
+    /// transform. The render target is guaranteed to be valid for the lifetime of the
+    /// `DrawingContext`.
+    pub fn pop_transform(&self) {
+        match self.transforms.pop() {
+            Some(original) => unsafe { self.render_target.SetTransform(&original) },
+            None => report_unbalanced("`pop_transform` called without a matching `push_transform`"),
+        }
+    }
+
+    /// Runs `f` with drawing clipped to `rect`, in the coordinates of the current
+    /// transform.
+    ///
+    /// While the current transform keeps the rectangle axis-aligned, such as for
+    /// translation and scaling, a cheap axis-aligned clip is used. Otherwise, such as
+    /// under rotation, the rectangle is clipped exactly through a layer masked by it.
+    /// See `push_layer`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create the layer or its mask.
+    pub fn with_clip<R>(&self, rect: &Rect, f: impl FnOnce() -> R) -> Result<R> {
+        self.with_layer(&LayerOptions::new().with_clip(*rect), f)
+    }
+
+    /// Runs `f` with everything it draws composited onto the render target as a group
+    /// with the given opacity.
+    ///
+    /// Unlike `with_opacity`, which fades each shape separately, the group is drawn
+    /// into a layer first, so overlapping shapes don't show through each other. The
+    /// opacity is clamped to `[0, 1]`. A fully transparent group is skipped without
+    /// calling `f`, and a fully opaque one is drawn directly, without a layer.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create the layer, or any
+    /// error returned by `f`.
+    pub fn with_group_opacity(&self, opacity: f32, f: impl FnOnce() -> Result<()>) -> Result<()> {
+        let opacity = opacity.clamp(0.0, 1.0);
+        if opacity <= 0.0 {
+            return Ok(());
+        }
+        if opacity >= 1.0 {
+            return f();
+        }
+        self.with_layer(&LayerOptions::new().with_opacity(opacity), f)?
+    }
+
+    /// Runs `f` inside a layer pushed with the given options, popping it afterwards.
+    ///
+    /// If `f` leaves layers of its own pushed, the mismatch is reported, and they are
+    /// popped together with this one.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to push the layer.
+    pub fn with_layer<R>(&self, options: &LayerOptions, f: impl FnOnce() -> R) -> Result<R> {
+        let depth = self.layers.depth();
+        self.push_layer(options)?;
+        let result = f();
+        if self.layers.depth() != depth + 1 {
+            report_unbalanced("a layer pushed inside `with_layer` was not popped by it");
+        }
+        while self.layers.depth() > depth {
+            self.pop_layer();
+        }
+        Ok(result)
+    }
+
+    /// Pushes a layer that clips, masks, and fades everything drawn until the matching
+    /// `pop_layer`.
+    ///
+    /// Each part of the options is only applied if it changes anything, using the
+    /// cheapest way Direct2D offers: a clip rectangle that the current transform keeps
+    /// axis-aligned becomes an axis-aligned clip, and a layer is only pushed for a
+    /// mask, an opacity below 1, or a clip rectangle that is rotated or skewed. Layers
+    /// are taken from a pool and returned to it when popped. See `LayerPool`.
+    ///
+    /// Layers and `with_clip` or `with_group_opacity` calls nest in any order, as long
+    /// as every push is popped inside the closure or layer it was pushed in. In debug
+    /// builds, a pop without a matching push panics, as does a frame that ends with
+    /// layers still pushed. In release builds, the mismatch is logged and recovered
+    /// from.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create the layer or its mask.
+    /// Nothing is left pushed in that case, and `pop_layer` must not be called.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for reading the render target transform,
+    /// creating the clip mask, and pushing the clip and layers. The render target and
+    /// factory are guaranteed to be valid for the lifetime of the `DrawingContext`.
+    pub fn push_layer(&self, options: &LayerOptions) -> Result<()> {
+        let mut pushed = Vec::new();
+        if let Err(e) = self.push_layer_parts(options, &mut pushed) {
+            self.pop_layer_parts(pushed);
+            return Err(e);
+        }
+        self.layers.push(pushed);
+        Ok(())
+    }
+
+    /// Pops the layer pushed by the innermost unpopped `push_layer` call.
+    ///
+    /// A pop without a matching push panics in debug builds and is logged and ignored
+    /// in release builds.
+    pub fn pop_layer(&self) {
+        match self.layers.pop() {
+            Some(pushed) => self.pop_layer_parts(pushed),
+            None => report_unbalanced("`pop_layer` called without a matching `push_layer`"),
+        }
+    }
+
+    /// Checks that every layer and transform pushed during the frame was popped,
+    /// popping any that weren't.
+    ///
+    /// This is called by the window once all event handlers have painted.
+    pub(crate) fn end_frame(&self) {
+        if self.layers.depth() > 0 {
+            report_unbalanced("the frame ended with layers still pushed");
+            while self.layers.depth() > 0 {
+                self.pop_layer();
+            }
+        }
+        if self.transforms.depth() > 0 {
+            report_unbalanced("the frame ended with transforms still pushed");
+            while self.transforms.depth() > 0 {
+                self.pop_transform();
+            }
+        }
+    }
+
+    /// Pushes the clip and layers for `options`, recording each in `pushed`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create a layer or mask. What
+    /// was pushed before the error is recorded in `pushed`.
+    fn push_layer_parts(&self, options: &LayerOptions, pushed: &mut Vec<Pushed>) -> Result<()> {
+        let opacity = options.opacity.clamp(0.0, 1.0);
+        let mut mask: Option<ID2D1Geometry> = match &options.mask {
+            Some(geometry) => Some(geometry.path_geometry(self)?.cast()?),
+            None => None,
+        };
+
+        if let Some(clip) = &options.clip {
+            let clip: D2D_RECT_F = (*clip).into();
+            let transform = self.transform();
+
+            if transform.M12 == 0.0 && transform.M21 == 0.0 {
+                unsafe {
+                    self.render_target
+                        .PushAxisAlignedClip(&clip, self.render_target.GetAntialiasMode())
+                };
+                pushed.push(Pushed::AxisAlignedClip);
+            } else {
+                let clip_mask: ID2D1Geometry =
+                    unsafe { self.d2d_factory.CreateRectangleGeometry(&clip)? }.cast()?;
+                // A layer has a single mask, so a rotated clip and a mask each get one.
+                if mask.is_some() {
+                    self.push_d2d_layer(Some(clip_mask), 1.0, pushed)?;
+                } else {
+                    mask = Some(clip_mask);
+                }
+            }
+        }
+
+        if mask.is_some() || opacity < 1.0 {
+            self.push_d2d_layer(mask, opacity, pushed)?;
+        }
+        Ok(())
+    }
+
+    /// Pushes a pooled layer with the given geometric mask and opacity, recording it in
+    /// `pushed`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create the layer.
+    fn push_d2d_layer(
+        &self,
+        mask: Option<ID2D1Geometry>,
+        opacity: f32,
+        pushed: &mut Vec<Pushed>,
+    ) -> Result<()> {
+        let layer = self.layers.acquire(self.render_target)?;
+        let parameters = D2D1_LAYER_PARAMETERS {
+            contentBounds: D2D_RECT_F {
+                left: f32::MIN,
+                top: f32::MIN,
+                right: f32::MAX,
+                bottom: f32::MAX,
+            },
+            geometricMask: ManuallyDrop::new(mask.clone()),
+            maskAntialiasMode: unsafe { self.render_target.GetAntialiasMode() },
+            maskTransform: Matrix3x2::identity(),
+            opacity,
+            opacityBrush: ManuallyDrop::new(None),
+            layerOptions: D2D1_LAYER_OPTIONS_NONE,
+        };
+        unsafe { self.render_target.PushLayer(&parameters, &layer) };
+        drop(ManuallyDrop::into_inner(parameters.geometricMask));
+        pushed.push(Pushed::Layer { layer, mask });
+        Ok(())
+    }
+
+    /// Pops what one `push_layer` call pushed, innermost first, returning its layers to
+    /// the pool.
+    fn pop_layer_parts(&self, pushed: Vec<Pushed>) {
+        for part in pushed.into_iter().rev() {
+            match part {
+                Pushed::AxisAlignedClip => unsafe { self.render_target.PopAxisAlignedClip() },
+                Pushed::Layer { layer, mask } => {
+                    unsafe { self.render_target.PopLayer() };
+                    drop(mask);
+                    self.layers.release(self.render_target, layer);
+                }
+            }
+        }
+    }
+}
+
+/// Reports a push or pop without its counterpart, which is a bug in the caller.
+///
+/// Debug builds panic, so the bug is found early. Release builds log the mismatch and
+/// carry on, since the caller recovers from it.
+fn report_unbalanced(message: &str) {
+    if cfg!(debug_assertions) {
+        panic!("{message}");
+    }
+    println!("{message}");
+}
+
+/// How coordinates under an axis-aligned transform map to device pixels.
+struct PixelGrid {
+    /// Device pixels per unit along each axis.
+    scale: Vector2,
+    /// The device pixel position of the origin.
+    offset: Vector2,
+}
+
+impl PixelGrid {
+    /// Maps `point` to device pixels, moves each coordinate with `snap`, which is given
+    /// the coordinate and the axis's scale, and maps the result back.
+    fn snap(&self, point: Vector2, snap: impl Fn(f32, f32) -> f32) -> Vector2 {
+        let axis = |value: f32, scale: f32, offset: f32| {
+            (snap(value * scale + offset, scale) - offset) / scale
+        };
+        Vector2::new(
+            axis(point.X, self.scale.X, self.offset.X),
+            axis(point.Y, self.scale.Y, self.offset.Y),
+        )
+    }
+}
+
+/// Rounds a stroke width in device pixels to a whole number of pixels, with halves
+/// rounded down, and at least one.
+fn stroke_width_pixels(width: f32) -> f32 {
+    (width - 0.5).ceil().max(1.0)
 }
@@ -0,0 +1,185 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use windows::{
+    core::*,
+    Win32::Graphics::Direct2D::{
+        Common::D2D_SIZE_F, ID2D1BitmapBrush, ID2D1Brush, ID2D1RenderTarget,
+        D2D1_BITMAP_BRUSH_PROPERTIES, D2D1_EXTEND_MODE_WRAP,
+    },
+};
+use windows_numerics::{Matrix3x2, Vector2};
+
+use crate::core::render::color::Color;
+use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::image_source::{ImageSource, Interpolation};
+
+/// How the interior of a shape is painted.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Fill {
+    /// A single solid color.
+    Solid(Color),
+    /// An image repeated in both directions.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Pattern(Pattern),
+}
+
+impl Fill {
+    /// Creates a new pattern fill that tiles `image` at `tile_size`.
+    pub fn pattern(image: Rc<ImageSource>, tile_size: Vector2) -> Self {
+        Fill::Pattern(Pattern::new(image, tile_size))
+    }
+
+    /// Runs `f` with a brush that paints this fill.
+    ///
+    /// Patterns are anchored at `origin`, so they tile in the shape's local
+    /// coordinates. The current brush opacity of the `DrawingContext` is applied to
+    /// pattern brushes as well.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to load the pattern image or to
+    /// create the bitmap brush.
+    pub(crate) fn with_brush<R>(
+        &self,
+        context: &DrawingContext,
+        origin: Vector2,
+        f: impl FnOnce(&ID2D1Brush) -> R,
+    ) -> Result<R> {
+        match self {
+            Fill::Solid(color) => Ok(context.with_brush_color(color, |brush| f(brush))),
+            Fill::Pattern(pattern) => pattern.with_brush(context, origin, f),
+        }
+    }
+}
+
+impl From<Color> for Fill {
+    fn from(color: Color) -> Self {
+        Fill::Solid(color)
+    }
+}
+
+impl From<Pattern> for Fill {
+    fn from(pattern: Pattern) -> Self {
+        Fill::Pattern(pattern)
+    }
+}
+
+/// An image tiled across a shape, painted with an `ID2D1BitmapBrush` in wrap mode.
+///
+/// The brush is created on first use and recreated when the render target changes
+/// (for example after device loss). Clones share the image but not the brush.
+pub struct Pattern {
+    /// The image to tile.
+    pub image: Rc<ImageSource>,
+    /// The size each tile is drawn at.
+    pub tile_size: Vector2,
+    /// The interpolation used when the tile size differs from the image size.
+    pub interpolation: Interpolation,
+    /// The cached brush, the render target it belongs to, and the image size in DIPs.
+    brush: RefCell<Option<(ID2D1RenderTarget, ID2D1BitmapBrush, D2D_SIZE_F)>>,
+}
+
+impl Pattern {
+    /// Creates a new `Pattern` that tiles `image` at `tile_size`.
+    pub fn new(image: Rc<ImageSource>, tile_size: Vector2) -> Self {
+        Self {
+            image,
+            tile_size,
+            interpolation: Interpolation::Linear,
+            brush: RefCell::new(None),
+        }
+    }
+
+    /// Sets the interpolation mode.
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// Returns the bitmap brush for the render target and the size of the image,
+    /// creating the brush if necessary.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to load the image or to create
+    /// the brush.
+    fn brush(&self, context: &DrawingContext) -> Result<(ID2D1BitmapBrush, D2D_SIZE_F)> {
+        if let Some((cached_target, brush, size)) = self.brush.borrow().as_ref() {
+            if cached_target == context.render_target {
+                return Ok((brush.clone(), *size));
+            }
+        }
+
+        let bitmap = self
+            .image
+            .bitmap(context.wic_factory, context.render_target)?;
+        let properties = D2D1_BITMAP_BRUSH_PROPERTIES {
+            extendModeX: D2D1_EXTEND_MODE_WRAP,
+            extendModeY: D2D1_EXTEND_MODE_WRAP,
+            interpolationMode: self.interpolation.into(),
+        };
+        let (brush, size) = unsafe {
+            let brush =
+                context
+                    .render_target
+                    .CreateBitmapBrush(&bitmap, Some(&properties), None)?;
+            (brush, bitmap.GetSize())
+        };
+
+        self.brush
+            .replace(Some((context.render_target.clone(), brush.clone(), size)));
+        Ok((brush, size))
+    }
+
+    /// Runs `f` with the bitmap brush scaled to the tile size and anchored at `origin`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create the brush.
+    fn with_brush<R>(
+        &self,
+        context: &DrawingContext,
+        origin: Vector2,
+        f: impl FnOnce(&ID2D1Brush) -> R,
+    ) -> Result<R> {
+        let (brush, image_size) = self.brush(context)?;
+
+        let scale = |tile: f32, image: f32| if image > 0.0 { tile / image } else { 1.0 };
+        let transform = Matrix3x2 {
+            M11: scale(self.tile_size.X, image_size.width),
+            M22: scale(self.tile_size.Y, image_size.height),
+            M31: origin.X,
+            M32: origin.Y,
+            ..Matrix3x2::default()
+        };
+
+        unsafe {
+            brush.SetTransform(&transform);
+            brush.SetOpacity(context.brush.GetOpacity());
+        }
+        Ok(f(&brush))
+    }
+}
+
+impl Clone for Pattern {
+    fn clone(&self) -> Self {
+        Self {
+            image: Rc::clone(&self.image),
+            tile_size: self.tile_size,
+            interpolation: self.interpolation,
+            brush: RefCell::new(None),
+        }
+    }
+}
+
+impl std::fmt::Debug for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pattern")
+            .field("image", &self.image.path())
+            .field("tile_size", &self.tile_size)
+            .field("interpolation", &self.interpolation)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,543 @@
+use std::cell::RefCell;
+
+use windows::{Win32::Graphics::Direct2D::ID2D1PathGeometry, core::Result};
+use windows_numerics::Vector2;
+
+use crate::core::render::color::Color;
+use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::objects::primitives::path::{PathCommand, write_commands};
+use crate::core::render::objects::primitives::path_geometry::build_path_geometry;
+use crate::core::render::rect::Rect;
+use crate::core::render::stroke_style::{LineCap, LineJoin, StrokeStyle};
+
+/// One segment of a `Figure`, running from the end of the previous segment, or the
+/// start of the figure, to its end point.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Segment {
+    /// A straight line to the given point.
+    Line(
+        #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2"))]
+        Vector2,
+    ),
+    /// A quadratic Bézier curve through a control point to an end point.
+    Quadratic {
+        /// The control point.
+        #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2"))]
+        control: Vector2,
+        /// The end point.
+        #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2"))]
+        end: Vector2,
+    },
+    /// A cubic Bézier curve through two control points to an end point.
+    Cubic {
+        /// The first control point.
+        #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2"))]
+        control1: Vector2,
+        /// The second control point.
+        #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2"))]
+        control2: Vector2,
+        /// The end point.
+        #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2"))]
+        end: Vector2,
+    },
+    /// An elliptical arc to an end point.
+    Arc {
+        /// The end point.
+        #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2"))]
+        end: Vector2,
+        /// The horizontal radius of the ellipse.
+        radius_x: f32,
+        /// The vertical radius of the ellipse.
+        radius_y: f32,
+        /// The rotation of the ellipse, in degrees.
+        rotation: f32,
+        /// Whether to take the larger of the two possible arcs.
+        large_arc: bool,
+        /// Whether the arc is swept clockwise.
+        clockwise: bool,
+    },
+}
+
+impl Segment {
+    /// Returns the point the segment ends at.
+    pub fn end_point(&self) -> Vector2 {
+        match *self {
+            Segment::Line(end)
+            | Segment::Quadratic { end, .. }
+            | Segment::Cubic { end, .. }
+            | Segment::Arc { end, .. } => end,
+        }
+    }
+
+    /// Returns the segment a path command draws, or `None` for `MoveTo` and `Close`.
+    fn from_command(command: PathCommand) -> Option<Self> {
+        match command {
+            PathCommand::MoveTo(_) | PathCommand::Close => None,
+            PathCommand::LineTo(point) => Some(Segment::Line(point)),
+            PathCommand::QuadTo { control, end } => Some(Segment::Quadratic { control, end }),
+            PathCommand::CubicTo {
+                control1,
+                control2,
+                end,
+            } => Some(Segment::Cubic {
+                control1,
+                control2,
+                end,
+            }),
+            PathCommand::ArcTo {
+                end,
+                radius_x,
+                radius_y,
+                rotation,
+                large_arc,
+                clockwise,
+            } => Some(Segment::Arc {
+                end,
+                radius_x,
+                radius_y,
+                rotation,
+                large_arc,
+                clockwise,
+            }),
+        }
+    }
+
+    /// Returns the path command that draws the segment.
+    fn to_command(self) -> PathCommand {
+        match self {
+            Segment::Line(point) => PathCommand::LineTo(point),
+            Segment::Quadratic { control, end } => PathCommand::QuadTo { control, end },
+            Segment::Cubic {
+                control1,
+                control2,
+                end,
+            } => PathCommand::CubicTo {
+                control1,
+                control2,
+                end,
+            },
+            Segment::Arc {
+                end,
+                radius_x,
+                radius_y,
+                rotation,
+                large_arc,
+                clockwise,
+            } => PathCommand::ArcTo {
+                end,
+                radius_x,
+                radius_y,
+                rotation,
+                large_arc,
+                clockwise,
+            },
+        }
+    }
+}
+
+/// A connected run of segments in a `Geometry`, starting at a point and optionally
+/// closed with a straight line back to it.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Figure {
+    /// The point the first segment starts at.
+    #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2"))]
+    pub start: Vector2,
+    /// The segments, in order.
+    pub segments: Vec<Segment>,
+    /// Whether the figure ends with a line back to its start.
+    pub closed: bool,
+}
+
+impl Figure {
+    /// Creates a new, open `Figure` starting at `start` with no segments.
+    pub fn new(start: Vector2) -> Self {
+        Self {
+            start,
+            segments: Vec::new(),
+            closed: false,
+        }
+    }
+
+    /// Creates a new, closed `Figure` with straight lines between the given points, or
+    /// `None` if there are no points.
+    pub fn polygon(points: &[Vector2]) -> Option<Self> {
+        let (&start, rest) = points.split_first()?;
+        Some(Self {
+            start,
+            segments: rest.iter().copied().map(Segment::Line).collect(),
+            closed: true,
+        })
+    }
+
+    /// Creates a new, open `Figure` with straight lines between the given points, or
+    /// `None` if there are no points.
+    pub fn polyline(points: &[Vector2]) -> Option<Self> {
+        Self::polygon(points).map(|figure| Self {
+            closed: false,
+            ..figure
+        })
+    }
+
+    /// Adds a straight line to `point`.
+    pub fn line_to(mut self, point: Vector2) -> Self {
+        self.segments.push(Segment::Line(point));
+        self
+    }
+
+    /// Adds a quadratic Bézier curve to `end`.
+    pub fn quad_to(mut self, control: Vector2, end: Vector2) -> Self {
+        self.segments.push(Segment::Quadratic { control, end });
+        self
+    }
+
+    /// Adds a cubic Bézier curve to `end`.
+    pub fn cubic_to(mut self, control1: Vector2, control2: Vector2, end: Vector2) -> Self {
+        self.segments.push(Segment::Cubic {
+            control1,
+            control2,
+            end,
+        });
+        self
+    }
+
+    /// Adds an elliptical arc to `end`.
+    pub fn arc_to(
+        mut self,
+        end: Vector2,
+        radius_x: f32,
+        radius_y: f32,
+        rotation: f32,
+        large_arc: bool,
+        clockwise: bool,
+    ) -> Self {
+        self.segments.push(Segment::Arc {
+            end,
+            radius_x,
+            radius_y,
+            rotation,
+            large_arc,
+            clockwise,
+        });
+        self
+    }
+
+    /// Closes the figure with a straight line back to its start.
+    pub fn close(mut self) -> Self {
+        self.closed = true;
+        self
+    }
+
+    /// Returns the box around the figure's points and control points, growing the box
+    /// around each arc's end point by the arc's diameter, as `Path::bounds` does.
+    fn bounds(&self) -> Option<Rect> {
+        self.segments
+            .iter()
+            .filter_map(|segment| match *segment {
+                Segment::Line(point) => Rect::from_points([point]),
+                Segment::Quadratic { control, end } => Rect::from_points([control, end]),
+                Segment::Cubic {
+                    control1,
+                    control2,
+                    end,
+                } => Rect::from_points([control1, control2, end]),
+                Segment::Arc {
+                    end,
+                    radius_x,
+                    radius_y,
+                    ..
+                } => Rect::from_points([end])
+                    .map(|rect| rect.inflate(radius_x.abs().max(radius_y.abs()) * 2.0)),
+            })
+            .chain(Rect::from_points([self.start]))
+            .reduce(|bounds, rect| bounds.union(&rect))
+    }
+}
+
+/// A backend-independent description of a shape made of lines, curves, and arcs, drawn
+/// with `DrawingContext::draw_geometry`.
+///
+/// A geometry is a list of figures, each a connected run of segments. Drawables that
+/// describe their shape as a `Geometry` don't need to know how it is drawn. Direct2D
+/// draws it through an `ID2D1PathGeometry`, which is built on the first draw and cached
+/// until the figures are changed.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use my_gui::core::render::geometry::{Figure, Geometry};
+/// use windows_numerics::Vector2;
+///
+/// let diamond = Geometry::from_figures(vec![
+///     Figure::new(Vector2 { X: 50.0, Y: 0.0 })
+///         .line_to(Vector2 { X: 100.0, Y: 50.0 })
+///         .line_to(Vector2 { X: 50.0, Y: 100.0 })
+///         .line_to(Vector2 { X: 0.0, Y: 50.0 })
+///         .close(),
+/// ]);
+/// ```
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Geometry {
+    figures: Vec<Figure>,
+    /// The Direct2D geometry, built from the figures on the first draw.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    path_geometry: RefCell<Option<ID2D1PathGeometry>>,
+}
+
+impl Geometry {
+    /// Creates a new, empty `Geometry`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new `Geometry` from its figures.
+    pub fn from_figures(figures: Vec<Figure>) -> Self {
+        Self {
+            figures,
+            path_geometry: RefCell::new(None),
+        }
+    }
+
+    /// Creates a new `Geometry` with a single closed figure with straight lines between
+    /// the given points.
+    pub fn polygon(points: &[Vector2]) -> Self {
+        Self::from_figures(Figure::polygon(points).into_iter().collect())
+    }
+
+    /// Creates a new `Geometry` with a single open figure with straight lines between
+    /// the given points.
+    pub fn polyline(points: &[Vector2]) -> Self {
+        Self::from_figures(Figure::polyline(points).into_iter().collect())
+    }
+
+    /// Creates a new `Geometry` with the figures the given path commands describe.
+    ///
+    /// `MoveTo` starts a new figure and `Close` closes the current one, moving back to
+    /// its start. A drawing command outside a figure starts one at the current point.
+    pub fn from_commands(commands: &[PathCommand]) -> Self {
+        let mut figures = Vec::new();
+        let mut figure: Option<Figure> = None;
+        let mut start = Vector2::default();
+        let mut current = Vector2::default();
+        for &command in commands {
+            match command {
+                PathCommand::MoveTo(point) => {
+                    figures.extend(figure.take());
+                    start = point;
+                    current = point;
+                }
+                PathCommand::Close => {
+                    if let Some(closed) = figure.take() {
+                        figures.push(closed.close());
+                    }
+                    current = start;
+                }
+                _ => {
+                    let Some(segment) = Segment::from_command(command) else {
+                        continue;
+                    };
+                    if figure.is_none() {
+                        start = current;
+                    }
+                    figure
+                        .get_or_insert_with(|| Figure::new(current))
+                        .segments
+                        .push(segment);
+                    current = segment.end_point();
+                }
+            }
+        }
+        figures.extend(figure);
+        Self::from_figures(figures)
+    }
+
+    /// Returns the figures of the geometry.
+    pub fn figures(&self) -> &[Figure] {
+        &self.figures
+    }
+
+    /// Replaces the figures of the geometry, invalidating the cached geometry.
+    pub fn set_figures(&mut self, figures: Vec<Figure>) {
+        self.figures = figures;
+        self.path_geometry.replace(None);
+    }
+
+    /// Adds a figure to the geometry, invalidating the cached geometry.
+    pub fn add_figure(&mut self, figure: Figure) {
+        self.figures.push(figure);
+        self.path_geometry.replace(None);
+    }
+
+    /// Returns `true` if the geometry has no figures.
+    pub fn is_empty(&self) -> bool {
+        self.figures.is_empty()
+    }
+
+    /// Returns the box around the points and control points of every figure, or `None`
+    /// if the geometry is empty.
+    pub fn bounds(&self) -> Option<Rect> {
+        self.figures
+            .iter()
+            .filter_map(Figure::bounds)
+            .reduce(|bounds, rect| bounds.union(&rect))
+    }
+
+    /// Returns the Direct2D geometry for the figures, building it first if necessary.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to build the path geometry.
+    pub(crate) fn path_geometry(&self, context: &DrawingContext) -> Result<ID2D1PathGeometry> {
+        let mut cached = self.path_geometry.borrow_mut();
+        if let Some(geometry) = cached.as_ref() {
+            return Ok(geometry.clone());
+        }
+
+        let commands: Vec<PathCommand> = self
+            .figures
+            .iter()
+            .flat_map(|figure| {
+                std::iter::once(PathCommand::MoveTo(figure.start))
+                    .chain(figure.segments.iter().map(|segment| segment.to_command()))
+                    .chain(figure.closed.then_some(PathCommand::Close))
+            })
+            .collect();
+        let geometry = build_path_geometry(context.d2d_factory, |sink| {
+            write_commands(sink, &commands);
+            Ok(())
+        })?;
+        *cached = Some(geometry.clone());
+        Ok(geometry)
+    }
+}
+
+/// The outline `DrawingContext::draw_geometry` strokes a `Geometry` with.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use my_gui::core::render::color::Color;
+/// use my_gui::core::render::geometry::GeometryStroke;
+/// use my_gui::core::render::stroke_style::{LineCap, LineJoin};
+///
+/// let stroke = GeometryStroke::new(&Color::BLACK, 2.0)
+///     .with_line_cap(LineCap::Round)
+///     .with_line_join(LineJoin::Round);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeometryStroke<'a> {
+    /// The stroke color.
+    pub color: &'a Color,
+    /// The width of the stroke. Strokes of zero or negative width aren't drawn.
+    pub width: f32,
+    /// The dash pattern of the stroke.
+    pub style: &'a StrokeStyle,
+    /// The shape of the ends of open figures and of each dash.
+    pub line_cap: LineCap,
+    /// The shape of the corners between segments.
+    pub line_join: LineJoin,
+}
+
+impl<'a> GeometryStroke<'a> {
+    /// Creates a new solid `GeometryStroke` with flat caps and miter joins.
+    pub fn new(color: &'a Color, width: f32) -> Self {
+        Self {
+            color,
+            width,
+            style: &StrokeStyle::Solid,
+            line_cap: LineCap::Flat,
+            line_join: LineJoin::Miter,
+        }
+    }
+
+    /// Sets the dash pattern of the stroke.
+    pub fn with_style(mut self, style: &'a StrokeStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the shape of the ends of open figures and of each dash.
+    pub fn with_line_cap(mut self, line_cap: LineCap) -> Self {
+        self.line_cap = line_cap;
+        self
+    }
+
+    /// Sets the shape of the corners between segments.
+    pub fn with_line_join(mut self, line_join: LineJoin) -> Self {
+        self.line_join = line_join;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(x: f32, y: f32) -> Vector2 {
+        Vector2 { X: x, Y: y }
+    }
+
+    #[test]
+    fn from_commands_starts_a_figure_at_each_move_and_closes_on_close() {
+        let geometry = Geometry::from_commands(&[
+            PathCommand::MoveTo(point(0.0, 0.0)),
+            PathCommand::LineTo(point(10.0, 0.0)),
+            PathCommand::LineTo(point(10.0, 10.0)),
+            PathCommand::Close,
+            PathCommand::MoveTo(point(20.0, 0.0)),
+            PathCommand::LineTo(point(30.0, 0.0)),
+        ]);
+
+        assert_eq!(
+            geometry.figures(),
+            [
+                Figure::new(point(0.0, 0.0))
+                    .line_to(point(10.0, 0.0))
+                    .line_to(point(10.0, 10.0))
+                    .close(),
+                Figure::new(point(20.0, 0.0)).line_to(point(30.0, 0.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn from_commands_starts_a_figure_at_the_current_point_after_close() {
+        let geometry = Geometry::from_commands(&[
+            PathCommand::MoveTo(point(5.0, 5.0)),
+            PathCommand::LineTo(point(10.0, 5.0)),
+            PathCommand::Close,
+            PathCommand::LineTo(point(5.0, 10.0)),
+        ]);
+
+        assert_eq!(
+            geometry.figures(),
+            [
+                Figure::new(point(5.0, 5.0)).line_to(point(10.0, 5.0)).close(),
+                Figure::new(point(5.0, 5.0)).line_to(point(5.0, 10.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn from_commands_skips_moves_without_segments() {
+        let geometry = Geometry::from_commands(&[
+            PathCommand::MoveTo(point(0.0, 0.0)),
+            PathCommand::MoveTo(point(1.0, 1.0)),
+        ]);
+
+        assert!(geometry.is_empty());
+    }
+
+    #[test]
+    fn polyline_is_an_open_figure() {
+        let points = [point(0.0, 0.0), point(10.0, 0.0), point(10.0, 10.0)];
+
+        let figure = Figure::polyline(&points).unwrap();
+
+        assert!(!figure.closed);
+        assert_eq!(figure.segments.len(), 2);
+        assert!(Figure::polyline(&[]).is_none());
+    }
+}
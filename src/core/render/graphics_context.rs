@@ -0,0 +1,124 @@
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use windows::{
+    Win32::Graphics::Direct2D::{
+        D2D1_DEBUG_LEVEL_INFORMATION, D2D1_DEBUG_LEVEL_NONE, D2D1_FACTORY_OPTIONS,
+        D2D1_FACTORY_TYPE_SINGLE_THREADED, D2D1CreateFactory, ID2D1Factory1,
+    },
+    Win32::Graphics::DirectWrite::{
+        DWRITE_FACTORY_TYPE_SHARED, DWriteCreateFactory, IDWriteFactory,
+    },
+    Win32::Graphics::Imaging::{CLSID_WICImagingFactory, IWICImagingFactory},
+    Win32::System::Com::{
+        CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED, CoCreateInstance, CoInitializeEx,
+        CoUninitialize,
+    },
+    core::Result,
+};
+
+thread_local! {
+    /// The thread's `GraphicsContext`, while any `Direct2DContext` on it holds it.
+    static SHARED: RefCell<Weak<GraphicsContext>> = const { RefCell::new(Weak::new()) };
+}
+
+/// The Direct2D, DirectWrite, and WIC factories shared by every `Direct2DContext` on
+/// a thread.
+///
+/// The factories are device-independent, so one set serves all windows. Sharing them
+/// saves creating them again for each window, and lets resources created from them,
+/// such as geometries and font collections, be used with any window on the thread.
+///
+/// The context is created by the first `GraphicsContext::shared` call on a thread and
+/// released with the last `Rc` to it. It initializes COM for the thread once and
+/// uninitializes it after the factories have been released, so COM stays initialized
+/// exactly as long as it is used, however many windows come and go.
+pub struct GraphicsContext {
+    /// The Direct2D factory. It is single-threaded, like the windows that use it.
+    pub d2d_factory: ID2D1Factory1,
+    /// The DirectWrite factory.
+    pub dwrite_factory: IDWriteFactory,
+    /// The WIC factory for decoding and encoding images.
+    pub wic_factory: IWICImagingFactory,
+    /// Uninitializes COM when dropped. It is declared last so it is dropped after the
+    /// factories.
+    _apartment: ComApartment,
+}
+
+impl GraphicsContext {
+    /// Returns the thread's `GraphicsContext`, creating it if no `Direct2DContext` on
+    /// the thread holds it.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to initialize COM, for example
+    /// because the thread was already initialized for a multi-threaded apartment, or
+    /// if it fails to create the factories.
+    pub fn shared() -> Result<Rc<Self>> {
+        if let Some(context) = SHARED.with(|shared| shared.borrow().upgrade()) {
+            return Ok(context);
+        }
+        let context = Rc::new(Self::new()?);
+        SHARED.with(|shared| shared.replace(Rc::downgrade(&context)));
+        Ok(context)
+    }
+
+    /// Initializes COM and creates the factories.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for creating the factories.
+    fn new() -> Result<Self> {
+        let apartment = ComApartment::new()?;
+
+        let d2d_factory_options = D2D1_FACTORY_OPTIONS {
+            debugLevel: if cfg!(debug_assertions) {
+                D2D1_DEBUG_LEVEL_INFORMATION
+            } else {
+                D2D1_DEBUG_LEVEL_NONE
+            },
+        };
+        let d2d_factory = unsafe {
+            D2D1CreateFactory(
+                D2D1_FACTORY_TYPE_SINGLE_THREADED,
+                Some(&d2d_factory_options),
+            )?
+        };
+        let dwrite_factory = unsafe { DWriteCreateFactory(DWRITE_FACTORY_TYPE_SHARED)? };
+        let wic_factory =
+            unsafe { CoCreateInstance(&CLSID_WICImagingFactory, None, CLSCTX_INPROC_SERVER)? };
+
+        Ok(Self {
+            d2d_factory,
+            dwrite_factory,
+            wic_factory,
+            _apartment: apartment,
+        })
+    }
+}
+
+/// COM initialized for the current thread, as a single-threaded apartment, until this
+/// is dropped.
+struct ComApartment;
+
+impl ComApartment {
+    /// Initializes COM for the current thread.
+    ///
+    /// # Safety
+    ///
+    /// This function contains an `unsafe` block for initializing COM.
+    fn new() -> Result<Self> {
+        // `S_FALSE`, returned if COM was already initialized, must be balanced by
+        // `CoUninitialize` too.
+        unsafe { CoInitializeEx(None, COINIT_APARTMENTTHREADED).ok()? };
+        Ok(Self)
+    }
+}
+
+impl Drop for ComApartment {
+    fn drop(&mut self) {
+        unsafe { CoUninitialize() };
+    }
+}
@@ -0,0 +1,314 @@
+use std::{
+    cell::{OnceCell, RefCell},
+    path::{Path, PathBuf},
+};
+
+use windows::{
+    core::*,
+    Win32::Foundation::{E_POINTER, GENERIC_READ, GENERIC_WRITE},
+    Win32::Graphics::Direct2D::{
+        Common::{D2D1_ALPHA_MODE_PREMULTIPLIED, D2D1_PIXEL_FORMAT, D2D_SIZE_U},
+        ID2D1Bitmap, ID2D1RenderTarget, D2D1_BITMAP_INTERPOLATION_MODE,
+        D2D1_BITMAP_INTERPOLATION_MODE_LINEAR, D2D1_BITMAP_INTERPOLATION_MODE_NEAREST_NEIGHBOR,
+        D2D1_BITMAP_PROPERTIES,
+    },
+    Win32::Graphics::Dxgi::Common::DXGI_FORMAT_R8G8B8A8_UNORM,
+    Win32::Graphics::Imaging::*,
+};
+
+/// The interpolation used when a bitmap is drawn at a different size than its pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interpolation {
+    /// Uses the nearest source pixel, keeping hard pixel edges.
+    NearestNeighbor,
+    /// Blends the nearest source pixels linearly.
+    #[default]
+    Linear,
+}
+
+impl From<Interpolation> for D2D1_BITMAP_INTERPOLATION_MODE {
+    fn from(interpolation: Interpolation) -> Self {
+        match interpolation {
+            Interpolation::NearestNeighbor => D2D1_BITMAP_INTERPOLATION_MODE_NEAREST_NEIGHBOR,
+            Interpolation::Linear => D2D1_BITMAP_INTERPOLATION_MODE_LINEAR,
+        }
+    }
+}
+
+/// Where the pixels of an `ImageSource` come from.
+enum Pixels {
+    /// An image file, decoded on first use.
+    File {
+        path: PathBuf,
+        decoded: OnceCell<Result<IWICBitmap>>,
+    },
+    /// Pixels supplied by the application, as premultiplied RGBA8.
+    Memory {
+        width: u32,
+        height: u32,
+        data: Vec<u8>,
+    },
+}
+
+/// An image that drawables draw with `DrawingContext::draw_bitmap`, either decoded from
+/// a file on disk or supplied as pixels.
+///
+/// Decoding happens lazily through the Windows Imaging Component (WIC) the first time
+/// the image is needed, and supports any format WIC has a codec for (PNG, JPEG, BMP,
+/// and so on). The decoded pixels are device-independent and kept for the lifetime of
+/// the source.
+///
+/// The `ID2D1Bitmap` uploaded from those pixels is device-dependent. It is created on
+/// first draw and cached together with the render target it belongs to, so it is
+/// transparently recreated when the render target changes (for example after the
+/// device is lost). Drawables only hold the source, usually through an `Rc` shared
+/// with other drawables, and never the bitmap itself.
+pub struct ImageSource {
+    pixels: Pixels,
+    bitmap: RefCell<Option<(ID2D1RenderTarget, ID2D1Bitmap)>>,
+}
+
+impl ImageSource {
+    /// Creates a new `ImageSource` for the image file at `path`.
+    ///
+    /// The file is not read until the image is first drawn.
+    pub fn from_file(path: impl Into<PathBuf>) -> Self {
+        Self {
+            pixels: Pixels::File {
+                path: path.into(),
+                decoded: OnceCell::new(),
+            },
+            bitmap: RefCell::new(None),
+        }
+    }
+
+    /// Creates a new `ImageSource` from RGBA8 pixel data with straight
+    /// (non-premultiplied) alpha, such as an image generated by the application.
+    ///
+    /// Unlike a `PixelBuffer`, the pixels can't be changed afterwards, but they can be
+    /// shared by any number of drawables.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` is not exactly `width * height * 4` bytes long.
+    pub fn from_pixels(width: u32, height: u32, data: &[u8]) -> Self {
+        assert_eq!(
+            data.len(),
+            width as usize * height as usize * 4,
+            "pixel data length does not match a {width}x{height} RGBA8 buffer"
+        );
+        let mut premultiplied = Vec::with_capacity(data.len());
+        premultiply(data, &mut premultiplied);
+        Self {
+            pixels: Pixels::Memory {
+                width,
+                height,
+                data: premultiplied,
+            },
+            bitmap: RefCell::new(None),
+        }
+    }
+
+    /// Returns the path of the image file, or `None` if the image was created from
+    /// pixels.
+    pub fn path(&self) -> Option<&Path> {
+        match &self.pixels {
+            Pixels::File { path, .. } => Some(path),
+            Pixels::Memory { .. } => None,
+        }
+    }
+
+    /// Returns the Direct2D bitmap for this image on the given render target.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file cannot be decoded or if it fails
+    /// to create the bitmap. A decoding failure is remembered, so the file is not read
+    /// again on every draw.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for creating the Direct2D bitmap. The
+    /// caller must provide valid WIC factory and render target instances.
+    pub fn bitmap(
+        &self,
+        wic_factory: &IWICImagingFactory,
+        render_target: &ID2D1RenderTarget,
+    ) -> Result<ID2D1Bitmap> {
+        if let Some((cached_target, bitmap)) = self.bitmap.borrow().as_ref() {
+            if cached_target == render_target {
+                return Ok(bitmap.clone());
+            }
+        }
+
+        let bitmap = match &self.pixels {
+            Pixels::File { path, decoded } => {
+                let decoded = decoded.get_or_init(|| decode(wic_factory, path)).clone()?;
+                unsafe { render_target.CreateBitmapFromWicBitmap(&decoded, None)? }
+            }
+            Pixels::Memory {
+                width,
+                height,
+                data,
+            } => create_bitmap(render_target, *width, *height, data)?,
+        };
+
+        self.bitmap
+            .replace(Some((render_target.clone(), bitmap.clone())));
+        Ok(bitmap)
+    }
+}
+
+/// Converts RGBA8 pixels with straight alpha to premultiplied alpha, replacing the
+/// contents of `premultiplied`.
+pub(crate) fn premultiply(pixels: &[u8], premultiplied: &mut Vec<u8>) {
+    premultiplied.clear();
+    premultiplied.extend(pixels.chunks_exact(4).flat_map(|pixel| {
+        let alpha = pixel[3] as u32;
+        let scale = |channel: u8| ((channel as u32 * alpha + 127) / 255) as u8;
+        [scale(pixel[0]), scale(pixel[1]), scale(pixel[2]), pixel[3]]
+    }));
+}
+
+/// Converts premultiplied BGRA8 pixels, as read back from a render target, to RGBA8
+/// pixels with straight alpha.
+pub(crate) fn unpremultiply_bgra(pixels: &[u8]) -> Vec<u8> {
+    pixels
+        .chunks_exact(4)
+        .flat_map(|pixel| {
+            let alpha = pixel[3] as u32;
+            let unscale = |channel: u8| match alpha {
+                0 => 0,
+                _ => ((channel as u32 * 255 + alpha / 2) / alpha).min(255) as u8,
+            };
+            [unscale(pixel[2]), unscale(pixel[1]), unscale(pixel[0]), pixel[3]]
+        })
+        .collect()
+}
+
+/// Encodes RGBA8 pixels with straight alpha as a PNG file at `path`.
+///
+/// # Errors
+///
+/// This function will return an error, naming the file, if WIC fails to create the
+/// file or encode the image.
+///
+/// # Safety
+///
+/// This function contains an `unsafe` block for encoding with WIC. `pixels` must be
+/// `width * height * 4` bytes long.
+pub(crate) fn encode_png(
+    wic_factory: &IWICImagingFactory,
+    path: &Path,
+    width: u32,
+    height: u32,
+    pixels: &[u8],
+) -> Result<()> {
+    // PNG stores straight BGRA natively, so only the red and blue channels are swapped.
+    let bgra: Vec<u8> = pixels
+        .chunks_exact(4)
+        .flat_map(|pixel| [pixel[2], pixel[1], pixel[0], pixel[3]])
+        .collect();
+
+    let encode = || -> Result<()> {
+        unsafe {
+            let stream = wic_factory.CreateStream()?;
+            stream.InitializeFromFilename(&HSTRING::from(path), GENERIC_WRITE.0)?;
+            let encoder = wic_factory.CreateEncoder(&GUID_ContainerFormatPng, None)?;
+            encoder.Initialize(&stream, WICBitmapEncoderNoCache)?;
+
+            let mut frame = None;
+            encoder.CreateNewFrame(&mut frame, std::ptr::null_mut())?;
+            let frame = frame.ok_or_else(|| Error::from(E_POINTER))?;
+            frame.Initialize(None)?;
+            frame.SetSize(width, height)?;
+            let mut format = GUID_WICPixelFormat32bppBGRA;
+            frame.SetPixelFormat(&mut format)?;
+            frame.WritePixels(height, width * 4, &bgra)?;
+            frame.Commit()?;
+            encoder.Commit()
+        }
+    };
+
+    encode().map_err(|e| {
+        Error::new(
+            e.code(),
+            format!("failed to save image '{}': {}", path.display(), e.message()),
+        )
+    })
+}
+
+/// Creates a bitmap on `render_target` from premultiplied RGBA8 pixels.
+///
+/// # Errors
+///
+/// This function will return an error if it fails to create the bitmap.
+///
+/// # Safety
+///
+/// This function contains an `unsafe` block for creating the bitmap. `pixels` must be
+/// `width * height * 4` bytes long.
+pub(crate) fn create_bitmap(
+    render_target: &ID2D1RenderTarget,
+    width: u32,
+    height: u32,
+    pixels: &[u8],
+) -> Result<ID2D1Bitmap> {
+    let properties = D2D1_BITMAP_PROPERTIES {
+        pixelFormat: D2D1_PIXEL_FORMAT {
+            format: DXGI_FORMAT_R8G8B8A8_UNORM,
+            alphaMode: D2D1_ALPHA_MODE_PREMULTIPLIED,
+        },
+        dpiX: 96.0,
+        dpiY: 96.0,
+    };
+    unsafe {
+        render_target.CreateBitmap(
+            D2D_SIZE_U { width, height },
+            Some(pixels.as_ptr() as _),
+            width * 4,
+            &properties,
+        )
+    }
+}
+
+/// Decodes the first frame of the image at `path` into pre-multiplied BGRA pixels.
+///
+/// # Errors
+///
+/// This function will return an error, naming the file, if WIC fails to open or
+/// decode it.
+fn decode(wic_factory: &IWICImagingFactory, path: &Path) -> Result<IWICBitmap> {
+    let decode_frame = || -> Result<IWICBitmap> {
+        unsafe {
+            let decoder = wic_factory.CreateDecoderFromFilename(
+                &HSTRING::from(path),
+                None,
+                GENERIC_READ,
+                WICDecodeMetadataCacheOnDemand,
+            )?;
+            let frame = decoder.GetFrame(0)?;
+            let converter = wic_factory.CreateFormatConverter()?;
+            converter.Initialize(
+                &frame,
+                &GUID_WICPixelFormat32bppPBGRA,
+                WICBitmapDitherTypeNone,
+                None,
+                0.0,
+                WICBitmapPaletteTypeMedianCut,
+            )?;
+            wic_factory.CreateBitmapFromSource(&converter, WICBitmapCacheOnLoad)
+        }
+    };
+
+    decode_frame().map_err(|e| {
+        Error::new(
+            e.code(),
+            format!(
+                "failed to decode image '{}': {}",
+                path.display(),
+                e.message()
+            ),
+        )
+    })
+}
@@ -0,0 +1,169 @@
+use std::cell::RefCell;
+
+use windows::{
+    Win32::Graphics::Direct2D::{ID2D1Geometry, ID2D1Layer, ID2D1RenderTarget},
+    core::Result,
+};
+
+use crate::core::render::geometry::Geometry;
+use crate::core::render::rect::Rect;
+
+/// The options of a layer pushed with `DrawingContext::push_layer`.
+///
+/// The default options change nothing: the contents are fully opaque and neither
+/// clipped nor masked.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use my_gui::core::render::{geometry::Geometry, layer::LayerOptions, rect::Rect};
+/// use windows_numerics::Vector2;
+///
+/// let badge = Geometry::polygon(&[
+///     Vector2 { X: 50.0, Y: 0.0 },
+///     Vector2 { X: 100.0, Y: 50.0 },
+///     Vector2 { X: 50.0, Y: 100.0 },
+///     Vector2 { X: 0.0, Y: 50.0 },
+/// ]);
+/// let options = LayerOptions::new()
+///     .with_opacity(0.5)
+///     .with_clip(Rect::new(0.0, 0.0, 100.0, 80.0))
+///     .with_mask(badge);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LayerOptions {
+    /// The opacity the contents are composited with, from `0.0` (transparent) to `1.0`
+    /// (opaque).
+    pub opacity: f32,
+    /// A rectangle the contents are clipped to, in the coordinates of the current
+    /// transform.
+    pub clip: Option<Rect>,
+    /// A shape the contents are clipped to, in the coordinates of the current
+    /// transform.
+    pub mask: Option<Geometry>,
+}
+
+impl Default for LayerOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LayerOptions {
+    /// Creates new `LayerOptions` that change nothing.
+    pub fn new() -> Self {
+        Self {
+            opacity: 1.0,
+            clip: None,
+            mask: None,
+        }
+    }
+
+    /// Sets the opacity the contents are composited with.
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    /// Sets a rectangle the contents are clipped to.
+    pub fn with_clip(mut self, clip: Rect) -> Self {
+        self.clip = Some(clip);
+        self
+    }
+
+    /// Sets a shape the contents are clipped to.
+    pub fn with_mask(mut self, mask: Geometry) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+}
+
+/// One thing pushed onto the render target by `DrawingContext::push_layer`.
+pub(crate) enum Pushed {
+    /// An axis-aligned clip, popped with `PopAxisAlignedClip`.
+    AxisAlignedClip,
+    /// A layer, popped with `PopLayer`, and the mask it holds on to until then.
+    Layer {
+        layer: ID2D1Layer,
+        mask: Option<ID2D1Geometry>,
+    },
+}
+
+/// The layers pushed with `DrawingContext::push_layer` that haven't been popped yet,
+/// and a pool of `ID2D1Layer` objects to reuse between pushes.
+///
+/// Layers are device-dependent resources. The pool remembers the render target its
+/// layers were created for and starts over when the render target changes, such as
+/// after the device is lost. It is owned by the `Direct2DContext`.
+pub struct LayerPool {
+    /// The layers that aren't pushed, and the render target they belong to.
+    free: RefCell<Option<(ID2D1RenderTarget, Vec<ID2D1Layer>)>>,
+    /// What each unpopped `push_layer` call pushed, innermost last.
+    pushed: RefCell<Vec<Vec<Pushed>>>,
+}
+
+impl LayerPool {
+    /// Creates a new, empty `LayerPool`.
+    pub fn new() -> Self {
+        Self {
+            free: RefCell::new(None),
+            pushed: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Returns the number of layers pushed with `DrawingContext::push_layer` that
+    /// haven't been popped yet.
+    pub fn depth(&self) -> usize {
+        self.pushed.borrow().len()
+    }
+
+    /// Returns a layer for `render_target`, reusing a free one if possible.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create the layer.
+    ///
+    /// # Safety
+    ///
+    /// This function contains an `unsafe` block for creating the layer. The caller must
+    /// provide a valid render target.
+    pub(crate) fn acquire(&self, render_target: &ID2D1RenderTarget) -> Result<ID2D1Layer> {
+        let mut free = self.free.borrow_mut();
+        if free
+            .as_ref()
+            .is_none_or(|(target, _)| target != render_target)
+        {
+            *free = Some((render_target.clone(), Vec::new()));
+        }
+        if let Some(layer) = free.as_mut().and_then(|(_, layers)| layers.pop()) {
+            return Ok(layer);
+        }
+        unsafe { render_target.CreateLayer(None) }
+    }
+
+    /// Returns a popped layer to the pool, unless the render target has changed since
+    /// it was created.
+    pub(crate) fn release(&self, render_target: &ID2D1RenderTarget, layer: ID2D1Layer) {
+        let mut free = self.free.borrow_mut();
+        let matching = free.as_mut().filter(|(target, _)| target == render_target);
+        if let Some((_, layers)) = matching {
+            layers.push(layer);
+        }
+    }
+
+    /// Records what one `push_layer` call pushed.
+    pub(crate) fn push(&self, pushed: Vec<Pushed>) {
+        self.pushed.borrow_mut().push(pushed);
+    }
+
+    /// Removes and returns what the innermost unpopped `push_layer` call pushed.
+    pub(crate) fn pop(&self) -> Option<Vec<Pushed>> {
+        self.pushed.borrow_mut().pop()
+    }
+}
+
+impl Default for LayerPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -4,20 +4,88 @@
 //! It defines a flexible system for drawing objects to the screen, organized around
 //! a `Scene` that manages a collection of `Drawable` items.
 //!
+//! Only `Color`, `Rect`, and `Transform` are available on platforms other than Windows,
+//! for use with the renderers in `core::backend`.
+//!
 //! ## Key Components
 //!
 //! - **`Scene`**: A container for all objects that should be rendered in a window.
+//! - **`Camera`**: The pan and zoom a `Scene` is viewed with.
+//! - **`SceneDescription`**: A serializable description of a `Scene`, available with
+//!   the `serde` feature.
+//! - **`SceneEditor`**: Makes changes to a `Scene` with undo and redo.
 //! - **`Drawable`**: A trait for objects that can be drawn. Any object implementing
 //!   this trait can be added to the `Scene`.
 //! - **`DrawingContext`**: A struct that bundles together the necessary Direct2D resources
 //!   (like the render target and brushes) for a drawing operation.
-//! - **`Direct2DContext`**: Manages the lifetime of core Direct2D and DirectWrite
-//!   factories and resources.
+//! - **`Rect`**: An axis-aligned rectangle used for destinations, regions, and bounds.
+//! - **`Color`**: An RGBA color used by drawables to configure fills and strokes.
+//! - **`AntialiasMode`**: Whether the edges of shapes are smoothed or kept crisp.
+//! - **`BrushCache`**: The solid color brushes drawables are drawn with, one per color.
+//! - **`Fill`**: How a shape's interior is painted, either a solid color or a tiled
+//!   image pattern.
+//! - **`Geometry`**: A backend-independent shape made of figures of lines, curves, and
+//!   arcs, drawn with `DrawingContext::draw_geometry` and outlined with a
+//!   `GeometryStroke`.
+//! - **`LayerOptions`**: The opacity, clip rectangle, and mask of a layer pushed with
+//!   `DrawingContext::push_layer`.
+//! - **`Shadow`**: A soft drop shadow that shapes can draw beneath themselves.
+//! - **`StrokeStyle`**: The dash pattern used for lines and outlines, backed by a
+//!   cache of Direct2D stroke style objects.
+//! - **`ImageSource`**: An image file decoded with WIC, or pixels supplied by the
+//!   application, uploaded to Direct2D on demand and drawn with
+//!   `DrawingContext::draw_bitmap`.
+//! - **`TextRendering`**: The gamma, contrast, and ClearType level text is rendered
+//!   with, as presets or explicit DirectWrite settings.
+//! - **`Transform`**: A per-object rotation and scale applied around a pivot point.
+//! - **`GraphicsContext`**: The Direct2D, DirectWrite, and WIC factories, shared by
+//!   every window on a thread.
+//! - **`Direct2DContext`**: Manages a window's Direct2D and DirectWrite resources.
 //! - **`objects`**: A submodule containing concrete implementations of the `Drawable`
-//!   trait, such as `TextObject`.
+//!   trait, such as `TextObject` and the shapes in `objects::primitives`.
 
+#[cfg(windows)]
+pub mod antialias;
+#[cfg(windows)]
+pub mod brush_cache;
+#[cfg(windows)]
+pub mod camera;
+pub mod color;
+#[cfg(windows)]
 pub mod direct2d_context;
+#[cfg(windows)]
 pub mod drawing_context;
+#[cfg(windows)]
 pub mod drawable;
+#[cfg(windows)]
+pub mod fill;
+#[cfg(windows)]
+pub mod geometry;
+#[cfg(windows)]
+pub mod graphics_context;
+#[cfg(windows)]
+pub mod image_source;
+#[cfg(windows)]
+pub mod layer;
+#[cfg(windows)]
 pub mod objects;
+pub mod rect;
+#[cfg(windows)]
 pub mod scene;
+#[cfg(all(windows, feature = "serde"))]
+pub mod scene_description;
+#[cfg(windows)]
+pub mod scene_editor;
+#[cfg(feature = "serde")]
+mod serde_vector2;
+#[cfg(windows)]
+pub mod shadow;
+#[cfg(windows)]
+mod spatial_index;
+#[cfg(windows)]
+pub mod stroke_style;
+#[cfg(windows)]
+pub mod swap_chain;
+#[cfg(windows)]
+pub mod text_rendering;
+pub mod transform;
@@ -0,0 +1,274 @@
+use std::cell::RefCell;
+
+use windows::{
+    core::*,
+    Win32::Foundation::E_POINTER,
+    Win32::Graphics::Direct2D::{
+        CLSID_D2D1GaussianBlur,
+        Common::{D2D1_BORDER_MODE_HARD, D2D_POINT_2U, D2D_RECT_F, D2D_RECT_U, D2D_SIZE_U},
+        ID2D1Bitmap, ID2D1DeviceContext, ID2D1Effect, ID2D1Image, ID2D1RenderTarget,
+        D2D1_BITMAP_PROPERTIES, D2D1_COMPOSITE_MODE_SOURCE_OVER,
+        D2D1_GAUSSIANBLUR_PROP_BORDER_MODE, D2D1_GAUSSIANBLUR_PROP_STANDARD_DEVIATION,
+        D2D1_INTERPOLATION_MODE_LINEAR, D2D1_PROPERTY_TYPE_ENUM, D2D1_PROPERTY_TYPE_FLOAT,
+    },
+};
+use windows_numerics::{Matrix3x2, Vector2};
+
+use crate::core::render::color::Color;
+use crate::core::render::drawable::Drawable;
+use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::rect::Rect;
+
+/// The device-dependent resources used to blur a region.
+struct BlurResources {
+    render_target: ID2D1RenderTarget,
+    size: D2D_SIZE_U,
+    bitmap: ID2D1Bitmap,
+    effect: ID2D1Effect,
+}
+
+/// A `Drawable` that blurs the content already drawn behind a region, for
+/// frosted-glass panels.
+///
+/// The region captures whatever has been drawn before it, so it must be added to the
+/// scene *after* the content it blurs and *before* anything that should appear on top
+/// of it, such as the panel's own text.
+///
+/// Blurring requires the render target to support Direct2D effects (an
+/// `ID2D1DeviceContext`, available on Windows 8 and later). If it doesn't, or if any
+/// step of the blur fails, the region is filled with the translucent fallback color
+/// instead.
+///
+/// The region follows any enclosing transform, such as that of a `Canvas`. Under
+/// rotation, the axis-aligned box around the rotated region is blurred.
+///
+/// Like other drawables, the region fades with its own `opacity` and any opacity
+/// applied around it with `DrawingContext::with_opacity`, such as that of a `Canvas`.
+pub struct BlurRegion {
+    /// The region to blur.
+    pub bounds: Rect,
+    /// The distance over which the content is blurred.
+    pub blur_radius: f32,
+    /// The color used to fill the region when blurring is unavailable.
+    pub fallback: Color,
+    /// The opacity of the region, from `0.0` (transparent) to `1.0` (opaque).
+    pub opacity: f32,
+    resources: RefCell<Option<BlurResources>>,
+}
+
+impl BlurRegion {
+    /// Creates a new `BlurRegion` with a translucent white fallback.
+    pub fn new(bounds: Rect, blur_radius: f32) -> Self {
+        Self {
+            bounds,
+            blur_radius,
+            fallback: Color::new(1.0, 1.0, 1.0, 0.35),
+            opacity: 1.0,
+            resources: RefCell::new(None),
+        }
+    }
+
+    /// Sets the opacity of the region.
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    /// Sets the color used to fill the region when blurring is unavailable.
+    pub fn with_fallback(mut self, fallback: Color) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    /// Returns the capture bitmap and blur effect for a region of the given pixel size,
+    /// recreating them if the render target or size has changed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create the bitmap or effect.
+    fn resources(
+        &self,
+        context: &DrawingContext,
+        device_context: &ID2D1DeviceContext,
+        size: D2D_SIZE_U,
+    ) -> Result<(ID2D1Bitmap, ID2D1Effect)> {
+        let mut cached = self.resources.borrow_mut();
+        if let Some(resources) = cached.as_ref() {
+            if &resources.render_target == context.render_target
+                && resources.size.width == size.width
+                && resources.size.height == size.height
+            {
+                return Ok((resources.bitmap.clone(), resources.effect.clone()));
+            }
+        }
+
+        let (mut dpi_x, mut dpi_y) = (0.0, 0.0);
+        let bitmap = unsafe {
+            context.render_target.GetDpi(&mut dpi_x, &mut dpi_y);
+            let properties = D2D1_BITMAP_PROPERTIES {
+                pixelFormat: context.render_target.GetPixelFormat(),
+                dpiX: dpi_x,
+                dpiY: dpi_y,
+            };
+            context
+                .render_target
+                .CreateBitmap(size, None, 0, &properties)?
+        };
+        let effect = unsafe { device_context.CreateEffect(&CLSID_D2D1GaussianBlur)? };
+
+        *cached = Some(BlurResources {
+            render_target: context.render_target.clone(),
+            size,
+            bitmap: bitmap.clone(),
+            effect: effect.clone(),
+        });
+        Ok((bitmap, effect))
+    }
+
+    /// Captures the region and draws it back blurred.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the render target does not support
+    /// effects, or if capturing or blurring the region fails.
+    fn draw_blurred(&self, context: &DrawingContext) -> Result<()> {
+        let device_context: ID2D1DeviceContext = context.render_target.cast()?;
+
+        // The capture works in device pixels, so map the region through the current
+        // transform, such as that of an enclosing canvas, convert it from DIPs, and
+        // clamp it to the render target.
+        let mut transform = Matrix3x2::default();
+        let (mut dpi_x, mut dpi_y) = (0.0, 0.0);
+        let pixel_size = unsafe {
+            context.render_target.GetTransform(&mut transform);
+            context.render_target.GetDpi(&mut dpi_x, &mut dpi_y);
+            context.render_target.GetPixelSize()
+        };
+        let bounds = self.bounds.transform(&transform);
+        let to_pixels =
+            |dips: f32, dpi: f32, max: u32| ((dips * dpi / 96.0).round().max(0.0) as u32).min(max);
+        let source = D2D_RECT_U {
+            left: to_pixels(bounds.x, dpi_x, pixel_size.width),
+            top: to_pixels(bounds.y, dpi_y, pixel_size.height),
+            right: to_pixels(bounds.right(), dpi_x, pixel_size.width),
+            bottom: to_pixels(bounds.bottom(), dpi_y, pixel_size.height),
+        };
+        if source.right <= source.left || source.bottom <= source.top {
+            return Ok(());
+        }
+        let size = D2D_SIZE_U {
+            width: source.right - source.left,
+            height: source.bottom - source.top,
+        };
+
+        let (bitmap, effect) = self.resources(context, &device_context, size)?;
+
+        // A radius covers roughly three standard deviations of the Gaussian.
+        let standard_deviation = self.blur_radius / 3.0;
+        unsafe {
+            bitmap.CopyFromRenderTarget(
+                Some(&D2D_POINT_2U { x: 0, y: 0 }),
+                context.render_target,
+                Some(&source),
+            )?;
+            effect.SetInput(0, &bitmap, true);
+            effect.SetValue(
+                D2D1_GAUSSIANBLUR_PROP_STANDARD_DEVIATION.0 as u32,
+                D2D1_PROPERTY_TYPE_FLOAT,
+                &standard_deviation.to_le_bytes(),
+            )?;
+            // Hard borders stop the transparent surroundings from bleeding into the edges.
+            effect.SetValue(
+                D2D1_GAUSSIANBLUR_PROP_BORDER_MODE.0 as u32,
+                D2D1_PROPERTY_TYPE_ENUM,
+                &(D2D1_BORDER_MODE_HARD.0 as u32).to_le_bytes(),
+            )?;
+        }
+
+        let mut output: Option<ID2D1Image> = None;
+        unsafe { effect.GetOutput(&mut output) };
+        let Some(output) = output else {
+            return Err(Error::from(E_POINTER));
+        };
+
+        let offset = Vector2 {
+            X: source.left as f32 * 96.0 / dpi_x,
+            Y: source.top as f32 * 96.0 / dpi_y,
+        };
+        let image_rect = D2D_RECT_F {
+            left: 0.0,
+            top: 0.0,
+            right: size.width as f32 * 96.0 / dpi_x,
+            bottom: size.height as f32 * 96.0 / dpi_y,
+        };
+
+        // Images aren't drawn with the shared brush, so its opacity, which includes
+        // `with_opacity`, is applied with a layer. The offset is already in window
+        // DIPs, so draw without the current transform.
+        let opacity = unsafe { context.brush.GetOpacity() };
+        context.with_group_opacity(opacity, || {
+            unsafe {
+                context.render_target.SetTransform(&Matrix3x2::identity());
+                device_context.DrawImage(
+                    &output,
+                    Some(&offset),
+                    Some(&image_rect),
+                    D2D1_INTERPOLATION_MODE_LINEAR,
+                    D2D1_COMPOSITE_MODE_SOURCE_OVER,
+                );
+                context.render_target.SetTransform(&transform);
+            }
+            Ok(())
+        })
+    }
+}
+
+impl Clone for BlurRegion {
+    /// Copies the region and its settings. The copy creates its own capture bitmap and
+    /// blur effect on first draw.
+    fn clone(&self) -> Self {
+        Self {
+            bounds: self.bounds,
+            blur_radius: self.blur_radius,
+            fallback: self.fallback,
+            opacity: self.opacity,
+            resources: RefCell::new(None),
+        }
+    }
+}
+
+impl Drawable for BlurRegion {
+    /// Blurs the content behind the region, or fills it with the fallback color if
+    /// blurring is unavailable.
+    ///
+    /// # Errors
+    ///
+    /// This function does not return errors; failures fall back to a translucent fill.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for calling Direct2D methods. The caller
+    /// must ensure that the `drawing_context` contains valid Direct2D resources.
+    fn draw(&self, context: &DrawingContext) -> Result<()> {
+        if self.bounds.width <= 0.0 || self.bounds.height <= 0.0 || self.blur_radius <= 0.0 {
+            return Ok(());
+        }
+
+        context.with_opacity(self.opacity, || {
+            if self.draw_blurred(context).is_err() {
+                let bounds: D2D_RECT_F = self.bounds.into();
+                context.with_brush_color(&self.fallback, |brush| unsafe {
+                    context.render_target.FillRectangle(&bounds, brush);
+                });
+            }
+            Ok(())
+        })
+    }
+
+    /// Returns the blurred region.
+    fn bounds(&self) -> Option<Rect> {
+        Some(self.bounds)
+    }
+
+    crate::impl_clone_box!();
+}
@@ -0,0 +1,884 @@
+use std::time::Duration;
+
+use windows::{Win32::Graphics::Direct2D::Common::D2D_RECT_F, core::Result};
+use windows_numerics::{Matrix3x2, Vector2};
+
+use crate::core::backend::{Paint, Renderer};
+use crate::core::render::color::Color;
+use crate::core::render::drawable::Drawable;
+use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::rect::Rect;
+use crate::core::render::scene::{Layer, ObjectId, SceneLayer};
+use crate::core::render::shadow::Shadow;
+use crate::core::render::transform::{Transform, invert, transform_point};
+
+/// A `Drawable` viewport that draws its child objects clipped to a rectangle, with an
+/// optional scroll offset.
+///
+/// Children are positioned relative to the top-left corner of the canvas's content
+/// area, minus the scroll offset, and anything they draw outside the content area is
+/// cut off. The content area is the canvas rectangle shrunk by its padding, which is
+/// zero unless set with `set_padding`. This makes a
+/// canvas suitable for panels and scrolling lists. To move a set of objects together
+/// without clipping them, use the lighter `Group` instead.
+///
+/// The canvas can also be scaled and rotated, such as to zoom into its content. The
+/// children keep their local, unscaled coordinates, and the clip follows the
+/// transformed rectangle exactly, even under rotation.
+///
+/// Setting an opacity below 1 fades the whole canvas as a group, so overlapping
+/// children don't show through each other.
+///
+/// Clipping can be turned off with `set_clip_enabled`, for children such as popups and
+/// shadows that need to overflow the canvas while keeping its local coordinates.
+///
+/// The canvas can fill its rectangle with a background color behind the children and
+/// stroke a border on top of them, which always match the canvas's current size. It
+/// can also cast a drop shadow, drawn beneath the background and outside the clip.
+///
+/// Children are clipped along with everything they draw, including their own
+/// shadows, so a child's shadow is cut off at the edge of the content area just like
+/// a child scrolled partly out of view. Leave room in the padding for shadows that
+/// should show in full, or turn clipping off.
+///
+/// Children can be removed and accessed as their concrete types by the id returned when
+/// they were added, at any time between frames. They are kept in a `SceneLayer`, so they
+/// are ordered by z-index and `children_mut` offers the rest of the layer's API.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use my_gui::core::render::{objects::canvas::Canvas, objects::text_object::TextObject};
+///
+/// let mut list = Canvas::new(50.0, 50.0, 300.0, 200.0).with_content_size(300.0, 1000.0);
+/// for row in 0..40 {
+///     let y = row as f32 * 25.0;
+///     list.add_child(Box::new(TextObject::new(&format!("Item {row}"), 5.0, y)));
+/// }
+/// list.scroll_by(0.0, 100.0);
+/// ```
+pub struct Canvas {
+    /// The area the canvas occupies and clips its children to.
+    pub rect: Rect,
+    /// The color the canvas is filled with behind its children, or `None` for a
+    /// transparent background.
+    pub background: Option<Color>,
+    /// The color and width of a border stroked along the edges of the canvas on top of
+    /// its children, or `None` for no border.
+    ///
+    /// The border is centered on the edges and isn't clipped, so half of it lies
+    /// outside the canvas.
+    pub border: Option<(Color, f32)>,
+    /// The rotation and scale applied to the whole canvas, including its background,
+    /// border, and clip, or `None` for no transform.
+    ///
+    /// Without an explicit origin, the transform pivots around the center of the canvas.
+    pub transform: Option<Transform>,
+    /// An optional drop shadow drawn beneath the canvas.
+    pub shadow: Option<Shadow>,
+    /// How far the children are scrolled, as the distance from the top-left corner of
+    /// the content to the top-left corner of the content area.
+    scroll_offset: Vector2,
+    /// The width and height of the content, used to limit scrolling, or `None` for no
+    /// upper limit.
+    content_size: Option<(f32, f32)>,
+    /// The space between each edge of the canvas and its content area, as left, top,
+    /// right, and bottom.
+    padding: (f32, f32, f32, f32),
+    /// Whether the children are clipped to the canvas.
+    clip_enabled: bool,
+    /// The opacity of the whole canvas, from 0 (invisible) to 1 (opaque).
+    opacity: f32,
+    /// The child objects, in draw order.
+    children: SceneLayer,
+}
+
+impl Canvas {
+    /// Creates a new, empty `Canvas` covering the given rectangle.
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self {
+            rect: Rect::new(x, y, width, height),
+            background: None,
+            border: None,
+            transform: None,
+            shadow: None,
+            scroll_offset: Vector2::default(),
+            content_size: None,
+            padding: (0.0, 0.0, 0.0, 0.0),
+            clip_enabled: true,
+            opacity: 1.0,
+            children: SceneLayer::new(Layer::Content),
+        }
+    }
+
+    /// Sets the size of the content, which limits how far the canvas can be scrolled.
+    pub fn with_content_size(mut self, width: f32, height: f32) -> Self {
+        self.set_content_size(Some((width, height)));
+        self
+    }
+
+    /// Sets the color the canvas is filled with behind its children.
+    pub fn with_background(mut self, color: Color) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    /// Sets the color and width of the border stroked along the edges of the canvas.
+    pub fn with_border(mut self, color: Color, width: f32) -> Self {
+        self.border = Some((color, width));
+        self
+    }
+
+    /// Adds a drop shadow beneath the canvas.
+    ///
+    /// The shadow follows the canvas's transform and opacity. See `Shadow` for how the
+    /// blur is rendered.
+    pub fn with_shadow(mut self, offset: Vector2, blur_radius: f32, color: Color) -> Self {
+        self.shadow = Some(Shadow::new(offset, blur_radius, color));
+        self
+    }
+
+    /// Sets the rotation and scale applied to the whole canvas.
+    pub fn with_transform(mut self, transform: Transform) -> Self {
+        self.transform = Some(transform);
+        self
+    }
+
+    /// Changes the rotation and scale applied to the whole canvas, or removes it with
+    /// `None`.
+    pub fn set_transform(&mut self, transform: Option<Transform>) {
+        self.transform = transform;
+    }
+
+    /// Changes the scale factors of the canvas, such as to zoom into its content,
+    /// keeping any rotation.
+    pub fn set_scale(&mut self, x: f32, y: f32) {
+        let transform = self.transform.unwrap_or_default();
+        self.transform = Some(transform.with_scale(x, y));
+    }
+
+    /// Changes the clockwise rotation of the canvas, in degrees, keeping any scale.
+    ///
+    /// The rotation pivots around `origin`, or the center of the canvas if `None`. The
+    /// same pivot is used for the scale.
+    pub fn set_rotation(&mut self, degrees: f32, origin: Option<Vector2>) {
+        let transform = self.transform.unwrap_or_default();
+        self.transform = Some(Transform {
+            origin,
+            ..transform.with_rotation(degrees)
+        });
+    }
+
+    /// Sets the space between each edge of the canvas and its content area.
+    pub fn with_padding(mut self, left: f32, top: f32, right: f32, bottom: f32) -> Self {
+        self.set_padding(left, top, right, bottom);
+        self
+    }
+
+    /// Sets the opacity of the whole canvas.
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.set_opacity(opacity);
+        self
+    }
+
+    /// Returns the opacity of the whole canvas.
+    pub fn opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    /// Changes the opacity of the whole canvas, clamped to `[0, 1]`.
+    ///
+    /// Below 1, the canvas is drawn into a Direct2D layer and then blended as a group,
+    /// which costs an extra pass. At 1, the canvas is drawn directly.
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity.clamp(0.0, 1.0);
+    }
+
+    /// Moves the canvas, and all its children with it, to the given position.
+    pub fn set_position(&mut self, x: f32, y: f32) {
+        self.rect.x = x;
+        self.rect.y = y;
+    }
+
+    /// Changes the size of the canvas, keeping the scroll offset within range.
+    pub fn set_size(&mut self, width: f32, height: f32) {
+        self.rect.width = width;
+        self.rect.height = height;
+        self.scroll_to(self.scroll_offset);
+    }
+
+    /// Moves and resizes the canvas, keeping the scroll offset within range.
+    pub fn set_rect(&mut self, rect: Rect) {
+        self.set_position(rect.x, rect.y);
+        self.set_size(rect.width, rect.height);
+    }
+
+    /// Returns `true` if the children are clipped to the canvas, which is the default.
+    pub fn clip_enabled(&self) -> bool {
+        self.clip_enabled
+    }
+
+    /// Turns clipping of the children to the canvas on or off.
+    ///
+    /// Without clipping, the children are still positioned relative to the canvas and
+    /// scrolled with it, but can draw and be hit outside it.
+    pub fn set_clip_enabled(&mut self, enabled: bool) {
+        self.clip_enabled = enabled;
+    }
+
+    /// Returns the space between each edge of the canvas and its content area, as left,
+    /// top, right, and bottom.
+    pub fn padding(&self) -> (f32, f32, f32, f32) {
+        self.padding
+    }
+
+    /// Changes the space between each edge of the canvas and its content area, keeping
+    /// the scroll offset within range.
+    ///
+    /// The background and border still cover the whole canvas, while the children are
+    /// moved in by the left and top padding and clipped to the content area.
+    pub fn set_padding(&mut self, left: f32, top: f32, right: f32, bottom: f32) {
+        self.padding = (left, top, right, bottom);
+        self.scroll_to(self.scroll_offset);
+    }
+
+    /// Returns the area inside the padding that the children are clipped to, in the
+    /// coordinates the canvas is drawn in before its transform.
+    pub fn content_rect(&self) -> Rect {
+        let (left, top, right, bottom) = self.padding;
+        Rect::new(
+            self.rect.x + left,
+            self.rect.y + top,
+            (self.rect.width - left - right).max(0.0),
+            (self.rect.height - top - bottom).max(0.0),
+        )
+    }
+
+    /// Returns the size of the content, or `None` if scrolling is unlimited.
+    pub fn content_size(&self) -> Option<(f32, f32)> {
+        self.content_size
+    }
+
+    /// Changes the size of the content, keeping the scroll offset within range, or
+    /// removes the limit with `None`.
+    pub fn set_content_size(&mut self, content_size: Option<(f32, f32)>) {
+        self.content_size = content_size;
+        self.scroll_to(self.scroll_offset);
+    }
+
+    /// Returns how far the children are scrolled.
+    pub fn scroll_offset(&self) -> Vector2 {
+        self.scroll_offset
+    }
+
+    /// Returns the largest scroll offset in each direction, or `None` for a direction
+    /// without a limit.
+    ///
+    /// The content can't be scrolled in a direction where it fits inside the content
+    /// area.
+    pub fn max_scroll_offset(&self) -> Option<Vector2> {
+        let viewport = self.content_rect();
+        self.content_size.map(|(width, height)| Vector2 {
+            X: (width - viewport.width).max(0.0),
+            Y: (height - viewport.height).max(0.0),
+        })
+    }
+
+    /// Scrolls the children so that `offset` of the content is at the top-left corner
+    /// of the content area.
+    ///
+    /// The offset is clamped so it is never negative and, if the content size is set,
+    /// never scrolls past the end of the content.
+    pub fn scroll_to(&mut self, offset: Vector2) {
+        let max = self.max_scroll_offset().unwrap_or(Vector2 {
+            X: f32::INFINITY,
+            Y: f32::INFINITY,
+        });
+        self.scroll_offset = Vector2 {
+            X: offset.X.min(max.X).max(0.0),
+            Y: offset.Y.min(max.Y).max(0.0),
+        };
+    }
+
+    /// Scrolls the children by the given distance, such as for a mouse wheel notch.
+    /// Positive distances reveal content further right and down.
+    pub fn scroll_by(&mut self, dx: f32, dy: f32) {
+        self.scroll_to(Vector2 {
+            X: self.scroll_offset.X + dx,
+            Y: self.scroll_offset.Y + dy,
+        });
+    }
+
+    /// Adds a child object with a z-index of 0, positioned relative to the content's
+    /// top-left corner.
+    ///
+    /// Returns the id used to find or remove the child later.
+    pub fn add_child(&mut self, child: Box<dyn Drawable>) -> ObjectId {
+        self.children.add_object(child)
+    }
+
+    /// Adds a child object with the given z-index, positioned relative to the content's
+    /// top-left corner.
+    pub fn add_child_with_z_index(&mut self, child: Box<dyn Drawable>, z_index: i32) -> ObjectId {
+        self.children.add_object_with_z_index(child, z_index)
+    }
+
+    /// Removes a child from the canvas.
+    ///
+    /// Returns `false` if the canvas has no child with the given id.
+    pub fn remove_child(&mut self, id: ObjectId) -> bool {
+        self.children.remove_object(id)
+    }
+
+    /// Removes every child from the canvas.
+    pub fn clear(&mut self) {
+        self.children.retain(|_, _| false);
+    }
+
+    /// Returns the number of children in the canvas.
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    /// Returns `true` if the canvas has no children.
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// Returns a reference to a child as its concrete type, or `None` if the canvas has
+    /// no child with the given id or it is not a `T`.
+    pub fn get_child<T: Drawable + 'static>(&self, id: ObjectId) -> Option<&T> {
+        self.children.get_object(id)
+    }
+
+    /// Returns a mutable reference to a child as its concrete type, or `None` if the
+    /// canvas has no child with the given id or it is not a `T`.
+    pub fn get_child_mut<T: Drawable + 'static>(&mut self, id: ObjectId) -> Option<&mut T> {
+        self.children.get_object_mut(id)
+    }
+
+    /// Returns the canvas's children.
+    pub fn children(&self) -> &SceneLayer {
+        &self.children
+    }
+
+    /// Returns the canvas's children, for removing, reordering, or changing them.
+    pub fn children_mut(&mut self) -> &mut SceneLayer {
+        &mut self.children
+    }
+
+    /// Maps a point from the coordinates the canvas is drawn in, such as window
+    /// coordinates for a canvas added directly to a scene, to its children's local
+    /// coordinates, taking the transform, padding, and scroll offset into account.
+    ///
+    /// Returns `None` if clipping is enabled and the point is outside the content area,
+    /// where the children are clipped away, or if the canvas's transform can't be
+    /// inverted.
+    pub fn map_to_local(&self, point: Vector2) -> Option<Vector2> {
+        let point = transform_point(&invert(&self.frame_transform())?, point);
+        let viewport = self.content_rect();
+        if self.clip_enabled && !viewport.contains(point) {
+            return None;
+        }
+        Some(Vector2 {
+            X: point.X - viewport.x + self.scroll_offset.X,
+            Y: point.Y - viewport.y + self.scroll_offset.Y,
+        })
+    }
+
+    /// Returns the children under `point`, front to back, where `point` is in the
+    /// coordinates the canvas is drawn in, as for `map_to_local`.
+    ///
+    /// Ids are only unique within the canvas that handed them out, so each hit is the
+    /// path of ids from a child of this canvas down to the object under the point.
+    /// Nested canvases are searched recursively, and hits inside a nested canvas come
+    /// before the nested canvas itself. Children clipped away by a canvas are never hit,
+    /// but children overflowing a canvas with clipping disabled are.
+    ///
+    /// Together with `Scene::hit_test`, which finds the canvas itself, this gives
+    /// everything under the cursor.
+    pub fn hit_test(&self, point: Vector2) -> Vec<Vec<ObjectId>> {
+        let Some(local) = self.map_to_local(point) else {
+            return Vec::new();
+        };
+        let mut hits = Vec::new();
+        for id in self.children.hit_test(local) {
+            if let Some(canvas) = self.children.get_object::<Canvas>(id) {
+                hits.extend(canvas.hit_test(local).into_iter().map(|mut path| {
+                    path.insert(0, id);
+                    path
+                }));
+                // Without clipping, the nested canvas may only have been found through
+                // its overflowing children.
+                if !canvas.frame_contains(local) {
+                    continue;
+                }
+            }
+            hits.push(vec![id]);
+        }
+        hits
+    }
+
+    /// Copies the canvas along with every child, or returns `None` if any child can't
+    /// be copied.
+    pub(crate) fn snapshot(&self) -> Option<Self> {
+        Some(Self {
+            rect: self.rect,
+            background: self.background,
+            border: self.border,
+            transform: self.transform,
+            shadow: self.shadow,
+            scroll_offset: self.scroll_offset,
+            content_size: self.content_size,
+            padding: self.padding,
+            clip_enabled: self.clip_enabled,
+            opacity: self.opacity,
+            children: self.children.snapshot()?,
+        })
+    }
+
+    /// Returns `true` if `point`, in the coordinates the canvas is drawn in, lies inside
+    /// the canvas rectangle.
+    fn frame_contains(&self, point: Vector2) -> bool {
+        invert(&self.frame_transform())
+            .is_some_and(|inverse| self.rect.contains(transform_point(&inverse, point)))
+    }
+
+    /// Returns the matrix that maps the children's coordinates to the coordinates the
+    /// canvas itself is drawn in.
+    pub fn local_transform(&self) -> Matrix3x2 {
+        self.content_transform() * self.frame_transform()
+    }
+
+    /// Returns the matrix for the canvas's own transform, which is applied to its
+    /// rectangle as well as its children.
+    pub(crate) fn frame_transform(&self) -> Matrix3x2 {
+        match &self.transform {
+            Some(transform) if !transform.is_identity() => {
+                let center = Vector2 {
+                    X: self.rect.x + self.rect.width / 2.0,
+                    Y: self.rect.y + self.rect.height / 2.0,
+                };
+                transform.to_matrix(center)
+            }
+            _ => Matrix3x2::identity(),
+        }
+    }
+
+    /// Returns the matrix that offsets the children by the position of the content area
+    /// and the scroll offset.
+    fn content_transform(&self) -> Matrix3x2 {
+        let viewport = self.content_rect();
+        Matrix3x2::translation(
+            viewport.x - self.scroll_offset.X,
+            viewport.y - self.scroll_offset.Y,
+        )
+    }
+}
+
+impl Drawable for Canvas {
+    /// Draws the shadow, fills the background, draws the children clipped to the canvas
+    /// and offset by its position and scroll offset, and then strokes the border, all
+    /// with the canvas's transform and opacity.
+    ///
+    /// The transform, clip, and offset are applied on top of the render target's
+    /// current transform, so canvases can be nested.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if any of the children fail to draw, or if
+    /// the clip can't be created.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for calling Direct2D drawing methods. The
+    /// caller must ensure that the `drawing_context` contains valid Direct2D resources.
+    fn draw(&self, context: &DrawingContext) -> Result<()> {
+        context.with_group_opacity(self.opacity, || {
+            context.with_transform(&self.frame_transform(), || {
+                let rect: D2D_RECT_F = self.rect.into();
+
+                if let Some(shadow) = &self.shadow {
+                    shadow.draw_for_rect(context, self.rect);
+                }
+                if let Some(color) = &self.background {
+                    context.with_brush_color(color, |brush| unsafe {
+                        context.render_target.FillRectangle(&rect, brush);
+                    });
+                }
+
+                let draw_children = || {
+                    context.with_transform(&self.content_transform(), || {
+                        self.children.draw_all(context)
+                    })
+                };
+                if self.clip_enabled {
+                    context.with_clip(&self.content_rect(), draw_children)??;
+                } else {
+                    draw_children()?;
+                }
+
+                // The border is drawn after the clip is popped so its outer half isn't
+                // cut off.
+                if let Some((color, width)) = self.border.filter(|(_, width)| *width > 0.0) {
+                    context.with_brush_color(&color, |brush| unsafe {
+                        context
+                            .render_target
+                            .DrawRectangle(&rect, brush, width, None);
+                    });
+                }
+                Ok(())
+            })
+        })
+    }
+
+    /// Renders the canvas through `renderer` in the same order as `draw`: the
+    /// background, then the children, clipped to the content area unless clipping is
+    /// disabled, and then the border.
+    ///
+    /// The shadow is skipped, and the opacity is applied to each shape instead of to
+    /// the canvas as a whole.
+    fn render(&self, renderer: &mut dyn Renderer) {
+        renderer.with_opacity(self.opacity, |renderer| {
+            renderer.with_transform(&self.frame_transform(), |renderer| {
+                if let Some(color) = self.background {
+                    renderer.draw_rect(self.rect, Paint::Fill(color));
+                }
+
+                let render_children = |renderer: &mut dyn Renderer| {
+                    renderer.with_transform(&self.content_transform(), |renderer| {
+                        self.children.render_all(renderer)
+                    })
+                };
+                if self.clip_enabled {
+                    renderer.with_clip(self.content_rect(), render_children);
+                } else {
+                    render_children(renderer);
+                }
+
+                if let Some((color, width)) = self.border.filter(|(_, width)| *width > 0.0) {
+                    renderer.draw_rect(self.rect, Paint::Stroke { color, width });
+                }
+            })
+        })
+    }
+
+    /// Advances the animation state of every child.
+    fn update(&mut self, dt: Duration) {
+        self.children.update_all(dt);
+    }
+
+    /// Returns the canvas rectangle grown by half the border width and extended to
+    /// cover the shadow, mapped through the canvas's transform.
+    ///
+    /// With clipping disabled, the bounds also cover the children, and are `None` if any
+    /// child has no bounds, since the canvas could then draw anywhere.
+    fn bounds(&self) -> Option<Rect> {
+        let border_width = self.border.map_or(0.0, |(_, width)| width.max(0.0));
+        let mut frame = self.rect.inflate(border_width / 2.0);
+        if let Some(shadow) = &self.shadow {
+            let shadow_rect = Rect::new(
+                self.rect.x + shadow.offset.X,
+                self.rect.y + shadow.offset.Y,
+                self.rect.width,
+                self.rect.height,
+            );
+            frame = frame.union(&shadow_rect.inflate(shadow.blur_radius.max(0.0)));
+        }
+        let frame = frame.transform(&self.frame_transform());
+        if self.clip_enabled {
+            return Some(frame);
+        }
+        let local = self.local_transform();
+        self.children.iter().try_fold(frame, |bounds, (_, child)| {
+            Some(bounds.union(&child.bounds()?.transform(&local)))
+        })
+    }
+
+    /// Copies the canvas along with every child, or returns `None` if any child can't
+    /// be copied.
+    fn clone_box(&self) -> Option<Box<dyn Drawable>> {
+        Some(Box::new(self.snapshot()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::backend::{DrawCommand, RecordingRenderer};
+    use crate::core::render::direct2d_context::Direct2DContext;
+    use crate::core::render::objects::primitives::Rectangle;
+    use crate::core::render::scene::Scene;
+    use crate::core::window::config::WindowConfig;
+
+    /// The width and height of the bitmaps the tests draw into.
+    const SIZE: u32 = 64;
+
+    /// Draws `canvas` into a transparent bitmap and returns its RGBA8 pixels.
+    fn render(canvas: Canvas) -> Vec<u8> {
+        let context = Direct2DContext::new(&WindowConfig::default()).unwrap();
+        let mut scene = Scene::new();
+        scene.add_object(Box::new(canvas));
+        context.render_to_bitmap(&scene, SIZE, SIZE).unwrap()
+    }
+
+    /// Returns the pixel at `(x, y)`.
+    fn pixel(pixels: &[u8], x: u32, y: u32) -> [u8; 4] {
+        let start = (y * SIZE + x) as usize * 4;
+        [
+            pixels[start],
+            pixels[start + 1],
+            pixels[start + 2],
+            pixels[start + 3],
+        ]
+    }
+
+    const BLACK: [u8; 4] = [0, 0, 0, 255];
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const CLEAR: [u8; 4] = [0, 0, 0, 0];
+
+    #[test]
+    fn shadow_is_drawn_beneath_the_canvas_and_extends_its_bounds() {
+        let offset = Vector2 { X: 8.0, Y: 8.0 };
+        let canvas = Canvas::new(16.0, 16.0, 32.0, 32.0)
+            .with_background(Color::RED)
+            .with_shadow(offset, 0.0, Color::BLACK);
+        assert_eq!(canvas.bounds(), Some(Rect::new(16.0, 16.0, 40.0, 40.0)));
+
+        let blurred = Canvas::new(0.0, 0.0, 10.0, 10.0).with_shadow(offset, 2.0, Color::BLACK);
+        assert_eq!(blurred.bounds(), Some(Rect::new(0.0, 0.0, 20.0, 20.0)));
+
+        let pixels = render(canvas);
+        assert_eq!(pixel(&pixels, 30, 30), RED);
+        assert_eq!(pixel(&pixels, 52, 52), BLACK);
+        assert_eq!(pixel(&pixels, 20, 52), CLEAR);
+    }
+
+    #[test]
+    fn child_shadows_are_clipped_to_the_content_area() {
+        let offset = Vector2 { X: 8.0, Y: 8.0 };
+        let mut canvas = Canvas::new(16.0, 16.0, 32.0, 32.0);
+        canvas.add_child(Box::new(
+            Rectangle::new(0.0, 0.0, 32.0, 32.0, Color::RED).with_shadow(offset, 0.0, Color::BLACK),
+        ));
+
+        let pixels = render(canvas.snapshot().unwrap());
+        assert_eq!(pixel(&pixels, 30, 30), RED);
+        assert_eq!(pixel(&pixels, 52, 52), CLEAR);
+
+        // Without clipping, the shadow shows in full.
+        canvas.set_clip_enabled(false);
+        let pixels = render(canvas);
+        assert_eq!(pixel(&pixels, 52, 52), BLACK);
+    }
+
+    #[test]
+    fn render_pops_its_clips_and_restores_the_transform() {
+        let mut inner = Canvas::new(5.0, 5.0, 20.0, 20.0).with_background(Color::BLUE);
+        inner.add_child(Box::new(Rectangle::new(0.0, 0.0, 4.0, 4.0, Color::RED)));
+        let mut outer = Canvas::new(10.0, 10.0, 50.0, 50.0).with_border(Color::WHITE, 2.0);
+        outer.add_child(Box::new(inner));
+        let mut unclipped = Canvas::new(0.0, 0.0, 10.0, 10.0);
+        unclipped.set_clip_enabled(false);
+        outer.add_child(Box::new(unclipped));
+
+        let mut renderer = RecordingRenderer::new();
+        outer.render(&mut renderer);
+
+        assert_eq!(renderer.clip_depth(), 0);
+        assert_eq!(renderer.transform(), Matrix3x2::identity());
+        let commands: Vec<_> = renderer
+            .commands()
+            .iter()
+            .filter(|command| !matches!(command, DrawCommand::SetTransform(_)))
+            .collect();
+        assert_eq!(
+            commands,
+            [
+                &DrawCommand::PushClip(Rect::new(10.0, 10.0, 50.0, 50.0)),
+                &DrawCommand::Rect {
+                    rect: Rect::new(5.0, 5.0, 20.0, 20.0),
+                    paint: Paint::Fill(Color::BLUE),
+                },
+                &DrawCommand::PushClip(Rect::new(5.0, 5.0, 20.0, 20.0)),
+                &DrawCommand::Rect {
+                    rect: Rect::new(0.0, 0.0, 4.0, 4.0),
+                    paint: Paint::Fill(Color::RED),
+                },
+                &DrawCommand::PopClip,
+                &DrawCommand::PopClip,
+                &DrawCommand::Rect {
+                    rect: Rect::new(10.0, 10.0, 50.0, 50.0),
+                    paint: Paint::Stroke {
+                        color: Color::WHITE,
+                        width: 2.0,
+                    },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_canvases_compose_their_transforms_and_clips() {
+        let mut inner = Canvas::new(10.0, 10.0, 20.0, 20.0);
+        inner.add_child(Box::new(Rectangle::new(0.0, 0.0, 40.0, 40.0, Color::RED)));
+        let mut outer = Canvas::new(100.0, 100.0, 50.0, 50.0);
+        outer.add_child(Box::new(inner));
+
+        let mut renderer = RecordingRenderer::new();
+        outer.render(&mut renderer);
+
+        // The rectangle overflows the inner canvas, so it must be drawn inside both
+        // clips, each in the coordinates of its own canvas.
+        let mut transform = Matrix3x2::identity();
+        let mut clips = Vec::new();
+        let mut drawn = Vec::new();
+        for command in renderer.commands() {
+            match command {
+                DrawCommand::SetTransform(matrix) => transform = *matrix,
+                DrawCommand::PushClip(rect) => clips.push(rect.transform(&transform)),
+                DrawCommand::PopClip => {
+                    clips.pop();
+                }
+                DrawCommand::Rect { rect, .. } => drawn.push((*rect, transform, clips.clone())),
+                _ => {}
+            }
+        }
+
+        assert_eq!(
+            drawn,
+            [(
+                Rect::new(0.0, 0.0, 40.0, 40.0),
+                Matrix3x2::translation(110.0, 110.0),
+                vec![
+                    Rect::new(100.0, 100.0, 50.0, 50.0),
+                    Rect::new(110.0, 110.0, 20.0, 20.0),
+                ],
+            )]
+        );
+        assert!(clips.is_empty());
+    }
+
+    #[test]
+    fn nested_map_to_local_applies_each_canvas_padding_and_scroll() {
+        let mut outer = Canvas::new(10.0, 10.0, 200.0, 200.0)
+            .with_padding(5.0, 5.0, 5.0, 5.0)
+            .with_content_size(400.0, 400.0);
+        outer.scroll_to(Vector2::new(20.0, 30.0));
+        let mut inner = Canvas::new(50.0, 60.0, 100.0, 100.0)
+            .with_padding(2.0, 4.0, 0.0, 0.0)
+            .with_content_size(300.0, 300.0);
+        inner.scroll_to(Vector2::new(7.0, 9.0));
+        let inner_id = outer.add_child(Box::new(inner));
+        let inner = outer.get_child::<Canvas>(inner_id).unwrap();
+
+        // The content area starts at (15, 15) in the window and is scrolled by (20, 30);
+        // the inner one starts at (52, 64) in the outer content and is scrolled by (7, 9).
+        let outer_local = outer.map_to_local(Vector2::new(100.0, 120.0)).unwrap();
+        assert_eq!(outer_local, Vector2::new(105.0, 135.0));
+        assert_eq!(
+            inner.map_to_local(outer_local),
+            Some(Vector2::new(60.0, 80.0))
+        );
+
+        // Points in the padding are outside the content area, where children are
+        // clipped away.
+        assert_eq!(outer.map_to_local(Vector2::new(12.0, 100.0)), None);
+        assert_eq!(inner.map_to_local(Vector2::new(51.0, 100.0)), None);
+    }
+
+    #[test]
+    fn hit_test_returns_paths_through_nested_canvases() {
+        let mut outer = Canvas::new(0.0, 0.0, 100.0, 100.0);
+        let backdrop = outer.add_child(Box::new(Rectangle::new(
+            0.0,
+            0.0,
+            100.0,
+            100.0,
+            Color::BLUE,
+        )));
+        let mut inner = Canvas::new(20.0, 20.0, 50.0, 50.0).with_content_size(100.0, 100.0);
+        inner.scroll_to(Vector2::new(10.0, 10.0));
+        let rectangle =
+            inner.add_child(Box::new(Rectangle::new(30.0, 30.0, 10.0, 10.0, Color::RED)));
+        let inner = outer.add_child(Box::new(inner));
+
+        // (45, 45) is (35, 35) in the scrolled inner canvas, on the rectangle.
+        assert_eq!(
+            outer.hit_test(Vector2::new(45.0, 45.0)),
+            [vec![inner, rectangle], vec![inner], vec![backdrop]]
+        );
+        // (25, 25) is (15, 15) in the inner canvas, beside the rectangle.
+        assert_eq!(
+            outer.hit_test(Vector2::new(25.0, 25.0)),
+            [vec![inner], vec![backdrop]]
+        );
+        assert_eq!(outer.hit_test(Vector2::new(80.0, 80.0)), [vec![backdrop]]);
+        assert!(outer.hit_test(Vector2::new(150.0, 50.0)).is_empty());
+    }
+
+    #[test]
+    fn scroll_to_clamps_negative_offsets_to_zero() {
+        let mut canvas = Canvas::new(0.0, 0.0, 100.0, 100.0);
+        canvas.scroll_to(Vector2::new(-10.0, 40.0));
+        assert_eq!(canvas.scroll_offset(), Vector2::new(0.0, 40.0));
+
+        canvas.set_content_size(Some((300.0, 300.0)));
+        canvas.scroll_to(Vector2::new(-10.0, -20.0));
+        assert_eq!(canvas.scroll_offset(), Vector2::new(0.0, 0.0));
+        canvas.scroll_by(-5.0, 50.0);
+        assert_eq!(canvas.scroll_offset(), Vector2::new(0.0, 50.0));
+    }
+
+    #[test]
+    fn scroll_to_keeps_content_smaller_than_the_viewport_at_zero() {
+        // The content is narrower than the viewport but taller.
+        let mut canvas = Canvas::new(0.0, 0.0, 100.0, 100.0).with_content_size(50.0, 300.0);
+        assert_eq!(canvas.max_scroll_offset(), Some(Vector2::new(0.0, 200.0)));
+
+        canvas.scroll_to(Vector2::new(30.0, 500.0));
+        assert_eq!(canvas.scroll_offset(), Vector2::new(0.0, 200.0));
+
+        // Shrinking the content below the viewport scrolls it back to the start.
+        canvas.set_content_size(Some((50.0, 80.0)));
+        assert_eq!(canvas.scroll_offset(), Vector2::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn local_transform_uses_the_clamped_scroll_offset() {
+        let mut canvas = Canvas::new(10.0, 20.0, 100.0, 100.0)
+            .with_padding(5.0, 5.0, 0.0, 0.0)
+            .with_content_size(150.0, 150.0);
+
+        // The content area is 95 DIPs square, so the offset stops at 55.
+        canvas.scroll_to(Vector2::new(500.0, 500.0));
+        assert_eq!(canvas.scroll_offset(), Vector2::new(55.0, 55.0));
+        let origin = transform_point(&canvas.local_transform(), Vector2::default());
+        assert_eq!(origin, Vector2::new(-40.0, -30.0));
+
+        // Growing the canvas shrinks the range, and the transform follows.
+        canvas.set_size(150.0, 180.0);
+        assert_eq!(canvas.scroll_offset(), Vector2::new(5.0, 0.0));
+        let origin = transform_point(&canvas.local_transform(), Vector2::default());
+        assert_eq!(origin, Vector2::new(10.0, 25.0));
+    }
+
+    #[test]
+    fn frame_transform_ignores_the_scroll_offset() {
+        let mut canvas = Canvas::new(0.0, 0.0, 100.0, 100.0)
+            .with_content_size(200.0, 200.0)
+            .with_transform(Transform::scale(2.0, 2.0));
+        let frame = canvas.frame_transform();
+
+        canvas.scroll_to(Vector2::new(150.0, -10.0));
+        assert_eq!(canvas.scroll_offset(), Vector2::new(100.0, 0.0));
+        assert_eq!(canvas.frame_transform(), frame);
+
+        // The scale pivots around the center of the canvas, after the content is
+        // offset by the clamped scroll.
+        let child = Vector2::new(120.0, 30.0);
+        let drawn = transform_point(&canvas.local_transform(), child);
+        assert_eq!(drawn, Vector2::new(-10.0, 10.0));
+        assert_eq!(canvas.map_to_local(drawn), Some(child));
+    }
+}
@@ -0,0 +1,170 @@
+use std::time::Duration;
+
+use windows::core::Result;
+use windows_numerics::{Matrix3x2, Vector2};
+
+use crate::core::backend::Renderer;
+use crate::core::render::drawable::Drawable;
+use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::rect::Rect;
+use crate::core::render::scene::{Layer, ObjectId, SceneLayer};
+use crate::core::render::transform::Transform;
+
+/// A `Drawable` that draws a set of child objects with a shared position and
+/// transform.
+///
+/// Children are positioned relative to the group's position, so moving, rotating, or
+/// scaling the group moves all of them together. Nothing is clipped: children may
+/// extend in any direction past the group's position. This makes a group lighter than
+/// a `Canvas`, which clips its children to a rectangle.
+///
+/// The children are kept in a `SceneLayer`, so they are ordered by z-index and can be
+/// removed, reordered, and accessed as their concrete types through `children_mut`,
+/// just like the objects of a `Scene`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use my_gui::core::render::{
+///     color::Color,
+///     objects::{group::Group, primitives::Rectangle, text_object::TextObject},
+///     scene::Scene,
+///     transform::Transform,
+/// };
+///
+/// let mut card = Group::new(100.0, 100.0);
+/// card.add_child(Box::new(Rectangle::new(0.0, 0.0, 160.0, 90.0, Color::BLUE)));
+/// card.add_child(Box::new(TextObject::new("Card", 10.0, 10.0)));
+/// card.set_transform(Some(Transform::rotation(15.0)));
+///
+/// let mut scene = Scene::new();
+/// scene.add_object(Box::new(card));
+/// ```
+pub struct Group {
+    /// The x-coordinate of the group's origin, which its children are positioned from.
+    pub x: f32,
+    /// The y-coordinate of the group's origin, which its children are positioned from.
+    pub y: f32,
+    /// The rotation and scale applied to all children, or `None` for no transform.
+    ///
+    /// Without an explicit origin, the transform pivots around the group's origin.
+    pub transform: Option<Transform>,
+    /// The child objects, in draw order.
+    children: SceneLayer,
+}
+
+impl Group {
+    /// Creates a new, empty `Group` with its origin at the given position.
+    pub fn new(x: f32, y: f32) -> Self {
+        Self {
+            x,
+            y,
+            transform: None,
+            children: SceneLayer::new(Layer::Content),
+        }
+    }
+
+    /// Sets the rotation and scale applied to all children.
+    pub fn with_transform(mut self, transform: Transform) -> Self {
+        self.transform = Some(transform);
+        self
+    }
+
+    /// Moves the group's origin, and all its children with it, to the given position.
+    pub fn set_position(&mut self, x: f32, y: f32) {
+        self.x = x;
+        self.y = y;
+    }
+
+    /// Changes the rotation and scale applied to all children, or removes it with
+    /// `None`.
+    pub fn set_transform(&mut self, transform: Option<Transform>) {
+        self.transform = transform;
+    }
+
+    /// Adds a child object with a z-index of 0, positioned relative to the group's
+    /// origin.
+    ///
+    /// Returns the id used to find or remove the child later through `children_mut`.
+    pub fn add_child(&mut self, child: Box<dyn Drawable>) -> ObjectId {
+        self.children.add_object(child)
+    }
+
+    /// Adds a child object with the given z-index, positioned relative to the group's
+    /// origin.
+    pub fn add_child_with_z_index(&mut self, child: Box<dyn Drawable>, z_index: i32) -> ObjectId {
+        self.children.add_object_with_z_index(child, z_index)
+    }
+
+    /// Returns the group's children.
+    pub fn children(&self) -> &SceneLayer {
+        &self.children
+    }
+
+    /// Returns the group's children, for removing, reordering, or changing them.
+    pub fn children_mut(&mut self) -> &mut SceneLayer {
+        &mut self.children
+    }
+
+    /// Returns the matrix that maps the children's coordinates to the coordinates the
+    /// group itself is drawn in.
+    pub fn local_transform(&self) -> Matrix3x2 {
+        let translation = Matrix3x2::translation(self.x, self.y);
+        match &self.transform {
+            Some(transform) if !transform.is_identity() => {
+                transform.to_matrix(Vector2::default()) * translation
+            }
+            _ => translation,
+        }
+    }
+}
+
+impl Drawable for Group {
+    /// Draws the children with the group's position and transform composed onto the
+    /// render target's current transform.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if any of the children fail to draw.
+    fn draw(&self, context: &DrawingContext) -> Result<()> {
+        context.with_transform(&self.local_transform(), || self.children.draw_all(context))
+    }
+
+    /// Renders the children through `renderer` with the group's position and transform
+    /// composed onto its transform.
+    fn render(&self, renderer: &mut dyn Renderer) {
+        renderer.with_transform(&self.local_transform(), |renderer| {
+            self.children.render_all(renderer)
+        })
+    }
+
+    /// Advances the animation state of every child.
+    fn update(&mut self, dt: Duration) {
+        self.children.update_all(dt);
+    }
+
+    /// Returns the union of the children's bounds, mapped through the group's position
+    /// and transform.
+    ///
+    /// Returns `None` if the group is empty or any child has no bounds, since the group
+    /// could then draw anywhere.
+    fn bounds(&self) -> Option<Rect> {
+        let mut children = self.children.iter();
+        let (_, first) = children.next()?;
+        let bounds = children.try_fold(first.bounds()?, |bounds, (_, child)| {
+            Some(bounds.union(&child.bounds()?))
+        })?;
+        Some(bounds.transform(&self.local_transform()))
+    }
+
+    /// Copies the group along with every child, or returns `None` if any child can't be
+    /// copied.
+    fn clone_box(&self) -> Option<Box<dyn Drawable>> {
+        Some(Box::new(Self {
+            x: self.x,
+            y: self.y,
+            transform: self.transform,
+            children: self.children.snapshot()?,
+        }))
+    }
+}
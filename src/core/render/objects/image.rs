@@ -0,0 +1,81 @@
+use std::{path::PathBuf, rc::Rc};
+
+use windows::core::*;
+
+use crate::core::render::drawable::Drawable;
+use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::image_source::{ImageSource, Interpolation};
+use crate::core::render::rect::Rect;
+
+/// A `Drawable` image loaded from a file on disk.
+///
+/// The image is stretched to fill its destination rectangle. See `ImageSource` for
+/// details on how decoding and device-dependent resources are managed.
+#[derive(Clone)]
+pub struct Image {
+    /// The source of the image pixels, which may be shared with other drawables.
+    pub source: Rc<ImageSource>,
+    /// The x-coordinate of the top-left corner of the destination rectangle.
+    pub x: f32,
+    /// The y-coordinate of the top-left corner of the destination rectangle.
+    pub y: f32,
+    /// The width of the destination rectangle.
+    pub width: f32,
+    /// The height of the destination rectangle.
+    pub height: f32,
+    /// The opacity of the image, from `0.0` (transparent) to `1.0` (opaque).
+    pub opacity: f32,
+}
+
+impl Image {
+    /// Creates a new, fully opaque `Image` from the file at `path`.
+    ///
+    /// The file is decoded the first time the image is drawn; decoding errors are
+    /// reported by `draw`.
+    pub fn new(path: impl Into<PathBuf>, x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self::from_source(Rc::new(ImageSource::from_file(path)), x, y, width, height)
+    }
+
+    /// Creates a new, fully opaque `Image` from a shared `ImageSource`.
+    pub fn from_source(source: Rc<ImageSource>, x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self {
+            source,
+            x,
+            y,
+            width,
+            height,
+            opacity: 1.0,
+        }
+    }
+
+    /// Sets the opacity of the image.
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+}
+
+impl Drawable for Image {
+    /// Draws the image into its destination rectangle.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the image file cannot be decoded or if
+    /// it fails to create the Direct2D bitmap.
+    fn draw(&self, context: &DrawingContext) -> Result<()> {
+        context.draw_bitmap(
+            &self.source,
+            &Rect::new(self.x, self.y, self.width, self.height),
+            None,
+            self.opacity,
+            Interpolation::Linear,
+        )
+    }
+
+    /// Returns the destination rectangle.
+    fn bounds(&self) -> Option<Rect> {
+        Some(Rect::new(self.x, self.y, self.width, self.height))
+    }
+
+    crate::impl_clone_box!();
+}
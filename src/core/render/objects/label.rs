@@ -0,0 +1,105 @@
+use windows::{core::*, Win32::Graphics::Direct2D::Common::D2D_RECT_F};
+use windows_numerics::Vector2;
+
+use crate::core::render::drawable::Drawable;
+use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::fill::Fill;
+use crate::core::render::objects::text_object::TextObject;
+use crate::core::render::rect::Rect;
+
+/// A `Drawable` piece of text on a background that is sized to fit it.
+///
+/// The background is recomputed from the text's bounds every time the label is drawn,
+/// so it follows changes to the text, its alignment, and the window size.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use my_gui::core::render::{
+///     color::Color,
+///     objects::{label::Label, text_object::TextObject},
+/// };
+///
+/// let label = Label::new(TextObject::new("Score: 42", 20.0, 20.0), Color::BLUE)
+///     .with_padding(6.0);
+/// ```
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Label {
+    /// The text of the label.
+    pub text: TextObject,
+    /// How the background behind the text is painted.
+    pub background: Fill,
+    /// The space between the text and the edges of the background.
+    pub padding: f32,
+}
+
+impl Label {
+    /// Creates a new `Label` with a 4 DIP padding around the text.
+    pub fn new(text: TextObject, background: impl Into<Fill>) -> Self {
+        Self {
+            text,
+            background: background.into(),
+            padding: 4.0,
+        }
+    }
+
+    /// Sets the space between the text and the edges of the background.
+    pub fn with_padding(mut self, padding: f32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Returns the rectangle covered by the background, which is the text's bounds
+    /// grown by the padding.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to lay out the text.
+    pub fn background_rect(&self, context: &DrawingContext) -> Result<Rect> {
+        let bounds = context.text_bounds(&self.text)?;
+        Ok(Rect::new(
+            bounds.x - self.padding,
+            bounds.y - self.padding,
+            bounds.width + self.padding * 2.0,
+            bounds.height + self.padding * 2.0,
+        ))
+    }
+}
+
+impl Drawable for Label {
+    /// Fills the background and then draws the text on top of it.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to lay out or draw the text, or
+    /// to create the background brush.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for calling Direct2D drawing methods.
+    /// The caller must ensure that the `drawing_context` contains valid Direct2D resources.
+    fn draw(&self, context: &DrawingContext) -> Result<()> {
+        let background = self.background_rect(context)?;
+        let origin = Vector2 {
+            X: background.x,
+            Y: background.y,
+        };
+        let bounds: D2D_RECT_F = background.into();
+        self.background
+            .with_brush(context, origin, |brush| unsafe {
+                context.render_target.FillRectangle(&bounds, brush);
+            })?;
+
+        self.text.draw(context)
+    }
+
+    /// Returns the background rectangle, computed from the text's last layout.
+    ///
+    /// Returns `None` until the label has been drawn, as for `TextObject`.
+    fn bounds(&self) -> Option<Rect> {
+        Drawable::bounds(&self.text).map(|bounds| bounds.inflate(self.padding))
+    }
+
+    crate::impl_clone_box!();
+}
@@ -3,4 +3,17 @@
 //! This module contains concrete implementations of the `Drawable` trait.
 //! Each submodule represents a different type of drawable object.
 
-pub mod text_object;
\ No newline at end of file
+pub mod blur_region;
+pub mod canvas;
+pub mod group;
+pub mod image;
+pub mod label;
+pub mod nine_patch;
+pub mod pixel_buffer;
+pub mod primitives;
+pub mod scroll_bar;
+pub mod shared_surface;
+pub mod sprite;
+pub mod stack;
+pub mod text_object;
+mod text_outline;
\ No newline at end of file
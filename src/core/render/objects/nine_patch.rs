@@ -0,0 +1,163 @@
+use std::rc::Rc;
+
+use windows::core::*;
+
+use crate::core::render::drawable::Drawable;
+use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::image_source::{ImageSource, Interpolation};
+use crate::core::render::rect::Rect;
+
+/// The widths of the four borders of a `NinePatch`, in source image pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Insets {
+    /// The width of the left border.
+    pub left: f32,
+    /// The height of the top border.
+    pub top: f32,
+    /// The width of the right border.
+    pub right: f32,
+    /// The height of the bottom border.
+    pub bottom: f32,
+}
+
+impl Insets {
+    /// Creates new `Insets` from the four border sizes.
+    pub const fn new(left: f32, top: f32, right: f32, bottom: f32) -> Self {
+        Self {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    /// Creates new `Insets` with the same size on every side.
+    pub const fn uniform(inset: f32) -> Self {
+        Self::new(inset, inset, inset, inset)
+    }
+}
+
+/// A `Drawable` image that stretches without distorting its corners.
+///
+/// The source image is divided into nine regions by the insets. When drawn into the
+/// destination rectangle, the four corners keep their size, the top and bottom edges
+/// stretch horizontally, the left and right edges stretch vertically, and the center
+/// stretches in both directions. This is typically used for themed buttons and panels.
+///
+/// If the destination is smaller than the combined insets along an axis, the borders
+/// along that axis are scaled down proportionally so that no region has a negative size.
+#[derive(Clone)]
+pub struct NinePatch {
+    /// The shared source image.
+    pub source: Rc<ImageSource>,
+    /// The borders that divide the source image into nine regions.
+    pub insets: Insets,
+    /// The rectangle to draw the image into.
+    pub destination: Rect,
+    /// The opacity of the image, from `0.0` (transparent) to `1.0` (opaque).
+    pub opacity: f32,
+    /// The interpolation used for the stretched regions.
+    pub interpolation: Interpolation,
+}
+
+impl NinePatch {
+    /// Creates a new, fully opaque `NinePatch`.
+    pub fn new(source: Rc<ImageSource>, insets: Insets, destination: Rect) -> Self {
+        Self {
+            source,
+            insets,
+            destination,
+            opacity: 1.0,
+            interpolation: Interpolation::Linear,
+        }
+    }
+}
+
+/// Splits a span into three segments from its start, two border sizes, and its length.
+///
+/// The borders are scaled down proportionally if they do not fit within the length.
+fn split(start: f32, first: f32, last: f32, length: f32) -> [(f32, f32); 3] {
+    let length = length.max(0.0);
+    let borders = first + last;
+    let scale = if borders > length && borders > 0.0 {
+        length / borders
+    } else {
+        1.0
+    };
+    let first = first * scale;
+    let last = last * scale;
+    [
+        (start, first),
+        (start + first, length - first - last),
+        (start + length - last, last),
+    ]
+}
+
+impl Drawable for NinePatch {
+    /// Draws the nine regions of the image into the destination rectangle.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the image cannot be decoded or if it
+    /// fails to create the Direct2D bitmap.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for calling Direct2D methods. The caller
+    /// must ensure that the `drawing_context` contains valid Direct2D resources.
+    fn draw(&self, context: &DrawingContext) -> Result<()> {
+        let opacity = self.opacity.clamp(0.0, 1.0);
+        if opacity <= 0.0 {
+            return Ok(());
+        }
+
+        let bitmap = self
+            .source
+            .bitmap(context.wic_factory, context.render_target)?;
+        let size = unsafe { bitmap.GetSize() };
+        let insets = self.insets;
+        let destination = self.destination;
+
+        let source_columns = split(0.0, insets.left, insets.right, size.width);
+        let source_rows = split(0.0, insets.top, insets.bottom, size.height);
+        let destination_columns =
+            split(destination.x, insets.left, insets.right, destination.width);
+        let destination_rows = split(destination.y, insets.top, insets.bottom, destination.height);
+
+        let rows = source_rows.iter().zip(destination_rows.iter());
+        for (&(source_y, source_height), &(destination_y, destination_height)) in rows {
+            let columns = source_columns.iter().zip(destination_columns.iter());
+            for (&(source_x, source_width), &(destination_x, destination_width)) in columns {
+                if source_width <= 0.0
+                    || source_height <= 0.0
+                    || destination_width <= 0.0
+                    || destination_height <= 0.0
+                {
+                    continue;
+                }
+
+                context.draw_bitmap(
+                    &self.source,
+                    &Rect::new(
+                        destination_x,
+                        destination_y,
+                        destination_width,
+                        destination_height,
+                    ),
+                    Some(&Rect::new(source_x, source_y, source_width, source_height)),
+                    opacity,
+                    self.interpolation,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the destination rectangle.
+    fn bounds(&self) -> Option<Rect> {
+        Some(self.destination)
+    }
+
+    crate::impl_clone_box!();
+}
@@ -0,0 +1,207 @@
+use std::cell::{Cell, RefCell};
+
+use windows::{
+    core::*,
+    Win32::Graphics::Direct2D::{ID2D1Bitmap, ID2D1RenderTarget},
+};
+
+use crate::core::render::drawable::Drawable;
+use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::image_source::{create_bitmap, premultiply, Interpolation};
+use crate::core::render::rect::Rect;
+
+/// A `Drawable` bitmap backed by a CPU-side buffer of RGBA pixels.
+///
+/// This is intended for procedurally generated images such as plots or fractals. The
+/// pixels are uploaded to an `ID2D1Bitmap` on first draw. Calling `update_pixels`
+/// marks the buffer as dirty, and the next draw copies the new pixels into the
+/// existing bitmap with `CopyFromMemory` instead of creating a new one. The bitmap is
+/// only recreated when the render target changes (for example after device loss).
+pub struct PixelBuffer {
+    pixel_width: u32,
+    pixel_height: u32,
+    /// The pixels in RGBA8 order with straight (non-premultiplied) alpha.
+    pixels: Vec<u8>,
+    /// The x-coordinate of the top-left corner of the destination rectangle.
+    pub x: f32,
+    /// The y-coordinate of the top-left corner of the destination rectangle.
+    pub y: f32,
+    /// The width of the destination rectangle.
+    pub width: f32,
+    /// The height of the destination rectangle.
+    pub height: f32,
+    /// The interpolation used when the destination size differs from the pixel size.
+    pub interpolation: Interpolation,
+    /// The opacity of the pixels, from `0.0` (transparent) to `1.0` (opaque).
+    pub opacity: f32,
+    dirty: Cell<bool>,
+    /// Scratch space for the premultiplied pixels, reused between uploads.
+    staging: RefCell<Vec<u8>>,
+    bitmap: RefCell<Option<(ID2D1RenderTarget, ID2D1Bitmap)>>,
+}
+
+impl PixelBuffer {
+    /// Creates a new `PixelBuffer` from RGBA8 pixel data.
+    ///
+    /// The destination rectangle defaults to the pixel size, positioned at the origin.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` is not exactly `width * height * 4` bytes long.
+    pub fn new(width: u32, height: u32, data: Vec<u8>) -> Self {
+        assert_eq!(
+            data.len(),
+            width as usize * height as usize * 4,
+            "pixel data length does not match a {width}x{height} RGBA8 buffer"
+        );
+        Self {
+            pixel_width: width,
+            pixel_height: height,
+            pixels: data,
+            x: 0.0,
+            y: 0.0,
+            width: width as f32,
+            height: height as f32,
+            interpolation: Interpolation::Linear,
+            opacity: 1.0,
+            dirty: Cell::new(true),
+            staging: RefCell::new(Vec::new()),
+            bitmap: RefCell::new(None),
+        }
+    }
+
+    /// Sets the destination rectangle the pixels are drawn into.
+    pub fn with_rect(mut self, x: f32, y: f32, width: f32, height: f32) -> Self {
+        self.x = x;
+        self.y = y;
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Sets the interpolation mode.
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// Sets the opacity of the pixels.
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    /// Returns the size of the buffer in pixels.
+    pub fn pixel_size(&self) -> (u32, u32) {
+        (self.pixel_width, self.pixel_height)
+    }
+
+    /// Returns the RGBA8 pixel data.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Replaces the pixel data, scheduling it to be uploaded on the next draw.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` is not the same length as the existing buffer.
+    pub fn update_pixels(&mut self, data: &[u8]) {
+        assert_eq!(
+            data.len(),
+            self.pixels.len(),
+            "pixel data length does not match the buffer size"
+        );
+        self.pixels.copy_from_slice(data);
+        self.dirty.set(true);
+    }
+
+    /// Converts the pixels to premultiplied alpha in the staging buffer.
+    fn premultiply(&self) {
+        premultiply(&self.pixels, &mut self.staging.borrow_mut());
+    }
+
+    /// Returns the bitmap for the given render target, uploading the pixels if needed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create or update the bitmap.
+    fn bitmap(&self, render_target: &ID2D1RenderTarget) -> Result<ID2D1Bitmap> {
+        let pitch = self.pixel_width * 4;
+        let mut cached = self.bitmap.borrow_mut();
+
+        if let Some((cached_target, bitmap)) = cached.as_ref() {
+            if cached_target == render_target {
+                if self.dirty.get() {
+                    self.premultiply();
+                    let staging = self.staging.borrow();
+                    unsafe { bitmap.CopyFromMemory(None, staging.as_ptr() as _, pitch)? };
+                    self.dirty.set(false);
+                }
+                return Ok(bitmap.clone());
+            }
+        }
+
+        self.premultiply();
+        let staging = self.staging.borrow();
+        let bitmap = create_bitmap(render_target, self.pixel_width, self.pixel_height, &staging)?;
+
+        *cached = Some((render_target.clone(), bitmap.clone()));
+        self.dirty.set(false);
+        Ok(bitmap)
+    }
+}
+
+impl Clone for PixelBuffer {
+    /// Copies the pixels and destination rectangle.
+    ///
+    /// The copy gets its own bitmap on first draw, so updating its pixels never
+    /// changes the original.
+    fn clone(&self) -> Self {
+        Self {
+            pixel_width: self.pixel_width,
+            pixel_height: self.pixel_height,
+            pixels: self.pixels.clone(),
+            x: self.x,
+            y: self.y,
+            width: self.width,
+            height: self.height,
+            interpolation: self.interpolation,
+            opacity: self.opacity,
+            dirty: Cell::new(true),
+            staging: RefCell::new(Vec::new()),
+            bitmap: RefCell::new(None),
+        }
+    }
+}
+
+impl Drawable for PixelBuffer {
+    /// Draws the pixels into the destination rectangle, uploading them first if needed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create or update the bitmap.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for creating and updating the bitmap. The
+    /// caller must ensure that the `drawing_context` contains valid Direct2D resources.
+    fn draw(&self, context: &DrawingContext) -> Result<()> {
+        let opacity = self.opacity.clamp(0.0, 1.0);
+        if opacity <= 0.0 || self.pixels.is_empty() {
+            return Ok(());
+        }
+
+        let bitmap = self.bitmap(context.render_target)?;
+        let destination = Rect::new(self.x, self.y, self.width, self.height);
+        context.draw_d2d_bitmap(&bitmap, &destination, None, opacity, self.interpolation);
+        Ok(())
+    }
+
+    /// Returns the destination rectangle.
+    fn bounds(&self) -> Option<Rect> {
+        Some(Rect::new(self.x, self.y, self.width, self.height))
+    }
+
+    crate::impl_clone_box!();
+}
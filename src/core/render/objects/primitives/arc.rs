@@ -0,0 +1,367 @@
+use std::cell::RefCell;
+
+use windows::core::*;
+use windows_numerics::Vector2;
+
+use crate::core::render::color::Color;
+use crate::core::render::drawable::Drawable;
+use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::geometry::{Figure, Geometry, GeometryStroke};
+use crate::core::render::rect::Rect;
+use crate::core::render::transform::{draw_transformed, transformed_bounds, Transform};
+
+/// The parameters that determine the shape of an arc, used to validate cached geometry.
+type ArcKey = [f32; 6];
+
+/// The shape shared by `Arc` and `PieSlice`.
+///
+/// Angles are in degrees, measured from the positive x-axis. Because the y-axis points
+/// down, positive sweeps run clockwise on screen and negative sweeps run counter-clockwise.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ArcShape {
+    center: Vector2,
+    radius_x: f32,
+    radius_y: f32,
+    start_angle: f32,
+    sweep_angle: f32,
+}
+
+impl ArcShape {
+    fn key(&self) -> ArcKey {
+        [
+            self.center.X,
+            self.center.Y,
+            self.radius_x,
+            self.radius_y,
+            self.start_angle,
+            self.sweep_angle,
+        ]
+    }
+
+    /// Returns `true` if the arc has no visible extent.
+    fn is_empty(&self) -> bool {
+        self.radius_x <= 0.0 || self.radius_y <= 0.0 || self.sweep_angle == 0.0
+    }
+
+    /// Returns `true` if the sweep covers the whole ellipse.
+    fn is_full(&self) -> bool {
+        self.sweep_angle.abs() >= 360.0
+    }
+
+    fn point_at(&self, angle: f32) -> Vector2 {
+        let radians = angle.to_radians();
+        Vector2 {
+            X: self.center.X + self.radius_x * radians.cos(),
+            Y: self.center.Y + self.radius_y * radians.sin(),
+        }
+    }
+
+    /// Returns the box around the arc, also containing the center for a pie slice, or
+    /// `None` if the arc is empty.
+    fn bounds(&self, pie: bool) -> Option<Rect> {
+        if self.is_empty() {
+            return None;
+        }
+        if self.is_full() {
+            return Some(Rect::new(
+                self.center.X - self.radius_x,
+                self.center.Y - self.radius_y,
+                self.radius_x * 2.0,
+                self.radius_y * 2.0,
+            ));
+        }
+
+        // The arc reaches past its end points wherever it crosses one of the ellipse's
+        // axes, which happens at multiples of 90 degrees.
+        let end_angle = self.start_angle + self.sweep_angle;
+        let (low, high) = if self.sweep_angle > 0.0 {
+            (self.start_angle, end_angle)
+        } else {
+            (end_angle, self.start_angle)
+        };
+        let first_quarter = (low / 90.0).ceil() as i32;
+        let last_quarter = (high / 90.0).floor() as i32;
+        let extremes =
+            (first_quarter..=last_quarter).map(|quarter| self.point_at(quarter as f32 * 90.0));
+
+        let ends = [self.point_at(self.start_angle), self.point_at(end_angle)];
+        let center = pie.then_some(self.center);
+        Rect::from_points(ends.into_iter().chain(extremes).chain(center))
+    }
+
+    /// Builds the geometry for the arc, optionally closed through the center as a pie
+    /// slice. A full sweep is built as two half arcs around the whole ellipse.
+    fn build(&self, pie: bool) -> Geometry {
+        let start = self.point_at(self.start_angle);
+        if self.is_full() {
+            let opposite = self.point_at(self.start_angle + 180.0);
+            return Geometry::from_figures(vec![
+                Figure::new(start)
+                    .arc_to(opposite, self.radius_x, self.radius_y, 0.0, false, true)
+                    .arc_to(start, self.radius_x, self.radius_y, 0.0, false, true)
+                    .close(),
+            ]);
+        }
+
+        let figure = if pie {
+            Figure::new(self.center).line_to(start)
+        } else {
+            Figure::new(start)
+        };
+        let figure = figure.arc_to(
+            self.point_at(self.start_angle + self.sweep_angle),
+            self.radius_x,
+            self.radius_y,
+            0.0,
+            self.sweep_angle.abs() > 180.0,
+            self.sweep_angle >= 0.0,
+        );
+        Geometry::from_figures(vec![if pie { figure.close() } else { figure }])
+    }
+
+    /// Returns the cached geometry, rebuilding it if the shape has changed.
+    fn geometry<'a>(&self, cache: &'a mut Option<(ArcKey, Geometry)>, pie: bool) -> &'a Geometry {
+        let key = self.key();
+        if cache.as_ref().is_none_or(|(cached_key, _)| *cached_key != key) {
+            *cache = Some((key, self.build(pie)));
+        }
+        let (_, geometry) = cache.get_or_insert_with(|| (key, self.build(pie)));
+        geometry
+    }
+}
+
+/// A `Drawable` elliptical arc stroke.
+///
+/// Angles are in degrees, measured clockwise from the positive x-axis. A sweep of
+/// 360° or more draws the full ellipse, a negative sweep runs counter-clockwise, and
+/// an arc with a zero radius or zero sweep draws nothing.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Arc {
+    /// The center of the ellipse the arc lies on.
+    #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2"))]
+    pub center: Vector2,
+    /// The horizontal radius.
+    pub radius_x: f32,
+    /// The vertical radius.
+    pub radius_y: f32,
+    /// The angle at which the arc starts, in degrees.
+    pub start_angle: f32,
+    /// The angle swept by the arc, in degrees.
+    pub sweep_angle: f32,
+    /// The width of the stroke.
+    pub stroke_width: f32,
+    /// The stroke color.
+    pub color: Color,
+    /// The opacity of the arc, from `0.0` (transparent) to `1.0` (opaque).
+    pub opacity: f32,
+    /// An optional rotation and scale, pivoting around the arc's center by default.
+    pub transform: Option<Transform>,
+    /// The cached geometry and the shape it was built for.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    geometry: RefCell<Option<(ArcKey, Geometry)>>,
+}
+
+impl Arc {
+    /// Creates a new `Arc`.
+    pub fn new(
+        center: Vector2,
+        radius_x: f32,
+        radius_y: f32,
+        start_angle: f32,
+        sweep_angle: f32,
+        stroke_width: f32,
+        color: Color,
+    ) -> Self {
+        Self {
+            center,
+            radius_x,
+            radius_y,
+            start_angle,
+            sweep_angle,
+            stroke_width,
+            color,
+            geometry: RefCell::new(None),
+            transform: None,
+            opacity: 1.0,
+        }
+    }
+
+    fn shape(&self) -> ArcShape {
+        ArcShape {
+            center: self.center,
+            radius_x: self.radius_x,
+            radius_y: self.radius_y,
+            start_angle: self.start_angle,
+            sweep_angle: self.sweep_angle,
+        }
+    }
+
+    /// Sets the opacity of the arc.
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    /// Sets a rotation and scale for the arc.
+    pub fn with_transform(mut self, transform: Transform) -> Self {
+        self.transform = Some(transform);
+        self
+    }
+}
+
+impl Drawable for Arc {
+    /// Strokes the arc, building its geometry first if the shape has changed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to build the path geometry.
+    fn draw(&self, context: &DrawingContext) -> Result<()> {
+        context.with_opacity(self.opacity, || {
+            draw_transformed(
+                context,
+                self.transform.as_ref(),
+                || self.center,
+                || {
+                    let shape = self.shape();
+                    if shape.is_empty() || self.stroke_width <= 0.0 {
+                        return Ok(());
+                    }
+
+                    let mut cache = self.geometry.borrow_mut();
+                    context.draw_geometry(
+                        shape.geometry(&mut cache, false),
+                        None,
+                        Some(GeometryStroke::new(&self.color, self.stroke_width)),
+                    )
+                },
+            )
+        })
+    }
+
+    /// Returns the box around the arc grown by half the stroke width, after the
+    /// transform.
+    fn bounds(&self) -> Option<Rect> {
+        let rect = self.shape().bounds(false)?;
+        Some(transformed_bounds(
+            self.transform.as_ref(),
+            || self.center,
+            rect.inflate(self.stroke_width.max(0.0) / 2.0),
+        ))
+    }
+
+    crate::impl_clone_box!();
+}
+
+/// A `Drawable` filled pie slice, bounded by an arc and two radii.
+///
+/// Angles follow the same conventions as `Arc`. A sweep of 360° or more fills the
+/// full ellipse.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PieSlice {
+    /// The center of the ellipse the slice is cut from.
+    #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2"))]
+    pub center: Vector2,
+    /// The horizontal radius.
+    pub radius_x: f32,
+    /// The vertical radius.
+    pub radius_y: f32,
+    /// The angle at which the slice starts, in degrees.
+    pub start_angle: f32,
+    /// The angle swept by the slice, in degrees.
+    pub sweep_angle: f32,
+    /// The fill color.
+    pub color: Color,
+    /// The opacity of the slice, from `0.0` (transparent) to `1.0` (opaque).
+    pub opacity: f32,
+    /// An optional rotation and scale, pivoting around the slice's center by default.
+    pub transform: Option<Transform>,
+    /// The cached geometry and the shape it was built for.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    geometry: RefCell<Option<(ArcKey, Geometry)>>,
+}
+
+impl PieSlice {
+    /// Creates a new `PieSlice`.
+    pub fn new(
+        center: Vector2,
+        radius_x: f32,
+        radius_y: f32,
+        start_angle: f32,
+        sweep_angle: f32,
+        color: Color,
+    ) -> Self {
+        Self {
+            center,
+            radius_x,
+            radius_y,
+            start_angle,
+            sweep_angle,
+            color,
+            geometry: RefCell::new(None),
+            transform: None,
+            opacity: 1.0,
+        }
+    }
+
+    fn shape(&self) -> ArcShape {
+        ArcShape {
+            center: self.center,
+            radius_x: self.radius_x,
+            radius_y: self.radius_y,
+            start_angle: self.start_angle,
+            sweep_angle: self.sweep_angle,
+        }
+    }
+
+    /// Sets the opacity of the slice.
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    /// Sets a rotation and scale for the slice.
+    pub fn with_transform(mut self, transform: Transform) -> Self {
+        self.transform = Some(transform);
+        self
+    }
+}
+
+impl Drawable for PieSlice {
+    /// Fills the slice, building its geometry first if the shape has changed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to build the path geometry.
+    fn draw(&self, context: &DrawingContext) -> Result<()> {
+        context.with_opacity(self.opacity, || {
+            draw_transformed(
+                context,
+                self.transform.as_ref(),
+                || self.center,
+                || {
+                    let shape = self.shape();
+                    if shape.is_empty() {
+                        return Ok(());
+                    }
+
+                    let mut cache = self.geometry.borrow_mut();
+                    context.draw_geometry(shape.geometry(&mut cache, true), Some(&self.color), None)
+                },
+            )
+        })
+    }
+
+    /// Returns the box around the slice, after the transform.
+    fn bounds(&self) -> Option<Rect> {
+        let rect = self.shape().bounds(true)?;
+        Some(transformed_bounds(
+            self.transform.as_ref(),
+            || self.center,
+            rect,
+        ))
+    }
+
+    crate::impl_clone_box!();
+}
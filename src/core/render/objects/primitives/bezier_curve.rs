@@ -0,0 +1,154 @@
+use std::cell::RefCell;
+
+use windows::core::*;
+use windows_numerics::Vector2;
+
+use crate::core::render::color::Color;
+use crate::core::render::drawable::Drawable;
+use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::geometry::{Figure, Geometry, GeometryStroke};
+use crate::core::render::rect::Rect;
+use crate::core::render::transform::{
+    bounds_center, draw_transformed, transformed_bounds, Transform,
+};
+
+/// A `Drawable` cubic Bézier curve.
+///
+/// The curve is defined by a start point, two control points, and an end point. It is
+/// drawn in the render target's current coordinate space, so it honors any transform
+/// that is active when the scene is drawn. The geometry is cached until the points are
+/// changed with `set_points`.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BezierCurve {
+    /// The start, first control, second control, and end points.
+    #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2::array"))]
+    points: [Vector2; 4],
+    /// The width of the stroke.
+    pub stroke_width: f32,
+    /// The stroke color.
+    pub color: Color,
+    /// The opacity of the curve, from `0.0` (transparent) to `1.0` (opaque).
+    pub opacity: f32,
+    /// An optional rotation and scale, pivoting around the center of the curve's points
+    /// by default.
+    pub transform: Option<Transform>,
+    /// The cached geometry, rebuilt when the points change.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    geometry: RefCell<Option<Geometry>>,
+}
+
+impl BezierCurve {
+    /// Creates a new cubic `BezierCurve`.
+    pub fn new(
+        start: Vector2,
+        control1: Vector2,
+        control2: Vector2,
+        end: Vector2,
+        stroke_width: f32,
+        color: Color,
+    ) -> Self {
+        Self {
+            points: [start, control1, control2, end],
+            stroke_width,
+            color,
+            geometry: RefCell::new(None),
+            transform: None,
+            opacity: 1.0,
+        }
+    }
+
+    /// Creates a new `BezierCurve` from a quadratic curve with a single control point.
+    ///
+    /// The quadratic curve is promoted to the equivalent cubic curve.
+    pub fn quadratic(start: Vector2, control: Vector2, end: Vector2, stroke_width: f32, color: Color) -> Self {
+        let (control1, control2) = quadratic_to_cubic(start, control, end);
+        Self::new(start, control1, control2, end, stroke_width, color)
+    }
+
+    /// Returns the start, first control, second control, and end points.
+    pub fn points(&self) -> [Vector2; 4] {
+        self.points
+    }
+
+    /// Replaces the points of the curve, invalidating the cached geometry.
+    pub fn set_points(&mut self, start: Vector2, control1: Vector2, control2: Vector2, end: Vector2) {
+        self.points = [start, control1, control2, end];
+        self.geometry.replace(None);
+    }
+
+    /// Sets the opacity of the curve.
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    /// Sets a rotation and scale for the curve.
+    pub fn with_transform(mut self, transform: Transform) -> Self {
+        self.transform = Some(transform);
+        self
+    }
+}
+
+/// Computes the two cubic control points equivalent to a quadratic control point.
+pub(crate) fn quadratic_to_cubic(start: Vector2, control: Vector2, end: Vector2) -> (Vector2, Vector2) {
+    let control1 = Vector2 {
+        X: start.X + 2.0 / 3.0 * (control.X - start.X),
+        Y: start.Y + 2.0 / 3.0 * (control.Y - start.Y),
+    };
+    let control2 = Vector2 {
+        X: end.X + 2.0 / 3.0 * (control.X - end.X),
+        Y: end.Y + 2.0 / 3.0 * (control.Y - end.Y),
+    };
+    (control1, control2)
+}
+
+impl Drawable for BezierCurve {
+    /// Strokes the curve, building its geometry first if necessary.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to build the path geometry.
+    fn draw(&self, context: &DrawingContext) -> Result<()> {
+        context.with_opacity(self.opacity, || {
+            draw_transformed(
+                context,
+                self.transform.as_ref(),
+                || bounds_center(self.points),
+                || {
+                    if self.stroke_width <= 0.0 {
+                        return Ok(());
+                    }
+
+                    let mut cached = self.geometry.borrow_mut();
+                    let geometry = cached.get_or_insert_with(|| {
+                        let [start, control1, control2, end] = self.points;
+                        Geometry::from_figures(vec![
+                            Figure::new(start).cubic_to(control1, control2, end),
+                        ])
+                    });
+                    context.draw_geometry(
+                        geometry,
+                        None,
+                        Some(GeometryStroke::new(&self.color, self.stroke_width)),
+                    )
+                },
+            )
+        })
+    }
+
+    /// Returns the box around the curve's points grown by half the stroke width, after
+    /// the transform.
+    ///
+    /// The curve always lies within its control points, so the box contains it.
+    fn bounds(&self) -> Option<Rect> {
+        let rect = Rect::from_points(self.points)?;
+        Some(transformed_bounds(
+            self.transform.as_ref(),
+            || bounds_center(self.points),
+            rect.inflate(self.stroke_width.max(0.0) / 2.0),
+        ))
+    }
+
+    crate::impl_clone_box!();
+}
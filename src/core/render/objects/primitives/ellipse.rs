@@ -0,0 +1,229 @@
+use windows::{core::*, Win32::Graphics::Direct2D::D2D1_ELLIPSE};
+use windows_numerics::Vector2;
+
+use crate::core::backend::{Paint, Renderer};
+use crate::core::render::color::Color;
+use crate::core::render::drawable::Drawable;
+use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::rect::Rect;
+use crate::core::render::transform::{
+    draw_transformed, render_transformed, transformed_bounds, Transform,
+};
+
+/// A `Drawable` ellipse (or circle) with an optional fill and an optional outline.
+///
+/// Like `Rectangle`, the outline is centered on the geometric edge of the ellipse.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ellipse {
+    /// The x-coordinate of the center.
+    pub center_x: f32,
+    /// The y-coordinate of the center.
+    pub center_y: f32,
+    /// The horizontal radius.
+    pub radius_x: f32,
+    /// The vertical radius.
+    pub radius_y: f32,
+    /// The fill color, or `None` for an unfilled ellipse.
+    pub fill: Option<Color>,
+    /// The outline color and width, or `None` for no outline.
+    ///
+    /// A width of zero or less is treated as no outline.
+    pub stroke: Option<(Color, f32)>,
+    /// The opacity of the ellipse, from `0.0` (transparent) to `1.0` (opaque).
+    pub opacity: f32,
+    /// An optional rotation and scale, pivoting around the ellipse's center by default.
+    pub transform: Option<Transform>,
+}
+
+impl Ellipse {
+    /// Creates a new filled `Ellipse` with no outline.
+    pub fn new(center_x: f32, center_y: f32, radius_x: f32, radius_y: f32, color: Color) -> Self {
+        Self {
+            center_x,
+            center_y,
+            radius_x,
+            radius_y,
+            fill: Some(color),
+            stroke: None,
+            transform: None,
+            opacity: 1.0,
+        }
+    }
+
+    /// Creates a new filled circle with no outline.
+    pub fn circle(center_x: f32, center_y: f32, radius: f32, color: Color) -> Self {
+        Self::new(center_x, center_y, radius, radius, color)
+    }
+
+    /// Creates a new unfilled `Ellipse` that only draws its outline.
+    pub fn outlined(
+        center_x: f32,
+        center_y: f32,
+        radius_x: f32,
+        radius_y: f32,
+        color: Color,
+        stroke_width: f32,
+    ) -> Self {
+        Self {
+            center_x,
+            center_y,
+            radius_x,
+            radius_y,
+            fill: None,
+            stroke: Some((color, stroke_width)),
+            transform: None,
+            opacity: 1.0,
+        }
+    }
+
+    /// Adds an outline to the ellipse, keeping any existing fill.
+    pub fn with_stroke(mut self, color: Color, stroke_width: f32) -> Self {
+        self.stroke = Some((color, stroke_width));
+        self
+    }
+
+    /// Returns the ellipse as a Direct2D `D2D1_ELLIPSE`.
+    fn ellipse(&self) -> D2D1_ELLIPSE {
+        D2D1_ELLIPSE {
+            point: Vector2 { X: self.center_x, Y: self.center_y },
+            radiusX: self.radius_x,
+            radiusY: self.radius_y,
+        }
+    }
+
+    /// Sets the opacity of the ellipse.
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    /// Sets a rotation and scale for the ellipse.
+    pub fn with_transform(mut self, transform: Transform) -> Self {
+        self.transform = Some(transform);
+        self
+    }
+}
+
+impl Drawable for Ellipse {
+    /// Fills the ellipse and then strokes its outline, if either is set.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for calling Direct2D drawing methods.
+    /// The caller must ensure that the `drawing_context` contains valid Direct2D resources.
+    fn draw(&self, context: &DrawingContext) -> Result<()> {
+        context.with_opacity(self.opacity, || {
+            draw_transformed(
+                context,
+                self.transform.as_ref(),
+                || Vector2 {
+                    X: self.center_x,
+                    Y: self.center_y,
+                },
+                || {
+                    let ellipse = self.ellipse();
+
+                    if let Some(fill) = &self.fill {
+                        context.with_brush_color(fill, |brush| unsafe {
+                            context.render_target.FillEllipse(&ellipse, brush);
+                        });
+                    }
+
+                    if let Some((color, stroke_width)) = &self.stroke {
+                        if *stroke_width > 0.0 {
+                            context.with_brush_color(color, |brush| unsafe {
+                                context.render_target.DrawEllipse(
+                                    &ellipse,
+                                    brush,
+                                    *stroke_width,
+                                    None,
+                                );
+                            });
+                        }
+                    }
+
+                    Ok(())
+                },
+            )
+        })
+    }
+
+    /// Fills the ellipse and strokes its outline through `renderer`.
+    fn render(&self, renderer: &mut dyn Renderer) {
+        let center = Vector2 {
+            X: self.center_x,
+            Y: self.center_y,
+        };
+        let radius = Vector2 {
+            X: self.radius_x,
+            Y: self.radius_y,
+        };
+        renderer.with_opacity(self.opacity, |renderer| {
+            render_transformed(
+                renderer,
+                self.transform.as_ref(),
+                || center,
+                |renderer| {
+                    if let Some(color) = self.fill {
+                        renderer.draw_ellipse(center, radius, Paint::Fill(color));
+                    }
+                    if let Some((color, width)) = self.stroke.filter(|(_, width)| *width > 0.0) {
+                        renderer.draw_ellipse(center, radius, Paint::Stroke { color, width });
+                    }
+                },
+            )
+        })
+    }
+
+    /// Returns the box around the ellipse grown by half the outline width, after the
+    /// transform.
+    fn bounds(&self) -> Option<Rect> {
+        let rect = Rect::new(
+            self.center_x - self.radius_x,
+            self.center_y - self.radius_y,
+            self.radius_x * 2.0,
+            self.radius_y * 2.0,
+        );
+        let stroke_width = self
+            .stroke
+            .as_ref()
+            .map_or(0.0, |(_, width)| width.max(0.0));
+        Some(transformed_bounds(
+            self.transform.as_ref(),
+            || Vector2 {
+                X: self.center_x,
+                Y: self.center_y,
+            },
+            rect.inflate(stroke_width / 2.0),
+        ))
+    }
+
+    crate::impl_clone_box!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::backend::{DrawCommand, RecordingRenderer};
+
+    #[test]
+    fn outlines_without_a_positive_width_are_not_drawn() {
+        for width in [0.0, -1.0] {
+            let ellipse =
+                Ellipse::new(50.0, 40.0, 30.0, 20.0, Color::BLUE).with_stroke(Color::WHITE, width);
+            let mut renderer = RecordingRenderer::new();
+            ellipse.render(&mut renderer);
+
+            assert_eq!(
+                renderer.commands(),
+                [DrawCommand::Ellipse {
+                    center: Vector2::new(50.0, 40.0),
+                    radius: Vector2::new(30.0, 20.0),
+                    paint: Paint::Fill(Color::BLUE),
+                }],
+                "stroke width {width}"
+            );
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use windows::core::*;
+use windows_numerics::Vector2;
+
+use crate::core::render::color::Color;
+use crate::core::render::drawable::Drawable;
+use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::rect::Rect;
+
+/// Bolder lines drawn every few cells of a `Grid`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MajorLines {
+    /// The number of cells between major lines.
+    pub every: u32,
+    /// The color of the major lines.
+    pub color: Color,
+    /// The thickness of the major lines.
+    pub thickness: f32,
+}
+
+/// A `Drawable` grid of evenly spaced horizontal and vertical lines, typically used as
+/// an editor background.
+///
+/// The grid starts at the top-left corner of its area. When no area is set, it covers
+/// the render target from the origin of the current coordinate space, so placing it
+/// under a clip restricts it to the clipped region. Drawing performs no allocations.
+///
+/// A grid that would need more than `Grid::MAX_LINES` lines in either direction, such
+/// as one with a tiny cell size, isn't drawn.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Grid {
+    /// The horizontal spacing between vertical lines.
+    pub cell_width: f32,
+    /// The vertical spacing between horizontal lines.
+    pub cell_height: f32,
+    /// The color of the regular lines.
+    pub color: Color,
+    /// The thickness of the regular lines.
+    pub thickness: f32,
+    /// Optional bolder lines drawn every few cells.
+    pub major: Option<MajorLines>,
+    /// The area to cover, or `None` to cover the whole render target.
+    pub area: Option<Rect>,
+    /// The opacity of the grid, from `0.0` (transparent) to `1.0` (opaque).
+    pub opacity: f32,
+}
+
+impl Grid {
+    /// The most lines a grid draws in each direction. Denser grids would take a long
+    /// time to draw and look like a solid fill at any sensible zoom, so they are
+    /// skipped.
+    pub const MAX_LINES: u32 = 8192;
+
+    /// Creates a new `Grid` covering the whole render target.
+    pub fn new(cell_width: f32, cell_height: f32, color: Color, thickness: f32) -> Self {
+        Self {
+            cell_width,
+            cell_height,
+            color,
+            thickness,
+            major: None,
+            area: None,
+            opacity: 1.0,
+        }
+    }
+
+    /// Draws a bolder line every `every` cells.
+    pub fn with_major_lines(mut self, every: u32, color: Color, thickness: f32) -> Self {
+        self.major = Some(MajorLines {
+            every,
+            color,
+            thickness,
+        });
+        self
+    }
+
+    /// Restricts the grid to the given area.
+    pub fn with_area(mut self, area: Rect) -> Self {
+        self.area = Some(area);
+        self
+    }
+
+    /// Draws every line of the grid for which `include(index)` is `true`, given the
+    /// number of vertical and horizontal lines.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for calling Direct2D drawing methods.
+    /// The caller must ensure that the `drawing_context` contains valid Direct2D resources.
+    fn draw_lines(
+        &self,
+        context: &DrawingContext,
+        area: Rect,
+        (columns, rows): (u32, u32),
+        color: &Color,
+        thickness: f32,
+        include: impl Fn(u32) -> bool,
+    ) {
+        if thickness <= 0.0 {
+            return;
+        }
+
+        let thickness = context.snap_stroke_width(thickness);
+        let snap = |x: f32, y: f32| context.snap_stroke_point(Vector2 { X: x, Y: y }, thickness);
+        context.with_brush_color(color, |brush| {
+            for index in (0..columns).filter(|&index| include(index)) {
+                let x = area.x + index as f32 * self.cell_width;
+                unsafe {
+                    context.render_target.DrawLine(
+                        snap(x, area.y),
+                        snap(x, area.bottom()),
+                        brush,
+                        thickness,
+                        None,
+                    );
+                }
+            }
+
+            for index in (0..rows).filter(|&index| include(index)) {
+                let y = area.y + index as f32 * self.cell_height;
+                unsafe {
+                    context.render_target.DrawLine(
+                        snap(area.x, y),
+                        snap(area.right(), y),
+                        brush,
+                        thickness,
+                        None,
+                    );
+                }
+            }
+        });
+    }
+
+    /// Sets the opacity of the grid.
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+}
+
+impl Drawable for Grid {
+    /// Draws the regular lines and then the major lines on top of them.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for calling Direct2D methods. The caller
+    /// must ensure that the `drawing_context` contains valid Direct2D resources.
+    fn draw(&self, context: &DrawingContext) -> Result<()> {
+        context.with_opacity(self.opacity, || {
+            let area = self.area.unwrap_or_else(|| {
+                let size = unsafe { context.render_target.GetSize() };
+                Rect::new(0.0, 0.0, size.width, size.height)
+            });
+            let (Some(columns), Some(rows)) = (
+                line_count(area.width, self.cell_width),
+                line_count(area.height, self.cell_height),
+            ) else {
+                return Ok(());
+            };
+
+            let major = self.major.filter(|major| major.every > 0);
+            let is_major = |index: u32| major.is_some_and(|major| index % major.every == 0);
+
+            let lines = (columns, rows);
+            self.draw_lines(context, area, lines, &self.color, self.thickness, |index| {
+                !is_major(index)
+            });
+            if let Some(major) = &major {
+                self.draw_lines(
+                    context,
+                    area,
+                    lines,
+                    &major.color,
+                    major.thickness,
+                    is_major,
+                );
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Returns the area the grid covers, or `None` when it covers the whole render
+    /// target, whose size isn't known outside of drawing.
+    fn bounds(&self) -> Option<Rect> {
+        self.area
+    }
+
+    crate::impl_clone_box!();
+}
+
+/// Returns the number of lines `spacing` apart that fit in `extent`, counting the one
+/// at its start, or `None` if the spacing isn't positive and finite or there would be
+/// more than `Grid::MAX_LINES`.
+fn line_count(extent: f32, spacing: f32) -> Option<u32> {
+    if !spacing.is_finite() || spacing <= 0.0 {
+        return None;
+    }
+    let count = (extent.max(0.0) / spacing).floor() + 1.0;
+    (count <= Grid::MAX_LINES as f32).then_some(count as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_count_includes_both_edges() {
+        assert_eq!(line_count(100.0, 10.0), Some(11));
+        assert_eq!(line_count(95.0, 10.0), Some(10));
+        assert_eq!(line_count(0.0, 10.0), Some(1));
+    }
+
+    #[test]
+    fn line_count_rejects_invalid_and_dense_spacing() {
+        assert_eq!(line_count(100.0, 0.0), None);
+        assert_eq!(line_count(100.0, -5.0), None);
+        assert_eq!(line_count(100.0, f32::NAN), None);
+        assert_eq!(line_count(100.0, 1e-6), None);
+        assert_eq!(line_count(f32::INFINITY, 10.0), None);
+        assert_eq!(
+            line_count((Grid::MAX_LINES - 1) as f32, 1.0),
+            Some(Grid::MAX_LINES)
+        );
+        assert_eq!(line_count(Grid::MAX_LINES as f32, 1.0), None);
+    }
+}
@@ -0,0 +1,341 @@
+use std::cell::RefCell;
+
+use windows::{core::*, Win32::Graphics::Direct2D::D2D1_ELLIPSE};
+use windows_numerics::Vector2;
+
+use crate::core::backend::Renderer;
+use crate::core::render::antialias::AntialiasMode;
+use crate::core::render::color::Color;
+use crate::core::render::drawable::Drawable;
+use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::geometry::{Figure, Geometry};
+use crate::core::render::rect::Rect;
+use crate::core::render::stroke_style::{LineCap, LineJoin, StrokeStyle};
+use crate::core::render::transform::{
+    bounds_center, draw_transformed, render_transformed, transformed_bounds, Transform,
+};
+
+/// A decoration drawn at one end of a `Line`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LineEnd {
+    /// The line ends with its line cap only.
+    #[default]
+    None,
+    /// A filled arrowhead pointing away from the line.
+    Arrow,
+    /// A filled circle whose edge touches the end point.
+    Circle,
+}
+
+impl LineEnd {
+    /// Returns how far the shaft must be shortened to make room for a decoration of
+    /// the given size.
+    fn inset(self, size: f32) -> f32 {
+        match self {
+            LineEnd::None => 0.0,
+            LineEnd::Arrow => size,
+            LineEnd::Circle => size / 2.0,
+        }
+    }
+}
+
+/// A `Drawable` straight line segment.
+///
+/// Either end can be decorated with an arrowhead or a circle, filled with the line
+/// color. The shaft is shortened so that it stops at the decoration. When the line is
+/// too short for both decorations at their full size, they are shrunk to fit and the
+/// shaft is skipped.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Line {
+    /// The start point of the line.
+    #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2"))]
+    pub start: Vector2,
+    /// The end point of the line.
+    #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2"))]
+    pub end: Vector2,
+    /// The width of the stroke.
+    pub stroke_width: f32,
+    /// The stroke color.
+    pub color: Color,
+    /// The dash pattern of the stroke.
+    pub stroke_style: StrokeStyle,
+    /// The shape of the line's ends and of each dash.
+    pub line_cap: LineCap,
+    /// The decoration drawn at the start point.
+    pub start_cap: LineEnd,
+    /// The decoration drawn at the end point.
+    pub end_cap: LineEnd,
+    /// The length of arrowheads and the diameter of circles at the ends.
+    pub cap_size: f32,
+    /// The opacity of the line, from `0.0` (transparent) to `1.0` (opaque).
+    pub opacity: f32,
+    /// An optional rotation and scale, pivoting around the line's midpoint by default.
+    pub transform: Option<Transform>,
+    /// The antialiasing mode to draw the line with, or `None` to use the current one.
+    ///
+    /// `AntialiasMode::Aliased` draws crisp one-pixel hairlines.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub antialias_mode: Option<AntialiasMode>,
+    /// The cached arrowhead geometry and the triangles it was built for.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    arrowheads: RefCell<Option<(Vec<[Vector2; 3]>, Geometry)>>,
+}
+
+impl Line {
+    /// Creates a new solid `Line`.
+    pub fn new(start: Vector2, end: Vector2, stroke_width: f32, color: Color) -> Self {
+        Self {
+            start,
+            end,
+            stroke_width,
+            color,
+            stroke_style: StrokeStyle::Solid,
+            line_cap: LineCap::Flat,
+            start_cap: LineEnd::None,
+            end_cap: LineEnd::None,
+            cap_size: 10.0,
+            transform: None,
+            antialias_mode: None,
+            arrowheads: RefCell::new(None),
+            opacity: 1.0,
+        }
+    }
+
+    /// Sets the dash pattern of the line.
+    pub fn with_stroke_style(mut self, stroke_style: StrokeStyle) -> Self {
+        self.stroke_style = stroke_style;
+        self
+    }
+
+    /// Sets the shape of the line's ends and of each dash.
+    pub fn with_line_cap(mut self, line_cap: LineCap) -> Self {
+        self.line_cap = line_cap;
+        self
+    }
+
+    /// Sets the decoration drawn at the start point.
+    pub fn with_start_cap(mut self, start_cap: LineEnd) -> Self {
+        self.start_cap = start_cap;
+        self
+    }
+
+    /// Sets the decoration drawn at the end point.
+    pub fn with_end_cap(mut self, end_cap: LineEnd) -> Self {
+        self.end_cap = end_cap;
+        self
+    }
+
+    /// Sets the length of arrowheads and the diameter of circles at the ends.
+    pub fn with_cap_size(mut self, cap_size: f32) -> Self {
+        self.cap_size = cap_size;
+        self
+    }
+
+    /// Sets the opacity of the line.
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    /// Sets a rotation and scale for the line.
+    pub fn with_transform(mut self, transform: Transform) -> Self {
+        self.transform = Some(transform);
+        self
+    }
+
+    /// Sets the antialiasing mode to draw the line with.
+    pub fn with_antialias_mode(mut self, antialias_mode: AntialiasMode) -> Self {
+        self.antialias_mode = Some(antialias_mode);
+        self
+    }
+}
+
+impl Line {
+    /// Strokes the line and fills its end decorations with the current antialiasing
+    /// mode.
+    fn draw_line(&self, context: &DrawingContext) -> Result<()> {
+        context.with_opacity(self.opacity, || {
+            draw_transformed(
+                context,
+                self.transform.as_ref(),
+                || bounds_center([self.start, self.end]),
+                || {
+                    if self.stroke_width <= 0.0 {
+                        return Ok(());
+                    }
+
+                    let dx = self.end.X - self.start.X;
+                    let dy = self.end.Y - self.start.Y;
+                    let length = (dx * dx + dy * dy).sqrt();
+                    let decorated =
+                        self.start_cap != LineEnd::None || self.end_cap != LineEnd::None;
+
+                    // A zero-length line has no direction to orient the decorations along.
+                    if !decorated || self.cap_size <= 0.0 || length <= f32::EPSILON {
+                        return self.draw_shaft(context, self.start, self.end);
+                    }
+
+                    let direction = Vector2 {
+                        X: dx / length,
+                        Y: dy / length,
+                    };
+                    let backward = Vector2 {
+                        X: -direction.X,
+                        Y: -direction.Y,
+                    };
+
+                    // Shrink the decorations if they would overlap on a short line.
+                    let start_inset = self.start_cap.inset(self.cap_size);
+                    let end_inset = self.end_cap.inset(self.cap_size);
+                    let total_inset = start_inset + end_inset;
+                    let scale = if total_inset > length {
+                        length / total_inset
+                    } else {
+                        1.0
+                    };
+                    let size = self.cap_size * scale;
+
+                    if total_inset < length {
+                        self.draw_shaft(
+                            context,
+                            offset(self.start, direction, start_inset),
+                            offset(self.end, backward, end_inset),
+                        )?;
+                    }
+
+                    // Each end is decorated facing outward from the line.
+                    let ends = [
+                        (self.start_cap, self.start, backward),
+                        (self.end_cap, self.end, direction),
+                    ];
+
+                    let mut triangles = Vec::new();
+                    for (cap, point, outward) in ends {
+                        match cap {
+                            LineEnd::None => {}
+                            LineEnd::Arrow => {
+                                let base = offset(point, outward, -size);
+                                let normal = Vector2 {
+                                    X: -outward.Y,
+                                    Y: outward.X,
+                                };
+                                triangles.push([
+                                    point,
+                                    offset(base, normal, size / 2.0),
+                                    offset(base, normal, -size / 2.0),
+                                ]);
+                            }
+                            LineEnd::Circle => {
+                                let ellipse = D2D1_ELLIPSE {
+                                    point: offset(point, outward, -size / 2.0),
+                                    radiusX: size / 2.0,
+                                    radiusY: size / 2.0,
+                                };
+                                context.with_brush_color(&self.color, |brush| unsafe {
+                                    context.render_target.FillEllipse(&ellipse, brush);
+                                });
+                            }
+                        }
+                    }
+
+                    if triangles.is_empty() {
+                        return Ok(());
+                    }
+                    let mut cached = self.arrowheads.borrow_mut();
+                    if cached.as_ref().is_none_or(|(key, _)| *key != triangles) {
+                        let figures = triangles
+                            .iter()
+                            .filter_map(|triangle| Figure::polygon(triangle.as_slice()))
+                            .collect();
+                        *cached = Some((triangles, Geometry::from_figures(figures)));
+                    }
+                    let Some((_, geometry)) = cached.as_ref() else {
+                        return Ok(());
+                    };
+                    context.draw_geometry(geometry, Some(&self.color), None)
+                },
+            )
+        })
+    }
+
+    /// Strokes the shaft between `start` and `end`.
+    fn draw_shaft(&self, context: &DrawingContext, start: Vector2, end: Vector2) -> Result<()> {
+        let stroke_width = context.snap_stroke_width(self.stroke_width);
+        let start = context.snap_stroke_point(start, stroke_width);
+        let end = context.snap_stroke_point(end, stroke_width);
+        let stroke_style =
+            context.stroke_style(&self.stroke_style, self.line_cap, LineJoin::Miter)?;
+        context.with_brush_color(&self.color, |brush| unsafe {
+            context
+                .render_target
+                .DrawLine(start, end, brush, stroke_width, stroke_style.as_ref());
+        });
+        Ok(())
+    }
+}
+
+/// Returns `point` moved `distance` along the unit vector `direction`.
+fn offset(point: Vector2, direction: Vector2, distance: f32) -> Vector2 {
+    Vector2 {
+        X: point.X + direction.X * distance,
+        Y: point.Y + direction.Y * distance,
+    }
+}
+
+impl Drawable for Line {
+    /// Strokes the line using its dash pattern and caps, then fills its end
+    /// decorations, with the line's antialiasing mode if it has one.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create the stroke style or
+    /// the arrowhead geometry.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for calling Direct2D drawing methods.
+    /// The caller must ensure that the `drawing_context` contains valid Direct2D resources.
+    fn draw(&self, context: &DrawingContext) -> Result<()> {
+        match self.antialias_mode {
+            Some(mode) => context.with_antialias_mode(mode, || self.draw_line(context)),
+            None => self.draw_line(context),
+        }
+    }
+
+    /// Strokes the line through `renderer`.
+    ///
+    /// The end decorations are skipped, so the line runs all the way to its end points,
+    /// and dashed lines are drawn solid.
+    fn render(&self, renderer: &mut dyn Renderer) {
+        if self.stroke_width <= 0.0 {
+            return;
+        }
+        renderer.with_opacity(self.opacity, |renderer| {
+            render_transformed(
+                renderer,
+                self.transform.as_ref(),
+                || bounds_center([self.start, self.end]),
+                |renderer| renderer.draw_line(self.start, self.end, self.color, self.stroke_width),
+            )
+        })
+    }
+
+    /// Returns the box around the end points grown by half the stroke width, or by the
+    /// size of the end decorations if they are larger, after the transform.
+    fn bounds(&self) -> Option<Rect> {
+        let rect = Rect::from_points([self.start, self.end])?;
+        let mut margin = self.stroke_width.max(0.0) / 2.0;
+        if self.start_cap != LineEnd::None || self.end_cap != LineEnd::None {
+            margin = margin.max(self.cap_size);
+        }
+        Some(transformed_bounds(
+            self.transform.as_ref(),
+            || bounds_center([self.start, self.end]),
+            rect.inflate(margin),
+        ))
+    }
+
+    crate::impl_clone_box!();
+}
@@ -0,0 +1,251 @@
+use windows::{
+    core::*,
+    Win32::Graphics::Direct2D::{ID2D1RenderTarget, ID2D1SolidColorBrush, D2D1_ELLIPSE},
+};
+use windows_numerics::Vector2;
+
+use crate::core::render::color::Color;
+use crate::core::render::drawable::Drawable;
+use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::rect::Rect;
+
+/// The shape of a plot marker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MarkerShape {
+    /// A diagonal cross (`×`).
+    #[default]
+    Cross,
+    /// An upright plus sign (`+`).
+    Plus,
+    /// A diamond outline.
+    Diamond,
+    /// A circle outline.
+    Circle,
+}
+
+/// Strokes a single marker centered on `center` with the given brush.
+///
+/// # Safety
+///
+/// This function contains `unsafe` blocks for calling Direct2D drawing methods. The
+/// caller must ensure that the render target and brush are valid.
+fn draw_marker(
+    render_target: &ID2D1RenderTarget,
+    brush: &ID2D1SolidColorBrush,
+    shape: MarkerShape,
+    center: Vector2,
+    size: f32,
+    stroke_width: f32,
+) {
+    let half = size / 2.0;
+    let at = |dx: f32, dy: f32| Vector2 {
+        X: center.X + dx,
+        Y: center.Y + dy,
+    };
+
+    unsafe {
+        match shape {
+            MarkerShape::Cross => {
+                render_target.DrawLine(at(-half, -half), at(half, half), brush, stroke_width, None);
+                render_target.DrawLine(at(-half, half), at(half, -half), brush, stroke_width, None);
+            }
+            MarkerShape::Plus => {
+                render_target.DrawLine(at(-half, 0.0), at(half, 0.0), brush, stroke_width, None);
+                render_target.DrawLine(at(0.0, -half), at(0.0, half), brush, stroke_width, None);
+            }
+            MarkerShape::Diamond => {
+                let corners = [at(0.0, -half), at(half, 0.0), at(0.0, half), at(-half, 0.0)];
+                for (index, &start) in corners.iter().enumerate() {
+                    let end = corners[(index + 1) % corners.len()];
+                    render_target.DrawLine(start, end, brush, stroke_width, None);
+                }
+            }
+            MarkerShape::Circle => {
+                let ellipse = D2D1_ELLIPSE {
+                    point: center,
+                    radiusX: half,
+                    radiusY: half,
+                };
+                render_target.DrawEllipse(&ellipse, brush, stroke_width, None);
+            }
+        }
+    }
+}
+
+/// Returns the box around a marker centered on `center`, grown by half the stroke width.
+fn marker_bounds(center: Vector2, size: f32, stroke_width: f32) -> Rect {
+    Rect::new(center.X - size / 2.0, center.Y - size / 2.0, size, size)
+        .inflate(stroke_width.max(0.0) / 2.0)
+}
+
+/// A `Drawable` marker at a single point, such as a data point on a plot.
+///
+/// To draw many markers with the same style, use a `MarkerSet`, which avoids boxing
+/// every point as a separate scene object.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Marker {
+    /// The center of the marker.
+    #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2"))]
+    pub position: Vector2,
+    /// The shape of the marker.
+    pub shape: MarkerShape,
+    /// The width and height of the marker.
+    pub size: f32,
+    /// The stroke color.
+    pub color: Color,
+    /// The width of the stroke.
+    pub stroke_width: f32,
+    /// The opacity of the marker, from `0.0` (transparent) to `1.0` (opaque).
+    pub opacity: f32,
+}
+
+impl Marker {
+    /// Creates a new `Marker` with a 1 DIP stroke.
+    pub fn new(position: Vector2, shape: MarkerShape, size: f32, color: Color) -> Self {
+        Self {
+            position,
+            shape,
+            size,
+            color,
+            stroke_width: 1.0,
+            opacity: 1.0,
+        }
+    }
+
+    /// Sets the width of the stroke.
+    pub fn with_stroke_width(mut self, stroke_width: f32) -> Self {
+        self.stroke_width = stroke_width;
+        self
+    }
+
+    /// Sets the opacity of the marker.
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+}
+
+impl Drawable for Marker {
+    /// Strokes the marker.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for calling Direct2D drawing methods.
+    /// The caller must ensure that the `drawing_context` contains valid Direct2D resources.
+    fn draw(&self, context: &DrawingContext) -> Result<()> {
+        context.with_opacity(self.opacity, || {
+            if self.size <= 0.0 || self.stroke_width <= 0.0 {
+                return Ok(());
+            }
+
+            context.with_brush_color(&self.color, |brush| {
+                draw_marker(
+                    context.render_target,
+                    brush,
+                    self.shape,
+                    self.position,
+                    self.size,
+                    self.stroke_width,
+                );
+            });
+            Ok(())
+        })
+    }
+
+    /// Returns the box around the marker grown by half the stroke width.
+    fn bounds(&self) -> Option<Rect> {
+        Some(marker_bounds(self.position, self.size, self.stroke_width))
+    }
+
+    crate::impl_clone_box!();
+}
+
+/// A `Drawable` set of identically styled markers, drawn together in one call.
+///
+/// This is the efficient way to plot large data sets: the whole set is a single scene
+/// object, and the brush is configured once for all of its points.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MarkerSet {
+    /// The centers of the markers.
+    #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2::vec"))]
+    pub points: Vec<Vector2>,
+    /// The shape of every marker.
+    pub shape: MarkerShape,
+    /// The width and height of every marker.
+    pub size: f32,
+    /// The stroke color.
+    pub color: Color,
+    /// The width of the stroke.
+    pub stroke_width: f32,
+    /// The opacity of the markers, from `0.0` (transparent) to `1.0` (opaque).
+    pub opacity: f32,
+}
+
+impl MarkerSet {
+    /// Creates a new `MarkerSet` with a 1 DIP stroke.
+    pub fn new(points: Vec<Vector2>, shape: MarkerShape, size: f32, color: Color) -> Self {
+        Self {
+            points,
+            shape,
+            size,
+            color,
+            stroke_width: 1.0,
+            opacity: 1.0,
+        }
+    }
+
+    /// Sets the width of the stroke.
+    pub fn with_stroke_width(mut self, stroke_width: f32) -> Self {
+        self.stroke_width = stroke_width;
+        self
+    }
+
+    /// Sets the opacity of the markers.
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+}
+
+impl Drawable for MarkerSet {
+    /// Strokes every marker in the set.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for calling Direct2D drawing methods.
+    /// The caller must ensure that the `drawing_context` contains valid Direct2D resources.
+    fn draw(&self, context: &DrawingContext) -> Result<()> {
+        context.with_opacity(self.opacity, || {
+            if self.points.is_empty() || self.size <= 0.0 || self.stroke_width <= 0.0 {
+                return Ok(());
+            }
+
+            context.with_brush_color(&self.color, |brush| {
+                for &point in &self.points {
+                    draw_marker(
+                        context.render_target,
+                        brush,
+                        self.shape,
+                        point,
+                        self.size,
+                        self.stroke_width,
+                    );
+                }
+            });
+            Ok(())
+        })
+    }
+
+    /// Returns the box around every marker in the set, or `None` if it is empty.
+    fn bounds(&self) -> Option<Rect> {
+        self.points
+            .iter()
+            .map(|&point| marker_bounds(point, self.size, self.stroke_width))
+            .reduce(|bounds, rect| bounds.union(&rect))
+    }
+
+    crate::impl_clone_box!();
+}
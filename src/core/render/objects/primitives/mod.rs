@@ -0,0 +1,30 @@
+//! # Primitive Shapes
+//!
+//! This module contains `Drawable` implementations for basic geometric shapes.
+
+pub mod arc;
+pub mod bezier_curve;
+pub mod ellipse;
+pub mod grid;
+pub mod line;
+pub mod marker;
+pub mod path;
+pub(crate) mod path_geometry;
+pub mod polygon;
+pub mod polyline;
+pub mod rectangle;
+pub mod svg_path;
+pub mod triangle;
+
+pub use arc::{Arc, PieSlice};
+pub use bezier_curve::BezierCurve;
+pub use ellipse::Ellipse;
+pub use grid::{Grid, MajorLines};
+pub use line::{Line, LineEnd};
+pub use marker::{Marker, MarkerSet, MarkerShape};
+pub use path::{Path, PathBuilder, PathCommand};
+pub use polygon::Polygon;
+pub use polyline::Polyline;
+pub use rectangle::Rectangle;
+pub use svg_path::SvgPathError;
+pub use triangle::Triangle;
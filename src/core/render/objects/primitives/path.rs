@@ -0,0 +1,491 @@
+use std::cell::RefCell;
+
+use windows::{
+    core::*,
+    Win32::Graphics::Direct2D::{
+        Common::{
+            D2D1_BEZIER_SEGMENT, D2D1_FIGURE_BEGIN_FILLED, D2D1_FIGURE_END_CLOSED,
+            D2D1_FIGURE_END_OPEN, D2D_SIZE_F,
+        },
+        ID2D1GeometrySink, D2D1_ARC_SEGMENT, D2D1_ARC_SIZE_LARGE,
+        D2D1_ARC_SIZE_SMALL, D2D1_QUADRATIC_BEZIER_SEGMENT, D2D1_SWEEP_DIRECTION_CLOCKWISE,
+        D2D1_SWEEP_DIRECTION_COUNTER_CLOCKWISE,
+    },
+};
+use windows_numerics::Vector2;
+
+use super::svg_path::{self, SvgPathError};
+use crate::core::render::color::Color;
+use crate::core::render::drawable::Drawable;
+use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::geometry::{Geometry, GeometryStroke};
+use crate::core::render::rect::Rect;
+use crate::core::render::stroke_style::{LineCap, LineJoin};
+use crate::core::render::transform::{
+    bounds_center, draw_transformed, transformed_bounds, Transform,
+};
+
+/// A single command in a `Path`.
+///
+/// All points are absolute, in the path's coordinate space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PathCommand {
+    /// Starts a new figure at the given point.
+    MoveTo(
+        #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2"))]
+        Vector2,
+    ),
+    /// Draws a straight line to the given point.
+    LineTo(
+        #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2"))]
+        Vector2,
+    ),
+    /// Draws a quadratic Bézier curve through a control point to an end point.
+    QuadTo {
+        /// The control point.
+        #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2"))]
+        control: Vector2,
+        /// The end point.
+        #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2"))]
+        end: Vector2,
+    },
+    /// Draws a cubic Bézier curve through two control points to an end point.
+    CubicTo {
+        /// The first control point.
+        #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2"))]
+        control1: Vector2,
+        /// The second control point.
+        #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2"))]
+        control2: Vector2,
+        /// The end point.
+        #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2"))]
+        end: Vector2,
+    },
+    /// Draws an elliptical arc to an end point.
+    ArcTo {
+        /// The end point.
+        #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2"))]
+        end: Vector2,
+        /// The horizontal radius of the ellipse.
+        radius_x: f32,
+        /// The vertical radius of the ellipse.
+        radius_y: f32,
+        /// The rotation of the ellipse, in degrees.
+        rotation: f32,
+        /// Whether to take the larger of the two possible arcs.
+        large_arc: bool,
+        /// Whether the arc is swept clockwise.
+        clockwise: bool,
+    },
+    /// Closes the current figure with a straight line back to its start.
+    Close,
+}
+
+impl PathCommand {
+    /// Returns the point the command ends at, or `None` for `Close`.
+    pub fn end_point(&self) -> Option<Vector2> {
+        match *self {
+            PathCommand::MoveTo(point) | PathCommand::LineTo(point) => Some(point),
+            PathCommand::QuadTo { end, .. }
+            | PathCommand::CubicTo { end, .. }
+            | PathCommand::ArcTo { end, .. } => Some(end),
+            PathCommand::Close => None,
+        }
+    }
+}
+
+/// A fluent builder for `Path` drawables.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use my_gui::core::render::{color::Color, objects::primitives::PathBuilder};
+/// use windows_numerics::Vector2;
+///
+/// let arrow = PathBuilder::new()
+///     .move_to(Vector2 { X: 0.0, Y: 10.0 })
+///     .line_to(Vector2 { X: 20.0, Y: 10.0 })
+///     .line_to(Vector2 { X: 20.0, Y: 0.0 })
+///     .line_to(Vector2 { X: 35.0, Y: 15.0 })
+///     .line_to(Vector2 { X: 20.0, Y: 30.0 })
+///     .line_to(Vector2 { X: 20.0, Y: 20.0 })
+///     .line_to(Vector2 { X: 0.0, Y: 20.0 })
+///     .close()
+///     .fill(Color::WHITE)
+///     .build();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PathBuilder {
+    commands: Vec<PathCommand>,
+    fill: Option<Color>,
+    stroke: Option<(Color, f32)>,
+    line_cap: LineCap,
+    line_join: LineJoin,
+    opacity: Option<f32>,
+    transform: Option<Transform>,
+}
+
+impl PathBuilder {
+    /// Creates a new, empty `PathBuilder`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts a new figure at `point`.
+    pub fn move_to(mut self, point: Vector2) -> Self {
+        self.commands.push(PathCommand::MoveTo(point));
+        self
+    }
+
+    /// Adds a straight line to `point`.
+    pub fn line_to(mut self, point: Vector2) -> Self {
+        self.commands.push(PathCommand::LineTo(point));
+        self
+    }
+
+    /// Adds a quadratic Bézier curve to `end`.
+    pub fn quad_to(mut self, control: Vector2, end: Vector2) -> Self {
+        self.commands.push(PathCommand::QuadTo { control, end });
+        self
+    }
+
+    /// Adds a cubic Bézier curve to `end`.
+    pub fn cubic_to(mut self, control1: Vector2, control2: Vector2, end: Vector2) -> Self {
+        self.commands.push(PathCommand::CubicTo {
+            control1,
+            control2,
+            end,
+        });
+        self
+    }
+
+    /// Adds an elliptical arc to `end`.
+    pub fn arc_to(
+        mut self,
+        end: Vector2,
+        radius_x: f32,
+        radius_y: f32,
+        rotation: f32,
+        large_arc: bool,
+        clockwise: bool,
+    ) -> Self {
+        self.commands.push(PathCommand::ArcTo {
+            end,
+            radius_x,
+            radius_y,
+            rotation,
+            large_arc,
+            clockwise,
+        });
+        self
+    }
+
+    /// Closes the current figure.
+    pub fn close(mut self) -> Self {
+        self.commands.push(PathCommand::Close);
+        self
+    }
+
+    /// Sets the fill color of the path.
+    pub fn fill(mut self, color: Color) -> Self {
+        self.fill = Some(color);
+        self
+    }
+
+    /// Sets the stroke color and width of the path.
+    pub fn stroke(mut self, color: Color, stroke_width: f32) -> Self {
+        self.stroke = Some((color, stroke_width));
+        self
+    }
+
+    /// Sets the shape of the ends of open figures.
+    pub fn line_cap(mut self, line_cap: LineCap) -> Self {
+        self.line_cap = line_cap;
+        self
+    }
+
+    /// Sets the shape of the corners between segments.
+    pub fn line_join(mut self, line_join: LineJoin) -> Self {
+        self.line_join = line_join;
+        self
+    }
+
+    /// Sets the opacity of the path.
+    pub fn opacity(mut self, opacity: f32) -> Self {
+        self.opacity = Some(opacity);
+        self
+    }
+
+    /// Sets a rotation and scale for the path.
+    pub fn transform(mut self, transform: Transform) -> Self {
+        self.transform = Some(transform);
+        self
+    }
+
+    /// Builds the `Path`.
+    pub fn build(self) -> Path {
+        Path {
+            commands: self.commands,
+            fill: self.fill,
+            stroke: self.stroke,
+            line_cap: self.line_cap,
+            line_join: self.line_join,
+            opacity: self.opacity.unwrap_or(1.0),
+            transform: self.transform,
+            geometry: RefCell::new(None),
+        }
+    }
+}
+
+/// A `Drawable` arbitrary vector shape made of lines, curves, and arcs.
+///
+/// Paths are usually created with a `PathBuilder`. The recorded commands are translated
+/// into a `Geometry` on the first draw, and the geometry is cached until the commands
+/// are changed with `set_commands`.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Path {
+    commands: Vec<PathCommand>,
+    /// The fill color, or `None` for an unfilled path.
+    pub fill: Option<Color>,
+    /// The stroke color and width, or `None` for no stroke.
+    pub stroke: Option<(Color, f32)>,
+    /// The shape of the ends of open figures.
+    pub line_cap: LineCap,
+    /// The shape of the corners between segments.
+    pub line_join: LineJoin,
+    /// The opacity of the path, from `0.0` (transparent) to `1.0` (opaque).
+    pub opacity: f32,
+    /// An optional rotation and scale, pivoting around the center of the path's points
+    /// by default.
+    pub transform: Option<Transform>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    geometry: RefCell<Option<Geometry>>,
+}
+
+impl Path {
+    /// Returns a new `PathBuilder`.
+    pub fn builder() -> PathBuilder {
+        PathBuilder::new()
+    }
+
+    /// Creates a new `Path` from SVG path data, such as the `d` attribute of an SVG
+    /// `<path>` element.
+    ///
+    /// The `M`, `L`, `H`, `V`, `C`, `Q`, `A`, and `Z` commands are supported in both
+    /// absolute and relative forms. The returned path has no fill or stroke; set them
+    /// before adding it to a scene.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the path data is malformed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use my_gui::core::render::{color::Color, objects::primitives::Path};
+    ///
+    /// let mut check = Path::from_svg_path_data("M2 8 l4 4 l8 -8").unwrap();
+    /// check.stroke = Some((Color::GREEN, 2.0));
+    /// ```
+    pub fn from_svg_path_data(data: &str) -> std::result::Result<Self, SvgPathError> {
+        Ok(PathBuilder {
+            commands: svg_path::parse(data)?,
+            ..PathBuilder::default()
+        }
+        .build())
+    }
+
+    /// Returns the commands that make up the path.
+    pub fn commands(&self) -> &[PathCommand] {
+        &self.commands
+    }
+
+    /// Replaces the commands of the path, invalidating the cached geometry.
+    pub fn set_commands(&mut self, commands: Vec<PathCommand>) {
+        self.commands = commands;
+        self.geometry.replace(None);
+    }
+}
+
+/// Replays path commands into a geometry sink.
+///
+/// Drawing commands issued outside of a figure implicitly start one at the current
+/// point. After `Close`, the current point returns to the start of the closed figure.
+///
+/// # Safety
+///
+/// This function contains `unsafe` blocks for calling geometry sink methods. The sink
+/// must be open.
+pub(crate) fn write_commands(sink: &ID2D1GeometrySink, commands: &[PathCommand]) {
+    let mut figure_open = false;
+    let mut start = Vector2::default();
+    let mut current = Vector2::default();
+
+    // Begins a figure at the current point if none is open, making it the new start.
+    let begin_figure = |figure_open: &mut bool, start: &mut Vector2, current: Vector2| {
+        if !*figure_open {
+            unsafe { sink.BeginFigure(current, D2D1_FIGURE_BEGIN_FILLED) };
+            *figure_open = true;
+            *start = current;
+        }
+    };
+
+    for command in commands {
+        match *command {
+            PathCommand::MoveTo(point) => {
+                if figure_open {
+                    unsafe { sink.EndFigure(D2D1_FIGURE_END_OPEN) };
+                    figure_open = false;
+                }
+                start = point;
+                current = point;
+            }
+            PathCommand::LineTo(point) => {
+                begin_figure(&mut figure_open, &mut start, current);
+                unsafe { sink.AddLine(point) };
+                current = point;
+            }
+            PathCommand::QuadTo { control, end } => {
+                begin_figure(&mut figure_open, &mut start, current);
+                unsafe {
+                    sink.AddQuadraticBezier(&D2D1_QUADRATIC_BEZIER_SEGMENT {
+                        point1: control,
+                        point2: end,
+                    })
+                };
+                current = end;
+            }
+            PathCommand::CubicTo {
+                control1,
+                control2,
+                end,
+            } => {
+                begin_figure(&mut figure_open, &mut start, current);
+                unsafe {
+                    sink.AddBezier(&D2D1_BEZIER_SEGMENT {
+                        point1: control1,
+                        point2: control2,
+                        point3: end,
+                    })
+                };
+                current = end;
+            }
+            PathCommand::ArcTo {
+                end,
+                radius_x,
+                radius_y,
+                rotation,
+                large_arc,
+                clockwise,
+            } => {
+                begin_figure(&mut figure_open, &mut start, current);
+                unsafe {
+                    sink.AddArc(&D2D1_ARC_SEGMENT {
+                        point: end,
+                        size: D2D_SIZE_F {
+                            width: radius_x.abs(),
+                            height: radius_y.abs(),
+                        },
+                        rotationAngle: rotation,
+                        sweepDirection: if clockwise {
+                            D2D1_SWEEP_DIRECTION_CLOCKWISE
+                        } else {
+                            D2D1_SWEEP_DIRECTION_COUNTER_CLOCKWISE
+                        },
+                        arcSize: if large_arc {
+                            D2D1_ARC_SIZE_LARGE
+                        } else {
+                            D2D1_ARC_SIZE_SMALL
+                        },
+                    })
+                };
+                current = end;
+            }
+            PathCommand::Close => {
+                if figure_open {
+                    unsafe { sink.EndFigure(D2D1_FIGURE_END_CLOSED) };
+                    figure_open = false;
+                }
+                current = start;
+            }
+        }
+    }
+
+    if figure_open {
+        unsafe { sink.EndFigure(D2D1_FIGURE_END_OPEN) };
+    }
+}
+
+impl Drawable for Path {
+    /// Fills and then strokes the path, building its geometry first if necessary.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to build the path geometry or
+    /// create the stroke style.
+    fn draw(&self, context: &DrawingContext) -> Result<()> {
+        context.with_opacity(self.opacity, || {
+            draw_transformed(
+                context,
+                self.transform.as_ref(),
+                || bounds_center(self.commands.iter().filter_map(PathCommand::end_point)),
+                || {
+                    let mut cached = self.geometry.borrow_mut();
+                    let geometry =
+                        cached.get_or_insert_with(|| Geometry::from_commands(&self.commands));
+                    let stroke = self.stroke.as_ref().map(|(color, width)| {
+                        GeometryStroke::new(color, *width)
+                            .with_line_cap(self.line_cap)
+                            .with_line_join(self.line_join)
+                    });
+                    context.draw_geometry(geometry, self.fill.as_ref(), stroke)
+                },
+            )
+        })
+    }
+
+    /// Returns the box around the path's points and control points grown by as far as
+    /// the stroke can reach past them, after the transform. See
+    /// `LineJoin::stroke_extent`.
+    ///
+    /// Arcs can bulge past their end points, so the box around an arc's end point is
+    /// grown by the arc's diameter, which may make the bounds larger than the path.
+    fn bounds(&self) -> Option<Rect> {
+        let rect = self
+            .commands
+            .iter()
+            .filter_map(|command| match *command {
+                PathCommand::MoveTo(point) | PathCommand::LineTo(point) => {
+                    Rect::from_points([point])
+                }
+                PathCommand::QuadTo { control, end } => Rect::from_points([control, end]),
+                PathCommand::CubicTo {
+                    control1,
+                    control2,
+                    end,
+                } => Rect::from_points([control1, control2, end]),
+                PathCommand::ArcTo {
+                    end,
+                    radius_x,
+                    radius_y,
+                    ..
+                } => Rect::from_points([end])
+                    .map(|rect| rect.inflate(radius_x.abs().max(radius_y.abs()) * 2.0)),
+                PathCommand::Close => None,
+            })
+            .reduce(|bounds, rect| bounds.union(&rect))?;
+        let stroke_extent = self
+            .stroke
+            .as_ref()
+            .map_or(0.0, |(_, width)| self.line_join.stroke_extent(*width));
+        Some(transformed_bounds(
+            self.transform.as_ref(),
+            || bounds_center(self.commands.iter().filter_map(PathCommand::end_point)),
+            rect.inflate(stroke_extent),
+        ))
+    }
+
+    crate::impl_clone_box!();
+}
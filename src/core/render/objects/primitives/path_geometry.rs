@@ -0,0 +1,34 @@
+use windows::{
+    core::*,
+    Win32::Graphics::Direct2D::{
+        ID2D1Factory, ID2D1Factory1, ID2D1GeometrySink, ID2D1PathGeometry,
+    },
+};
+
+/// Creates an `ID2D1PathGeometry` and populates it using the given closure.
+///
+/// The closure receives the open geometry sink and is responsible for beginning and
+/// ending its figures. The sink is closed once the closure returns.
+///
+/// # Errors
+///
+/// This function will return an error if it fails to create or open the path geometry,
+/// if the closure returns an error, or if closing the sink fails.
+///
+/// # Safety
+///
+/// This function contains `unsafe` blocks for creating and opening the geometry. The
+/// factory is a valid Direct2D factory for the lifetime of the call.
+pub(crate) fn build_path_geometry(
+    factory: &ID2D1Factory1,
+    f: impl FnOnce(&ID2D1GeometrySink) -> Result<()>,
+) -> Result<ID2D1PathGeometry> {
+    // `ID2D1Factory1` shadows `CreatePathGeometry` with a version returning
+    // `ID2D1PathGeometry1`, so call the base factory method explicitly.
+    let factory: &ID2D1Factory = factory;
+    let geometry = unsafe { factory.CreatePathGeometry()? };
+    let sink = unsafe { geometry.Open()? };
+    f(&sink)?;
+    unsafe { sink.Close()? };
+    Ok(geometry)
+}
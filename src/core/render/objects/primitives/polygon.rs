@@ -0,0 +1,110 @@
+use std::cell::RefCell;
+
+use windows::core::*;
+use windows_numerics::Vector2;
+
+use crate::core::render::color::Color;
+use crate::core::render::drawable::Drawable;
+use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::geometry::Geometry;
+use crate::core::render::rect::Rect;
+use crate::core::render::transform::{
+    bounds_center, draw_transformed, transformed_bounds, Transform,
+};
+
+/// A `Drawable` filled polygon defined by a list of vertices.
+///
+/// The polygon may be convex or concave. It is drawn as a `Geometry`, which is built on
+/// the first draw and cached until the vertex list is changed with `set_vertices`.
+/// Polygons with fewer than three vertices are not drawn.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Polygon {
+    /// The vertices of the polygon, in order.
+    #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2::vec"))]
+    vertices: Vec<Vector2>,
+    /// The fill color.
+    pub color: Color,
+    /// The opacity of the polygon, from `0.0` (transparent) to `1.0` (opaque).
+    pub opacity: f32,
+    /// An optional rotation and scale, pivoting around the polygon's bounding box center
+    /// by default.
+    pub transform: Option<Transform>,
+    /// The cached geometry, rebuilt when the vertices change.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    geometry: RefCell<Option<Geometry>>,
+}
+
+impl Polygon {
+    /// Creates a new `Polygon` from its vertices and fill color.
+    pub fn new(vertices: Vec<Vector2>, color: Color) -> Self {
+        Self {
+            vertices,
+            color,
+            geometry: RefCell::new(None),
+            transform: None,
+            opacity: 1.0,
+        }
+    }
+
+    /// Returns the vertices of the polygon.
+    pub fn vertices(&self) -> &[Vector2] {
+        &self.vertices
+    }
+
+    /// Replaces the vertices of the polygon, invalidating the cached geometry.
+    pub fn set_vertices(&mut self, vertices: Vec<Vector2>) {
+        self.vertices = vertices;
+        self.geometry.replace(None);
+    }
+
+    /// Sets the opacity of the polygon.
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    /// Sets a rotation and scale for the polygon.
+    pub fn with_transform(mut self, transform: Transform) -> Self {
+        self.transform = Some(transform);
+        self
+    }
+}
+
+impl Drawable for Polygon {
+    /// Fills the polygon, building its geometry first if necessary.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to build the path geometry.
+    fn draw(&self, context: &DrawingContext) -> Result<()> {
+        context.with_opacity(self.opacity, || {
+            draw_transformed(
+                context,
+                self.transform.as_ref(),
+                || bounds_center(self.vertices.iter().copied()),
+                || {
+                    if self.vertices.len() < 3 {
+                        return Ok(());
+                    }
+
+                    let mut cached = self.geometry.borrow_mut();
+                    let geometry = cached.get_or_insert_with(|| Geometry::polygon(&self.vertices));
+                    context.draw_geometry(geometry, Some(&self.color), None)
+                },
+            )
+        })
+    }
+
+    /// Returns the box around the vertices, after the transform.
+    fn bounds(&self) -> Option<Rect> {
+        let rect = Rect::from_points(self.vertices.iter().copied())?;
+        Some(transformed_bounds(
+            self.transform.as_ref(),
+            || bounds_center(self.vertices.iter().copied()),
+            rect,
+        ))
+    }
+
+    crate::impl_clone_box!();
+}
@@ -0,0 +1,146 @@
+use std::cell::RefCell;
+
+use windows::core::*;
+use windows_numerics::Vector2;
+
+use crate::core::render::color::Color;
+use crate::core::render::drawable::Drawable;
+use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::geometry::{Geometry, GeometryStroke};
+use crate::core::render::rect::Rect;
+use crate::core::render::stroke_style::{LineCap, LineJoin};
+use crate::core::render::transform::{
+    bounds_center, draw_transformed, transformed_bounds, Transform,
+};
+
+/// A `Drawable` strip of connected line segments.
+///
+/// The points are drawn as a `Geometry` with a single open figure, so adjacent segments
+/// share proper joins instead of overlapping like separate lines would. The geometry
+/// is cached until the points are changed with `set_points`. Polylines with fewer
+/// than two points are not drawn.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Polyline {
+    /// The points of the line strip, in order.
+    #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2::vec"))]
+    points: Vec<Vector2>,
+    /// The width of the stroke.
+    pub stroke_width: f32,
+    /// The stroke color.
+    pub color: Color,
+    /// The shape of the polyline's two ends.
+    pub line_cap: LineCap,
+    /// The shape of the corners between segments.
+    pub line_join: LineJoin,
+    /// The opacity of the polyline, from `0.0` (transparent) to `1.0` (opaque).
+    pub opacity: f32,
+    /// An optional rotation and scale, pivoting around the polyline's bounding box center
+    /// by default.
+    pub transform: Option<Transform>,
+    /// The cached geometry, rebuilt when the points change.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    geometry: RefCell<Option<Geometry>>,
+}
+
+impl Polyline {
+    /// Creates a new `Polyline` from its points, stroke width, and color.
+    pub fn new(points: Vec<Vector2>, stroke_width: f32, color: Color) -> Self {
+        Self {
+            points,
+            stroke_width,
+            color,
+            line_cap: LineCap::Flat,
+            line_join: LineJoin::Miter,
+            geometry: RefCell::new(None),
+            transform: None,
+            opacity: 1.0,
+        }
+    }
+
+    /// Sets the shape of the polyline's two ends.
+    pub fn with_line_cap(mut self, line_cap: LineCap) -> Self {
+        self.line_cap = line_cap;
+        self
+    }
+
+    /// Sets the shape of the corners between segments.
+    pub fn with_line_join(mut self, line_join: LineJoin) -> Self {
+        self.line_join = line_join;
+        self
+    }
+
+    /// Returns the points of the polyline.
+    pub fn points(&self) -> &[Vector2] {
+        &self.points
+    }
+
+    /// Replaces the points of the polyline, invalidating the cached geometry.
+    pub fn set_points(&mut self, points: Vec<Vector2>) {
+        self.points = points;
+        self.geometry.replace(None);
+    }
+
+    /// Sets the opacity of the polyline.
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    /// Sets a rotation and scale for the polyline.
+    pub fn with_transform(mut self, transform: Transform) -> Self {
+        self.transform = Some(transform);
+        self
+    }
+}
+
+impl Drawable for Polyline {
+    /// Strokes the polyline, building its geometry first if necessary.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to build the path geometry or
+    /// create the stroke style.
+    fn draw(&self, context: &DrawingContext) -> Result<()> {
+        context.with_opacity(self.opacity, || {
+            draw_transformed(
+                context,
+                self.transform.as_ref(),
+                || bounds_center(self.points.iter().copied()),
+                || {
+                    if self.points.len() < 2 || self.stroke_width <= 0.0 {
+                        return Ok(());
+                    }
+
+                    let mut cached = self.geometry.borrow_mut();
+                    let geometry = cached.get_or_insert_with(|| Geometry::polyline(&self.points));
+                    context.draw_geometry(
+                        geometry,
+                        None,
+                        Some(
+                            GeometryStroke::new(&self.color, self.stroke_width)
+                                .with_line_cap(self.line_cap)
+                                .with_line_join(self.line_join),
+                        ),
+                    )
+                },
+            )
+        })
+    }
+
+    /// Returns the box around the points grown by as far as the stroke can reach past
+    /// them, after the transform.
+    ///
+    /// Miter joins at sharp corners reach up to the miter limit times half the stroke
+    /// width. See `LineJoin::stroke_extent`.
+    fn bounds(&self) -> Option<Rect> {
+        let rect = Rect::from_points(self.points.iter().copied())?;
+        Some(transformed_bounds(
+            self.transform.as_ref(),
+            || bounds_center(self.points.iter().copied()),
+            rect.inflate(self.line_join.stroke_extent(self.stroke_width)),
+        ))
+    }
+
+    crate::impl_clone_box!();
+}
@@ -0,0 +1,262 @@
+use windows::{core::*, Win32::Graphics::Direct2D::Common::D2D_RECT_F};
+use windows_numerics::Vector2;
+
+use crate::core::backend::{Paint, Renderer};
+use crate::core::render::color::Color;
+use crate::core::render::drawable::Drawable;
+use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::fill::Fill;
+use crate::core::render::rect::Rect;
+use crate::core::render::shadow::Shadow;
+use crate::core::render::stroke_style::{LineCap, LineJoin, StrokeStyle};
+use crate::core::render::transform::{
+    draw_transformed, render_transformed, transformed_bounds, Transform,
+};
+
+/// A `Drawable` axis-aligned rectangle with an optional fill and an optional border.
+///
+/// The border (stroke) is centered on the geometric edge of the rectangle, as is
+/// native to Direct2D, so half of its width falls outside the rectangle's bounds.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rectangle {
+    /// The x-coordinate of the top-left corner.
+    pub x: f32,
+    /// The y-coordinate of the top-left corner.
+    pub y: f32,
+    /// The width of the rectangle.
+    pub width: f32,
+    /// The height of the rectangle.
+    pub height: f32,
+    /// The fill, or `None` for an unfilled rectangle.
+    pub fill: Option<Fill>,
+    /// The border color and width, or `None` for no border.
+    pub stroke: Option<(Color, f32)>,
+    /// The dash pattern of the border.
+    pub stroke_style: StrokeStyle,
+    /// An optional drop shadow drawn beneath the rectangle.
+    pub shadow: Option<Shadow>,
+    /// The opacity of the rectangle, from `0.0` (transparent) to `1.0` (opaque).
+    pub opacity: f32,
+    /// An optional rotation and scale, pivoting around the rectangle's center by default.
+    pub transform: Option<Transform>,
+}
+
+impl Rectangle {
+    /// Creates a new filled `Rectangle` with no border.
+    pub fn new(x: f32, y: f32, width: f32, height: f32, color: Color) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+            fill: Some(Fill::Solid(color)),
+            stroke: None,
+            stroke_style: StrokeStyle::Solid,
+            shadow: None,
+            transform: None,
+            opacity: 1.0,
+        }
+    }
+
+    /// Creates a new unfilled `Rectangle` that only draws its border.
+    pub fn outlined(x: f32, y: f32, width: f32, height: f32, color: Color, stroke_width: f32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+            fill: None,
+            stroke: Some((color, stroke_width)),
+            stroke_style: StrokeStyle::Solid,
+            shadow: None,
+            transform: None,
+            opacity: 1.0,
+        }
+    }
+
+    /// Sets the fill of the rectangle, such as a color or a tiled `Pattern`.
+    ///
+    /// Patterns are anchored at the rectangle's top-left corner.
+    pub fn with_fill(mut self, fill: impl Into<Fill>) -> Self {
+        self.fill = Some(fill.into());
+        self
+    }
+
+    /// Adds a border to the rectangle, keeping any existing fill.
+    pub fn with_stroke(mut self, color: Color, stroke_width: f32) -> Self {
+        self.stroke = Some((color, stroke_width));
+        self
+    }
+
+    /// Sets the dash pattern of the border.
+    pub fn with_stroke_style(mut self, stroke_style: StrokeStyle) -> Self {
+        self.stroke_style = stroke_style;
+        self
+    }
+
+    /// Adds a drop shadow beneath the rectangle.
+    ///
+    /// See `Shadow` for how the blur is rendered.
+    pub fn with_shadow(mut self, offset: Vector2, blur_radius: f32, color: Color) -> Self {
+        self.shadow = Some(Shadow::new(offset, blur_radius, color));
+        self
+    }
+
+    /// Returns the rectangle as a `Rect`.
+    fn rect(&self) -> Rect {
+        Rect::new(self.x, self.y, self.width, self.height)
+    }
+
+    /// Sets the opacity of the rectangle.
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    /// Sets a rotation and scale for the rectangle.
+    pub fn with_transform(mut self, transform: Transform) -> Self {
+        self.transform = Some(transform);
+        self
+    }
+}
+
+impl Drawable for Rectangle {
+    /// Draws the shadow, fills the rectangle, and then strokes its border, if each is set.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create the border's stroke style
+    /// or to load the image of a pattern fill.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for calling Direct2D drawing methods.
+    /// The caller must ensure that the `drawing_context` contains valid Direct2D resources.
+    fn draw(&self, context: &DrawingContext) -> Result<()> {
+        context.with_opacity(self.opacity, || {
+            draw_transformed(
+                context,
+                self.transform.as_ref(),
+                || Vector2 {
+                    X: self.x + self.width / 2.0,
+                    Y: self.y + self.height / 2.0,
+                },
+                || {
+                    let rect = self.rect();
+
+                    if let Some(shadow) = &self.shadow {
+                        shadow.draw_for_rect(context, rect);
+                    }
+
+                    if let Some(fill) = &self.fill {
+                        let origin = Vector2 {
+                            X: self.x,
+                            Y: self.y,
+                        };
+                        let fill_rect: D2D_RECT_F = context.snap_rect(&rect).into();
+                        fill.with_brush(context, origin, |brush| unsafe {
+                            context.render_target.FillRectangle(&fill_rect, brush);
+                        })?;
+                    }
+
+                    if let Some((color, stroke_width)) = &self.stroke {
+                        if *stroke_width > 0.0 {
+                            let stroke_width = context.snap_stroke_width(*stroke_width);
+                            let stroke_rect: D2D_RECT_F =
+                                context.snap_stroke_rect(&rect, stroke_width).into();
+                            let stroke_style = context.stroke_style(
+                                &self.stroke_style,
+                                LineCap::Flat,
+                                LineJoin::Miter,
+                            )?;
+                            context.with_brush_color(color, |brush| unsafe {
+                                context.render_target.DrawRectangle(
+                                    &stroke_rect,
+                                    brush,
+                                    stroke_width,
+                                    stroke_style.as_ref(),
+                                );
+                            });
+                        }
+                    }
+
+                    Ok(())
+                },
+            )
+        })
+    }
+
+    /// Fills the rectangle and strokes its border through `renderer`.
+    ///
+    /// Pattern fills and the shadow are skipped, and dashed borders are drawn solid.
+    fn render(&self, renderer: &mut dyn Renderer) {
+        renderer.with_opacity(self.opacity, |renderer| {
+            render_transformed(
+                renderer,
+                self.transform.as_ref(),
+                || self.rect().center(),
+                |renderer| {
+                    if let Some(Fill::Solid(color)) = &self.fill {
+                        renderer.draw_rect(self.rect(), Paint::Fill(*color));
+                    }
+                    if let Some((color, width)) = self.stroke.filter(|(_, width)| *width > 0.0) {
+                        renderer.draw_rect(self.rect(), Paint::Stroke { color, width });
+                    }
+                },
+            )
+        })
+    }
+
+    /// Returns the rectangle grown by half the border width and extended to cover the
+    /// shadow, after the transform.
+    fn bounds(&self) -> Option<Rect> {
+        let rect = Rect::new(self.x, self.y, self.width, self.height);
+        let stroke_width = self
+            .stroke
+            .as_ref()
+            .map_or(0.0, |(_, width)| width.max(0.0));
+        let mut bounds = rect.inflate(stroke_width / 2.0);
+        if let Some(shadow) = &self.shadow {
+            let shadow_rect = Rect::new(
+                rect.x + shadow.offset.X,
+                rect.y + shadow.offset.Y,
+                rect.width,
+                rect.height,
+            );
+            bounds = bounds.union(&shadow_rect.inflate(shadow.blur_radius.max(0.0)));
+        }
+        Some(transformed_bounds(
+            self.transform.as_ref(),
+            || rect.center(),
+            bounds,
+        ))
+    }
+
+    crate::impl_clone_box!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::backend::{DrawCommand, RecordingRenderer};
+
+    #[test]
+    fn borders_without_a_positive_width_are_not_drawn() {
+        for width in [0.0, -1.0] {
+            let rectangle = Rectangle::new(10.0, 20.0, 30.0, 40.0, Color::BLUE)
+                .with_stroke(Color::WHITE, width);
+            let mut renderer = RecordingRenderer::new();
+            rectangle.render(&mut renderer);
+
+            assert_eq!(
+                renderer.commands(),
+                [DrawCommand::Rect {
+                    rect: Rect::new(10.0, 20.0, 30.0, 40.0),
+                    paint: Paint::Fill(Color::BLUE),
+                }],
+                "stroke width {width}"
+            );
+        }
+    }
+}
@@ -0,0 +1,400 @@
+use windows_numerics::Vector2;
+
+use super::path::PathCommand;
+
+/// An error produced when parsing SVG path data.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum SvgPathError {
+    /// The path data did not begin with a move-to command.
+    #[error("path data must begin with a move-to command")]
+    MissingMoveTo,
+    /// A character that is not valid at this position was found.
+    #[error("unexpected character '{character}' at offset {offset}")]
+    UnexpectedCharacter {
+        /// The unexpected character.
+        character: char,
+        /// The byte offset of the character in the path data.
+        offset: usize,
+    },
+    /// A number was expected but not found.
+    #[error("expected a number at offset {0}")]
+    ExpectedNumber(usize),
+    /// An arc flag (`0` or `1`) was expected but not found.
+    #[error("expected an arc flag (0 or 1) at offset {0}")]
+    ExpectedFlag(usize),
+}
+
+/// A cursor over SVG path data.
+struct Parser<'a> {
+    data: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(data: &'a str) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.data.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_separators(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\r' | b'\n' | b',')) {
+            self.pos += 1;
+        }
+    }
+
+    fn unexpected(&self) -> SvgPathError {
+        let character = self.data[self.pos..].chars().next().unwrap_or('\0');
+        SvgPathError::UnexpectedCharacter {
+            character,
+            offset: self.pos,
+        }
+    }
+
+    fn skip_digits(&mut self) -> usize {
+        let start = self.pos;
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.pos += 1;
+        }
+        self.pos - start
+    }
+
+    fn number(&mut self) -> Result<f32, SvgPathError> {
+        self.skip_separators();
+        let start = self.pos;
+
+        if matches!(self.peek(), Some(b'+' | b'-')) {
+            self.pos += 1;
+        }
+        let mut digits = self.skip_digits();
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            digits += self.skip_digits();
+        }
+        if digits == 0 {
+            self.pos = start;
+            return Err(SvgPathError::ExpectedNumber(start));
+        }
+
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            let mantissa_end = self.pos;
+            self.pos += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.pos += 1;
+            }
+            if self.skip_digits() == 0 {
+                self.pos = mantissa_end;
+            }
+        }
+
+        self.data[start..self.pos]
+            .parse()
+            .map_err(|_| SvgPathError::ExpectedNumber(start))
+    }
+
+    fn flag(&mut self) -> Result<bool, SvgPathError> {
+        self.skip_separators();
+        let flag = match self.peek() {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => return Err(SvgPathError::ExpectedFlag(self.pos)),
+        };
+        self.pos += 1;
+        Ok(flag)
+    }
+
+    fn point(&mut self, relative_to: Option<Vector2>) -> Result<Vector2, SvgPathError> {
+        let x = self.number()?;
+        let y = self.number()?;
+        let origin = relative_to.unwrap_or_default();
+        Ok(Vector2 {
+            X: origin.X + x,
+            Y: origin.Y + y,
+        })
+    }
+
+    /// Returns `true` if the next non-separator character can start a number.
+    fn at_number(&mut self) -> bool {
+        self.skip_separators();
+        matches!(self.peek(), Some(b'0'..=b'9' | b'.' | b'+' | b'-'))
+    }
+}
+
+/// Parses SVG path data (the `d` attribute) into absolute path commands.
+///
+/// Supports the `M`, `L`, `H`, `V`, `C`, `Q`, `A`, and `Z` commands in both their
+/// absolute (uppercase) and relative (lowercase) forms, as well as implicitly repeated
+/// commands, where extra coordinates after a move-to are treated as line-tos.
+///
+/// # Errors
+///
+/// This function will return an error if the data does not begin with a move-to
+/// command, contains an unknown command, or has missing or malformed numbers or flags.
+pub(crate) fn parse(data: &str) -> Result<Vec<PathCommand>, SvgPathError> {
+    let mut parser = Parser::new(data);
+    let mut commands = Vec::new();
+    let mut command: Option<u8> = None;
+    let mut start = Vector2::default();
+    let mut current = Vector2::default();
+
+    loop {
+        parser.skip_separators();
+        let Some(next) = parser.peek() else {
+            break;
+        };
+
+        if next.is_ascii_alphabetic() {
+            command = Some(next);
+            parser.pos += 1;
+        } else if command.is_none() || !parser.at_number() {
+            return Err(if commands.is_empty() {
+                SvgPathError::MissingMoveTo
+            } else {
+                parser.unexpected()
+            });
+        }
+
+        let Some(letter) = command else {
+            break;
+        };
+        if commands.is_empty() && !matches!(letter, b'M' | b'm') {
+            return Err(SvgPathError::MissingMoveTo);
+        }
+        let relative = letter.is_ascii_lowercase();
+        let origin = relative.then_some(current);
+
+        match letter.to_ascii_uppercase() {
+            b'M' => {
+                let point = parser.point(origin)?;
+                commands.push(PathCommand::MoveTo(point));
+                start = point;
+                current = point;
+                // Coordinates following a move-to are implicit line-tos.
+                command = Some(if relative { b'l' } else { b'L' });
+            }
+            b'L' => {
+                let point = parser.point(origin)?;
+                commands.push(PathCommand::LineTo(point));
+                current = point;
+            }
+            b'H' => {
+                let x = parser.number()?;
+                current.X = if relative { current.X + x } else { x };
+                commands.push(PathCommand::LineTo(current));
+            }
+            b'V' => {
+                let y = parser.number()?;
+                current.Y = if relative { current.Y + y } else { y };
+                commands.push(PathCommand::LineTo(current));
+            }
+            b'C' => {
+                let control1 = parser.point(origin)?;
+                let control2 = parser.point(origin)?;
+                let end = parser.point(origin)?;
+                commands.push(PathCommand::CubicTo {
+                    control1,
+                    control2,
+                    end,
+                });
+                current = end;
+            }
+            b'Q' => {
+                let control = parser.point(origin)?;
+                let end = parser.point(origin)?;
+                commands.push(PathCommand::QuadTo { control, end });
+                current = end;
+            }
+            b'A' => {
+                let radius_x = parser.number()?;
+                let radius_y = parser.number()?;
+                let rotation = parser.number()?;
+                let large_arc = parser.flag()?;
+                let clockwise = parser.flag()?;
+                let end = parser.point(origin)?;
+                commands.push(PathCommand::ArcTo {
+                    end,
+                    radius_x,
+                    radius_y,
+                    rotation,
+                    large_arc,
+                    clockwise,
+                });
+                current = end;
+            }
+            b'Z' => {
+                commands.push(PathCommand::Close);
+                current = start;
+                // A close-path takes no arguments, so it cannot be repeated implicitly.
+                command = None;
+            }
+            _ => {
+                parser.pos -= 1;
+                return Err(parser.unexpected());
+            }
+        }
+    }
+
+    if commands.is_empty() {
+        return Err(SvgPathError::MissingMoveTo);
+    }
+    Ok(commands)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(x: f32, y: f32) -> Vector2 {
+        Vector2 { X: x, Y: y }
+    }
+
+    #[test]
+    fn parses_absolute_commands() {
+        let commands =
+            parse("M 10 20 L 30 40 H 50 V 60 C 1 2 3 4 5 6 Q 7 8 9 10 A 5 6 45 1 0 11 12 Z")
+                .unwrap();
+        assert_eq!(
+            commands,
+            vec![
+                PathCommand::MoveTo(point(10.0, 20.0)),
+                PathCommand::LineTo(point(30.0, 40.0)),
+                PathCommand::LineTo(point(50.0, 40.0)),
+                PathCommand::LineTo(point(50.0, 60.0)),
+                PathCommand::CubicTo {
+                    control1: point(1.0, 2.0),
+                    control2: point(3.0, 4.0),
+                    end: point(5.0, 6.0),
+                },
+                PathCommand::QuadTo {
+                    control: point(7.0, 8.0),
+                    end: point(9.0, 10.0),
+                },
+                PathCommand::ArcTo {
+                    end: point(11.0, 12.0),
+                    radius_x: 5.0,
+                    radius_y: 6.0,
+                    rotation: 45.0,
+                    large_arc: true,
+                    clockwise: false,
+                },
+                PathCommand::Close,
+            ]
+        );
+    }
+
+    #[test]
+    fn resolves_relative_commands_against_the_current_point() {
+        let commands =
+            parse("m 10 10 l 5 0 h 5 v 5 c 1 0 2 0 3 3 q 1 1 2 2 a 1 1 0 0 1 -5 -5 z").unwrap();
+        assert_eq!(
+            commands,
+            vec![
+                PathCommand::MoveTo(point(10.0, 10.0)),
+                PathCommand::LineTo(point(15.0, 10.0)),
+                PathCommand::LineTo(point(20.0, 10.0)),
+                PathCommand::LineTo(point(20.0, 15.0)),
+                PathCommand::CubicTo {
+                    control1: point(21.0, 15.0),
+                    control2: point(22.0, 15.0),
+                    end: point(23.0, 18.0),
+                },
+                PathCommand::QuadTo {
+                    control: point(24.0, 19.0),
+                    end: point(25.0, 20.0),
+                },
+                PathCommand::ArcTo {
+                    end: point(20.0, 15.0),
+                    radius_x: 1.0,
+                    radius_y: 1.0,
+                    rotation: 0.0,
+                    large_arc: false,
+                    clockwise: true,
+                },
+                PathCommand::Close,
+            ]
+        );
+    }
+
+    #[test]
+    fn relative_move_after_close_starts_from_the_figure_start() {
+        let commands = parse("M 10 10 L 20 20 Z m 5 5 l 1 1").unwrap();
+        assert_eq!(commands[3], PathCommand::MoveTo(point(15.0, 15.0)));
+        assert_eq!(commands[4], PathCommand::LineTo(point(16.0, 16.0)));
+    }
+
+    #[test]
+    fn repeats_commands_implicitly() {
+        // Extra pairs after a move-to are line-tos, relative for `m`.
+        assert_eq!(
+            parse("m 1 1 2 2 3 3").unwrap(),
+            vec![
+                PathCommand::MoveTo(point(1.0, 1.0)),
+                PathCommand::LineTo(point(3.0, 3.0)),
+                PathCommand::LineTo(point(6.0, 6.0)),
+            ]
+        );
+        assert_eq!(
+            parse("M 0 0 L 1 1 2 2").unwrap(),
+            vec![
+                PathCommand::MoveTo(point(0.0, 0.0)),
+                PathCommand::LineTo(point(1.0, 1.0)),
+                PathCommand::LineTo(point(2.0, 2.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_compact_numbers() {
+        assert_eq!(
+            parse("M10-5L.5.5,1e1-2E-1").unwrap(),
+            vec![
+                PathCommand::MoveTo(point(10.0, -5.0)),
+                PathCommand::LineTo(point(0.5, 0.5)),
+                PathCommand::LineTo(point(10.0, -0.2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn requires_a_move_to_first() {
+        assert_eq!(parse(""), Err(SvgPathError::MissingMoveTo));
+        assert_eq!(parse("   "), Err(SvgPathError::MissingMoveTo));
+        assert_eq!(parse("L 1 2"), Err(SvgPathError::MissingMoveTo));
+        assert_eq!(parse("10 20"), Err(SvgPathError::MissingMoveTo));
+    }
+
+    #[test]
+    fn rejects_malformed_data() {
+        assert_eq!(parse("M 10"), Err(SvgPathError::ExpectedNumber(4)));
+        assert_eq!(parse("M 1 2 3"), Err(SvgPathError::ExpectedNumber(7)));
+        assert_eq!(parse("M 0 0 L x"), Err(SvgPathError::ExpectedNumber(8)));
+        assert_eq!(
+            parse("M 0 0 A 5 5 0 2 1 10 10"),
+            Err(SvgPathError::ExpectedFlag(14))
+        );
+        assert_eq!(
+            parse("M 0 0 X 1"),
+            Err(SvgPathError::UnexpectedCharacter {
+                character: 'X',
+                offset: 6
+            })
+        );
+        assert_eq!(
+            parse("M 0 0 #"),
+            Err(SvgPathError::UnexpectedCharacter {
+                character: '#',
+                offset: 6
+            })
+        );
+        // A close-path takes no arguments, so numbers after it are not repeated.
+        assert_eq!(
+            parse("M 0 0 Z 5 5"),
+            Err(SvgPathError::UnexpectedCharacter {
+                character: '5',
+                offset: 8
+            })
+        );
+    }
+}
@@ -0,0 +1,124 @@
+use std::cell::RefCell;
+
+use windows::core::*;
+use windows_numerics::Vector2;
+
+use crate::core::render::color::Color;
+use crate::core::render::drawable::Drawable;
+use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::geometry::{Geometry, GeometryStroke};
+use crate::core::render::rect::Rect;
+use crate::core::render::stroke_style::LineJoin;
+use crate::core::render::transform::{
+    bounds_center, draw_transformed, transformed_bounds, Transform,
+};
+
+/// A `Drawable` triangle with an optional fill and an optional outline.
+///
+/// The triangle is drawn as a `Geometry`, which is cached and rebuilt only when its
+/// points change.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Triangle {
+    /// The three corners of the triangle.
+    #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2::array"))]
+    pub points: [Vector2; 3],
+    /// The fill color, or `None` for an unfilled triangle.
+    pub fill: Option<Color>,
+    /// The outline color and width, or `None` for no outline.
+    pub stroke: Option<(Color, f32)>,
+    /// The opacity of the triangle, from `0.0` (transparent) to `1.0` (opaque).
+    pub opacity: f32,
+    /// An optional rotation and scale, pivoting around the triangle's bounding box center
+    /// by default.
+    pub transform: Option<Transform>,
+    /// The cached geometry and the points it was built for.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    geometry: RefCell<Option<([Vector2; 3], Geometry)>>,
+}
+
+impl Triangle {
+    /// Creates a new filled `Triangle` with no outline.
+    pub fn new(a: Vector2, b: Vector2, c: Vector2, color: Color) -> Self {
+        Self {
+            points: [a, b, c],
+            fill: Some(color),
+            stroke: None,
+            geometry: RefCell::new(None),
+            transform: None,
+            opacity: 1.0,
+        }
+    }
+
+    /// Adds an outline to the triangle, keeping any existing fill.
+    pub fn with_stroke(mut self, color: Color, stroke_width: f32) -> Self {
+        self.stroke = Some((color, stroke_width));
+        self
+    }
+
+    /// Sets the opacity of the triangle.
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    /// Sets a rotation and scale for the triangle.
+    pub fn with_transform(mut self, transform: Transform) -> Self {
+        self.transform = Some(transform);
+        self
+    }
+}
+
+impl Drawable for Triangle {
+    /// Fills the triangle and then strokes its outline, if either is set.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to build the path geometry.
+    fn draw(&self, context: &DrawingContext) -> Result<()> {
+        context.with_opacity(self.opacity, || {
+            draw_transformed(
+                context,
+                self.transform.as_ref(),
+                || bounds_center(self.points),
+                || {
+                    let mut cached = self.geometry.borrow_mut();
+                    if cached
+                        .as_ref()
+                        .is_none_or(|(points, _)| *points != self.points)
+                    {
+                        *cached = Some((self.points, Geometry::polygon(&self.points)));
+                    }
+                    let Some((_, geometry)) = cached.as_ref() else {
+                        return Ok(());
+                    };
+                    context.draw_geometry(
+                        geometry,
+                        self.fill.as_ref(),
+                        self.stroke
+                            .as_ref()
+                            .map(|(color, width)| GeometryStroke::new(color, *width)),
+                    )
+                },
+            )
+        })
+    }
+
+    /// Returns the box around the corners grown by as far as the outline can reach past
+    /// them, after the transform. See `LineJoin::stroke_extent`.
+    fn bounds(&self) -> Option<Rect> {
+        let rect = Rect::from_points(self.points)?;
+        // The outline is drawn with miter joins, which reach past sharp corners.
+        let stroke_extent = self
+            .stroke
+            .as_ref()
+            .map_or(0.0, |(_, width)| LineJoin::Miter.stroke_extent(*width));
+        Some(transformed_bounds(
+            self.transform.as_ref(),
+            || bounds_center(self.points),
+            rect.inflate(stroke_extent),
+        ))
+    }
+
+    crate::impl_clone_box!();
+}
@@ -0,0 +1,231 @@
+use windows::{Win32::Graphics::Direct2D::Common::D2D_RECT_F, core::Result};
+use windows_numerics::Vector2;
+
+use crate::core::render::color::Color;
+use crate::core::render::drawable::Drawable;
+use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::objects::canvas::Canvas;
+use crate::core::render::rect::Rect;
+
+/// The direction a `ScrollBar` scrolls in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ScrollOrientation {
+    /// Scrolls up and down, with the thumb moving along the height of the track.
+    #[default]
+    Vertical,
+    /// Scrolls left and right, with the thumb moving along the width of the track.
+    Horizontal,
+}
+
+/// A `Drawable` scroll bar: a track with a thumb showing which part of the content is
+/// visible.
+///
+/// The thumb's length is the track's length times the fraction of the content that
+/// fits in the viewport, but never less than `min_thumb_length`, and its position
+/// follows the scroll offset. When all the content fits, only the track is drawn.
+///
+/// The scroll bar only displays a range; it doesn't scroll anything itself. Use
+/// `sync_with` to copy the range from a `Canvas`, or let a `ScrollBarHandler` keep the
+/// two in step and drag the thumb.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use my_gui::core::render::objects::{
+///     canvas::Canvas,
+///     scroll_bar::{ScrollBar, ScrollOrientation},
+/// };
+///
+/// let list = Canvas::new(40.0, 60.0, 320.0, 400.0).with_content_size(320.0, 1600.0);
+/// let mut scroll_bar = ScrollBar::new(364.0, 60.0, 12.0, 400.0, ScrollOrientation::Vertical);
+/// scroll_bar.sync_with(&list);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScrollBar {
+    /// The track the thumb moves along.
+    pub rect: Rect,
+    /// The direction the scroll bar scrolls in.
+    pub orientation: ScrollOrientation,
+    /// The color of the track.
+    pub track_color: Color,
+    /// The color of the thumb.
+    pub thumb_color: Color,
+    /// The shortest the thumb gets, so it stays easy to grab for long content.
+    pub min_thumb_length: f32,
+    /// The length of the visible part of the content.
+    viewport_length: f32,
+    /// The length of the whole content.
+    content_length: f32,
+    /// How far the content is scrolled.
+    offset: f32,
+}
+
+impl ScrollBar {
+    /// Creates a new `ScrollBar` with its track covering the given rectangle and
+    /// nothing to scroll.
+    pub fn new(x: f32, y: f32, width: f32, height: f32, orientation: ScrollOrientation) -> Self {
+        Self {
+            rect: Rect::new(x, y, width, height),
+            orientation,
+            track_color: Color::new(1.0, 1.0, 1.0, 0.1),
+            thumb_color: Color::new(1.0, 1.0, 1.0, 0.5),
+            min_thumb_length: 20.0,
+            viewport_length: 0.0,
+            content_length: 0.0,
+            offset: 0.0,
+        }
+    }
+
+    /// Sets the colors of the track and the thumb.
+    pub fn with_colors(mut self, track_color: Color, thumb_color: Color) -> Self {
+        self.track_color = track_color;
+        self.thumb_color = thumb_color;
+        self
+    }
+
+    /// Sets the shortest the thumb gets.
+    pub fn with_min_thumb_length(mut self, min_thumb_length: f32) -> Self {
+        self.min_thumb_length = min_thumb_length;
+        self
+    }
+
+    /// Sets the range the scroll bar shows: the length of the visible part of the
+    /// content, the length of the whole content, and how far it is scrolled.
+    pub fn set_range(&mut self, viewport_length: f32, content_length: f32, offset: f32) {
+        self.viewport_length = viewport_length;
+        self.content_length = content_length;
+        self.offset = offset;
+    }
+
+    /// Copies the range from a canvas in the scroll bar's orientation.
+    ///
+    /// A canvas without a content size can be scrolled without limit, so the scroll bar
+    /// treats the content as ending one viewport past the visible part.
+    pub fn sync_with(&mut self, canvas: &Canvas) {
+        let (viewport_length, content_length, offset) = self.range_of(canvas);
+        self.set_range(viewport_length, content_length, offset);
+    }
+
+    /// Returns the viewport length, content length, and scroll offset of a canvas in
+    /// the scroll bar's orientation. See `sync_with`.
+    pub(crate) fn range_of(&self, canvas: &Canvas) -> (f32, f32, f32) {
+        let offset = self.along(canvas.scroll_offset());
+        let viewport = canvas.content_rect();
+        let viewport_length = match self.orientation {
+            ScrollOrientation::Vertical => viewport.height,
+            ScrollOrientation::Horizontal => viewport.width,
+        };
+        let content_length = match (canvas.content_size(), self.orientation) {
+            (Some((_, height)), ScrollOrientation::Vertical) => height,
+            (Some((width, _)), ScrollOrientation::Horizontal) => width,
+            (None, _) => offset + 2.0 * viewport_length,
+        };
+        (viewport_length, content_length, offset)
+    }
+
+    /// Returns how far the content is scrolled.
+    pub fn offset(&self) -> f32 {
+        self.offset
+    }
+
+    /// Returns the largest scroll offset, which is 0 when all the content fits.
+    pub fn max_offset(&self) -> f32 {
+        (self.content_length - self.viewport_length).max(0.0)
+    }
+
+    /// Returns the rectangle of the thumb, or `None` if all the content fits and there
+    /// is nothing to scroll.
+    pub fn thumb_rect(&self) -> Option<Rect> {
+        let max_offset = self.max_offset();
+        if max_offset <= 0.0 {
+            return None;
+        }
+        let (start, track_length) = self.track();
+        let thumb_length = self.thumb_length();
+        let position =
+            start + (track_length - thumb_length) * (self.offset / max_offset).clamp(0.0, 1.0);
+        Some(match self.orientation {
+            ScrollOrientation::Vertical => {
+                Rect::new(self.rect.x, position, self.rect.width, thumb_length)
+            }
+            ScrollOrientation::Horizontal => {
+                Rect::new(position, self.rect.y, thumb_length, self.rect.height)
+            }
+        })
+    }
+
+    /// Returns the scroll offset that puts the start of the thumb at `position` along
+    /// the track, such as while the thumb is dragged.
+    ///
+    /// The result is clamped to the scrollable range.
+    pub fn offset_at(&self, position: f32) -> f32 {
+        let (start, track_length) = self.track();
+        let travel = track_length - self.thumb_length();
+        if travel <= 0.0 {
+            return 0.0;
+        }
+        ((position - start) / travel).clamp(0.0, 1.0) * self.max_offset()
+    }
+
+    /// Returns the coordinate of `point` along the track, such as the y-coordinate for
+    /// a vertical scroll bar.
+    pub fn along(&self, point: Vector2) -> f32 {
+        match self.orientation {
+            ScrollOrientation::Vertical => point.Y,
+            ScrollOrientation::Horizontal => point.X,
+        }
+    }
+
+    /// Returns the position where the track starts and its length.
+    fn track(&self) -> (f32, f32) {
+        match self.orientation {
+            ScrollOrientation::Vertical => (self.rect.y, self.rect.height),
+            ScrollOrientation::Horizontal => (self.rect.x, self.rect.width),
+        }
+    }
+
+    /// Returns the length of the thumb, which is never longer than the track.
+    fn thumb_length(&self) -> f32 {
+        let (_, track_length) = self.track();
+        let fraction = if self.content_length > 0.0 {
+            (self.viewport_length / self.content_length).min(1.0)
+        } else {
+            1.0
+        };
+        (track_length * fraction)
+            .max(self.min_thumb_length)
+            .min(track_length)
+    }
+}
+
+impl Drawable for ScrollBar {
+    /// Fills the track and, if there is anything to scroll, the thumb.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for calling Direct2D drawing methods. The
+    /// caller must ensure that the `drawing_context` contains valid Direct2D resources.
+    fn draw(&self, context: &DrawingContext) -> Result<()> {
+        let track: D2D_RECT_F = self.rect.into();
+        context.with_brush_color(&self.track_color, |brush| unsafe {
+            context.render_target.FillRectangle(&track, brush);
+        });
+
+        if let Some(thumb) = self.thumb_rect() {
+            let thumb: D2D_RECT_F = thumb.into();
+            context.with_brush_color(&self.thumb_color, |brush| unsafe {
+                context.render_target.FillRectangle(&thumb, brush);
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns the track rectangle.
+    fn bounds(&self) -> Option<Rect> {
+        Some(self.rect)
+    }
+
+    crate::impl_clone_box!();
+}
@@ -0,0 +1,198 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use windows::{
+    Win32::Foundation::HANDLE,
+    Win32::Graphics::Direct2D::{Common::D2D_SIZE_U, ID2D1Bitmap1, ID2D1RenderTarget},
+    core::Result,
+};
+use windows_numerics::Matrix3x2;
+
+use crate::core::render::drawable::Drawable;
+use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::image_source::Interpolation;
+use crate::core::render::rect::Rect;
+
+/// Called with the size in pixels a `SharedSurface` is drawn at whenever it changes.
+/// It returns the handle of a texture recreated at that size, or `None` to keep the
+/// current one.
+pub type ResizeCallback = dyn FnMut(u32, u32) -> Option<HANDLE>;
+
+/// The bitmap drawing a shared texture, and what it was created for.
+#[derive(Clone)]
+struct SurfaceBitmap {
+    render_target: ID2D1RenderTarget,
+    handle: HANDLE,
+    bitmap: ID2D1Bitmap1,
+    size: D2D_SIZE_U,
+}
+
+/// A `Drawable` that shows a Direct3D texture shared by the application, such as the
+/// output of its own 3D renderer, inside the 2D scene.
+///
+/// The texture is opened by the handle from its `IDXGIResource::GetSharedHandle` and
+/// drawn stretched to `bounds`, without copying it, so each frame shows whatever the
+/// application last rendered into it. The application should flush its Direct3D
+/// device after rendering, so the frame is complete when the window paints.
+///
+/// Stretching a texture blurs it. To keep it sharp, set a callback with `on_resize`:
+/// it is told the size in pixels the surface covers whenever that changes, so the
+/// application can recreate its texture at that size and return the new handle. A
+/// texture that matches the covered size is drawn pixel for pixel.
+///
+/// Shared textures can only be drawn with `RendererConfig::SwapChain`, whose render
+/// target has a Direct3D device; with other renderers, `draw` returns an error.
+#[derive(Clone)]
+pub struct SharedSurface {
+    /// The rectangle the texture is drawn in.
+    pub bounds: Rect,
+    /// The opacity of the texture, from `0.0` (transparent) to `1.0` (opaque).
+    pub opacity: f32,
+    handle: Cell<HANDLE>,
+    on_resize: Option<Rc<RefCell<ResizeCallback>>>,
+    reported_size: Cell<Option<(u32, u32)>>,
+    bitmap: RefCell<Option<SurfaceBitmap>>,
+}
+
+impl SharedSurface {
+    /// Creates a new, fully opaque `SharedSurface` drawing the texture shared by
+    /// `handle` in `bounds`.
+    pub fn new(handle: HANDLE, bounds: Rect) -> Self {
+        Self {
+            bounds,
+            opacity: 1.0,
+            handle: Cell::new(handle),
+            on_resize: None,
+            reported_size: Cell::new(None),
+            bitmap: RefCell::new(None),
+        }
+    }
+
+    /// Sets the callback told the size in pixels the surface covers whenever it
+    /// changes, including on first draw.
+    pub fn on_resize(mut self, callback: impl FnMut(u32, u32) -> Option<HANDLE> + 'static) -> Self {
+        self.on_resize = Some(Rc::new(RefCell::new(callback)));
+        self
+    }
+
+    /// Returns the handle of the shared texture.
+    pub fn handle(&self) -> HANDLE {
+        self.handle.get()
+    }
+
+    /// Replaces the shared texture, such as after the application recreated it. The
+    /// new texture is opened on the next draw.
+    pub fn set_handle(&self, handle: HANDLE) {
+        self.handle.set(handle);
+    }
+
+    /// Returns the size in pixels `bounds` covers on the render target, after the
+    /// current transform.
+    ///
+    /// # Safety
+    ///
+    /// This function contains an `unsafe` block for reading the render target's
+    /// transform and DPI.
+    fn pixel_size(&self, context: &DrawingContext) -> (u32, u32) {
+        let mut transform = Matrix3x2::default();
+        let (mut dpi_x, mut dpi_y) = (0.0, 0.0);
+        unsafe {
+            context.render_target.GetTransform(&mut transform);
+            context.render_target.GetDpi(&mut dpi_x, &mut dpi_y);
+        }
+        let bounds = self.bounds.transform(&transform);
+        let to_pixels = |dips: f32, dpi: f32| (dips * dpi / 96.0).round().max(0.0) as u32;
+        (
+            to_pixels(bounds.width, dpi_x),
+            to_pixels(bounds.height, dpi_y),
+        )
+    }
+
+    /// Tells the resize callback the covered size if it changed, and switches to the
+    /// texture it returns.
+    fn report_size(&self, size: (u32, u32)) {
+        let Some(on_resize) = &self.on_resize else {
+            return;
+        };
+        if self.reported_size.get() == Some(size) {
+            return;
+        }
+        self.reported_size.set(Some(size));
+        if let Some(handle) = (on_resize.borrow_mut())(size.0, size.1) {
+            self.set_handle(handle);
+        }
+    }
+
+    /// Returns the bitmap drawing the shared texture and its size in pixels, opening
+    /// the texture again if the handle or render target has changed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the texture can't be opened on the render
+    /// target's device.
+    ///
+    /// # Safety
+    ///
+    /// This function contains an `unsafe` block for reading the bitmap's size.
+    fn bitmap(&self, context: &DrawingContext) -> Result<(ID2D1Bitmap1, D2D_SIZE_U)> {
+        let handle = self.handle.get();
+        let mut cached = self.bitmap.borrow_mut();
+        let current = cached.as_ref().filter(|bitmap| {
+            bitmap.handle == handle && &bitmap.render_target == context.render_target
+        });
+        if let Some(bitmap) = current {
+            return Ok((bitmap.bitmap.clone(), bitmap.size));
+        }
+
+        let surface = context.open_shared_surface(handle)?;
+        let bitmap = context.create_bitmap_from_dxgi_surface(&surface)?;
+        let size = unsafe { bitmap.GetPixelSize() };
+        *cached = Some(SurfaceBitmap {
+            render_target: context.render_target.clone(),
+            handle,
+            bitmap: bitmap.clone(),
+            size,
+        });
+        Ok((bitmap, size))
+    }
+}
+
+impl Drawable for SharedSurface {
+    /// Draws the shared texture stretched to `bounds`, after telling the resize
+    /// callback the covered size if it changed.
+    ///
+    /// A texture the size of the covered pixels is drawn without interpolation, so it
+    /// stays sharp.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the window doesn't render with
+    /// `RendererConfig::SwapChain`, or if the texture can't be opened.
+    fn draw(&self, context: &DrawingContext) -> Result<()> {
+        let opacity = self.opacity.clamp(0.0, 1.0);
+        if opacity <= 0.0 || self.bounds.width <= 0.0 || self.bounds.height <= 0.0 {
+            return Ok(());
+        }
+
+        let pixel_size = self.pixel_size(context);
+        self.report_size(pixel_size);
+
+        let (bitmap, size) = self.bitmap(context)?;
+        let interpolation = if (size.width, size.height) == pixel_size {
+            Interpolation::NearestNeighbor
+        } else {
+            Interpolation::Linear
+        };
+        context.draw_d2d_bitmap(&bitmap, &self.bounds, None, opacity, interpolation);
+        Ok(())
+    }
+
+    /// Returns the rectangle the texture is drawn in.
+    fn bounds(&self) -> Option<Rect> {
+        Some(self.bounds)
+    }
+
+    crate::impl_clone_box!();
+}
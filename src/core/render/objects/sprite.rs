@@ -0,0 +1,116 @@
+use std::rc::Rc;
+
+use windows::core::*;
+use windows_numerics::Matrix3x2;
+
+use crate::core::render::drawable::Drawable;
+use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::image_source::{ImageSource, Interpolation};
+use crate::core::render::rect::Rect;
+
+/// A `Drawable` sub-region of a shared image, such as a frame from a texture atlas.
+///
+/// Sprites reference their `ImageSource` through an `Rc`, so any number of sprites can
+/// share a single decoded image and a single uploaded `ID2D1Bitmap`.
+///
+/// A negative `width` or `height` in the destination rectangle flips the sprite
+/// horizontally or vertically within the rectangle's absolute bounds.
+#[derive(Clone)]
+pub struct Sprite {
+    /// The shared source image.
+    pub source: Rc<ImageSource>,
+    /// The region of the source image to draw, in image pixels.
+    pub source_rect: Rect,
+    /// The rectangle to draw the region into.
+    pub destination: Rect,
+    /// The opacity of the sprite, from `0.0` (transparent) to `1.0` (opaque).
+    pub opacity: f32,
+    /// The interpolation used when the destination size differs from the source size.
+    pub interpolation: Interpolation,
+}
+
+impl Sprite {
+    /// Creates a new, fully opaque `Sprite`.
+    pub fn new(source: Rc<ImageSource>, source_rect: Rect, destination: Rect) -> Self {
+        Self {
+            source,
+            source_rect,
+            destination,
+            opacity: 1.0,
+            interpolation: Interpolation::Linear,
+        }
+    }
+
+    /// Sets the opacity of the sprite.
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    /// Sets the interpolation mode.
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+}
+
+impl Drawable for Sprite {
+    /// Draws the source region into the destination rectangle.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the image cannot be decoded or if it
+    /// fails to create the Direct2D bitmap.
+    fn draw(&self, context: &DrawingContext) -> Result<()> {
+        let opacity = self.opacity.clamp(0.0, 1.0);
+        let destination = self.destination;
+        if opacity <= 0.0 || destination.width == 0.0 || destination.height == 0.0 {
+            return Ok(());
+        }
+
+        let flip_x = destination.width < 0.0;
+        let flip_y = destination.height < 0.0;
+        let bounds = Rect::new(
+            destination.x.min(destination.right()),
+            destination.y.min(destination.bottom()),
+            destination.width.abs(),
+            destination.height.abs(),
+        );
+
+        if flip_x || flip_y {
+            let center = bounds.center();
+            let scale_x = if flip_x { -1.0 } else { 1.0 };
+            let scale_y = if flip_y { -1.0 } else { 1.0 };
+            let flip = Matrix3x2 {
+                M11: scale_x,
+                M12: 0.0,
+                M21: 0.0,
+                M22: scale_y,
+                M31: center.X - scale_x * center.X,
+                M32: center.Y - scale_y * center.Y,
+            };
+            context.push_transform(&flip);
+        }
+
+        let result = context.draw_bitmap(
+            &self.source,
+            &bounds,
+            Some(&self.source_rect),
+            opacity,
+            self.interpolation,
+        );
+
+        if flip_x || flip_y {
+            context.pop_transform();
+        }
+
+        result
+    }
+
+    /// Returns the destination rectangle.
+    fn bounds(&self) -> Option<Rect> {
+        Some(self.destination)
+    }
+
+    crate::impl_clone_box!();
+}
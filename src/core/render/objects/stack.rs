@@ -0,0 +1,456 @@
+use std::cell::Cell;
+use std::time::Duration;
+
+use windows::core::Result;
+use windows_numerics::{Matrix3x2, Vector2};
+
+use crate::core::render::drawable::Drawable;
+use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::objects::canvas::Canvas;
+use crate::core::render::rect::Rect;
+use crate::core::render::scene::ObjectId;
+
+/// Where the children of a stack are placed across the direction they are stacked in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StackAlignment {
+    /// Aligned to the left of a `VStack` or the top of an `HStack`.
+    #[default]
+    Start,
+    /// Centered across the stack.
+    Center,
+    /// Aligned to the right of a `VStack` or the bottom of an `HStack`.
+    End,
+}
+
+/// The direction a stack places its children in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Axis {
+    Vertical,
+    Horizontal,
+}
+
+/// A child of a stack, drawn at the offset the stack's layout gave it.
+struct Slot {
+    /// The distance the child is moved from its own position to its place in the
+    /// stack.
+    offset: Cell<Vector2>,
+    child: Box<dyn Drawable>,
+}
+
+impl Drawable for Slot {
+    /// Draws the child moved to its place in the stack.
+    fn draw(&self, context: &DrawingContext) -> Result<()> {
+        let offset = self.offset.get();
+        context.with_transform(&Matrix3x2::translation(offset.X, offset.Y), || {
+            self.child.draw(context)
+        })
+    }
+
+    /// Advances the animation state of the child.
+    fn update(&mut self, dt: Duration) {
+        self.child.update(dt);
+    }
+
+    /// Returns the child's bounds moved to its place in the stack.
+    fn bounds(&self) -> Option<Rect> {
+        let offset = self.offset.get();
+        self.child.bounds().map(|bounds| {
+            Rect::new(
+                bounds.x + offset.X,
+                bounds.y + offset.Y,
+                bounds.width,
+                bounds.height,
+            )
+        })
+    }
+
+    /// Copies the slot along with its child, or returns `None` if the child can't be
+    /// copied.
+    fn clone_box(&self) -> Option<Box<dyn Drawable>> {
+        Some(Box::new(Slot {
+            offset: Cell::new(self.offset.get()),
+            child: self.child.clone_box()?,
+        }))
+    }
+}
+
+/// Returns the child of a stack slot and the offset it is drawn at, or `None` if
+/// `object` isn't a slot, so tools can look inside stacks.
+pub(crate) fn slot_child(object: &dyn Drawable) -> Option<(Matrix3x2, &dyn Drawable)> {
+    let slot = object.as_any().downcast_ref::<Slot>()?;
+    let offset = slot.offset.get();
+    Some((Matrix3x2::translation(offset.X, offset.Y), &*slot.child))
+}
+
+/// Returns the slots of a stack's canvas, in draw order.
+fn slots(canvas: &Canvas) -> impl Iterator<Item = &Slot> {
+    canvas
+        .children()
+        .iter()
+        .filter_map(|(_, object)| object.as_any().downcast_ref::<Slot>())
+}
+
+/// Places the slots of a stack's canvas one after another along `axis`, starting at
+/// the top-left corner of the content.
+fn arrange(
+    canvas: &Canvas,
+    axis: Axis,
+    spacing: f32,
+    alignment: StackAlignment,
+    default_slot_size: (f32, f32),
+) {
+    let viewport = canvas.content_rect();
+    let available = match axis {
+        Axis::Vertical => viewport.width,
+        Axis::Horizontal => viewport.height,
+    };
+    let mut cursor = 0.0;
+    for slot in slots(canvas) {
+        let bounds = slot.child.bounds().unwrap_or(Rect::new(
+            0.0,
+            0.0,
+            default_slot_size.0,
+            default_slot_size.1,
+        ));
+        let (length, thickness) = match axis {
+            Axis::Vertical => (bounds.height, bounds.width),
+            Axis::Horizontal => (bounds.width, bounds.height),
+        };
+        let across = match alignment {
+            StackAlignment::Start => 0.0,
+            StackAlignment::Center => (available - thickness) / 2.0,
+            StackAlignment::End => available - thickness,
+        };
+        let (x, y) = match axis {
+            Axis::Vertical => (across, cursor),
+            Axis::Horizontal => (cursor, across),
+        };
+        slot.offset.set(Vector2 {
+            X: x - bounds.x,
+            Y: y - bounds.y,
+        });
+        cursor += length + spacing;
+    }
+}
+
+/// Draws the children that don't know their size yet into an empty clip, so that
+/// children such as text lay themselves out before the stack is arranged.
+///
+/// # Errors
+///
+/// This function will return an error if any of the children fail to draw.
+fn measure_unbounded(canvas: &Canvas, context: &DrawingContext) -> Result<()> {
+    if slots(canvas).all(|slot| slot.child.bounds().is_some()) {
+        return Ok(());
+    }
+    context.with_clip(&Rect::new(0.0, 0.0, 0.0, 0.0), || {
+        slots(canvas)
+            .filter(|slot| slot.child.bounds().is_none())
+            .try_for_each(|slot| slot.child.draw(context))
+    })?
+}
+
+/// Defines a stack container that places its children along `$axis`.
+macro_rules! stack {
+    ($(#[$meta:meta])* $name:ident, $axis:expr) => {
+        $(#[$meta])*
+        pub struct $name {
+            /// The gap left between neighboring children.
+            pub spacing: f32,
+            /// Where the children are placed across the stack.
+            pub alignment: StackAlignment,
+            /// The width and height reserved for a child without bounds.
+            pub default_slot_size: (f32, f32),
+            /// The canvas the children are drawn in, which clips and scrolls them.
+            canvas: Canvas,
+        }
+
+        impl $name {
+            /// Creates a new, empty stack covering the given rectangle.
+            pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+                Self {
+                    spacing: 0.0,
+                    alignment: StackAlignment::default(),
+                    default_slot_size: (0.0, 0.0),
+                    canvas: Canvas::new(x, y, width, height),
+                }
+            }
+
+            /// Sets the gap left between neighboring children.
+            pub fn with_spacing(mut self, spacing: f32) -> Self {
+                self.spacing = spacing;
+                self
+            }
+
+            /// Sets where the children are placed across the stack.
+            pub fn with_alignment(mut self, alignment: StackAlignment) -> Self {
+                self.alignment = alignment;
+                self
+            }
+
+            /// Sets the width and height reserved for a child without bounds.
+            pub fn with_default_slot_size(mut self, width: f32, height: f32) -> Self {
+                self.default_slot_size = (width, height);
+                self
+            }
+
+            /// Adds a child after the existing ones.
+            ///
+            /// The child is moved into place when the stack is laid out, so its own
+            /// position only matters relative to its bounds. Returns the id used to
+            /// find or remove the child later.
+            pub fn add_child(&mut self, child: Box<dyn Drawable>) -> ObjectId {
+                self.canvas.add_child(Box::new(Slot {
+                    offset: Cell::new(Vector2::default()),
+                    child,
+                }))
+            }
+
+            /// Removes a child, closing the gap it leaves.
+            ///
+            /// Returns `false` if the stack has no child with the given id.
+            pub fn remove_child(&mut self, id: ObjectId) -> bool {
+                self.canvas.remove_child(id)
+            }
+
+            /// Removes every child from the stack.
+            pub fn clear(&mut self) {
+                self.canvas.clear();
+            }
+
+            /// Returns the number of children in the stack.
+            pub fn len(&self) -> usize {
+                self.canvas.len()
+            }
+
+            /// Returns `true` if the stack has no children.
+            pub fn is_empty(&self) -> bool {
+                self.canvas.is_empty()
+            }
+
+            /// Returns a reference to a child as its concrete type, or `None` if the
+            /// stack has no child with the given id or it is not a `T`.
+            pub fn get_child<T: Drawable + 'static>(&self, id: ObjectId) -> Option<&T> {
+                // Dereference the box first, or the box itself would be converted to
+                // `Any`.
+                (*self.canvas.get_child::<Slot>(id)?.child)
+                    .as_any()
+                    .downcast_ref()
+            }
+
+            /// Returns a mutable reference to a child as its concrete type, or `None`
+            /// if the stack has no child with the given id or it is not a `T`.
+            ///
+            /// Changes to the child's size are picked up the next time the stack is
+            /// laid out.
+            pub fn get_child_mut<T: Drawable + 'static>(&mut self, id: ObjectId) -> Option<&mut T> {
+                (*self.canvas.get_child_mut::<Slot>(id)?.child)
+                    .as_any_mut()
+                    .downcast_mut()
+            }
+
+            /// Returns the canvas the children are drawn in, such as to read its
+            /// rectangle or hit-test the children.
+            pub fn canvas(&self) -> &Canvas {
+                &self.canvas
+            }
+
+            /// Returns the canvas the children are drawn in, such as to move the
+            /// stack, give it a background, or scroll it.
+            pub fn canvas_mut(&mut self) -> &mut Canvas {
+                &mut self.canvas
+            }
+
+            /// Moves the children to their places in the stack.
+            ///
+            /// The stack is laid out every time it is drawn, so this is only needed to
+            /// hit-test the children after changing them and before the next frame.
+            pub fn layout(&self) {
+                arrange(
+                    &self.canvas,
+                    $axis,
+                    self.spacing,
+                    self.alignment,
+                    self.default_slot_size,
+                );
+            }
+        }
+
+        impl Drawable for $name {
+            /// Lays out the children and draws them in the stack's canvas.
+            ///
+            /// Children without bounds, such as text that hasn't been drawn yet, are
+            /// first drawn invisibly so they can measure themselves.
+            ///
+            /// # Errors
+            ///
+            /// This function will return an error if any of the children fail to draw.
+            fn draw(&self, context: &DrawingContext) -> Result<()> {
+                measure_unbounded(&self.canvas, context)?;
+                self.layout();
+                self.canvas.draw(context)
+            }
+
+            /// Advances the animation state of every child.
+            fn update(&mut self, dt: Duration) {
+                self.canvas.update(dt);
+            }
+
+            /// Returns the bounds of the stack's canvas.
+            fn bounds(&self) -> Option<Rect> {
+                self.layout();
+                self.canvas.bounds()
+            }
+
+            /// Copies the stack along with every child, or returns `None` if any child
+            /// can't be copied.
+            fn clone_box(&self) -> Option<Box<dyn Drawable>> {
+                Some(Box::new(Self {
+                    spacing: self.spacing,
+                    alignment: self.alignment,
+                    default_slot_size: self.default_slot_size,
+                    canvas: self.canvas.snapshot()?,
+                }))
+            }
+        }
+    };
+}
+
+stack!(
+    /// A `Drawable` container that places its children one below the other.
+    ///
+    /// Each child is moved so that the top-left corner of its bounds sits below the
+    /// previous child, separated by `spacing`, and placed across the stack by
+    /// `alignment`. Children without bounds take up `default_slot_size`. The layout is
+    /// recomputed every time the stack is drawn, so children can change size freely.
+    ///
+    /// The children are drawn in a `Canvas`, so they are clipped to the stack's
+    /// rectangle, and the canvas can give the stack a background or scroll it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use my_gui::core::render::{
+    ///     color::Color,
+    ///     objects::{primitives::Rectangle, stack::VStack, text_object::TextObject},
+    /// };
+    ///
+    /// let mut panel = VStack::new(20.0, 20.0, 240.0, 400.0).with_spacing(8.0);
+    /// panel.add_child(Box::new(TextObject::new("Volume", 0.0, 0.0)));
+    /// panel.add_child(Box::new(Rectangle::new(0.0, 0.0, 200.0, 12.0, Color::BLUE)));
+    /// ```
+    VStack,
+    Axis::Vertical
+);
+
+stack!(
+    /// A `Drawable` container that places its children side by side, from left to
+    /// right.
+    ///
+    /// Each child is moved so that the top-left corner of its bounds sits to the right
+    /// of the previous child, separated by `spacing`, and placed across the stack by
+    /// `alignment`. Children without bounds take up `default_slot_size`. The layout is
+    /// recomputed every time the stack is drawn, so children can change size freely.
+    ///
+    /// The children are drawn in a `Canvas`, so they are clipped to the stack's
+    /// rectangle, and the canvas can give the stack a background or scroll it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use my_gui::core::render::{
+    ///     color::Color,
+    ///     objects::{primitives::Rectangle, stack::{HStack, StackAlignment}},
+    /// };
+    ///
+    /// let mut toolbar = HStack::new(0.0, 0.0, 400.0, 40.0)
+    ///     .with_spacing(4.0)
+    ///     .with_alignment(StackAlignment::Center);
+    /// for _ in 0..5 {
+    ///     toolbar.add_child(Box::new(Rectangle::new(0.0, 0.0, 32.0, 32.0, Color::BLUE)));
+    /// }
+    /// ```
+    HStack,
+    Axis::Horizontal
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::render::color::Color;
+    use crate::core::render::objects::primitives::Rectangle;
+    use crate::core::render::objects::text_object::TextObject;
+
+    /// Returns the offset of every slot in the canvas, in draw order.
+    fn offsets(canvas: &Canvas) -> Vec<Vector2> {
+        slots(canvas).map(|slot| slot.offset.get()).collect()
+    }
+
+    fn column(alignment: StackAlignment) -> VStack {
+        let mut stack = VStack::new(0.0, 0.0, 100.0, 200.0)
+            .with_spacing(10.0)
+            .with_alignment(alignment);
+        stack.add_child(Box::new(Rectangle::new(5.0, 5.0, 40.0, 20.0, Color::RED)));
+        stack.add_child(Box::new(Rectangle::new(0.0, 0.0, 60.0, 30.0, Color::GREEN)));
+        let full_width = Rectangle::new(-10.0, 0.0, 100.0, 10.0, Color::BLUE);
+        stack.add_child(Box::new(full_width));
+        stack.layout();
+        stack
+    }
+
+    #[test]
+    fn arrange_places_children_below_each_other_with_spacing() {
+        let expected_rows = [-5.0, 30.0, 70.0];
+        let cases = [
+            (StackAlignment::Start, [-5.0, 0.0, 10.0]),
+            (StackAlignment::Center, [25.0, 20.0, 10.0]),
+            (StackAlignment::End, [55.0, 40.0, 10.0]),
+        ];
+        for (alignment, expected_columns) in cases {
+            let stack = column(alignment);
+            let expected: Vec<_> = expected_columns
+                .iter()
+                .zip(expected_rows)
+                .map(|(&x, y)| Vector2::new(x, y))
+                .collect();
+            assert_eq!(offsets(stack.canvas()), expected, "{alignment:?}");
+        }
+    }
+
+    #[test]
+    fn arranged_slots_report_their_place_in_the_stack() {
+        let stack = column(StackAlignment::Center);
+        let bounds: Vec<_> = stack
+            .canvas()
+            .children()
+            .iter()
+            .map(|(_, object)| object.bounds())
+            .collect();
+        assert_eq!(
+            bounds,
+            [
+                Some(Rect::new(30.0, 0.0, 40.0, 20.0)),
+                Some(Rect::new(20.0, 30.0, 60.0, 30.0)),
+                Some(Rect::new(0.0, 70.0, 100.0, 10.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn arrange_reserves_the_default_slot_size_for_children_without_bounds() {
+        let mut stack = HStack::new(0.0, 0.0, 200.0, 50.0)
+            .with_spacing(5.0)
+            .with_alignment(StackAlignment::End)
+            .with_default_slot_size(30.0, 20.0);
+        // Text has no bounds until it has been drawn.
+        stack.add_child(Box::new(TextObject::new("Volume", 0.0, 0.0)));
+        stack.add_child(Box::new(Rectangle::new(0.0, 0.0, 10.0, 10.0, Color::BLUE)));
+        stack.layout();
+
+        assert_eq!(
+            offsets(stack.canvas()),
+            [Vector2::new(0.0, 30.0), Vector2::new(35.0, 40.0)]
+        );
+    }
+}
@@ -1,13 +1,582 @@
-use windows::{core::*, Win32::Graphics::Direct2D::D2D1_DRAW_TEXT_OPTIONS_NONE};
-use windows_numerics::Vector2;
+use std::cell::RefCell;
+use std::ops::Range;
 
+use windows::{
+    core::*,
+    Win32::Foundation::{E_INVALIDARG, E_NOT_SUFFICIENT_BUFFER},
+    Win32::Graphics::Direct2D::{
+        Common::{D2D_RECT_F, D2D_SIZE_F},
+        ID2D1Brush, ID2D1RenderTarget, ID2D1SolidColorBrush,
+    },
+    Win32::Graphics::DirectWrite::{
+        IDWriteFactory, IDWriteTextFormat, IDWriteTextLayout, IDWriteTextLayout1,
+        DWRITE_FLOW_DIRECTION, DWRITE_FLOW_DIRECTION_BOTTOM_TO_TOP,
+        DWRITE_FLOW_DIRECTION_LEFT_TO_RIGHT, DWRITE_FLOW_DIRECTION_RIGHT_TO_LEFT,
+        DWRITE_FLOW_DIRECTION_TOP_TO_BOTTOM, DWRITE_FONT_METRICS, DWRITE_FONT_STRETCH,
+        DWRITE_FONT_STRETCH_CONDENSED, DWRITE_FONT_STRETCH_EXPANDED,
+        DWRITE_FONT_STRETCH_EXTRA_CONDENSED, DWRITE_FONT_STRETCH_EXTRA_EXPANDED,
+        DWRITE_FONT_STRETCH_NORMAL, DWRITE_FONT_STRETCH_SEMI_CONDENSED,
+        DWRITE_FONT_STRETCH_SEMI_EXPANDED, DWRITE_FONT_STRETCH_ULTRA_CONDENSED,
+        DWRITE_FONT_STRETCH_ULTRA_EXPANDED, DWRITE_FONT_STYLE, DWRITE_FONT_STYLE_ITALIC,
+        DWRITE_FONT_STYLE_NORMAL, DWRITE_FONT_STYLE_OBLIQUE, DWRITE_FONT_WEIGHT,
+        DWRITE_FONT_WEIGHT_BLACK, DWRITE_FONT_WEIGHT_BOLD, DWRITE_FONT_WEIGHT_LIGHT,
+        DWRITE_FONT_WEIGHT_MEDIUM, DWRITE_FONT_WEIGHT_NORMAL, DWRITE_FONT_WEIGHT_SEMI_BOLD,
+        DWRITE_FONT_WEIGHT_THIN, DWRITE_HIT_TEST_METRICS, DWRITE_LINE_METRICS,
+        DWRITE_LINE_SPACING_METHOD, DWRITE_LINE_SPACING_METHOD_DEFAULT,
+        DWRITE_LINE_SPACING_METHOD_PROPORTIONAL, DWRITE_LINE_SPACING_METHOD_UNIFORM,
+        DWRITE_OVERHANG_METRICS, DWRITE_PARAGRAPH_ALIGNMENT, DWRITE_PARAGRAPH_ALIGNMENT_CENTER,
+        DWRITE_PARAGRAPH_ALIGNMENT_FAR, DWRITE_PARAGRAPH_ALIGNMENT_NEAR, DWRITE_READING_DIRECTION,
+        DWRITE_READING_DIRECTION_BOTTOM_TO_TOP, DWRITE_READING_DIRECTION_LEFT_TO_RIGHT,
+        DWRITE_READING_DIRECTION_RIGHT_TO_LEFT, DWRITE_READING_DIRECTION_TOP_TO_BOTTOM,
+        DWRITE_TEXT_ALIGNMENT, DWRITE_TEXT_ALIGNMENT_CENTER, DWRITE_TEXT_ALIGNMENT_JUSTIFIED,
+        DWRITE_TEXT_ALIGNMENT_LEADING, DWRITE_TEXT_ALIGNMENT_TRAILING, DWRITE_TEXT_METRICS,
+        DWRITE_TEXT_RANGE, DWRITE_WORD_WRAPPING, DWRITE_WORD_WRAPPING_CHARACTER,
+        DWRITE_WORD_WRAPPING_NO_WRAP, DWRITE_WORD_WRAPPING_WHOLE_WORD, DWRITE_WORD_WRAPPING_WRAP,
+    },
+};
+use windows_numerics::{Matrix3x2, Vector2};
+
+use crate::core::backend::Renderer;
+use crate::core::render::color::Color;
 use crate::core::render::drawable::Drawable;
 use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::objects::text_outline::{layout_geometry, GlyphGeometry};
+use crate::core::render::rect::Rect;
+
+/// The horizontal alignment of text within its layout box.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextAlignment {
+    /// Aligned to the leading edge (the left, for left-to-right text).
+    #[default]
+    Leading,
+    /// Centered horizontally.
+    Center,
+    /// Aligned to the trailing edge (the right, for left-to-right text).
+    Trailing,
+    /// Spread to fill the width of the box, except for the last line.
+    Justified,
+}
+
+impl From<TextAlignment> for DWRITE_TEXT_ALIGNMENT {
+    fn from(alignment: TextAlignment) -> Self {
+        match alignment {
+            TextAlignment::Leading => DWRITE_TEXT_ALIGNMENT_LEADING,
+            TextAlignment::Center => DWRITE_TEXT_ALIGNMENT_CENTER,
+            TextAlignment::Trailing => DWRITE_TEXT_ALIGNMENT_TRAILING,
+            TextAlignment::Justified => DWRITE_TEXT_ALIGNMENT_JUSTIFIED,
+        }
+    }
+}
+
+/// The vertical alignment of text within its layout box.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ParagraphAlignment {
+    /// Aligned to the top of the box.
+    #[default]
+    Near,
+    /// Centered vertically.
+    Center,
+    /// Aligned to the bottom of the box.
+    Far,
+}
+
+impl From<ParagraphAlignment> for DWRITE_PARAGRAPH_ALIGNMENT {
+    fn from(alignment: ParagraphAlignment) -> Self {
+        match alignment {
+            ParagraphAlignment::Near => DWRITE_PARAGRAPH_ALIGNMENT_NEAR,
+            ParagraphAlignment::Center => DWRITE_PARAGRAPH_ALIGNMENT_CENTER,
+            ParagraphAlignment::Far => DWRITE_PARAGRAPH_ALIGNMENT_FAR,
+        }
+    }
+}
+
+/// How text is broken into lines when it doesn't fit the width of its layout box.
+///
+/// Line breaks follow the Unicode line breaking rules for the text's locale, so
+/// Japanese text never starts a line with closing punctuation or small kana (kinsoku
+/// shori) in any of the wrapping modes except `Character`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Wrapping {
+    /// Wraps between words, breaking a word only if it is wider than the box.
+    #[default]
+    Wrap,
+    /// Never wraps, so long lines overflow the box.
+    NoWrap,
+    /// Wraps at any character.
+    Character,
+    /// Wraps only between words, letting words wider than the box overflow.
+    WholeWord,
+}
+
+impl From<Wrapping> for DWRITE_WORD_WRAPPING {
+    fn from(wrapping: Wrapping) -> Self {
+        match wrapping {
+            Wrapping::Wrap => DWRITE_WORD_WRAPPING_WRAP,
+            Wrapping::NoWrap => DWRITE_WORD_WRAPPING_NO_WRAP,
+            Wrapping::Character => DWRITE_WORD_WRAPPING_CHARACTER,
+            Wrapping::WholeWord => DWRITE_WORD_WRAPPING_WHOLE_WORD,
+        }
+    }
+}
+
+/// The direction characters advance within a line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ReadingDirection {
+    /// Left to right, as in English.
+    #[default]
+    LeftToRight,
+    /// Right to left, as in Arabic and Hebrew.
+    RightToLeft,
+    /// Top to bottom, as in vertical Japanese and Chinese.
+    TopToBottom,
+    /// Bottom to top.
+    BottomToTop,
+}
+
+impl ReadingDirection {
+    /// Returns `true` if characters advance vertically.
+    pub fn is_vertical(self) -> bool {
+        matches!(self, Self::TopToBottom | Self::BottomToTop)
+    }
+}
+
+impl From<ReadingDirection> for DWRITE_READING_DIRECTION {
+    fn from(direction: ReadingDirection) -> Self {
+        match direction {
+            ReadingDirection::LeftToRight => DWRITE_READING_DIRECTION_LEFT_TO_RIGHT,
+            ReadingDirection::RightToLeft => DWRITE_READING_DIRECTION_RIGHT_TO_LEFT,
+            ReadingDirection::TopToBottom => DWRITE_READING_DIRECTION_TOP_TO_BOTTOM,
+            ReadingDirection::BottomToTop => DWRITE_READING_DIRECTION_BOTTOM_TO_TOP,
+        }
+    }
+}
+
+/// The direction successive lines are placed in.
+///
+/// The flow direction must be perpendicular to the reading direction: horizontal text
+/// flows vertically, and vertical text flows horizontally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FlowDirection {
+    /// Lines are stacked downwards.
+    #[default]
+    TopToBottom,
+    /// Lines are stacked upwards.
+    BottomToTop,
+    /// Columns are placed left to right.
+    LeftToRight,
+    /// Columns are placed right to left, as in vertical Japanese.
+    RightToLeft,
+}
+
+impl FlowDirection {
+    /// Returns `true` if lines are stacked vertically.
+    pub fn is_vertical(self) -> bool {
+        matches!(self, Self::TopToBottom | Self::BottomToTop)
+    }
+}
+
+impl From<FlowDirection> for DWRITE_FLOW_DIRECTION {
+    fn from(direction: FlowDirection) -> Self {
+        match direction {
+            FlowDirection::TopToBottom => DWRITE_FLOW_DIRECTION_TOP_TO_BOTTOM,
+            FlowDirection::BottomToTop => DWRITE_FLOW_DIRECTION_BOTTOM_TO_TOP,
+            FlowDirection::LeftToRight => DWRITE_FLOW_DIRECTION_LEFT_TO_RIGHT,
+            FlowDirection::RightToLeft => DWRITE_FLOW_DIRECTION_RIGHT_TO_LEFT,
+        }
+    }
+}
+
+/// How the distance between lines of text is determined.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LineSpacing {
+    /// Lines are spaced according to the metrics of the fonts on each line.
+    #[default]
+    Default,
+    /// Every line has the same height, regardless of its content.
+    Uniform {
+        /// The distance from the top of one line to the top of the next.
+        height: f32,
+        /// The distance from the top of a line to its baseline, typically about 80% of
+        /// the height.
+        baseline: f32,
+    },
+    /// Lines are spaced at a multiple of their default height, so `1.5` adds half a
+    /// line between lines. Requires Windows 10 or later.
+    Proportional(f32),
+}
+
+impl From<LineSpacing> for (DWRITE_LINE_SPACING_METHOD, f32, f32) {
+    fn from(spacing: LineSpacing) -> Self {
+        match spacing {
+            LineSpacing::Default => (DWRITE_LINE_SPACING_METHOD_DEFAULT, 0.0, 0.0),
+            LineSpacing::Uniform { height, baseline } => {
+                (DWRITE_LINE_SPACING_METHOD_UNIFORM, height, baseline)
+            }
+            LineSpacing::Proportional(factor) => {
+                (DWRITE_LINE_SPACING_METHOD_PROPORTIONAL, factor, factor)
+            }
+        }
+    }
+}
+
+/// The thickness of a font.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FontWeight {
+    /// Thin (100).
+    Thin,
+    /// Light (300).
+    Light,
+    /// Normal (400).
+    #[default]
+    Normal,
+    /// Medium (500).
+    Medium,
+    /// Semi-bold (600).
+    SemiBold,
+    /// Bold (700).
+    Bold,
+    /// Black (900).
+    Black,
+}
+
+impl From<FontWeight> for DWRITE_FONT_WEIGHT {
+    fn from(weight: FontWeight) -> Self {
+        match weight {
+            FontWeight::Thin => DWRITE_FONT_WEIGHT_THIN,
+            FontWeight::Light => DWRITE_FONT_WEIGHT_LIGHT,
+            FontWeight::Normal => DWRITE_FONT_WEIGHT_NORMAL,
+            FontWeight::Medium => DWRITE_FONT_WEIGHT_MEDIUM,
+            FontWeight::SemiBold => DWRITE_FONT_WEIGHT_SEMI_BOLD,
+            FontWeight::Bold => DWRITE_FONT_WEIGHT_BOLD,
+            FontWeight::Black => DWRITE_FONT_WEIGHT_BLACK,
+        }
+    }
+}
+
+/// The slant of a font.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FontStyle {
+    /// Upright.
+    #[default]
+    Normal,
+    /// The font's italic face.
+    Italic,
+    /// An artificially slanted version of the upright face.
+    Oblique,
+}
+
+impl From<FontStyle> for DWRITE_FONT_STYLE {
+    fn from(style: FontStyle) -> Self {
+        match style {
+            FontStyle::Normal => DWRITE_FONT_STYLE_NORMAL,
+            FontStyle::Italic => DWRITE_FONT_STYLE_ITALIC,
+            FontStyle::Oblique => DWRITE_FONT_STYLE_OBLIQUE,
+        }
+    }
+}
+
+/// The width of a font, for families that include condensed or expanded faces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FontStretch {
+    /// Ultra-condensed (50%).
+    UltraCondensed,
+    /// Extra-condensed (62.5%).
+    ExtraCondensed,
+    /// Condensed (75%).
+    Condensed,
+    /// Semi-condensed (87.5%).
+    SemiCondensed,
+    /// Normal (100%).
+    #[default]
+    Normal,
+    /// Semi-expanded (112.5%).
+    SemiExpanded,
+    /// Expanded (125%).
+    Expanded,
+    /// Extra-expanded (150%).
+    ExtraExpanded,
+    /// Ultra-expanded (200%).
+    UltraExpanded,
+}
+
+impl From<FontStretch> for DWRITE_FONT_STRETCH {
+    fn from(stretch: FontStretch) -> Self {
+        match stretch {
+            FontStretch::UltraCondensed => DWRITE_FONT_STRETCH_ULTRA_CONDENSED,
+            FontStretch::ExtraCondensed => DWRITE_FONT_STRETCH_EXTRA_CONDENSED,
+            FontStretch::Condensed => DWRITE_FONT_STRETCH_CONDENSED,
+            FontStretch::SemiCondensed => DWRITE_FONT_STRETCH_SEMI_CONDENSED,
+            FontStretch::Normal => DWRITE_FONT_STRETCH_NORMAL,
+            FontStretch::SemiExpanded => DWRITE_FONT_STRETCH_SEMI_EXPANDED,
+            FontStretch::Expanded => DWRITE_FONT_STRETCH_EXPANDED,
+            FontStretch::ExtraExpanded => DWRITE_FONT_STRETCH_EXTRA_EXPANDED,
+            FontStretch::UltraExpanded => DWRITE_FONT_STRETCH_ULTRA_EXPANDED,
+        }
+    }
+}
+
+/// Formatting applied to a range of a `TextObject`'s text.
+///
+/// The range is in bytes of the Rust string, like a string slice, and must start and
+/// end on character boundaries. It is converted to the UTF-16 code units DirectWrite
+/// uses when the text is laid out. Unset properties keep the object's defaults.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TextSpan {
+    /// The byte range of the text the span applies to.
+    pub range: Range<usize>,
+    /// The color of the text, or `None` to use the color of the rest of the text.
+    pub color: Option<Color>,
+    /// The font weight, or `None` to use the text format's weight.
+    pub weight: Option<FontWeight>,
+    /// The font style, or `None` to use the text format's style.
+    pub style: Option<FontStyle>,
+    /// The extra space added after each character, or `None` to use the object's
+    /// letter spacing.
+    pub letter_spacing: Option<f32>,
+}
+
+impl TextSpan {
+    /// Creates a new `TextSpan` over `range` that changes nothing until properties are
+    /// set.
+    pub fn new(range: Range<usize>) -> Self {
+        Self {
+            range,
+            color: None,
+            weight: None,
+            style: None,
+            letter_spacing: None,
+        }
+    }
+
+    /// Sets the color of the text in the span.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Sets the font weight of the text in the span.
+    pub fn with_weight(mut self, weight: FontWeight) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    /// Sets the font style of the text in the span.
+    pub fn with_style(mut self, style: FontStyle) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    /// Sets the extra space added after each character in the span.
+    pub fn with_letter_spacing(mut self, letter_spacing: f32) -> Self {
+        self.letter_spacing = Some(letter_spacing);
+        self
+    }
+}
+
+/// The measured size of a laid-out `TextObject`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TextMetrics {
+    /// The width of the widest line, excluding trailing whitespace.
+    pub width: f32,
+    /// The total height of all lines.
+    pub height: f32,
+    /// The number of lines after wrapping.
+    pub line_count: u32,
+    /// The height of each line, in order.
+    pub line_heights: Vec<f32>,
+    /// The distance from the top of the layout box to the baseline of the first line,
+    /// or `0.0` if there are no lines.
+    pub baseline: f32,
+}
+
+/// The position of a single line of a laid-out `TextObject`.
+///
+/// Positions are relative to the top of the layout box and include the offset from
+/// the paragraph alignment, so adding the object's `y` gives window coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct LineMetrics {
+    /// The distance from the top of the layout box to the top of the line.
+    pub top: f32,
+    /// The height of the line.
+    pub height: f32,
+    /// The distance from the top of the layout box to the baseline of the line.
+    pub baseline: f32,
+}
+
+/// The vertical metrics of a font at a particular size, in DIPs.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FontMetrics {
+    /// The distance from the baseline to the top of the tallest glyphs.
+    pub ascent: f32,
+    /// The distance from the baseline to the bottom of the lowest glyphs.
+    pub descent: f32,
+    /// The recommended extra space between lines.
+    pub line_gap: f32,
+    /// The height of capital letters above the baseline.
+    pub cap_height: f32,
+    /// The height of lowercase letters such as `x` above the baseline.
+    pub x_height: f32,
+}
+
+impl FontMetrics {
+    /// Reads the metrics of the font the text format resolves to in its font collection.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the text format's font family is not in
+    /// its font collection, or if it fails to read the font.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for querying DirectWrite. The caller must
+    /// ensure that the text format is valid.
+    pub fn from_text_format(text_format: &IDWriteTextFormat) -> Result<Self> {
+        unsafe {
+            let collection = text_format.GetFontCollection()?;
+            let mut family_name = vec![0u16; text_format.GetFontFamilyNameLength() as usize + 1];
+            text_format.GetFontFamilyName(&mut family_name)?;
+
+            let mut index = 0;
+            let mut exists = BOOL::default();
+            collection.FindFamilyName(PCWSTR(family_name.as_ptr()), &mut index, &mut exists)?;
+            if !exists.as_bool() {
+                let name = String::from_utf16_lossy(&family_name[..family_name.len() - 1]);
+                return Err(Error::new(
+                    E_INVALIDARG,
+                    format!("the font family {name} is not installed"),
+                ));
+            }
+
+            let font = collection.GetFontFamily(index)?.GetFirstMatchingFont(
+                text_format.GetFontWeight(),
+                text_format.GetFontStretch(),
+                text_format.GetFontStyle(),
+            )?;
+            let mut metrics = DWRITE_FONT_METRICS::default();
+            font.GetMetrics(&mut metrics);
+
+            let scale = text_format.GetFontSize() / f32::from(metrics.designUnitsPerEm.max(1));
+            Ok(Self {
+                ascent: f32::from(metrics.ascent) * scale,
+                descent: f32::from(metrics.descent) * scale,
+                line_gap: f32::from(metrics.lineGap) * scale,
+                cap_height: f32::from(metrics.capHeight) * scale,
+                x_height: f32::from(metrics.xHeight) * scale,
+            })
+        }
+    }
+}
+
+/// The result of hit-testing a point against a laid-out `TextObject`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TextHit {
+    /// The byte index of the character nearest the point.
+    pub index: usize,
+    /// Whether the point is on the trailing half of the character, so a caret placed
+    /// at the point belongs after it rather than before it.
+    pub is_trailing: bool,
+    /// Whether the point lies within the text itself rather than beside it.
+    pub is_inside: bool,
+}
+
+impl TextHit {
+    /// Returns the byte index a caret placed at the hit point belongs at.
+    pub fn caret_index(&self, text: &str) -> usize {
+        if self.is_trailing {
+            text[self.index..]
+                .chars()
+                .next()
+                .map_or(self.index, |character| self.index + character.len_utf8())
+        } else {
+            self.index
+        }
+    }
+}
+
+/// The location of a caret placed before a character of a laid-out `TextObject`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CaretPosition {
+    /// The x-coordinate of the caret, relative to the object's origin.
+    pub x: f32,
+    /// The y-coordinate of the top of the caret, relative to the object's origin.
+    pub y: f32,
+    /// The height of the caret, which is the height of its line.
+    pub height: f32,
+}
+
+/// The inputs a cached text layout was created from.
+#[derive(Clone)]
+struct LayoutKey {
+    text: String,
+    text_format: IDWriteTextFormat,
+    width: f32,
+    height: f32,
+    text_alignment: TextAlignment,
+    paragraph_alignment: ParagraphAlignment,
+    wrapping: Wrapping,
+    reading_direction: ReadingDirection,
+    flow_direction: FlowDirection,
+    tab_stop_width: Option<f32>,
+    line_spacing: LineSpacing,
+    locale: Option<String>,
+    letter_spacing: Option<f32>,
+    indent: f32,
+    spans: Vec<TextSpan>,
+}
+
+/// A cached text layout, along with the brushes used for colored spans.
+#[derive(Clone)]
+struct CachedLayout {
+    key: LayoutKey,
+    layout: IDWriteTextLayout,
+    /// The render target the span brushes belong to and the brushes in span order,
+    /// or `None` if the span colors haven't been applied yet.
+    brushes: Option<(ID2D1RenderTarget, Vec<ID2D1SolidColorBrush>)>,
+    /// The glyph outlines of the layout, or `None` if they haven't been needed yet.
+    /// They refer to the span brushes, so they are discarded along with them.
+    outline: Option<Vec<GlyphGeometry>>,
+}
 
 /// A `Drawable` object that represents a piece of text.
 ///
 /// This struct holds the text string and its position, and it implements the `Drawable`
 /// trait to render itself using Direct2D and DirectWrite.
+///
+/// The text is laid out in a box whose top-left corner is at `(x, y)`. The box is
+/// `max_width` by `max_height` when they are set; otherwise it takes the size of the
+/// render target. The alignments position the text within that box, and lines are
+/// wrapped at its width according to `wrapping`. Text that overflows the box is not
+/// clipped. Setting an explicit width keeps the wrapping stable when the window is
+/// resized.
+///
+/// Vertical text, such as 縦書き Japanese, is laid out by setting the reading direction
+/// to `TopToBottom` and the flow direction to `RightToLeft` (see
+/// `with_vertical_layout`). The layout box keeps its screen orientation, so each
+/// column runs down the height of the box and wraps at its bottom edge, while columns
+/// advance across its width. The text alignment then positions text along the columns
+/// and the paragraph alignment positions the columns across the box. Kana and kanji
+/// stay upright, while embedded Latin text is rotated to run down the column.
+///
+/// Ranges of the text can be given their own color, weight, and style with
+/// `TextSpan`s, which keeps a single layout so wrapping still works across them. A
+/// selected range is highlighted by filling the rectangles it covers behind the glyphs.
+///
+/// The DirectWrite text layout is cached and reused across frames. It is rebuilt only
+/// when the text, the text format, the layout box, the layout options such as alignment
+/// and wrapping, or the spans change. Each object caches a single layout, so memory stays
+/// bounded no matter how often the text changes.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TextObject {
     /// The text to be rendered.
     pub text: String,
@@ -15,6 +584,49 @@ pub struct TextObject {
     pub x: f32,
     /// The y-coordinate of the top-left corner of the text layout box.
     pub y: f32,
+    /// The width of the layout box, or `None` to use the render target width.
+    pub max_width: Option<f32>,
+    /// The height of the layout box, or `None` to use the render target height.
+    pub max_height: Option<f32>,
+    /// The horizontal alignment of the text within the layout box.
+    pub text_alignment: TextAlignment,
+    /// The vertical alignment of the text within the layout box.
+    pub paragraph_alignment: ParagraphAlignment,
+    /// How lines are wrapped at the width of the layout box.
+    pub wrapping: Wrapping,
+    /// The direction characters advance within a line.
+    pub reading_direction: ReadingDirection,
+    /// The direction successive lines are placed in.
+    pub flow_direction: FlowDirection,
+    /// The distance between tab stops, or `None` to use the text format's default of
+    /// four times the font size.
+    pub tab_stop_width: Option<f32>,
+    /// The spacing between lines.
+    pub line_spacing: LineSpacing,
+    /// The locale used to select fonts and shape the text, such as `ja-JP`, or `None`
+    /// to use the text format's locale.
+    pub locale: Option<String>,
+    /// Extra space added after each character, or `None` for the font's own spacing.
+    pub letter_spacing: Option<f32>,
+    /// The indentation of the first line of each paragraph.
+    pub indent: f32,
+    /// The color of the text, or `None` to use the default brush color.
+    pub color: Option<Color>,
+    /// The color and width of an outline stroked around the glyphs, or `None` for
+    /// plain text.
+    pub outline: Option<(Color, f32)>,
+    /// Formatting applied to ranges of the text.
+    pub spans: Vec<TextSpan>,
+    /// Byte ranges of the text to fill behind the glyphs, such as search matches, and
+    /// their colors. Later ranges are painted over earlier ones where they overlap.
+    pub highlights: Vec<(Range<usize>, Color)>,
+    /// A byte range of the text to highlight as selected, and the highlight color.
+    pub selection: Option<(Range<usize>, Color)>,
+    /// The opacity of the text, from `0.0` (transparent) to `1.0` (opaque).
+    pub opacity: f32,
+    /// The most recently created text layout and the inputs it was created from.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    layout: RefCell<Option<CachedLayout>>,
 }
 
 impl TextObject {
@@ -24,7 +636,954 @@ impl TextObject {
             text: text.to_string(),
             x,
             y,
+            max_width: None,
+            max_height: None,
+            text_alignment: TextAlignment::Leading,
+            paragraph_alignment: ParagraphAlignment::Near,
+            wrapping: Wrapping::Wrap,
+            reading_direction: ReadingDirection::LeftToRight,
+            flow_direction: FlowDirection::TopToBottom,
+            tab_stop_width: None,
+            line_spacing: LineSpacing::Default,
+            locale: None,
+            letter_spacing: None,
+            indent: 0.0,
+            color: None,
+            outline: None,
+            spans: Vec::new(),
+            highlights: Vec::new(),
+            selection: None,
+            opacity: 1.0,
+            layout: RefCell::new(None),
+        }
+    }
+
+    /// Sets the size of the layout box the text is aligned and wrapped within.
+    pub fn with_layout_size(mut self, width: f32, height: f32) -> Self {
+        self.max_width = Some(width);
+        self.max_height = Some(height);
+        self
+    }
+
+    /// Sets the width of the layout box, keeping the render target height.
+    pub fn with_max_width(mut self, max_width: f32) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    /// Sets the height of the layout box, keeping the render target width.
+    pub fn with_max_height(mut self, max_height: f32) -> Self {
+        self.max_height = Some(max_height);
+        self
+    }
+
+    /// Sets how lines are wrapped at the width of the layout box.
+    pub fn with_wrapping(mut self, wrapping: Wrapping) -> Self {
+        self.wrapping = wrapping;
+        self
+    }
+
+    /// Sets the horizontal alignment of the text within the layout box.
+    pub fn with_text_alignment(mut self, text_alignment: TextAlignment) -> Self {
+        self.text_alignment = text_alignment;
+        self
+    }
+
+    /// Sets the vertical alignment of the text within the layout box.
+    pub fn with_paragraph_alignment(mut self, paragraph_alignment: ParagraphAlignment) -> Self {
+        self.paragraph_alignment = paragraph_alignment;
+        self
+    }
+
+    /// Sets the reading and flow directions.
+    ///
+    /// One of the directions must be horizontal and the other vertical; otherwise the
+    /// text fails to draw.
+    pub fn with_direction(mut self, reading: ReadingDirection, flow: FlowDirection) -> Self {
+        self.reading_direction = reading;
+        self.flow_direction = flow;
+        self
+    }
+
+    /// Lays the text out vertically, in columns read top to bottom and placed right to
+    /// left, as is traditional for Japanese.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use my_gui::core::render::objects::text_object::TextObject;
+    ///
+    /// // Columns wrap at 300 DIPs tall and fill the 200 DIP width from the right.
+    /// let text = TextObject::new("こんにちは、世界。DirectWriteで縦書き", 10.0, 10.0)
+    ///     .with_layout_size(200.0, 300.0)
+    ///     .with_vertical_layout();
+    /// ```
+    pub fn with_vertical_layout(self) -> Self {
+        self.with_direction(ReadingDirection::TopToBottom, FlowDirection::RightToLeft)
+    }
+
+    /// Sets the distance between tab stops.
+    ///
+    /// Tab stops are measured from the leading edge of the layout box, so a tab at the
+    /// start of a wrapped line advances to the first stop just as it would on a new
+    /// line. Text wider than a column pushes the following tab to the next stop.
+    pub fn with_tab_stop_width(mut self, tab_stop_width: f32) -> Self {
+        self.tab_stop_width = Some(tab_stop_width);
+        self
+    }
+
+    /// Sets the spacing between lines.
+    ///
+    /// The spacing is part of the layout, so wrapping, alignment, and the measured
+    /// height all reflect it.
+    pub fn with_line_spacing(mut self, line_spacing: LineSpacing) -> Self {
+        self.line_spacing = line_spacing;
+        self
+    }
+
+    /// Sets the locale used to select fonts and shape the text, such as `ja-JP`.
+    ///
+    /// The locale affects which glyph variants are chosen for characters shared by
+    /// several languages, such as kanji in Japanese and Chinese text.
+    pub fn with_locale(mut self, locale: &str) -> Self {
+        self.locale = Some(locale.to_string());
+        self
+    }
+
+    /// Sets the extra space added after each character, as is common for headings.
+    ///
+    /// No space is added after the last character, so aligned text stays centered
+    /// and its measured width covers only the glyphs. Letter spacing requires
+    /// `IDWriteTextLayout1` (Windows 8 and later) and is ignored where it is
+    /// unavailable.
+    pub fn with_letter_spacing(mut self, letter_spacing: f32) -> Self {
+        self.letter_spacing = Some(letter_spacing);
+        self
+    }
+
+    /// Sets the indentation of the first line of each paragraph.
+    ///
+    /// The indent is part of the layout, so wrapping and measurement account for it.
+    /// Like letter spacing, it requires `IDWriteTextLayout1` (Windows 8 and later) and
+    /// is ignored where it is unavailable.
+    pub fn with_indent(mut self, indent: f32) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// Sets the color of the text.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Strokes an outline of the given color and width around the glyphs, as is common
+    /// for text drawn over busy game scenes.
+    ///
+    /// The outline is centered on the edges of the glyphs and the glyphs are filled on
+    /// top of it, so only the outer half of its width shows. Outlined text is drawn as
+    /// geometry, so color fonts such as emoji are filled with a single color.
+    pub fn with_outline(mut self, color: Color, width: f32) -> Self {
+        self.outline = Some((color, width));
+        self
+    }
+
+    /// Sets the formatting applied to ranges of the text.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use my_gui::core::render::{
+    ///     color::Color,
+    ///     objects::text_object::{FontWeight, TextObject, TextSpan},
+    /// };
+    ///
+    /// let text = TextObject::new("let x = 1;", 10.0, 10.0).with_spans(vec![
+    ///     TextSpan::new(0..3).with_color(Color::BLUE).with_weight(FontWeight::Bold),
+    ///     TextSpan::new(8..9).with_color(Color::GREEN),
+    /// ]);
+    /// ```
+    pub fn with_spans(mut self, spans: Vec<TextSpan>) -> Self {
+        self.spans = spans;
+        self
+    }
+
+    /// Sets the byte ranges of the text to fill behind the glyphs and their colors.
+    ///
+    /// Unlike span ranges, highlight ranges are clamped to the text, so stale search
+    /// results never fail to draw after the text gets shorter.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use my_gui::core::render::{color::Color, objects::text_object::TextObject};
+    ///
+    /// let text = TextObject::new("find the needle in the needle stack", 10.0, 10.0)
+    ///     .with_highlights(vec![(9..15, Color::YELLOW), (23..29, Color::YELLOW)]);
+    /// ```
+    pub fn with_highlights(mut self, highlights: Vec<(Range<usize>, Color)>) -> Self {
+        self.highlights = highlights;
+        self
+    }
+
+    /// Highlights a byte range of the text as selected with the given color.
+    pub fn with_selection(mut self, range: Range<usize>, color: Color) -> Self {
+        self.selection = Some((range, color));
+        self
+    }
+
+    /// Sets the opacity of the text.
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    /// Replaces the text, discarding the cached layout if the text has changed.
+    ///
+    /// Setting the same text again keeps the cached layout, so a label can be updated
+    /// every frame without re-creating its layout unless its contents change.
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        if text != self.text {
+            self.text = text;
+            *self.layout.get_mut() = None;
+        }
+    }
+
+    /// Sets the color of the text.
+    ///
+    /// The color is applied when drawing, so the cached layout is kept.
+    pub fn set_color(&mut self, color: Color) {
+        self.color = Some(color);
+    }
+
+    /// Moves the text so the top-left corner of its layout box is at `(x, y)`.
+    ///
+    /// The layout is independent of the position, so the cached layout is kept.
+    pub fn set_position(&mut self, x: f32, y: f32) {
+        self.x = x;
+        self.y = y;
+    }
+
+    /// Returns the DirectWrite text layout for the text, its layout box, its
+    /// alignment, and its wrapping, reusing the cached layout if none of them has
+    /// changed.
+    ///
+    /// `fallback_size` is used for any dimension of the layout box that isn't set.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a span's range is not a valid range of
+    /// the text, if the reading and flow directions are both horizontal or both
+    /// vertical, if the tab stop width or line spacing is invalid, or if it fails to
+    /// create or configure the text layout.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for creating and configuring the text
+    /// layout. The caller must ensure that the factory and text format are valid.
+    fn layout(
+        &self,
+        dwrite_factory: &IDWriteFactory,
+        text_format: &IDWriteTextFormat,
+        fallback_size: D2D_SIZE_F,
+    ) -> Result<IDWriteTextLayout> {
+        let width = self.max_width.unwrap_or(fallback_size.width);
+        let height = self.max_height.unwrap_or(fallback_size.height);
+
+        let mut cached = self.layout.borrow_mut();
+        if let Some(CachedLayout {
+            key,
+            layout: text_layout,
+            ..
+        }) = cached.as_ref()
+        {
+            if key.text == self.text
+                && &key.text_format == text_format
+                && key.width == width
+                && key.height == height
+                && key.text_alignment == self.text_alignment
+                && key.paragraph_alignment == self.paragraph_alignment
+                && key.wrapping == self.wrapping
+                && key.reading_direction == self.reading_direction
+                && key.flow_direction == self.flow_direction
+                && key.tab_stop_width == self.tab_stop_width
+                && key.line_spacing == self.line_spacing
+                && key.locale == self.locale
+                && key.letter_spacing == self.letter_spacing
+                && key.indent == self.indent
+                && key.spans == self.spans
+            {
+                return Ok(text_layout.clone());
+            }
+        }
+
+        if self.reading_direction.is_vertical() == self.flow_direction.is_vertical() {
+            return Err(Error::new(
+                E_INVALIDARG,
+                format!(
+                    "reading direction {:?} conflicts with flow direction {:?}",
+                    self.reading_direction, self.flow_direction
+                ),
+            ));
+        }
+
+        let text_utf16: Vec<u16> = self.text.encode_utf16().collect();
+        let ranges = self.span_ranges()?;
+
+        let text_layout = unsafe {
+            let text_layout =
+                dwrite_factory.CreateTextLayout(&text_utf16, text_format, width, height)?;
+            text_layout.SetTextAlignment(self.text_alignment.into())?;
+            text_layout.SetParagraphAlignment(self.paragraph_alignment.into())?;
+            text_layout.SetWordWrapping(self.wrapping.into())?;
+            text_layout.SetReadingDirection(self.reading_direction.into())?;
+            text_layout.SetFlowDirection(self.flow_direction.into())?;
+            if let Some(tab_stop_width) = self.tab_stop_width {
+                text_layout.SetIncrementalTabStop(tab_stop_width)?;
+            }
+            let (method, spacing, baseline) = self.line_spacing.into();
+            text_layout.SetLineSpacing(method, spacing, baseline)?;
+            if let Some(locale) = &self.locale {
+                // An unrecognized locale leaves the text format's locale in place rather
+                // than failing the whole layout.
+                let _ = text_layout.SetLocaleName(
+                    &HSTRING::from(locale.as_str()),
+                    DWRITE_TEXT_RANGE {
+                        startPosition: 0,
+                        length: text_utf16.len() as u32,
+                    },
+                );
+            }
+            for (span, &range) in self.spans.iter().zip(&ranges) {
+                if let Some(weight) = span.weight {
+                    text_layout.SetFontWeight(weight.into(), range)?;
+                }
+                if let Some(style) = span.style {
+                    text_layout.SetFontStyle(style.into(), range)?;
+                }
+            }
+            self.apply_character_spacing(&text_layout, &ranges)?;
+            text_layout
+        };
+
+        let key = LayoutKey {
+            text: self.text.clone(),
+            text_format: text_format.clone(),
+            width,
+            height,
+            text_alignment: self.text_alignment,
+            paragraph_alignment: self.paragraph_alignment,
+            wrapping: self.wrapping,
+            reading_direction: self.reading_direction,
+            flow_direction: self.flow_direction,
+            tab_stop_width: self.tab_stop_width,
+            line_spacing: self.line_spacing,
+            locale: self.locale.clone(),
+            letter_spacing: self.letter_spacing,
+            indent: self.indent,
+            spans: self.spans.clone(),
+        };
+        *cached = Some(CachedLayout {
+            key,
+            layout: text_layout.clone(),
+            brushes: None,
+            outline: None,
+        });
+        Ok(text_layout)
+    }
+
+    /// Applies the object's letter spacing to the whole text, each span's letter
+    /// spacing to its range, and the indent to the first character of each paragraph,
+    /// if the layout supports character spacing.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to set the character spacing.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for configuring the text layout. The
+    /// caller must ensure that the text layout is valid.
+    fn apply_character_spacing(
+        &self,
+        text_layout: &IDWriteTextLayout,
+        span_ranges: &[DWRITE_TEXT_RANGE],
+    ) -> Result<()> {
+        let has_span_spacing = self.spans.iter().any(|span| span.letter_spacing.is_some());
+        if self.letter_spacing.is_none() && !has_span_spacing && self.indent == 0.0 {
+            return Ok(());
+        }
+        let Ok(text_layout) = text_layout.cast::<IDWriteTextLayout1>() else {
+            return Ok(());
+        };
+
+        // Leave the last character unspaced so the spacing doesn't widen the text past
+        // its final glyph.
+        let last_length = self.text.chars().last().map_or(0, char::len_utf16);
+        let unspaced_start = self.text.encode_utf16().count() - last_length;
+
+        unsafe {
+            if let Some(letter_spacing) = self.letter_spacing {
+                text_layout.SetCharacterSpacing(
+                    0.0,
+                    letter_spacing,
+                    0.0,
+                    DWRITE_TEXT_RANGE {
+                        startPosition: 0,
+                        length: unspaced_start as u32,
+                    },
+                )?;
+            }
+            for (span, &range) in self.spans.iter().zip(span_ranges) {
+                if let Some(letter_spacing) = span.letter_spacing {
+                    text_layout.SetCharacterSpacing(0.0, letter_spacing, 0.0, range)?;
+                }
+            }
+
+            if self.indent != 0.0 {
+                // DirectWrite has no paragraph indent, so the indent is added as leading
+                // space on the first character of each paragraph. Setting the spacing
+                // replaces it, so the character's own letter spacing is set again.
+                for index in self.paragraph_starts() {
+                    let character = self.text[index..].chars().next().unwrap_or_default();
+                    let position = self.utf16_index(index).unwrap_or_default();
+                    let trailing = self
+                        .spans
+                        .iter()
+                        .rev()
+                        .filter(|span| span.range.contains(&index))
+                        .find_map(|span| span.letter_spacing)
+                        .or(self.letter_spacing)
+                        .filter(|_| (position as usize) < unspaced_start)
+                        .unwrap_or(0.0);
+                    text_layout.SetCharacterSpacing(
+                        self.indent,
+                        trailing,
+                        0.0,
+                        DWRITE_TEXT_RANGE {
+                            startPosition: position,
+                            length: character.len_utf16() as u32,
+                        },
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the byte index of the first character of each non-empty paragraph.
+    fn paragraph_starts(&self) -> Vec<usize> {
+        let mut starts = Vec::new();
+        let mut at_start = true;
+        let mut characters = self.text.char_indices().peekable();
+        while let Some((index, character)) = characters.next() {
+            let is_break = match character {
+                '\n' | '\u{2029}' => true,
+                // A carriage return followed by a line feed is a single break.
+                '\r' => !matches!(characters.peek(), Some((_, '\n'))),
+                _ => false,
+            };
+            if at_start && !is_break && character != '\r' {
+                starts.push(index);
+            }
+            at_start = is_break;
+        }
+        starts
+    }
+
+    /// Returns the outlines of the glyphs in the cached layout, creating them if
+    /// necessary.
+    ///
+    /// This must be called after `apply_span_colors`, so the outlines pick up the span
+    /// brushes.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create the outlines.
+    fn outline_geometry(&self, context: &DrawingContext) -> Result<Vec<GlyphGeometry>> {
+        let mut cached = self.layout.borrow_mut();
+        let Some(cached) = cached.as_mut() else {
+            return Ok(Vec::new());
+        };
+
+        if let Some(outline) = &cached.outline {
+            return Ok(outline.clone());
+        }
+        let outline = layout_geometry(context.d2d_factory, &cached.layout)?;
+        cached.outline = Some(outline.clone());
+        Ok(outline)
+    }
+
+    /// Converts the byte range of every span into a DirectWrite UTF-16 range.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a span's range is reversed, extends past
+    /// the end of the text, or does not lie on character boundaries.
+    fn span_ranges(&self) -> Result<Vec<DWRITE_TEXT_RANGE>> {
+        self.spans
+            .iter()
+            .map(|span| self.text_range(&span.range, "text span"))
+            .collect()
+    }
+
+    /// Converts a byte range of the text into a DirectWrite UTF-16 range.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error, naming the range as `what`, if the range is
+    /// reversed, extends past the end of the text, or does not lie on character
+    /// boundaries.
+    fn text_range(&self, range: &Range<usize>, what: &str) -> Result<DWRITE_TEXT_RANGE> {
+        let Range { start, end } = *range;
+        match (self.utf16_index(start), self.utf16_index(end)) {
+            (Some(start_position), Some(end_position)) if start <= end => Ok(DWRITE_TEXT_RANGE {
+                startPosition: start_position,
+                length: end_position - start_position,
+            }),
+            _ => Err(Error::new(
+                E_INVALIDARG,
+                format!(
+                    "{what} {start}..{end} is not a valid range of a {}-byte string",
+                    self.text.len()
+                ),
+            )),
+        }
+    }
+
+    /// Converts a byte range of the text to a DirectWrite text range, clamping it to the
+    /// text and rounding both ends down to character boundaries.
+    ///
+    /// Reversed ranges become empty.
+    fn clamped_text_range(&self, range: &Range<usize>) -> DWRITE_TEXT_RANGE {
+        let clamp = |index: usize| {
+            let mut index = index.min(self.text.len());
+            while !self.text.is_char_boundary(index) {
+                index -= 1;
+            }
+            self.utf16_index(index).unwrap_or_default()
+        };
+        let start_position = clamp(range.start);
+        let end_position = clamp(range.end).max(start_position);
+        DWRITE_TEXT_RANGE {
+            startPosition: start_position,
+            length: end_position - start_position,
+        }
+    }
+
+    /// Converts a byte index into the text to the UTF-16 position DirectWrite uses, or
+    /// returns `None` if the index is past the end or not on a character boundary.
+    fn utf16_index(&self, index: usize) -> Option<u32> {
+        self.text
+            .is_char_boundary(index)
+            .then(|| self.text[..index].encode_utf16().count() as u32)
+    }
+
+    /// Converts a UTF-16 position from DirectWrite to a byte index into the text.
+    ///
+    /// Positions inside a surrogate pair round down to the start of the character, and
+    /// positions past the end map to the length of the text.
+    fn byte_index(&self, utf16_index: u32) -> usize {
+        let mut position = 0;
+        for (index, character) in self.text.char_indices() {
+            position += character.len_utf16() as u32;
+            if position > utf16_index {
+                return index;
+            }
+        }
+        self.text.len()
+    }
+
+    /// Applies the span colors to the cached layout as drawing effects, creating a brush
+    /// per colored span for the render target if necessary.
+    ///
+    /// The brushes follow the opacity of the context's brush, so span colors fade along
+    /// with the rest of the text.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create a brush or to set a
+    /// drawing effect.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for creating brushes and configuring the
+    /// text layout. The caller must ensure that the `drawing_context` contains valid
+    /// Direct2D resources.
+    fn apply_span_colors(&self, context: &DrawingContext) -> Result<()> {
+        let mut cached = self.layout.borrow_mut();
+        let Some(cached) = cached.as_mut() else {
+            return Ok(());
+        };
+
+        let current =
+            matches!(&cached.brushes, Some((target, _)) if target == context.render_target);
+        if !current {
+            let ranges = self.span_ranges()?;
+            let mut brushes = Vec::new();
+            for (span, range) in self.spans.iter().zip(ranges) {
+                let Some(color) = span.color else {
+                    continue;
+                };
+                let brush = unsafe {
+                    let brush = context
+                        .render_target
+                        .CreateSolidColorBrush(&color.into(), None)?;
+                    cached.layout.SetDrawingEffect(&brush, range)?;
+                    brush
+                };
+                brushes.push(brush);
+            }
+            cached.brushes = Some((context.render_target.clone(), brushes));
+            cached.outline = None;
+        }
+
+        if let Some((_, brushes)) = &cached.brushes {
+            let opacity = unsafe { context.brush.GetOpacity() };
+            for brush in brushes {
+                unsafe { brush.SetOpacity(opacity) };
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Measures the text as it would be laid out with the given text format.
+    ///
+    /// This does not need a render target, so it can be used before drawing begins.
+    /// `fallback_size` is used for any dimension of the layout box that isn't set,
+    /// just as the render target size is when drawing.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create the text layout or to
+    /// read its metrics, or if a span range does not fall on character boundaries of
+    /// the text.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for creating the text layout and reading
+    /// its metrics. The caller must ensure that the factory and text format are valid.
+    pub fn measure(
+        &self,
+        dwrite_factory: &IDWriteFactory,
+        text_format: &IDWriteTextFormat,
+        fallback_size: D2D_SIZE_F,
+    ) -> Result<TextMetrics> {
+        let (metrics, lines) = self.read_metrics(dwrite_factory, text_format, fallback_size)?;
+
+        Ok(TextMetrics {
+            width: metrics.width,
+            height: metrics.height,
+            line_count: metrics.lineCount,
+            line_heights: lines.iter().map(|line| line.height).collect(),
+            baseline: lines
+                .first()
+                .map_or(0.0, |line| metrics.top + line.baseline),
+        })
+    }
+
+    /// Returns the bounding rectangle of the laid-out text in the same coordinates as
+    /// the object's `x` and `y`.
+    ///
+    /// The rectangle covers the lines of text rather than the whole layout box, so it
+    /// moves with the alignment and hugs the text, which makes it suitable for sizing a
+    /// background behind a label. Trailing whitespace is excluded from the width.
+    /// `fallback_size` is used for any dimension of the layout box that isn't set, as
+    /// with `measure`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create the text layout or to
+    /// read its metrics.
+    pub fn bounds(
+        &self,
+        dwrite_factory: &IDWriteFactory,
+        text_format: &IDWriteTextFormat,
+        fallback_size: D2D_SIZE_F,
+    ) -> Result<Rect> {
+        let (metrics, _) = self.read_metrics(dwrite_factory, text_format, fallback_size)?;
+        Ok(Rect::new(
+            self.x + metrics.left,
+            self.y + metrics.top,
+            metrics.width,
+            metrics.height,
+        ))
+    }
+
+    /// Returns the position of each line of the text as it would be laid out with the
+    /// given text format.
+    ///
+    /// `fallback_size` is used for any dimension of the layout box that isn't set, as
+    /// with `measure`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create the text layout or to
+    /// read its metrics, or if a span range does not fall on character boundaries of
+    /// the text.
+    pub fn line_metrics(
+        &self,
+        dwrite_factory: &IDWriteFactory,
+        text_format: &IDWriteTextFormat,
+        fallback_size: D2D_SIZE_F,
+    ) -> Result<Vec<LineMetrics>> {
+        let (metrics, lines) = self.read_metrics(dwrite_factory, text_format, fallback_size)?;
+
+        let mut top = metrics.top;
+        Ok(lines
+            .iter()
+            .map(|line| {
+                let line_metrics = LineMetrics {
+                    top,
+                    height: line.height,
+                    baseline: top + line.baseline,
+                };
+                top += line.height;
+                line_metrics
+            })
+            .collect())
+    }
+
+    /// Finds the character of the text nearest to `point`, as laid out with the given
+    /// text format.
+    ///
+    /// `point` is relative to the object's origin, `(x, y)`. Points outside the text
+    /// hit the nearest character, with `is_inside` set to `false`. `fallback_size` is
+    /// used for any dimension of the layout box that isn't set, as with `measure`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create the text layout or to
+    /// hit-test it.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for creating and hit-testing the text
+    /// layout. The caller must ensure that the factory and text format are valid.
+    pub fn hit_test_point(
+        &self,
+        dwrite_factory: &IDWriteFactory,
+        text_format: &IDWriteTextFormat,
+        fallback_size: D2D_SIZE_F,
+        point: Vector2,
+    ) -> Result<TextHit> {
+        let text_layout = self.layout(dwrite_factory, text_format, fallback_size)?;
+
+        let mut is_trailing = BOOL::default();
+        let mut is_inside = BOOL::default();
+        let mut metrics = DWRITE_HIT_TEST_METRICS::default();
+        unsafe {
+            text_layout.HitTestPoint(
+                point.X,
+                point.Y,
+                &mut is_trailing,
+                &mut is_inside,
+                &mut metrics,
+            )?;
+        }
+
+        Ok(TextHit {
+            index: self.byte_index(metrics.textPosition),
+            is_trailing: is_trailing.as_bool(),
+            is_inside: is_inside.as_bool(),
+        })
+    }
+
+    /// Returns where a caret placed before the character at byte `index` is drawn, as
+    /// laid out with the given text format.
+    ///
+    /// An `index` equal to the length of the text places the caret after the last
+    /// character. The position is relative to the object's origin, `(x, y)`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `index` is past the end of the text or
+    /// not on a character boundary, or if it fails to create or hit-test the text
+    /// layout.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for creating and hit-testing the text
+    /// layout. The caller must ensure that the factory and text format are valid.
+    pub fn hit_test_index(
+        &self,
+        dwrite_factory: &IDWriteFactory,
+        text_format: &IDWriteTextFormat,
+        fallback_size: D2D_SIZE_F,
+        index: usize,
+    ) -> Result<CaretPosition> {
+        let Some(position) = self.utf16_index(index) else {
+            return Err(Error::new(
+                E_INVALIDARG,
+                format!(
+                    "text index {index} is not a character boundary of a {}-byte string",
+                    self.text.len()
+                ),
+            ));
+        };
+        let text_layout = self.layout(dwrite_factory, text_format, fallback_size)?;
+
+        let (mut x, mut y) = (0.0, 0.0);
+        let mut metrics = DWRITE_HIT_TEST_METRICS::default();
+        unsafe {
+            text_layout.HitTestTextPosition(position, false, &mut x, &mut y, &mut metrics)?;
+        }
+
+        Ok(CaretPosition {
+            x,
+            y,
+            height: metrics.height,
+        })
+    }
+
+    /// Returns the rectangles covered by a byte range of the text, as laid out with the
+    /// given text format.
+    ///
+    /// The rectangles are relative to the object's origin, `(x, y)`. A range that
+    /// spans several lines produces a rectangle per line, and a line that mixes
+    /// left-to-right and right-to-left runs can produce several separate rectangles.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the range is not a valid range of the
+    /// text, or if it fails to create or hit-test the text layout.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for creating and hit-testing the text
+    /// layout. The caller must ensure that the factory and text format are valid.
+    pub fn range_rects(
+        &self,
+        dwrite_factory: &IDWriteFactory,
+        text_format: &IDWriteTextFormat,
+        fallback_size: D2D_SIZE_F,
+        range: Range<usize>,
+    ) -> Result<Vec<Rect>> {
+        let text_range = self.text_range(&range, "text range")?;
+        let text_layout = self.layout(dwrite_factory, text_format, fallback_size)?;
+        Self::hit_test_range(&text_layout, text_range)
+    }
+
+    /// Returns the rectangles covered by `text_range` in `text_layout`, relative to the
+    /// layout's origin.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to hit-test the text layout.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for hit-testing the text layout. The
+    /// caller must ensure that the text layout is valid.
+    fn hit_test_range(
+        text_layout: &IDWriteTextLayout,
+        text_range: DWRITE_TEXT_RANGE,
+    ) -> Result<Vec<Rect>> {
+        if text_range.length == 0 {
+            return Ok(Vec::new());
         }
+
+        // The first call only reports how many rectangles the range needs.
+        let mut count = 0;
+        let result = unsafe {
+            text_layout.HitTestTextRange(
+                text_range.startPosition,
+                text_range.length,
+                0.0,
+                0.0,
+                None,
+                &mut count,
+            )
+        };
+        if let Err(error) = result {
+            if error.code() != E_NOT_SUFFICIENT_BUFFER {
+                return Err(error);
+            }
+        }
+
+        let mut metrics = vec![DWRITE_HIT_TEST_METRICS::default(); count as usize];
+        unsafe {
+            text_layout.HitTestTextRange(
+                text_range.startPosition,
+                text_range.length,
+                0.0,
+                0.0,
+                Some(&mut metrics),
+                &mut count,
+            )?;
+        }
+        metrics.truncate(count as usize);
+
+        Ok(metrics
+            .iter()
+            .map(|metric| Rect::new(metric.left, metric.top, metric.width, metric.height))
+            .collect())
+    }
+
+    /// Fills the rectangles covered by `text_range` with `color`, behind where the
+    /// glyphs will be drawn.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to hit-test the text layout.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for calling Direct2D drawing methods.
+    /// The caller must ensure that the `drawing_context` contains valid Direct2D resources.
+    fn fill_range(
+        &self,
+        context: &DrawingContext,
+        text_layout: &IDWriteTextLayout,
+        text_range: DWRITE_TEXT_RANGE,
+        color: &Color,
+    ) -> Result<()> {
+        let rects = Self::hit_test_range(text_layout, text_range)?;
+        context.with_brush_color(color, |brush| {
+            for rect in rects {
+                let bounds: D2D_RECT_F = Rect {
+                    x: rect.x + self.x,
+                    y: rect.y + self.y,
+                    ..rect
+                }
+                .into();
+                unsafe { context.render_target.FillRectangle(&bounds, brush) };
+            }
+        });
+        Ok(())
+    }
+
+    /// Reads the overall and per-line metrics of the text layout.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create the text layout or to
+    /// read its metrics.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for creating the text layout and reading
+    /// its metrics. The caller must ensure that the factory and text format are valid.
+    fn read_metrics(
+        &self,
+        dwrite_factory: &IDWriteFactory,
+        text_format: &IDWriteTextFormat,
+        fallback_size: D2D_SIZE_F,
+    ) -> Result<(DWRITE_TEXT_METRICS, Vec<DWRITE_LINE_METRICS>)> {
+        let text_layout = self.layout(dwrite_factory, text_format, fallback_size)?;
+
+        let mut metrics = DWRITE_TEXT_METRICS::default();
+        unsafe { text_layout.GetMetrics(&mut metrics)? };
+
+        let mut lines = vec![DWRITE_LINE_METRICS::default(); metrics.lineCount as usize];
+        let mut line_count = 0;
+        unsafe { text_layout.GetLineMetrics(Some(&mut lines), &mut line_count)? };
+        lines.truncate(line_count as usize);
+
+        Ok((metrics, lines))
     }
 }
 
@@ -33,7 +1592,8 @@ impl Drawable for TextObject {
     ///
     /// # Errors
     ///
-    /// This function will return an error if it fails to create the text layout.
+    /// This function will return an error if it fails to create or configure the text
+    /// layout, or if a span's range is not a valid range of the text.
     ///
     /// # Safety
     ///
@@ -41,30 +1601,467 @@ impl Drawable for TextObject {
     /// the text. The caller must ensure that the `drawing_context` contains valid
     /// Direct2D and DirectWrite resources.
     fn draw(&self, context: &DrawingContext) -> Result<()> {
-        let text_utf16: Vec<u16> = self.text.encode_utf16().collect();
+        context.with_opacity(self.opacity, || {
+            let size = unsafe { context.render_target.GetSize() };
+            let text_layout = self.layout(context.dwrite_factory, context.text_format, size)?;
+            self.apply_span_colors(context)?;
 
-        let size = unsafe { context.render_target.GetSize() };
+            let origin = Vector2 { X: self.x, Y: self.y };
 
-        let text_layout = unsafe {
-            context.dwrite_factory.CreateTextLayout(
-                &text_utf16,
-                context.text_format,
-                size.width,
-                size.height,
-            )?
+            for (range, color) in &self.highlights {
+                let text_range = self.clamped_text_range(range);
+                self.fill_range(context, &text_layout, text_range, color)?;
+            }
+
+            if let Some((range, color)) = &self.selection {
+                let text_range = self.text_range(range, "selection")?;
+                self.fill_range(context, &text_layout, text_range, color)?;
+            }
+
+            if let Some((outline_color, outline_width)) = &self.outline {
+                let glyphs = self.outline_geometry(context)?;
+                context.with_transform(&Matrix3x2::translation(self.x, self.y), || {
+                    context.with_brush_color(outline_color, |brush| {
+                        for glyph in &glyphs {
+                            unsafe {
+                                context.render_target.DrawGeometry(
+                                    &glyph.geometry,
+                                    brush,
+                                    *outline_width,
+                                    None,
+                                )
+                            };
+                        }
+                    });
+
+                    let fill = |brush: &ID2D1SolidColorBrush| {
+                        let brush: &ID2D1Brush = brush;
+                        for glyph in &glyphs {
+                            let glyph_brush = glyph.brush.as_ref().unwrap_or(brush);
+                            unsafe {
+                                context.render_target.FillGeometry(
+                                    &glyph.geometry,
+                                    glyph_brush,
+                                    None,
+                                )
+                            };
+                        }
+                    };
+                    match &self.color {
+                        Some(color) => context.with_brush_color(color, fill),
+                        None => fill(context.brush),
+                    }
+                });
+                return Ok(());
+            }
+
+            let draw = |brush: &ID2D1SolidColorBrush| unsafe {
+                context.render_target.DrawTextLayout(
+                    origin,
+                    &text_layout,
+                    brush,
+                    context.text_options,
+                );
+            };
+            match &self.color {
+                Some(color) => context.with_brush_color(color, draw),
+                None => draw(context.brush),
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Draws the text through `renderer` in its default font, in the text color or
+    /// white like the default brush.
+    ///
+    /// The layout box, alignment, wrapping, spans, highlights, selection, and outline
+    /// are skipped.
+    fn render(&self, renderer: &mut dyn Renderer) {
+        let origin = Vector2 {
+            X: self.x,
+            Y: self.y,
+        };
+        let color = self.color.unwrap_or(Color::WHITE);
+        renderer.with_opacity(self.opacity, |renderer| {
+            renderer.draw_text(&self.text, origin, color)
+        })
+    }
+
+    /// Returns the bounding rectangle of the text as it was last laid out, like
+    /// `TextObject::bounds`, grown to cover the ink of the glyphs and the outline.
+    ///
+    /// Glyphs such as italics and accents can overhang the line metrics, so the
+    /// rectangle is united with the layout's overhang metrics, and grown by half the
+    /// outline width. Overhangs past an unbounded layout box aren't meaningful and are
+    /// left out on that side.
+    ///
+    /// Measuring needs the window's text format, so this uses the layout cached by the
+    /// last `draw` or measurement. It returns `None` before the text has been laid out
+    /// and after `set_text`, until the text is drawn again.
+    fn bounds(&self) -> Option<Rect> {
+        let cached = self.layout.borrow();
+        let text_layout = &cached.as_ref()?.layout;
+        let mut metrics = DWRITE_TEXT_METRICS::default();
+        let mut overhang = DWRITE_OVERHANG_METRICS::default();
+        unsafe {
+            text_layout.GetMetrics(&mut metrics).ok()?;
+            text_layout.GetOverhangMetrics(&mut overhang).ok()?;
+        }
+        let (max_width, max_height) =
+            unsafe { (text_layout.GetMaxWidth(), text_layout.GetMaxHeight()) };
+
+        // The overhangs are the distances from the edges of the layout box to the ink,
+        // positive where the ink sticks out.
+        let text = Rect::new(metrics.left, metrics.top, metrics.width, metrics.height);
+        let left = -overhang.left;
+        let top = -overhang.top;
+        let right = if max_width < f32::MAX {
+            max_width + overhang.right
+        } else {
+            text.right()
+        };
+        let bottom = if max_height < f32::MAX {
+            max_height + overhang.bottom
+        } else {
+            text.bottom()
         };
+        let ink = Rect::new(left, top, (right - left).max(0.0), (bottom - top).max(0.0));
+
+        let outline = self
+            .outline
+            .as_ref()
+            .map_or(0.0, |(_, width)| width.max(0.0) / 2.0);
+        let rect = text.union(&ink).inflate(outline);
+        Some(Rect::new(
+            self.x + rect.x,
+            self.y + rect.y,
+            rect.width,
+            rect.height,
+        ))
+    }
+
+    crate::impl_clone_box!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use windows::Win32::Graphics::DirectWrite::{
+        DWRITE_FACTORY_TYPE_SHARED, DWriteCreateFactory, IDWriteFontCollection,
+    };
 
-        let origin = Vector2 { X: self.x, Y: self.y };
+    /// The Japanese text the app shows by default.
+    const JAPANESE_SAMPLE: &str = "日本語ハローワールドテスト。";
 
+    /// Creates a DirectWrite factory and a 20 DIP text format in the window's default
+    /// font, so text can be laid out without a window.
+    fn text_format() -> (IDWriteFactory, IDWriteTextFormat) {
         unsafe {
-            context.render_target.DrawTextLayout(
-                origin,
-                &text_layout,
-                context.brush,
-                D2D1_DRAW_TEXT_OPTIONS_NONE,
-            );
+            let factory: IDWriteFactory = DWriteCreateFactory(DWRITE_FACTORY_TYPE_SHARED).unwrap();
+            let text_format = factory
+                .CreateTextFormat(
+                    w!("MS Gothic"),
+                    None::<&IDWriteFontCollection>,
+                    DWRITE_FONT_WEIGHT_NORMAL,
+                    DWRITE_FONT_STYLE_NORMAL,
+                    DWRITE_FONT_STRETCH_NORMAL,
+                    20.0,
+                    w!("ja-JP"),
+                )
+                .unwrap();
+            (factory, text_format)
         }
+    }
 
-        Ok(())
+    /// A fallback size larger than any layout box in these tests.
+    const SIZE: D2D_SIZE_F = D2D_SIZE_F {
+        width: 1000.0,
+        height: 1000.0,
+    };
+
+    #[test]
+    fn vertical_layout_wraps_columns_at_the_box_height() {
+        let (factory, text_format) = text_format();
+        let horizontal = TextObject::new(JAPANESE_SAMPLE, 0.0, 0.0).with_layout_size(400.0, 100.0);
+        let vertical = horizontal.clone().with_vertical_layout();
+
+        // Thirteen 20 DIP characters fit on one 400 DIP line.
+        let metrics = horizontal.measure(&factory, &text_format, SIZE).unwrap();
+        assert_eq!(metrics.line_count, 1);
+
+        // Vertically, they run down 100 DIP columns, at most five to a column, and the
+        // columns advance across the width.
+        let metrics = vertical.measure(&factory, &text_format, SIZE).unwrap();
+        assert!(metrics.line_count >= 3, "{metrics:?}");
+        assert!(metrics.height <= 100.5, "{metrics:?}");
+        assert!(metrics.width < 200.0, "{metrics:?}");
+    }
+
+    #[test]
+    fn vertical_layout_reads_down_and_places_columns_right_to_left() {
+        let (factory, text_format) = text_format();
+        let text = TextObject::new(JAPANESE_SAMPLE, 0.0, 0.0)
+            .with_layout_size(400.0, 100.0)
+            .with_vertical_layout();
+        let caret = |index| {
+            text.hit_test_index(&factory, &text_format, SIZE, index)
+                .unwrap()
+        };
+
+        // Each character is three bytes long.
+        let first = caret(0);
+        let second = caret(3);
+        let last = caret(JAPANESE_SAMPLE.len() - 3);
+
+        // The first column is at the right edge of the box, and reads top to bottom.
+        assert!(first.x > 300.0, "{first:?}");
+        assert!((second.x - first.x).abs() < 1.0, "{first:?} {second:?}");
+        assert!(second.y > first.y, "{first:?} {second:?}");
+        // The last character is in a column further left.
+        assert!(last.x < first.x, "{first:?} {last:?}");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn conflicting_directions_fail_to_lay_out() {
+        let (factory, text_format) = text_format();
+        let text = TextObject::new(JAPANESE_SAMPLE, 0.0, 0.0)
+            .with_direction(ReadingDirection::TopToBottom, FlowDirection::LeftToRight);
+        assert!(text.measure(&factory, &text_format, SIZE).is_ok());
+
+        let text = text.with_direction(ReadingDirection::TopToBottom, FlowDirection::TopToBottom);
+        let error = text.measure(&factory, &text_format, SIZE).unwrap_err();
+        assert_eq!(error.code(), E_INVALIDARG);
+    }
+
+    /// A string mixing one-, two-, and four-byte characters. The emoji takes two UTF-16
+    /// code units.
+    const MIXED: &str = "aé😀b";
+
+    fn range(start: u32, length: u32) -> DWRITE_TEXT_RANGE {
+        DWRITE_TEXT_RANGE {
+            startPosition: start,
+            length,
+        }
+    }
+
+    #[test]
+    fn text_range_converts_bytes_to_utf16() {
+        let text = TextObject::new(MIXED, 0.0, 0.0);
+        assert_eq!(text.text_range(&(0..0), "range").unwrap(), range(0, 0));
+        assert_eq!(text.text_range(&(1..3), "range").unwrap(), range(1, 1));
+        assert_eq!(text.text_range(&(3..7), "range").unwrap(), range(2, 2));
+        assert_eq!(text.text_range(&(3..8), "range").unwrap(), range(2, 3));
+        assert_eq!(text.text_range(&(0..8), "range").unwrap(), range(0, 5));
+    }
+
+    #[test]
+    fn text_range_rejects_invalid_ranges() {
+        let text = TextObject::new(MIXED, 0.0, 0.0);
+        // Inside a character, past the end, and reversed.
+        for invalid in [2..3, 3..5, 0..9, 9..9, 3..1] {
+            let error = text.text_range(&invalid, "range").unwrap_err();
+            assert_eq!(error.code(), E_INVALIDARG, "{invalid:?}");
+        }
+    }
+
+    #[test]
+    fn span_ranges_are_validated() {
+        let text = TextObject::new(MIXED, 0.0, 0.0)
+            .with_spans(vec![TextSpan::new(0..1), TextSpan::new(3..8)]);
+        assert_eq!(text.span_ranges().unwrap(), vec![range(0, 1), range(2, 3)]);
+
+        let text = text.with_spans(vec![TextSpan::new(0..1), TextSpan::new(4..8)]);
+        assert_eq!(text.span_ranges().unwrap_err().code(), E_INVALIDARG);
+
+        // An invalid span fails the layout rather than being drawn in the wrong place.
+        let (factory, text_format) = text_format();
+        let error = text.measure(&factory, &text_format, SIZE).unwrap_err();
+        assert_eq!(error.code(), E_INVALIDARG);
+    }
+
+    #[test]
+    fn clamped_text_range_rounds_down_to_characters() {
+        let text = TextObject::new(MIXED, 0.0, 0.0);
+        assert_eq!(text.clamped_text_range(&(5..100)), range(2, 3));
+        assert_eq!(text.clamped_text_range(&(2..4)), range(1, 1));
+        assert_eq!(text.clamped_text_range(&(7..3)), range(4, 0));
+    }
+
+    #[test]
+    fn utf16_positions_map_back_to_byte_indices() {
+        let text = TextObject::new(MIXED, 0.0, 0.0);
+        let positions: Vec<_> = [0, 1, 3, 7, 8]
+            .into_iter()
+            .map(|index| text.utf16_index(index))
+            .collect();
+        assert_eq!(positions, [Some(0), Some(1), Some(2), Some(4), Some(5)]);
+        assert_eq!(text.utf16_index(2), None);
+        assert_eq!(text.utf16_index(9), None);
+
+        assert_eq!(text.byte_index(2), 3);
+        // A position inside the surrogate pair rounds down to the emoji.
+        assert_eq!(text.byte_index(3), 3);
+        assert_eq!(text.byte_index(4), 7);
+        assert_eq!(text.byte_index(100), MIXED.len());
+    }
+
+    #[test]
+    fn line_metrics_describe_each_wrapped_line() {
+        let (factory, text_format) = text_format();
+        let text = TextObject::new("one two three four five six seven", 0.0, 0.0)
+            .with_layout_size(90.0, 500.0);
+
+        let metrics = text.measure(&factory, &text_format, SIZE).unwrap();
+        let lines = text.line_metrics(&factory, &text_format, SIZE).unwrap();
+        assert!(metrics.line_count > 1, "{metrics:?}");
+        assert_eq!(lines.len(), metrics.line_count as usize);
+        assert_eq!(lines[0].baseline, metrics.baseline);
+
+        // Lines are stacked without gaps, with each baseline inside its line.
+        let mut top = 0.0;
+        for (line, &height) in lines.iter().zip(&metrics.line_heights) {
+            assert_eq!(line.top, top);
+            assert_eq!(line.height, height);
+            assert!(line.baseline > line.top && line.baseline <= line.top + line.height);
+            top += line.height;
+        }
+        assert!((top - metrics.height).abs() < 0.01, "{top} {metrics:?}");
+    }
+
+    #[test]
+    fn tabs_advance_to_the_tab_stop_width() {
+        let (factory, text_format) = text_format();
+        // The caret before `c`, after two tabs.
+        let c_offset = |tab_stop_width| {
+            TextObject::new("a\tb\tc", 0.0, 0.0)
+                .with_tab_stop_width(tab_stop_width)
+                .hit_test_index(&factory, &text_format, SIZE, 4)
+                .unwrap()
+                .x
+        };
+
+        // `a` and `b` are narrower than a stop, so `c` starts at the second stop.
+        assert!((c_offset(40.0) - 80.0).abs() < 0.5);
+        assert!((c_offset(80.0) - 160.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn only_layout_changes_discard_the_cached_layout() {
+        let (factory, text_format) = text_format();
+        let mut text = TextObject::new("Hello", 0.0, 0.0);
+        let layout = |text: &TextObject| text.layout(&factory, &text_format, SIZE).unwrap();
+
+        let first = layout(&text);
+        assert_eq!(layout(&text), first);
+
+        // The position and color are applied when drawing.
+        text.set_position(10.0, 20.0);
+        text.set_color(Color::new(1.0, 0.0, 0.0, 1.0));
+        assert_eq!(layout(&text), first);
+
+        // Setting the same text again keeps the layout; new text replaces it.
+        text.set_text("Hello");
+        assert_eq!(layout(&text), first);
+        text.set_text("World");
+        assert!(text.layout.borrow().is_none());
+        let second = layout(&text);
+        assert_ne!(second, first);
+
+        // So does a different layout box.
+        let small = D2D_SIZE_F {
+            width: 50.0,
+            height: 50.0,
+        };
+        assert_ne!(text.layout(&factory, &text_format, small).unwrap(), second);
+    }
+
+    #[test]
+    fn locales_are_applied_and_invalid_ones_ignored() {
+        let (factory, text_format) = text_format();
+        let locale_of = |text: &TextObject| {
+            let layout = text.layout(&factory, &text_format, SIZE).unwrap();
+            let mut name = [0u16; 85];
+            unsafe { layout.GetLocaleName(0, &mut name, None).unwrap() };
+            let length = name.iter().position(|&c| c == 0).unwrap_or(name.len());
+            String::from_utf16_lossy(&name[..length])
+        };
+
+        let text = TextObject::new(JAPANESE_SAMPLE, 0.0, 0.0);
+        assert_eq!(locale_of(&text.clone().with_locale("en-US")), "en-US");
+        assert_eq!(locale_of(&text.clone().with_locale("ja-JP")), "ja-JP");
+
+        // A name longer than any locale is ignored, and the text is laid out as if no
+        // locale had been set.
+        let expected = text.measure(&factory, &text_format, SIZE).unwrap();
+        let invalid = text.with_locale(&"x".repeat(100));
+        let metrics = invalid.measure(&factory, &text_format, SIZE).unwrap();
+        assert_eq!(metrics.width, expected.width);
+        assert_eq!(metrics.line_count, expected.line_count);
+    }
+
+    #[test]
+    fn indent_and_wrapping_set_the_line_count() {
+        let (factory, text_format) = text_format();
+        let line_count = |text: TextObject| {
+            text.with_layout_size(55.0, 500.0)
+                .measure(&factory, &text_format, SIZE)
+                .unwrap()
+                .line_count
+        };
+
+        // Latin letters are 10 DIPs wide in the test font, so two words fit on a line,
+        // but an indent leaves room for only one on the first.
+        assert_eq!(line_count(TextObject::new("aa bb cc dd", 0.0, 0.0)), 2);
+        assert_eq!(
+            line_count(TextObject::new("aa bb cc dd", 0.0, 0.0).with_indent(30.0)),
+            3
+        );
+
+        // A word wider than the box is broken only by `Wrap`.
+        let word = || TextObject::new("aaaaaaaaaa", 0.0, 0.0);
+        assert_eq!(line_count(word()), 2);
+        assert_eq!(line_count(word().with_wrapping(Wrapping::WholeWord)), 1);
+        assert_eq!(line_count(word().with_wrapping(Wrapping::NoWrap)), 1);
+        assert_eq!(line_count(word().with_wrapping(Wrapping::Character)), 2);
+    }
+
+    #[test]
+    fn culling_bounds_cover_the_outline_around_the_text() {
+        let (factory, text_format) = text_format();
+        let text = TextObject::new("Outlined", 10.0, 10.0).with_outline(Color::BLACK, 6.0);
+
+        // Measuring caches the layout the drawable bounds are read from.
+        let tight = text.bounds(&factory, &text_format, SIZE).unwrap();
+        let culling = Drawable::bounds(&text).unwrap();
+        assert!(culling.x <= tight.x - 3.0, "{culling:?} {tight:?}");
+        assert!(culling.y <= tight.y - 3.0, "{culling:?} {tight:?}");
+        assert!(culling.right() >= tight.right() + 3.0, "{culling:?} {tight:?}");
+        assert!(culling.bottom() >= tight.bottom() + 3.0, "{culling:?} {tight:?}");
+    }
+
+    #[test]
+    fn set_text_changes_what_the_next_frame_renders() {
+        use crate::core::backend::{DrawCommand, RecordingRenderer};
+
+        let (factory, text_format) = text_format();
+        let mut text = TextObject::new("before", 10.0, 20.0).with_color(Color::RED);
+        let text_command = |renderer: &RecordingRenderer| match renderer.commands() {
+            [DrawCommand::Text { text, .. }] => text.clone(),
+            commands => panic!("expected one text command, got {commands:?}"),
+        };
+
+        let mut renderer = RecordingRenderer::new();
+        text.render(&mut renderer);
+        assert_eq!(text_command(&renderer), "before");
+        let before = text.measure(&factory, &text_format, SIZE).unwrap();
+
+        text.set_text("after, and longer");
+        let mut renderer = RecordingRenderer::new();
+        text.render(&mut renderer);
+        assert_eq!(text_command(&renderer), "after, and longer");
+        // The cached layout was discarded, so the new text is measured too.
+        let after = text.measure(&factory, &text_format, SIZE).unwrap();
+        assert!(after.width > before.width, "{before:?} {after:?}");
+    }
+}
@@ -0,0 +1,204 @@
+use std::cell::RefCell;
+use std::ffi::c_void;
+use std::rc::Rc;
+
+use windows::{
+    core::*,
+    Win32::Graphics::Direct2D::{
+        Common::D2D_RECT_F, ID2D1Brush, ID2D1Factory, ID2D1Geometry, ID2D1GeometrySink,
+    },
+    Win32::Graphics::DirectWrite::{
+        IDWriteInlineObject, IDWritePixelSnapping_Impl, IDWriteTextLayout, IDWriteTextRenderer,
+        IDWriteTextRenderer_Impl, DWRITE_GLYPH_RUN, DWRITE_GLYPH_RUN_DESCRIPTION, DWRITE_MATRIX,
+        DWRITE_MEASURING_MODE, DWRITE_STRIKETHROUGH, DWRITE_UNDERLINE,
+    },
+};
+use windows_numerics::Matrix3x2;
+
+/// The outline of part of a text layout, such as one glyph run or an underline.
+#[derive(Clone)]
+pub(crate) struct GlyphGeometry {
+    /// The outline, relative to the origin of the text layout.
+    pub geometry: ID2D1Geometry,
+    /// The brush set on this part of the text as a drawing effect, if any.
+    pub brush: Option<ID2D1Brush>,
+}
+
+/// Converts the glyphs, underlines, and strikethroughs of `text_layout` into geometries.
+///
+/// The layout is drawn through a custom `IDWriteTextRenderer`, so wrapping, alignment,
+/// font fallback, and per-range formatting all carry over. Inline objects are skipped.
+///
+/// # Errors
+///
+/// This function will return an error if it fails to draw the layout or to create any
+/// of the geometries.
+///
+/// # Safety
+///
+/// This function contains `unsafe` blocks for drawing the text layout. The caller must
+/// ensure that the factory and text layout are valid.
+pub(crate) fn layout_geometry(
+    factory: &ID2D1Factory,
+    text_layout: &IDWriteTextLayout,
+) -> Result<Vec<GlyphGeometry>> {
+    let geometries = Rc::new(RefCell::new(Vec::new()));
+    let renderer: IDWriteTextRenderer = OutlineRenderer {
+        factory: factory.clone(),
+        geometries: Rc::clone(&geometries),
+    }
+    .into();
+
+    unsafe { text_layout.Draw(None, &renderer, 0.0, 0.0)? };
+
+    Ok(geometries.take())
+}
+
+/// An `IDWriteTextRenderer` that collects outlines instead of drawing.
+#[implement(IDWriteTextRenderer)]
+struct OutlineRenderer {
+    factory: ID2D1Factory,
+    geometries: Rc<RefCell<Vec<GlyphGeometry>>>,
+}
+
+impl OutlineRenderer {
+    /// Moves `geometry` to `(x, y)` and records it with the drawing effect's brush.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create the transformed
+    /// geometry.
+    fn push(&self, geometry: &ID2D1Geometry, x: f32, y: f32, effect: Ref<IUnknown>) -> Result<()> {
+        let geometry = unsafe {
+            self.factory
+                .CreateTransformedGeometry(geometry, &Matrix3x2::translation(x, y))?
+        };
+        let brush = effect.as_ref().and_then(|effect| effect.cast().ok());
+        self.geometries.borrow_mut().push(GlyphGeometry {
+            geometry: geometry.cast()?,
+            brush,
+        });
+        Ok(())
+    }
+
+    /// Records a rectangle, such as an underline, relative to `(x, y)`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create the geometry.
+    fn push_rect(&self, rect: D2D_RECT_F, x: f32, y: f32, effect: Ref<IUnknown>) -> Result<()> {
+        let geometry = unsafe { self.factory.CreateRectangleGeometry(&rect)? };
+        self.push(&geometry.cast()?, x, y, effect)
+    }
+}
+
+impl IDWriteTextRenderer_Impl for OutlineRenderer_Impl {
+    fn DrawGlyphRun(
+        &self,
+        _context: *const c_void,
+        baseline_x: f32,
+        baseline_y: f32,
+        _measuring_mode: DWRITE_MEASURING_MODE,
+        glyph_run: *const DWRITE_GLYPH_RUN,
+        _description: *const DWRITE_GLYPH_RUN_DESCRIPTION,
+        effect: Ref<IUnknown>,
+    ) -> Result<()> {
+        let glyph_run = unsafe { &*glyph_run };
+        let Some(font_face) = glyph_run.fontFace.as_ref() else {
+            return Ok(());
+        };
+
+        let path = unsafe { self.factory.CreatePathGeometry()? };
+        unsafe {
+            let sink: ID2D1GeometrySink = path.Open()?;
+            font_face.GetGlyphRunOutline(
+                glyph_run.fontEmSize,
+                glyph_run.glyphIndices,
+                Some(glyph_run.glyphAdvances),
+                (!glyph_run.glyphOffsets.is_null()).then_some(glyph_run.glyphOffsets),
+                glyph_run.glyphCount,
+                glyph_run.isSideways.as_bool(),
+                glyph_run.bidiLevel % 2 == 1,
+                &sink,
+            )?;
+            sink.Close()?;
+        }
+
+        self.push(&path.cast()?, baseline_x, baseline_y, effect)
+    }
+
+    fn DrawUnderline(
+        &self,
+        _context: *const c_void,
+        baseline_x: f32,
+        baseline_y: f32,
+        underline: *const DWRITE_UNDERLINE,
+        effect: Ref<IUnknown>,
+    ) -> Result<()> {
+        let underline = unsafe { &*underline };
+        let rect = D2D_RECT_F {
+            left: 0.0,
+            top: underline.offset,
+            right: underline.width,
+            bottom: underline.offset + underline.thickness,
+        };
+        self.push_rect(rect, baseline_x, baseline_y, effect)
+    }
+
+    fn DrawStrikethrough(
+        &self,
+        _context: *const c_void,
+        baseline_x: f32,
+        baseline_y: f32,
+        strikethrough: *const DWRITE_STRIKETHROUGH,
+        effect: Ref<IUnknown>,
+    ) -> Result<()> {
+        let strikethrough = unsafe { &*strikethrough };
+        let rect = D2D_RECT_F {
+            left: 0.0,
+            top: strikethrough.offset,
+            right: strikethrough.width,
+            bottom: strikethrough.offset + strikethrough.thickness,
+        };
+        self.push_rect(rect, baseline_x, baseline_y, effect)
+    }
+
+    fn DrawInlineObject(
+        &self,
+        _context: *const c_void,
+        _origin_x: f32,
+        _origin_y: f32,
+        _inline_object: Ref<IDWriteInlineObject>,
+        _is_sideways: BOOL,
+        _is_right_to_left: BOOL,
+        _effect: Ref<IUnknown>,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl IDWritePixelSnapping_Impl for OutlineRenderer_Impl {
+    fn IsPixelSnappingDisabled(&self, _context: *const c_void) -> Result<BOOL> {
+        // Outlines are scaled and transformed freely, so glyphs shouldn't be snapped.
+        Ok(true.into())
+    }
+
+    fn GetCurrentTransform(
+        &self,
+        _context: *const c_void,
+        transform: *mut DWRITE_MATRIX,
+    ) -> Result<()> {
+        unsafe {
+            *transform = DWRITE_MATRIX {
+                m11: 1.0,
+                m22: 1.0,
+                ..Default::default()
+            };
+        }
+        Ok(())
+    }
+
+    fn GetPixelsPerDip(&self, _context: *const c_void) -> Result<f32> {
+        Ok(1.0)
+    }
+}
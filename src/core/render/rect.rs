@@ -0,0 +1,141 @@
+#[cfg(windows)]
+use windows::Win32::Graphics::Direct2D::Common::D2D_RECT_F;
+use windows_numerics::{Matrix3x2, Vector2};
+
+/// An axis-aligned rectangle described by its top-left corner and size.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rect {
+    /// The x-coordinate of the top-left corner.
+    pub x: f32,
+    /// The y-coordinate of the top-left corner.
+    pub y: f32,
+    /// The width of the rectangle.
+    pub width: f32,
+    /// The height of the rectangle.
+    pub height: f32,
+}
+
+impl Rect {
+    /// Creates a new `Rect` from its top-left corner and size.
+    pub const fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Returns the smallest rectangle containing all of `points`, or `None` if there
+    /// are none.
+    pub fn from_points(points: impl IntoIterator<Item = Vector2>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let (min, max) = points.fold((first, first), |(min, max), point| {
+            (
+                Vector2 {
+                    X: min.X.min(point.X),
+                    Y: min.Y.min(point.Y),
+                },
+                Vector2 {
+                    X: max.X.max(point.X),
+                    Y: max.Y.max(point.Y),
+                },
+            )
+        });
+        Some(Self::new(min.X, min.Y, max.X - min.X, max.Y - min.Y))
+    }
+
+    /// Returns the x-coordinate of the right edge.
+    pub fn right(&self) -> f32 {
+        self.x + self.width
+    }
+
+    /// Returns the y-coordinate of the bottom edge.
+    pub fn bottom(&self) -> f32 {
+        self.y + self.height
+    }
+
+    /// Returns the center point of the rectangle.
+    pub fn center(&self) -> Vector2 {
+        Vector2 {
+            X: self.x + self.width / 2.0,
+            Y: self.y + self.height / 2.0,
+        }
+    }
+
+    /// Returns the rectangle grown by `amount` on every side.
+    pub fn inflate(&self, amount: f32) -> Self {
+        Self::new(
+            self.x - amount,
+            self.y - amount,
+            self.width + amount * 2.0,
+            self.height + amount * 2.0,
+        )
+    }
+
+    /// Returns the smallest rectangle containing both this rectangle and `other`.
+    pub fn union(&self, other: &Rect) -> Self {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        Self::new(
+            x,
+            y,
+            self.right().max(other.right()) - x,
+            self.bottom().max(other.bottom()) - y,
+        )
+    }
+
+    /// Returns the smallest axis-aligned rectangle containing this rectangle after it
+    /// is transformed by `matrix`.
+    pub fn transform(&self, matrix: &Matrix3x2) -> Self {
+        let corners = [
+            Vector2 { X: self.x, Y: self.y },
+            Vector2 { X: self.right(), Y: self.y },
+            Vector2 { X: self.x, Y: self.bottom() },
+            Vector2 { X: self.right(), Y: self.bottom() },
+        ];
+        Self::from_points(corners.map(|corner| Vector2 {
+            X: corner.X * matrix.M11 + corner.Y * matrix.M21 + matrix.M31,
+            Y: corner.X * matrix.M12 + corner.Y * matrix.M22 + matrix.M32,
+        }))
+        .unwrap_or(*self)
+    }
+
+    /// Returns `true` if the two rectangles overlap or touch.
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.x <= other.right()
+            && other.x <= self.right()
+            && self.y <= other.bottom()
+            && other.y <= self.bottom()
+    }
+
+    /// Returns the area shared by the two rectangles, or `None` if they don't overlap.
+    pub fn intersection(&self, other: &Rect) -> Option<Self> {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+        (right > x && bottom > y).then(|| Self::new(x, y, right - x, bottom - y))
+    }
+
+    /// Returns `true` if the point lies inside the rectangle.
+    ///
+    /// The left and top edges are inclusive; the right and bottom edges are exclusive.
+    pub fn contains(&self, point: Vector2) -> bool {
+        point.X >= self.x && point.X < self.right() && point.Y >= self.y && point.Y < self.bottom()
+    }
+}
+
+#[cfg(windows)]
+impl From<Rect> for D2D_RECT_F {
+    fn from(rect: Rect) -> Self {
+        D2D_RECT_F {
+            left: rect.x,
+            top: rect.y,
+            right: rect.right(),
+            bottom: rect.bottom(),
+        }
+    }
+}
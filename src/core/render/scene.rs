@@ -1,7 +1,645 @@
+use std::cell::{Cell, RefCell, RefMut};
+use std::time::{Duration, Instant};
+
 use windows::core::Result;
+use windows_numerics::Vector2;
 
+use crate::core::backend::Renderer;
+use crate::core::render::camera::Camera;
 use crate::core::render::drawable::Drawable;
 use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::rect::Rect;
+use crate::core::render::spatial_index::{DrawKey, SpatialIndex};
+
+/// Statistics about a `Scene`, returned by `Scene::stats`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SceneStats {
+    /// The number of objects in all layers of the scene.
+    pub object_count: usize,
+    /// How long the last call to `Scene::draw_all` took, or `None` if draw timing is
+    /// disabled or the scene hasn't been drawn since it was enabled.
+    ///
+    /// This is the time spent issuing drawing commands. Direct2D may defer some of
+    /// the work until the frame is presented, which isn't included.
+    pub draw_time: Option<Duration>,
+}
+
+/// One of the fixed layers of a `Scene`, listed in the order they are drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Layer {
+    /// Drawn first, behind everything else, such as a wallpaper or a grid.
+    Background,
+    /// The main content of the scene. Objects added directly to a `Scene` go here.
+    #[default]
+    Content,
+    /// Drawn last, on top of everything else, such as selection handles or debug
+    /// information.
+    ///
+    /// Unlike the other layers, the overlay isn't moved by the scene's `Camera`, so its
+    /// objects stay in place in the window.
+    Overlay,
+}
+
+impl Layer {
+    /// Every layer, in the order they are drawn.
+    pub const ALL: [Layer; 3] = [Layer::Background, Layer::Content, Layer::Overlay];
+
+    /// Returns the position of the layer in `Layer::ALL`.
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Returns `true` if the objects in the layer are in world coordinates and so are
+    /// moved by the scene's `Camera`, and `false` for the overlay, whose objects are in
+    /// screen coordinates.
+    pub fn follows_camera(self) -> bool {
+        self != Layer::Overlay
+    }
+}
+
+/// A handle to an object in a `Scene`, returned by `Scene::add_object`.
+///
+/// Ids are never reused, so a handle stays valid while other objects are added and
+/// removed, and never refers to a different object after its own is removed. The id
+/// records the layer the object was added to, so the scene can find it again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ObjectId {
+    layer: Layer,
+    index: u64,
+}
+
+impl ObjectId {
+    /// Returns the layer the object belongs to.
+    pub fn layer(&self) -> Layer {
+        self.layer
+    }
+}
+
+/// An object in a `SceneLayer` with the values that determine when it is drawn.
+struct SceneObject {
+    /// The handle returned when the object was added.
+    id: ObjectId,
+    /// The z-index of the object. Objects with higher z-indices are drawn on top.
+    z_index: i32,
+    /// When the object was added or last brought to the front, which orders objects
+    /// with the same z-index.
+    sequence: u64,
+    /// The heap-allocated drawable object.
+    drawable: Box<dyn Drawable>,
+}
+
+impl SceneObject {
+    /// Returns the key the objects of a layer are sorted by.
+    fn key(&self) -> DrawKey {
+        (self.z_index, self.sequence)
+    }
+}
+
+/// One layer of a `Scene`: an ordered list of `Drawable` objects.
+///
+/// Objects are drawn in order of their z-index, and objects with the same z-index are
+/// drawn in the order they were added. The z-index always takes precedence: methods
+/// that move an object to a position, such as `move_to_back` and `insert_object_at`,
+/// only move it among the objects with the same z-index.
+pub struct SceneLayer {
+    /// Which layer of the scene this is, recorded in the ids it hands out.
+    layer: Layer,
+    /// The objects, sorted into draw order.
+    objects: Vec<SceneObject>,
+    /// The id to give the next object added to the layer.
+    next_id: u64,
+    /// The sequence number to give the next object added or brought to the front.
+    next_sequence: u64,
+    /// The spatial index used to speed up hit testing and culling, if enabled.
+    index: Option<RefCell<SpatialIndex>>,
+}
+
+impl SceneLayer {
+    /// Creates a new, empty layer whose ids record `layer`.
+    pub(crate) fn new(layer: Layer) -> Self {
+        Self {
+            layer,
+            objects: Vec::new(),
+            next_id: 0,
+            next_sequence: 0,
+            index: None,
+        }
+    }
+
+    /// Adds a `Drawable` object to the layer with a z-index of 0.
+    ///
+    /// The object is moved onto the heap and stored as a trait object (`Box<dyn Drawable>`),
+    /// allowing the layer to manage objects of different concrete types. It is drawn
+    /// after every object already in the layer with the same or a lower z-index.
+    ///
+    /// Returns the id used to remove the object later.
+    pub fn add_object(&mut self, object: Box<dyn Drawable>) -> ObjectId {
+        self.add_object_with_z_index(object, 0)
+    }
+
+    /// Adds a `Drawable` object to the layer with the given z-index.
+    ///
+    /// Objects with higher z-indices are drawn on top of objects with lower ones.
+    ///
+    /// Returns the id used to remove the object later.
+    pub fn add_object_with_z_index(&mut self, object: Box<dyn Drawable>, z_index: i32) -> ObjectId {
+        let id = ObjectId {
+            layer: self.layer,
+            index: self.next_id,
+        };
+        self.next_id += 1;
+        let sequence = self.next_sequence();
+
+        // The new object has the highest sequence number, so it goes after every object
+        // with the same or a lower z-index.
+        let index = self
+            .objects
+            .partition_point(|object| object.z_index <= z_index);
+        self.objects.insert(
+            index,
+            SceneObject {
+                id,
+                z_index,
+                sequence,
+                drawable: object,
+            },
+        );
+        self.mark_dirty(id);
+        id
+    }
+
+    /// Reserves space for at least `additional` more objects, to avoid repeated
+    /// reallocation when adding many objects one at a time.
+    pub fn reserve(&mut self, additional: usize) {
+        self.objects.reserve(additional);
+    }
+
+    /// Inserts a `Drawable` object into the layer so that it is drawn at `index` in the
+    /// draw order, shifting the objects after it back by one.
+    ///
+    /// The object takes the z-index of the object currently at `index`, or of the last
+    /// object when inserting at the end, so it lands exactly where requested. In an
+    /// empty layer its z-index is 0.
+    ///
+    /// Returns the id used to remove the object later.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the number of objects in the layer.
+    pub fn insert_object_at(&mut self, index: usize, object: Box<dyn Drawable>) -> ObjectId {
+        assert!(
+            index <= self.objects.len(),
+            "insertion index {index} is past the end of a layer with {} objects",
+            self.objects.len()
+        );
+        let z_index = self
+            .objects
+            .get(index)
+            .or(self.objects.last())
+            .map_or(0, |object| object.z_index);
+
+        let id = ObjectId {
+            layer: self.layer,
+            index: self.next_id,
+        };
+        self.next_id += 1;
+        self.objects.insert(
+            index,
+            SceneObject {
+                id,
+                z_index,
+                sequence: 0,
+                drawable: object,
+            },
+        );
+        self.renumber();
+        self.mark_stale();
+        id
+    }
+
+    /// Removes the object with the given id from the layer.
+    ///
+    /// The remaining objects keep their order. Returns `false` if the object is not in
+    /// the layer, for example because it was already removed.
+    pub fn remove_object(&mut self, id: ObjectId) -> bool {
+        match self.position(id) {
+            Some(index) => {
+                self.objects.remove(index);
+                self.mark_dirty(id);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Keeps only the objects for which `keep` returns `true`, removing the rest in a
+    /// single pass.
+    ///
+    /// `keep` is called once for each object, in draw order. The remaining objects keep
+    /// their order and ids.
+    pub fn retain(&mut self, mut keep: impl FnMut(ObjectId, &dyn Drawable) -> bool) {
+        self.objects
+            .retain(|object| keep(object.id, &*object.drawable));
+        self.mark_stale();
+    }
+
+    /// Removes every object for which `remove` returns `true`, and returns how many
+    /// were removed.
+    ///
+    /// This is the opposite of `retain`.
+    pub fn remove_where(
+        &mut self,
+        mut remove: impl FnMut(ObjectId, &dyn Drawable) -> bool,
+    ) -> usize {
+        let len = self.objects.len();
+        self.retain(|id, drawable| !remove(id, drawable));
+        len - self.objects.len()
+    }
+
+    /// Returns `true` if the object with the given id is in the layer.
+    pub fn contains(&self, id: ObjectId) -> bool {
+        self.position(id).is_some()
+    }
+
+    /// Returns the number of objects in the layer.
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    /// Returns `true` if the layer has no objects.
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    /// Returns an iterator over the ids and objects of the layer, in draw order.
+    pub fn iter(&self) -> impl Iterator<Item = (ObjectId, &dyn Drawable)> {
+        self.objects
+            .iter()
+            .map(|object| (object.id, &*object.drawable))
+    }
+
+    /// Returns the object with the given id as its concrete type.
+    ///
+    /// Returns `None` if the object is not in the layer or is not a `T`.
+    pub fn get_object<T: Drawable + 'static>(&self, id: ObjectId) -> Option<&T> {
+        let index = self.position(id)?;
+        // Dereference the box first, or the box itself would be converted to `Any`.
+        (*self.objects[index].drawable).as_any().downcast_ref()
+    }
+
+    /// Returns the object with the given id as its concrete type, for changing it in
+    /// place.
+    ///
+    /// Returns `None` if the object is not in the layer or is not a `T`.
+    pub fn get_object_mut<T: Drawable + 'static>(&mut self, id: ObjectId) -> Option<&mut T> {
+        let index = self.position(id)?;
+        // The object may be moved or resized through the reference.
+        self.mark_dirty(id);
+        (*self.objects[index].drawable).as_any_mut().downcast_mut()
+    }
+
+    /// Returns an independent copy of the layer, with the same ids, z-indices, and draw
+    /// order.
+    ///
+    /// Returns `None` if any object in the layer can't be copied. See
+    /// `Drawable::clone_box`.
+    pub fn snapshot(&self) -> Option<Self> {
+        let objects = self
+            .objects
+            .iter()
+            .map(|object| {
+                Some(SceneObject {
+                    id: object.id,
+                    z_index: object.z_index,
+                    sequence: object.sequence,
+                    drawable: object.drawable.clone_box()?,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self {
+            layer: self.layer,
+            objects,
+            next_id: self.next_id,
+            next_sequence: self.next_sequence,
+            index: self
+                .index
+                .as_ref()
+                .map(|index| RefCell::new(SpatialIndex::new(index.borrow().region()))),
+        })
+    }
+
+    /// Returns the ids of the objects whose bounds contain `point`, front to back.
+    ///
+    /// The first id is the object drawn on top, such as the one a click should go to.
+    /// Objects without bounds are never hit. See `Drawable::bounds`.
+    pub fn hit_test(&self, point: Vector2) -> Vec<ObjectId> {
+        if let Some(index) = self.spatial_index() {
+            let mut hits = index.query_point(point);
+            hits.sort_unstable_by(|(a, _), (b, _)| b.cmp(a));
+            return hits.into_iter().map(|(_, id)| id).collect();
+        }
+        self.objects
+            .iter()
+            .rev()
+            .filter(|object| {
+                object
+                    .drawable
+                    .bounds()
+                    .is_some_and(|bounds| bounds.contains(point))
+            })
+            .map(|object| object.id)
+            .collect()
+    }
+
+    /// Returns the z-index of the object with the given id, or `None` if it is not in
+    /// the layer.
+    pub fn z_index(&self, id: ObjectId) -> Option<i32> {
+        self.position(id).map(|index| self.objects[index].z_index)
+    }
+
+    /// Changes the z-index of the object with the given id.
+    ///
+    /// The object keeps its place among the objects with the new z-index as if it had
+    /// always had it. Returns `false` if the object is not in the layer.
+    pub fn set_z_index(&mut self, id: ObjectId, z_index: i32) -> bool {
+        let Some(index) = self.position(id) else {
+            return false;
+        };
+        if self.objects[index].z_index != z_index {
+            self.objects[index].z_index = z_index;
+            self.sort();
+            self.mark_dirty(id);
+        }
+        true
+    }
+
+    /// Moves the object with the given id in front of every other object with the
+    /// same z-index, such as when the user starts dragging it.
+    ///
+    /// Objects with a higher z-index are still drawn on top of it. Returns `false` if
+    /// the object is not in the layer.
+    pub fn move_to_front(&mut self, id: ObjectId) -> bool {
+        let Some(index) = self.position(id) else {
+            return false;
+        };
+        self.objects[index].sequence = self.next_sequence();
+        self.sort();
+        self.mark_dirty(id);
+        true
+    }
+
+    /// Moves the object with the given id behind every other object with the same
+    /// z-index, such as a background that finished loading after the content.
+    ///
+    /// Objects with a lower z-index are still drawn behind it. Returns `false` if the
+    /// object is not in the layer.
+    pub fn move_to_back(&mut self, id: ObjectId) -> bool {
+        let Some(index) = self.position(id) else {
+            return false;
+        };
+        let z_index = self.objects[index].z_index;
+        let layer_start = self
+            .objects
+            .partition_point(|object| object.z_index < z_index);
+        self.objects[layer_start..=index].rotate_right(1);
+        self.renumber();
+        self.mark_stale();
+        true
+    }
+
+    /// Returns the z-index and sequence number of the object with the given id.
+    pub(crate) fn draw_key(&self, id: ObjectId) -> Option<DrawKey> {
+        self.position(id).map(|index| self.objects[index].key())
+    }
+
+    /// Removes the object with the given id, returning it along with its draw key so
+    /// that `restore_object` can put it back exactly where it was.
+    pub(crate) fn take_object(&mut self, id: ObjectId) -> Option<(DrawKey, Box<dyn Drawable>)> {
+        let index = self.position(id)?;
+        let object = self.objects.remove(index);
+        self.mark_dirty(id);
+        Some((object.key(), object.drawable))
+    }
+
+    /// Puts an object removed by `take_object` back with its original id, at the place
+    /// in the draw order given by `key`.
+    ///
+    /// If the layer was renumbered since `key` was taken, another object may now have
+    /// the same key. The restored object then goes in front of it, and the layer is
+    /// renumbered so that every object keeps a distinct key.
+    pub(crate) fn restore_object(
+        &mut self,
+        id: ObjectId,
+        key: DrawKey,
+        drawable: Box<dyn Drawable>,
+    ) {
+        let (z_index, sequence) = key;
+        let index = self.objects.partition_point(|object| object.key() < key);
+        let taken = self
+            .objects
+            .get(index)
+            .is_some_and(|object| object.key() == key);
+        self.objects.insert(
+            index,
+            SceneObject {
+                id,
+                z_index,
+                sequence,
+                drawable,
+            },
+        );
+        if taken {
+            self.renumber();
+            self.mark_stale();
+        } else {
+            // Objects added later must still be drawn after it.
+            self.next_sequence = self.next_sequence.max(sequence + 1);
+            self.mark_dirty(id);
+        }
+    }
+
+    /// Replaces the object with the given id, keeping its place in the draw order, and
+    /// returns the previous object.
+    pub(crate) fn replace_object(
+        &mut self,
+        id: ObjectId,
+        drawable: Box<dyn Drawable>,
+    ) -> Option<Box<dyn Drawable>> {
+        let index = self.position(id)?;
+        self.mark_dirty(id);
+        Some(std::mem::replace(
+            &mut self.objects[index].drawable,
+            drawable,
+        ))
+    }
+
+    /// Returns the index of the object with the given id in the draw order.
+    fn position(&self, id: ObjectId) -> Option<usize> {
+        self.objects.iter().position(|object| object.id == id)
+    }
+
+    /// Returns a new sequence number, higher than any given out before.
+    fn next_sequence(&mut self) -> u64 {
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        sequence
+    }
+
+    /// Renumbers the sequence numbers to match the current draw order, after an object
+    /// was placed between two others.
+    fn renumber(&mut self) {
+        for (sequence, object) in self.objects.iter_mut().enumerate() {
+            object.sequence = sequence as u64;
+        }
+        self.next_sequence = self.objects.len() as u64;
+    }
+
+    /// Restores the draw order after an object's z-index or sequence number changed.
+    fn sort(&mut self) {
+        // Only one object is out of place, so this is close to linear.
+        self.objects.sort_by_key(SceneObject::key);
+    }
+
+    /// Enables a spatial index covering `region`, or disables it with `None`.
+    ///
+    /// With an index, `hit_test` and `draw_all` only check the objects near the point
+    /// or the visible area, instead of every object in the layer. This pays off for
+    /// layers with thousands of objects. Objects outside `region` or without bounds
+    /// are still checked every time, so results are the same either way.
+    ///
+    /// The index caches each object's bounds. It is updated automatically when objects
+    /// are added, removed, reordered, returned by `get_object_mut`, or animated by
+    /// `update_all`. If an object's bounds change any other way, such as text being
+    /// laid out again for a new window size, call `invalidate_spatial_index`.
+    pub fn set_spatial_index(&mut self, region: Option<Rect>) {
+        self.index = region.map(|region| RefCell::new(SpatialIndex::new(region)));
+    }
+
+    /// Makes the spatial index, if enabled, re-read the bounds of every object before
+    /// it is next used.
+    pub fn invalidate_spatial_index(&mut self) {
+        self.mark_stale();
+    }
+
+    /// Marks one object in the spatial index, if enabled, as changed.
+    fn mark_dirty(&mut self, id: ObjectId) {
+        if let Some(index) = &mut self.index {
+            index.get_mut().mark_dirty(id);
+        }
+    }
+
+    /// Marks every object in the spatial index, if enabled, as changed, such as after
+    /// the sequence numbers were renumbered.
+    fn mark_stale(&mut self) {
+        if let Some(index) = &mut self.index {
+            index.get_mut().mark_stale();
+        }
+    }
+
+    /// Returns the spatial index, if enabled, after bringing it up to date with the
+    /// objects.
+    fn spatial_index(&self) -> Option<RefMut<'_, SpatialIndex>> {
+        let mut index = self.index.as_ref()?.borrow_mut();
+        if index.is_stale() {
+            index.rebuild(
+                self.objects
+                    .iter()
+                    .map(|object| (object.id, object.key(), object.drawable.bounds())),
+            );
+        } else {
+            let mut dirty = index.take_dirty();
+            if !dirty.is_empty() {
+                for object in &self.objects {
+                    if dirty.remove(&object.id) {
+                        index.insert(object.id, object.key(), object.drawable.bounds());
+                    }
+                }
+                // Whatever wasn't found has been removed.
+                for id in dirty {
+                    index.remove(id);
+                }
+            }
+            // Objects such as text only have bounds once they have been drawn.
+            for (id, key) in index.unbounded() {
+                let bounds = self
+                    .object_with_key(key)
+                    .and_then(|object| object.drawable.bounds());
+                if bounds.is_some() {
+                    index.insert(id, key, bounds);
+                }
+            }
+        }
+        Some(index)
+    }
+
+    /// Returns the object with the given draw key, using the sorted draw order.
+    fn object_with_key(&self, key: DrawKey) -> Option<&SceneObject> {
+        let index = self
+            .objects
+            .binary_search_by_key(&key, SceneObject::key)
+            .ok()?;
+        Some(&self.objects[index])
+    }
+
+    /// Advances the animation state of all objects in the layer by `dt`.
+    ///
+    /// This method iterates through all the `Drawable` objects in the layer and calls
+    /// their respective `update` methods.
+    pub fn update_all(&mut self, dt: Duration) {
+        for object in &mut self.objects {
+            object.drawable.update(dt);
+        }
+        // Any object may have moved.
+        self.mark_stale();
+    }
+
+    /// Draws all objects in the layer using the provided `DrawingContext`.
+    ///
+    /// This method iterates through all the `Drawable` objects in the layer in draw
+    /// order and calls their respective `draw` methods, passing the drawing context to
+    /// each.
+    ///
+    /// Objects whose bounds lie entirely outside the visible part of the render target
+    /// are skipped. Objects without bounds are always drawn. See `Drawable::bounds`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if any of the `draw` calls fail.
+    pub fn draw_all(&self, context: &DrawingContext) -> Result<()> {
+        let visible = context.visible_rect();
+        if let (Some(visible), Some(index)) = (visible, self.spatial_index()) {
+            let mut keys = index.query_rect(&visible);
+            drop(index);
+            keys.sort_unstable();
+            keys.dedup();
+            for key in keys {
+                if let Some(object) = self.object_with_key(key) {
+                    object.drawable.draw(context)?;
+                }
+            }
+            return Ok(());
+        }
+        for object in &self.objects {
+            let culled = visible
+                .zip(object.drawable.bounds())
+                .is_some_and(|(visible, bounds)| !bounds.intersects(&visible));
+            if !culled {
+                object.drawable.draw(context)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Renders all objects in the layer through `renderer`, in draw order.
+    ///
+    /// Unlike `draw_all`, nothing is culled, since a `Renderer` has no visible area.
+    pub fn render_all(&self, renderer: &mut dyn Renderer) {
+        for object in &self.objects {
+            object.drawable.render(renderer);
+        }
+    }
+}
 
 /// Represents a scene containing a collection of `Drawable` objects.
 ///
@@ -9,41 +647,407 @@ use crate::core::render::drawing_context::DrawingContext;
 /// for a particular view. It holds a list of objects that implement the `Drawable`
 /// trait, allowing for a heterogeneous collection of shapes, text, and other
 /// graphical elements.
+///
+/// The objects are split into three layers, the background, the content, and the
+/// overlay, which are drawn in that order regardless of the z-indices of their
+/// objects. Objects added directly to the scene go to the content layer; use
+/// `layer_mut` to add objects to the others. Methods that take an `ObjectId` work for
+/// objects in any layer.
+///
+/// The background and content layers are viewed through the scene's `Camera`, which
+/// pans and zooms them without changing the objects. See `set_camera`.
 pub struct Scene {
-    /// A vector of heap-allocated drawable objects.
-    objects: Vec<Box<dyn Drawable>>,
+    /// The layers, in the order of `Layer::ALL`.
+    layers: [SceneLayer; 3],
+    /// The view of the background and content layers.
+    camera: Camera,
+    /// Whether `draw_all` measures how long it takes.
+    draw_timing: bool,
+    /// How long the last timed call to `draw_all` took.
+    last_draw_time: Cell<Option<Duration>>,
 }
 
 impl Scene {
     /// Creates a new, empty `Scene`.
     pub fn new() -> Self {
         Self {
-            objects: Vec::new(),
+            layers: Layer::ALL.map(SceneLayer::new),
+            camera: Camera::new(),
+            draw_timing: false,
+            last_draw_time: Cell::new(None),
         }
     }
 
-    /// Adds a `Drawable` object to the scene.
+    /// Creates a new, empty `Scene` with space for at least `capacity` objects in the
+    /// content layer.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut scene = Self::new();
+        scene.layer_mut(Layer::Content).reserve(capacity);
+        scene
+    }
+
+    /// Enables a spatial index covering `region` on every layer, to speed up hit
+    /// testing and culling in scenes with thousands of objects.
     ///
-    /// The object is moved onto the heap and stored as a trait object (`Box<dyn Drawable>`),
-    /// allowing the scene to manage objects of different concrete types.
-    pub fn add_object(&mut self, object: Box<dyn Drawable>) {
-        self.objects.push(object);
+    /// `region` should cover the area most objects lie in, such as the window or the
+    /// whole document. See `SceneLayer::set_spatial_index`.
+    pub fn with_spatial_index(mut self, region: Rect) -> Self {
+        for layer in &mut self.layers {
+            layer.set_spatial_index(Some(region));
+        }
+        self
+    }
+
+    /// Makes the spatial index of every layer, if enabled, re-read the bounds of every
+    /// object before it is next used. See `SceneLayer::invalidate_spatial_index`.
+    pub fn invalidate_spatial_index(&mut self) {
+        for layer in &mut self.layers {
+            layer.invalidate_spatial_index();
+        }
     }
 
-    /// Draws all objects in the scene using the provided `DrawingContext`.
+    /// Returns one layer of the scene.
+    pub fn layer(&self, layer: Layer) -> &SceneLayer {
+        &self.layers[layer.index()]
+    }
+
+    /// Returns one layer of the scene, for adding or rearranging its objects.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use my_gui::core::render::{
+    ///     color::Color,
+    ///     objects::primitives::Rectangle,
+    ///     scene::{Layer, Scene},
+    /// };
+    ///
+    /// let mut scene = Scene::new();
+    /// scene
+    ///     .layer_mut(Layer::Overlay)
+    ///     .add_object(Box::new(Rectangle::outlined(10.0, 10.0, 50.0, 50.0, Color::YELLOW, 1.0)));
+    /// ```
+    pub fn layer_mut(&mut self, layer: Layer) -> &mut SceneLayer {
+        &mut self.layers[layer.index()]
+    }
+
+    /// Adds a `Drawable` object to the content layer with a z-index of 0.
     ///
-    /// This method iterates through all the `Drawable` objects in the scene and calls
-    /// their respective `draw` methods, passing the drawing context to each.
+    /// Returns the id used to remove the object later. See `SceneLayer::add_object`.
+    pub fn add_object(&mut self, object: Box<dyn Drawable>) -> ObjectId {
+        self.layer_mut(Layer::Content).add_object(object)
+    }
+
+    /// Adds a `Drawable` object to the content layer with the given z-index.
+    ///
+    /// Returns the id used to remove the object later.
+    pub fn add_object_with_z_index(&mut self, object: Box<dyn Drawable>, z_index: i32) -> ObjectId {
+        self.layer_mut(Layer::Content)
+            .add_object_with_z_index(object, z_index)
+    }
+
+    /// Inserts a `Drawable` object into the content layer so that it is drawn at
+    /// `index` in the layer's draw order. See `SceneLayer::insert_object_at`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the number of objects in the content layer.
+    pub fn insert_object_at(&mut self, index: usize, object: Box<dyn Drawable>) -> ObjectId {
+        self.layer_mut(Layer::Content)
+            .insert_object_at(index, object)
+    }
+
+    /// Removes the object with the given id from the scene.
+    ///
+    /// Returns `false` if the object is not in the scene, for example because it was
+    /// already removed.
+    pub fn remove_object(&mut self, id: ObjectId) -> bool {
+        self.layer_mut(id.layer).remove_object(id)
+    }
+
+    /// Keeps only the objects for which `keep` returns `true`, removing the rest.
+    ///
+    /// `keep` is called once for each object, layer by layer from the background to
+    /// the overlay, in draw order. Use `Drawable::as_any` to check an object's concrete
+    /// type. See `SceneLayer::retain`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use my_gui::core::render::{objects::primitives::Ellipse, scene::Scene};
+    ///
+    /// let mut scene = Scene::new();
+    /// // Remove every ellipse that has drifted below the window.
+    /// scene.retain(|_, drawable| {
+    ///     drawable
+    ///         .as_any()
+    ///         .downcast_ref::<Ellipse>()
+    ///         .is_none_or(|ellipse| ellipse.center_y < 600.0)
+    /// });
+    /// ```
+    pub fn retain(&mut self, mut keep: impl FnMut(ObjectId, &dyn Drawable) -> bool) {
+        for layer in &mut self.layers {
+            layer.retain(&mut keep);
+        }
+    }
+
+    /// Removes every object for which `remove` returns `true`, and returns how many
+    /// were removed. See `retain`.
+    pub fn remove_where(
+        &mut self,
+        mut remove: impl FnMut(ObjectId, &dyn Drawable) -> bool,
+    ) -> usize {
+        self.layers
+            .iter_mut()
+            .map(|layer| layer.remove_where(&mut remove))
+            .sum()
+    }
+
+    /// Returns `true` if the object with the given id is in the scene.
+    pub fn contains(&self, id: ObjectId) -> bool {
+        self.layer(id.layer).contains(id)
+    }
+
+    /// Returns the number of objects in all layers of the scene.
+    pub fn len(&self) -> usize {
+        self.layers.iter().map(SceneLayer::len).sum()
+    }
+
+    /// Returns `true` if no layer of the scene has any objects.
+    pub fn is_empty(&self) -> bool {
+        self.layers.iter().all(SceneLayer::is_empty)
+    }
+
+    /// Returns the object with the given id as its concrete type.
+    ///
+    /// Returns `None` if the object is not in the scene or is not a `T`.
+    pub fn get_object<T: Drawable + 'static>(&self, id: ObjectId) -> Option<&T> {
+        self.layer(id.layer).get_object(id)
+    }
+
+    /// Returns the object with the given id as its concrete type, for changing it in
+    /// place.
+    ///
+    /// Returns `None` if the object is not in the scene or is not a `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use my_gui::core::render::{objects::text_object::TextObject, scene::Scene};
+    ///
+    /// let mut scene = Scene::new();
+    /// let status = scene.add_object(Box::new(TextObject::new("Ready", 10.0, 10.0)));
+    ///
+    /// if let Some(text) = scene.get_object_mut::<TextObject>(status) {
+    ///     text.set_text("Loading...");
+    /// }
+    /// ```
+    pub fn get_object_mut<T: Drawable + 'static>(&mut self, id: ObjectId) -> Option<&mut T> {
+        self.layer_mut(id.layer).get_object_mut(id)
+    }
+
+    /// Returns an independent copy of the scene, such as a state to return to on undo.
+    ///
+    /// Every object is copied with `Drawable::clone_box`, so changing the copy never
+    /// changes the original. Ids are preserved: an id from the original refers to the
+    /// same object in the copy, and objects added to either scene afterwards get the
+    /// same new ids.
+    ///
+    /// Returns `None` if any object can't be copied, such as a user-defined drawable
+    /// that doesn't implement `clone_box`.
+    pub fn snapshot(&self) -> Option<Self> {
+        let [background, content, overlay] = &self.layers;
+        Some(Self {
+            layers: [
+                background.snapshot()?,
+                content.snapshot()?,
+                overlay.snapshot()?,
+            ],
+            camera: self.camera,
+            draw_timing: self.draw_timing,
+            last_draw_time: Cell::new(None),
+        })
+    }
+
+    /// Returns the ids of the objects whose bounds contain `point`, front to back.
+    ///
+    /// `point` is in screen coordinates, such as the position of a mouse event. It is
+    /// converted to world coordinates with the camera for the layers that follow it.
+    ///
+    /// Hits in the overlay come first, then the content, then the background. Within a
+    /// layer, the object drawn on top comes first. See `Drawable::bounds`.
+    pub fn hit_test(&self, point: Vector2) -> Vec<ObjectId> {
+        let world = self.camera.screen_to_world(point);
+        Layer::ALL
+            .iter()
+            .rev()
+            .flat_map(|&layer| {
+                let point = if layer.follows_camera() { world } else { point };
+                self.layer(layer).hit_test(point)
+            })
+            .collect()
+    }
+
+    /// Returns the camera the background and content layers are viewed with.
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
+
+    /// Returns the camera, for panning or zooming the view.
+    pub fn camera_mut(&mut self) -> &mut Camera {
+        &mut self.camera
+    }
+
+    /// Sets the camera the background and content layers are viewed with.
+    ///
+    /// The objects themselves are unchanged. Culling and `hit_test` take the camera
+    /// into account.
+    pub fn set_camera(&mut self, camera: Camera) {
+        self.camera = camera;
+    }
+
+    /// Returns the point of the world shown at `point` in the window. See
+    /// `Camera::screen_to_world`.
+    pub fn screen_to_world(&self, point: Vector2) -> Vector2 {
+        self.camera.screen_to_world(point)
+    }
+
+    /// Returns the point in the window where `point` of the world is shown. See
+    /// `Camera::world_to_screen`.
+    pub fn world_to_screen(&self, point: Vector2) -> Vector2 {
+        self.camera.world_to_screen(point)
+    }
+
+    /// Returns the z-index of the object with the given id within its layer, or `None`
+    /// if it is not in the scene.
+    pub fn z_index(&self, id: ObjectId) -> Option<i32> {
+        self.layer(id.layer).z_index(id)
+    }
+
+    /// Changes the z-index of the object with the given id within its layer.
+    ///
+    /// Returns `false` if the object is not in the scene.
+    pub fn set_z_index(&mut self, id: ObjectId, z_index: i32) -> bool {
+        self.layer_mut(id.layer).set_z_index(id, z_index)
+    }
+
+    /// Moves the object with the given id in front of every other object with the
+    /// same z-index in its layer.
+    ///
+    /// Returns `false` if the object is not in the scene.
+    pub fn move_to_front(&mut self, id: ObjectId) -> bool {
+        self.layer_mut(id.layer).move_to_front(id)
+    }
+
+    /// Moves the object with the given id behind every other object with the same
+    /// z-index in its layer.
+    ///
+    /// Returns `false` if the object is not in the scene.
+    pub fn move_to_back(&mut self, id: ObjectId) -> bool {
+        self.layer_mut(id.layer).move_to_back(id)
+    }
+
+    /// Enables or disables measuring how long each call to `draw_all` takes, which is
+    /// reported by `stats`.
+    ///
+    /// Timing is disabled by default. While it is disabled, drawing doesn't read the
+    /// clock at all.
+    pub fn set_draw_timing(&mut self, enabled: bool) {
+        self.draw_timing = enabled;
+        if !enabled {
+            self.last_draw_time.set(None);
+        }
+    }
+
+    /// Returns statistics about the scene, such as for a diagnostics overlay.
+    ///
+    /// The draw time is only reported while draw timing is enabled. See
+    /// `set_draw_timing`.
+    pub fn stats(&self) -> SceneStats {
+        SceneStats {
+            object_count: self.len(),
+            draw_time: self.last_draw_time.get(),
+        }
+    }
+
+    /// Advances the animation state of all objects in the scene by `dt`.
+    pub fn update_all(&mut self, dt: Duration) {
+        for layer in &mut self.layers {
+            layer.update_all(dt);
+        }
+    }
+
+    /// Draws all objects in the scene using the provided `DrawingContext`, one layer at
+    /// a time from the background to the overlay.
+    ///
+    /// The background and content layers are drawn through the camera, and the overlay
+    /// in screen coordinates.
+    ///
+    /// If draw timing is enabled, the time taken is recorded for `stats`.
     ///
     /// # Errors
     ///
     /// This function will return an error if any of the `draw` calls fail.
     pub fn draw_all(&self, context: &DrawingContext) -> Result<()> {
-        for object in &self.objects {
-            object.draw(context)?;
+        let start = self.draw_timing.then(Instant::now);
+        let view = self.camera.view_matrix();
+        for layer in Layer::ALL {
+            if layer.follows_camera() && !self.camera.is_identity() {
+                context.with_transform(&view, || self.layer(layer).draw_all(context))?;
+            } else {
+                self.layer(layer).draw_all(context)?;
+            }
+        }
+        if let Some(start) = start {
+            self.last_draw_time.set(Some(start.elapsed()));
         }
         Ok(())
     }
+
+    /// Renders the scene through a backend-independent `Renderer`, such as the
+    /// `RecordingRenderer` used in tests.
+    ///
+    /// The layers are rendered in the same order and with the same camera as in
+    /// `draw_all`, and each object is drawn with `Drawable::render`. The target isn't
+    /// cleared first.
+    pub fn render(&self, renderer: &mut dyn Renderer) {
+        let view = self.camera.view_matrix();
+        for layer in Layer::ALL {
+            if layer.follows_camera() && !self.camera.is_identity() {
+                renderer.with_transform(&view, |renderer| self.layer(layer).render_all(renderer));
+            } else {
+                self.layer(layer).render_all(renderer);
+            }
+        }
+    }
+}
+
+impl Extend<Box<dyn Drawable>> for SceneLayer {
+    /// Adds every object to the layer with a z-index of 0, in order.
+    ///
+    /// This has the same result as calling `add_object` for each object, but moves the
+    /// objects with higher z-indices out of the way only once.
+    fn extend<I: IntoIterator<Item = Box<dyn Drawable>>>(&mut self, objects: I) {
+        let index = self.objects.partition_point(|object| object.z_index <= 0);
+        let added: Vec<SceneObject> = objects
+            .into_iter()
+            .map(|drawable| {
+                let id = ObjectId {
+                    layer: self.layer,
+                    index: self.next_id,
+                };
+                self.next_id += 1;
+                SceneObject {
+                    id,
+                    z_index: 0,
+                    sequence: self.next_sequence(),
+                    drawable,
+                }
+            })
+            .collect();
+        self.objects.splice(index..index, added);
+        self.mark_stale();
+    }
 }
 
 impl Default for Scene {
@@ -51,3 +1055,211 @@ impl Default for Scene {
         Self::new()
     }
 }
+
+impl Extend<Box<dyn Drawable>> for Scene {
+    /// Adds every object to the content layer with a z-index of 0, in order. See
+    /// `SceneLayer::extend`.
+    fn extend<I: IntoIterator<Item = Box<dyn Drawable>>>(&mut self, objects: I) {
+        self.layer_mut(Layer::Content).extend(objects);
+    }
+}
+
+impl FromIterator<Box<dyn Drawable>> for Scene {
+    /// Creates a scene with every object in the content layer, in order.
+    fn from_iter<I: IntoIterator<Item = Box<dyn Drawable>>>(objects: I) -> Self {
+        let mut scene = Self::new();
+        scene.extend(objects);
+        scene
+    }
+}
+
+/// Creates a `Scene` with the given objects in its content layer, in draw order.
+///
+/// Each object is boxed automatically, so this is a shorter way to build a fixed scene
+/// than calling `Scene::add_object` for each one.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use my_gui::{
+///     core::render::{
+///         color::Color,
+///         objects::{primitives::Rectangle, text_object::TextObject},
+///     },
+///     scene,
+/// };
+///
+/// let scene = scene![
+///     Rectangle::new(10.0, 10.0, 200.0, 100.0, Color::BLUE),
+///     TextObject::new("Hello", 20.0, 20.0),
+/// ];
+/// ```
+#[macro_export]
+macro_rules! scene {
+    ($($object:expr),* $(,)?) => {
+        <$crate::core::render::scene::Scene as ::std::iter::FromIterator<_>>::from_iter([
+            $(::std::boxed::Box::new($object)
+                as ::std::boxed::Box<dyn $crate::core::render::drawable::Drawable>),*
+        ])
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::backend::{DrawCommand, RecordingRenderer};
+    use crate::core::render::color::Color;
+    use crate::core::render::objects::primitives::{Ellipse, Rectangle};
+
+    /// Creates a rectangle told apart from the others by its x-coordinate.
+    fn rectangle(x: f32) -> Box<dyn Drawable> {
+        Box::new(Rectangle::new(x, 0.0, 10.0, 10.0, Color::BLUE))
+    }
+
+    /// Returns the x-coordinates of the rectangles in a layer, in draw order.
+    fn draw_order(scene: &Scene, layer: Layer) -> Vec<f32> {
+        scene
+            .layer(layer)
+            .iter()
+            .filter_map(|(_, object)| object.as_any().downcast_ref::<Rectangle>())
+            .map(|rectangle| rectangle.x)
+            .collect()
+    }
+
+    #[test]
+    fn ids_stay_valid_when_other_objects_are_removed() {
+        let mut scene = Scene::new();
+        let a = scene.add_object(rectangle(1.0));
+        let b = scene.add_object(rectangle(2.0));
+        let c = scene.add_object(rectangle(3.0));
+
+        assert!(scene.remove_object(b));
+        assert!(!scene.remove_object(b));
+        assert!(scene.contains(a) && !scene.contains(b) && scene.contains(c));
+        assert_eq!(scene.len(), 2);
+        assert_eq!(draw_order(&scene, Layer::Content), [1.0, 3.0]);
+        assert_eq!(scene.get_object::<Rectangle>(c).map(|r| r.x), Some(3.0));
+        assert!(scene.get_object::<Rectangle>(b).is_none());
+    }
+
+    #[test]
+    fn re_added_objects_get_new_ids_and_are_drawn_last() {
+        let mut scene = Scene::new();
+        let a = scene.add_object(rectangle(1.0));
+        scene.add_object(rectangle(2.0));
+        scene.add_object(rectangle(3.0));
+
+        scene.remove_object(a);
+        let again = scene.add_object(rectangle(1.0));
+        assert_ne!(again, a);
+        assert!(!scene.contains(a));
+        assert_eq!(draw_order(&scene, Layer::Content), [2.0, 3.0, 1.0]);
+
+        // Removing the old id again doesn't touch the re-added object.
+        assert!(!scene.remove_object(a));
+        assert!(scene.contains(again));
+    }
+
+    #[test]
+    fn z_index_takes_precedence_over_insertion_order() {
+        let mut scene = Scene::new();
+        scene.add_object_with_z_index(rectangle(1.0), 1);
+        scene.add_object_with_z_index(rectangle(2.0), 0);
+        scene.add_object_with_z_index(rectangle(3.0), 1);
+        let d = scene.add_object_with_z_index(rectangle(4.0), 0);
+        assert_eq!(draw_order(&scene, Layer::Content), [2.0, 4.0, 1.0, 3.0]);
+
+        scene.remove_object(d);
+        scene.add_object_with_z_index(rectangle(5.0), 0);
+        assert_eq!(draw_order(&scene, Layer::Content), [2.0, 5.0, 1.0, 3.0]);
+    }
+
+    #[test]
+    fn ids_find_objects_in_their_own_layer() {
+        let mut scene = Scene::new();
+        let content = scene.add_object(rectangle(1.0));
+        let overlay = scene.layer_mut(Layer::Overlay).add_object(rectangle(2.0));
+
+        // Each layer numbers its objects separately, but the ids still differ.
+        assert_ne!(content, overlay);
+        assert_eq!(overlay.layer(), Layer::Overlay);
+
+        assert!(scene.remove_object(overlay));
+        assert!(scene.contains(content));
+        assert!(scene.layer(Layer::Overlay).is_empty());
+        assert_eq!(draw_order(&scene, Layer::Content), [1.0]);
+    }
+
+    #[test]
+    fn objects_downcast_only_to_their_own_type() {
+        let mut scene = Scene::new();
+        let square = scene.add_object(rectangle(1.0));
+        let ellipse = scene.add_object(Box::new(Ellipse::new(5.0, 5.0, 5.0, 5.0, Color::RED)));
+
+        assert!(scene.get_object::<Rectangle>(square).is_some());
+        assert!(scene.get_object::<Ellipse>(square).is_none());
+        assert!(scene.get_object::<Ellipse>(ellipse).is_some());
+        assert!(scene.get_object::<Rectangle>(ellipse).is_none());
+
+        assert!(scene.get_object_mut::<Ellipse>(square).is_none());
+        assert!(scene.get_object_mut::<Rectangle>(ellipse).is_none());
+        if let Some(ellipse) = scene.get_object_mut::<Ellipse>(ellipse) {
+            ellipse.center_x = 20.0;
+        }
+        assert_eq!(
+            scene.get_object::<Ellipse>(ellipse).map(|e| e.center_x),
+            Some(20.0)
+        );
+
+        // A failed downcast leaves the object in place.
+        assert_eq!(scene.len(), 2);
+    }
+
+    /// Returns the x-coordinates of the rectangles rendered by `scene`, in order, with
+    /// the horizontal translation they were rendered with.
+    fn render_order(scene: &Scene) -> Vec<(f32, f32)> {
+        let mut renderer = RecordingRenderer::new();
+        scene.render(&mut renderer);
+        renderer
+            .drawn()
+            .into_iter()
+            .filter_map(|(transform, command)| match command {
+                DrawCommand::Rect { rect, .. } => Some((rect.x, transform.M31)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn render_draws_the_layers_in_order_and_each_by_z_index() {
+        let mut scene = Scene::new();
+        scene.add_object_with_z_index(rectangle(1.0), 1);
+        scene.add_object(rectangle(2.0));
+        scene.layer_mut(Layer::Overlay).add_object(rectangle(3.0));
+        scene
+            .layer_mut(Layer::Background)
+            .add_object(rectangle(4.0));
+        scene.add_object(rectangle(5.0));
+
+        assert_eq!(
+            render_order(&scene),
+            [(4.0, 0.0), (2.0, 0.0), (5.0, 0.0), (1.0, 0.0), (3.0, 0.0)]
+        );
+    }
+
+    #[test]
+    fn render_views_every_layer_but_the_overlay_through_the_camera() {
+        let mut scene = Scene::new();
+        scene
+            .layer_mut(Layer::Background)
+            .add_object(rectangle(1.0));
+        scene.add_object(rectangle(2.0));
+        scene.layer_mut(Layer::Overlay).add_object(rectangle(3.0));
+        scene.set_camera(Camera::new().with_position(50.0, 0.0));
+
+        assert_eq!(
+            render_order(&scene),
+            [(1.0, -50.0), (2.0, -50.0), (3.0, 0.0)]
+        );
+    }
+}
@@ -0,0 +1,232 @@
+//! # Scene Descriptions
+//!
+//! This module converts a `Scene` to and from a `SceneDescription`, a plain list of
+//! the scene's objects that can be serialized with serde, for example to load a layout
+//! designed in a JSON file or to save a drawing made by the user.
+//!
+//! Each object is tagged with its type name, so a JSON description looks like this:
+//!
+//! ```json
+//! {
+//!   "objects": [
+//!     { "layer": "Background", "type": "Grid", "cell_width": 20.0, ... },
+//!     { "z_index": 1, "type": "Rectangle", "x": 10.0, "y": 10.0, ... }
+//!   ]
+//! }
+//! ```
+//!
+//! Loading a description with an unknown type fails with serde's error naming the type
+//! and listing the supported ones.
+//!
+//! Containers (`Canvas`, `Group`, `VStack`, and `HStack`) can't be described. Their
+//! children are arbitrary `Box<dyn Drawable>` objects, which may include user-defined
+//! drawables and images with no description of their own, so a scene containing a
+//! container fails with `SceneDescriptionError::UnsupportedObject`. Describe the
+//! children as top-level objects instead.
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::render::drawable::Drawable;
+use crate::core::render::objects::label::Label;
+use crate::core::render::objects::primitives::{
+    Arc, BezierCurve, Ellipse, Grid, Line, Marker, MarkerSet, Path, PieSlice, Polygon, Polyline,
+    Rectangle, Triangle,
+};
+use crate::core::render::objects::scroll_bar::ScrollBar;
+use crate::core::render::objects::text_object::TextObject;
+use crate::core::render::scene::{Layer, ObjectId, Scene};
+
+/// An error that occurs when describing a `Scene`.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum SceneDescriptionError {
+    /// The scene contains an object whose type can't be described, such as a
+    /// user-defined drawable, an image, or a container.
+    #[error("object {0:?} is not a built-in drawable that can be described")]
+    UnsupportedObject(ObjectId),
+}
+
+/// A serializable description of every object in a `Scene`.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct SceneDescription {
+    /// The objects, in draw order.
+    pub objects: Vec<ObjectDescription>,
+}
+
+/// A serializable description of one object in a `Scene`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ObjectDescription {
+    /// The layer the object is in.
+    #[serde(default)]
+    pub layer: Layer,
+    /// The z-index of the object within its layer.
+    #[serde(default)]
+    pub z_index: i32,
+    /// The object itself.
+    #[serde(flatten)]
+    pub drawable: DrawableDescription,
+}
+
+/// Declares `DrawableDescription` with a variant for each describable drawable type,
+/// along with the conversions to and from `Drawable` objects.
+macro_rules! drawable_descriptions {
+    ($($name:ident),* $(,)?) => {
+        /// One of the built-in drawables, tagged with its type name.
+        #[derive(Clone, Serialize, Deserialize)]
+        #[serde(tag = "type")]
+        pub enum DrawableDescription {
+            $(
+                #[doc = concat!("A `", stringify!($name), "`.")]
+                $name($name),
+            )*
+        }
+
+        impl DrawableDescription {
+            /// Returns a description of `drawable`, or `None` if it isn't one of the
+            /// built-in drawables.
+            pub fn from_drawable(drawable: &dyn Drawable) -> Option<Self> {
+                let drawable = drawable.as_any();
+                $(
+                    if let Some(object) = drawable.downcast_ref::<$name>() {
+                        return Some(DrawableDescription::$name(object.clone()));
+                    }
+                )*
+                None
+            }
+
+            /// Converts the description into a drawable for adding to a `Scene`.
+            pub fn into_drawable(self) -> Box<dyn Drawable> {
+                match self {
+                    $(DrawableDescription::$name(object) => Box::new(object),)*
+                }
+            }
+        }
+    };
+}
+
+drawable_descriptions!(
+    Rectangle,
+    Ellipse,
+    Line,
+    Triangle,
+    Polygon,
+    Polyline,
+    BezierCurve,
+    Arc,
+    PieSlice,
+    Path,
+    Marker,
+    MarkerSet,
+    Grid,
+    TextObject,
+    Label,
+    ScrollBar,
+);
+
+impl Scene {
+    /// Describes every object in the scene, along with its layer and z-index.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the scene contains an object that isn't
+    /// one of the built-in drawables in `DrawableDescription`, including containers. Rectangles and labels
+    /// with a pattern fill can be described but fail to serialize.
+    pub fn to_description(&self) -> Result<SceneDescription, SceneDescriptionError> {
+        let mut objects = Vec::with_capacity(self.len());
+        for layer in Layer::ALL {
+            let scene_layer = self.layer(layer);
+            for (id, drawable) in scene_layer.iter() {
+                let drawable = DrawableDescription::from_drawable(drawable)
+                    .ok_or(SceneDescriptionError::UnsupportedObject(id))?;
+                objects.push(ObjectDescription {
+                    layer,
+                    z_index: scene_layer.z_index(id).unwrap_or_default(),
+                    drawable,
+                });
+            }
+        }
+        Ok(SceneDescription { objects })
+    }
+
+    /// Creates a scene containing the described objects.
+    ///
+    /// Objects are added in the order they are listed, so objects in the same layer
+    /// with the same z-index keep their relative draw order.
+    pub fn from_description(description: SceneDescription) -> Self {
+        let mut scene = Scene::new();
+        for object in description.objects {
+            scene
+                .layer_mut(object.layer)
+                .add_object_with_z_index(object.drawable.into_drawable(), object.z_index);
+        }
+        scene
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::render::color::Color;
+    use crate::core::render::objects::canvas::Canvas;
+    use crate::core::render::objects::scroll_bar::ScrollOrientation;
+
+    /// Creates a scene with an object of several types in every layer.
+    fn scene() -> Scene {
+        let mut scene = Scene::new();
+        scene
+            .layer_mut(Layer::Background)
+            .add_object(Box::new(Grid::new(20.0, 20.0, Color::WHITE, 1.0)));
+        scene.add_object_with_z_index(
+            Box::new(Rectangle::new(10.0, 10.0, 50.0, 50.0, Color::BLUE)),
+            1,
+        );
+        scene.add_object(Box::new(TextObject::new("Hello", 20.0, 20.0)));
+        scene
+            .layer_mut(Layer::Overlay)
+            .add_object(Box::new(ScrollBar::new(
+                300.0,
+                0.0,
+                12.0,
+                200.0,
+                ScrollOrientation::Vertical,
+            )));
+        scene
+    }
+
+    #[test]
+    fn descriptions_round_trip_through_json() {
+        let json = serde_json::to_string(&scene().to_description().unwrap()).unwrap();
+
+        let description: SceneDescription = serde_json::from_str(&json).unwrap();
+        let scene = Scene::from_description(description);
+        assert_eq!(scene.layer(Layer::Background).len(), 1);
+        assert_eq!(scene.layer(Layer::Content).len(), 2);
+        assert_eq!(scene.layer(Layer::Overlay).len(), 1);
+
+        // The rectangle keeps its z-index, so it is still drawn above the text.
+        let content: Vec<_> = scene.layer(Layer::Content).iter().collect();
+        assert!(content[0].1.as_any().is::<TextObject>());
+        assert_eq!(scene.z_index(content[1].0), Some(1));
+
+        let round_trip = serde_json::to_string(&scene.to_description().unwrap()).unwrap();
+        assert_eq!(round_trip, json);
+    }
+
+    #[test]
+    fn containers_are_not_describable() {
+        let mut scene = scene();
+        let canvas = scene.add_object(Box::new(Canvas::new(0.0, 0.0, 100.0, 100.0)));
+        assert_eq!(
+            scene.to_description().err(),
+            Some(SceneDescriptionError::UnsupportedObject(canvas))
+        );
+    }
+
+    #[test]
+    fn unknown_types_fail_to_load() {
+        let json = r#"{ "objects": [{ "type": "Circle", "radius": 5.0 }] }"#;
+        let error = serde_json::from_str::<SceneDescription>(json)
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("Circle"), "{error}");
+    }
+}
@@ -0,0 +1,366 @@
+//! # Scene Editing with Undo
+//!
+//! This module provides `SceneEditor`, which makes changes to a `Scene` and records
+//! how to reverse them, so an editor built on the framework can offer undo and redo.
+
+use crate::core::render::drawable::Drawable;
+use crate::core::render::scene::{Layer, ObjectId, Scene};
+use crate::core::render::spatial_index::DrawKey;
+
+/// A recorded change that can be applied to a `Scene`.
+///
+/// Applying a command returns the command that reverses it, so the same commands
+/// serve for both undo and redo.
+enum Command {
+    /// Puts an object back with its original id and place in the draw order.
+    Insert {
+        id: ObjectId,
+        key: DrawKey,
+        drawable: Box<dyn Drawable>,
+    },
+    /// Removes an object.
+    Remove { id: ObjectId },
+    /// Moves an object to a place in the draw order.
+    Reorder { id: ObjectId, key: DrawKey },
+    /// Replaces an object with another version of it.
+    Replace {
+        id: ObjectId,
+        drawable: Box<dyn Drawable>,
+    },
+}
+
+impl Command {
+    /// Applies the command to `scene`, returning the command that reverses it, or
+    /// `None` if the object it refers to isn't in the scene.
+    fn apply(self, scene: &mut Scene) -> Option<Command> {
+        match self {
+            Command::Insert { id, key, drawable } => {
+                scene
+                    .layer_mut(id.layer())
+                    .restore_object(id, key, drawable);
+                Some(Command::Remove { id })
+            }
+            Command::Remove { id } => {
+                let (key, drawable) = scene.layer_mut(id.layer()).take_object(id)?;
+                Some(Command::Insert { id, key, drawable })
+            }
+            Command::Reorder { id, key } => {
+                let layer = scene.layer_mut(id.layer());
+                let (previous, drawable) = layer.take_object(id)?;
+                layer.restore_object(id, key, drawable);
+                Some(Command::Reorder { id, key: previous })
+            }
+            Command::Replace { id, drawable } => {
+                let previous = scene.layer_mut(id.layer()).replace_object(id, drawable)?;
+                Some(Command::Replace {
+                    id,
+                    drawable: previous,
+                })
+            }
+        }
+    }
+}
+
+/// Makes changes to a `Scene` while recording them, so they can be undone and redone.
+///
+/// The editor only holds the history; each method takes the scene to change, so the
+/// scene can stay in `App::scene` and be drawn as usual. Always pass the same scene.
+/// Undone objects are restored with their original ids, so handles stay valid across
+/// undo and redo.
+///
+/// Changes made to the scene without the editor aren't recorded. Undoing past them is
+/// safe but may not restore the exact previous state.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use my_gui::core::render::{
+///     color::Color, objects::primitives::Rectangle, scene::Scene, scene_editor::SceneEditor,
+/// };
+///
+/// let mut scene = Scene::new();
+/// let mut editor = SceneEditor::new();
+///
+/// let id = editor.add_object(
+///     &mut scene,
+///     Box::new(Rectangle::new(10.0, 10.0, 50.0, 50.0, Color::BLUE)),
+/// );
+/// editor.edit::<Rectangle>(&mut scene, id, |rectangle| rectangle.x += 20.0);
+///
+/// editor.undo(&mut scene); // The rectangle is back at x = 10.
+/// editor.undo(&mut scene); // The rectangle is gone.
+/// editor.redo(&mut scene); // The rectangle is back, with the same id.
+/// assert!(scene.contains(id));
+/// ```
+#[derive(Default)]
+pub struct SceneEditor {
+    /// The commands that undo the recorded changes, most recent last.
+    undo_stack: Vec<Command>,
+    /// The commands that redo the undone changes, most recently undone last.
+    redo_stack: Vec<Command>,
+}
+
+impl SceneEditor {
+    /// Creates a new `SceneEditor` with an empty history.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an object to the content layer of `scene` with a z-index of 0. See
+    /// `Scene::add_object`.
+    pub fn add_object(&mut self, scene: &mut Scene, object: Box<dyn Drawable>) -> ObjectId {
+        self.add_object_to_layer(scene, Layer::Content, object, 0)
+    }
+
+    /// Adds an object to a layer of `scene` with the given z-index. See
+    /// `SceneLayer::add_object_with_z_index`.
+    pub fn add_object_to_layer(
+        &mut self,
+        scene: &mut Scene,
+        layer: Layer,
+        object: Box<dyn Drawable>,
+        z_index: i32,
+    ) -> ObjectId {
+        let id = scene
+            .layer_mut(layer)
+            .add_object_with_z_index(object, z_index);
+        self.record(Command::Remove { id });
+        id
+    }
+
+    /// Removes an object from `scene`.
+    ///
+    /// Returns `false` if the object is not in the scene, in which case nothing is
+    /// recorded.
+    pub fn remove_object(&mut self, scene: &mut Scene, id: ObjectId) -> bool {
+        self.apply(scene, Command::Remove { id })
+    }
+
+    /// Changes the z-index of an object in `scene`. See `Scene::set_z_index`.
+    ///
+    /// Returns `false` if the object is not in the scene.
+    pub fn set_z_index(&mut self, scene: &mut Scene, id: ObjectId, z_index: i32) -> bool {
+        let Some(previous) = scene.layer(id.layer()).draw_key(id) else {
+            return false;
+        };
+        scene.set_z_index(id, z_index);
+        self.record(Command::Reorder { id, key: previous });
+        true
+    }
+
+    /// Moves an object in `scene` in front of the others with the same z-index. See
+    /// `Scene::move_to_front`.
+    ///
+    /// Returns `false` if the object is not in the scene.
+    pub fn move_to_front(&mut self, scene: &mut Scene, id: ObjectId) -> bool {
+        let Some(previous) = scene.layer(id.layer()).draw_key(id) else {
+            return false;
+        };
+        scene.move_to_front(id);
+        self.record(Command::Reorder { id, key: previous });
+        true
+    }
+
+    /// Changes an object in `scene` as its concrete type, such as to move or recolor it.
+    ///
+    /// The object is copied with `Drawable::clone_box` before `f` runs, and the copy is
+    /// restored on undo. Returns `false` without calling `f` if the object is not in
+    /// the scene, is not a `T`, or can't be copied.
+    pub fn edit<T: Drawable + 'static>(
+        &mut self,
+        scene: &mut Scene,
+        id: ObjectId,
+        f: impl FnOnce(&mut T),
+    ) -> bool {
+        let Some(previous) = scene
+            .get_object::<T>(id)
+            .and_then(|object| object.clone_box())
+        else {
+            return false;
+        };
+        let Some(object) = scene.get_object_mut::<T>(id) else {
+            return false;
+        };
+        f(object);
+        self.record(Command::Replace {
+            id,
+            drawable: previous,
+        });
+        true
+    }
+
+    /// Reverses the most recent change that hasn't been undone yet.
+    ///
+    /// Returns `false` if there is nothing to undo.
+    pub fn undo(&mut self, scene: &mut Scene) -> bool {
+        let Some(command) = self.undo_stack.pop() else {
+            return false;
+        };
+        if let Some(inverse) = command.apply(scene) {
+            self.redo_stack.push(inverse);
+        }
+        true
+    }
+
+    /// Makes the most recently undone change again.
+    ///
+    /// Returns `false` if there is nothing to redo.
+    pub fn redo(&mut self, scene: &mut Scene) -> bool {
+        let Some(command) = self.redo_stack.pop() else {
+            return false;
+        };
+        if let Some(inverse) = command.apply(scene) {
+            self.undo_stack.push(inverse);
+        }
+        true
+    }
+
+    /// Returns `true` if there is a change to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    /// Returns `true` if there is an undone change to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// Forgets every recorded change, such as after the document is saved.
+    pub fn clear_history(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+
+    /// Applies a new change to `scene` and records how to reverse it.
+    ///
+    /// Returns `false` if the change couldn't be applied.
+    fn apply(&mut self, scene: &mut Scene, command: Command) -> bool {
+        match command.apply(scene) {
+            Some(inverse) => {
+                self.record(inverse);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Records how to reverse a change that has just been made, which discards the
+    /// changes that were undone before it.
+    fn record(&mut self, inverse: Command) {
+        self.undo_stack.push(inverse);
+        self.redo_stack.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::render::color::Color;
+    use crate::core::render::objects::primitives::Rectangle;
+    use crate::core::render::rect::Rect;
+    use windows_numerics::Vector2;
+
+    /// Creates a rectangle told apart from the others by its x-coordinate.
+    fn rectangle(x: f32) -> Box<dyn Drawable> {
+        Box::new(Rectangle::new(x, 0.0, 10.0, 10.0, Color::BLUE))
+    }
+
+    /// Returns the x-coordinates of the rectangles in the content layer, in draw order.
+    fn draw_order(scene: &Scene) -> Vec<f32> {
+        scene
+            .layer(Layer::Content)
+            .iter()
+            .filter_map(|(_, object)| object.as_any().downcast_ref::<Rectangle>())
+            .map(|rectangle| rectangle.x)
+            .collect()
+    }
+
+    /// Checks that no two objects in the content layer share a draw key.
+    fn assert_distinct_keys(scene: &Scene) {
+        let layer = scene.layer(Layer::Content);
+        let mut keys: Vec<_> = layer
+            .iter()
+            .filter_map(|(id, _)| layer.draw_key(id))
+            .collect();
+        keys.sort_unstable();
+        keys.dedup();
+        assert_eq!(keys.len(), layer.len());
+    }
+
+    #[test]
+    fn undo_and_redo_interleave_with_edits() {
+        let mut scene = Scene::new();
+        let mut editor = SceneEditor::new();
+
+        let a = editor.add_object(&mut scene, rectangle(1.0));
+        let b = editor.add_object(&mut scene, rectangle(2.0));
+        assert!(editor.edit::<Rectangle>(&mut scene, b, |rectangle| rectangle.x = 5.0));
+        assert_eq!(draw_order(&scene), [1.0, 5.0]);
+
+        assert!(editor.undo(&mut scene));
+        assert_eq!(draw_order(&scene), [1.0, 2.0]);
+        assert!(editor.undo(&mut scene));
+        assert_eq!(draw_order(&scene), [1.0]);
+        assert!(!scene.contains(b));
+
+        // Redoing the add restores the same id.
+        assert!(editor.redo(&mut scene));
+        assert_eq!(draw_order(&scene), [1.0, 2.0]);
+        assert!(scene.contains(b));
+        assert!(editor.can_redo());
+
+        // A new change discards the rest of the redo history.
+        assert!(editor.remove_object(&mut scene, a));
+        assert!(!editor.can_redo());
+        assert_eq!(draw_order(&scene), [2.0]);
+        assert!(editor.undo(&mut scene));
+        assert_eq!(draw_order(&scene), [1.0, 2.0]);
+
+        let c = editor.add_object(&mut scene, rectangle(3.0));
+        assert!(editor.set_z_index(&mut scene, a, 1));
+        assert_eq!(draw_order(&scene), [2.0, 3.0, 1.0]);
+        assert!(editor.move_to_front(&mut scene, b));
+        assert_eq!(draw_order(&scene), [3.0, 2.0, 1.0]);
+
+        assert!(editor.undo(&mut scene));
+        assert_eq!(draw_order(&scene), [2.0, 3.0, 1.0]);
+        assert!(editor.undo(&mut scene));
+        assert_eq!(draw_order(&scene), [1.0, 2.0, 3.0]);
+        assert!(editor.undo(&mut scene));
+        assert!(!scene.contains(c));
+        assert!(editor.undo(&mut scene));
+        assert!(editor.undo(&mut scene));
+        assert!(scene.is_empty());
+        assert!(!editor.undo(&mut scene));
+
+        while editor.redo(&mut scene) {}
+        assert_eq!(draw_order(&scene), [3.0, 2.0, 1.0]);
+        assert!(scene.contains(a) && scene.contains(b) && scene.contains(c));
+        assert_distinct_keys(&scene);
+    }
+
+    #[test]
+    fn restoring_after_renumbering_keeps_keys_distinct() {
+        let mut scene = Scene::new().with_spatial_index(Rect::new(0.0, 0.0, 100.0, 100.0));
+        let mut editor = SceneEditor::new();
+
+        let a = editor.add_object(&mut scene, rectangle(1.0));
+        let b = editor.add_object(&mut scene, rectangle(2.0));
+        assert!(editor.remove_object(&mut scene, a));
+
+        // Inserting renumbers the layer, so `c` takes the key `a` was removed with.
+        let c = scene.insert_object_at(0, rectangle(3.0));
+        assert!(editor.undo(&mut scene));
+        assert_eq!(draw_order(&scene), [1.0, 3.0, 2.0]);
+        assert_distinct_keys(&scene);
+
+        // The spatial index finds each object once, front to back.
+        let point = Vector2 { X: 5.0, Y: 5.0 };
+        assert_eq!(scene.hit_test(point), [b, c, a]);
+
+        // Objects added afterwards are still drawn last.
+        scene.add_object(rectangle(4.0));
+        assert_eq!(draw_order(&scene), [1.0, 3.0, 2.0, 4.0]);
+        assert_distinct_keys(&scene);
+    }
+}
@@ -0,0 +1,101 @@
+//! # Serde Support for `Vector2`
+//!
+//! `windows_numerics::Vector2` doesn't implement the serde traits, so fields of that
+//! type are serialized through this module with `#[serde(with = "...")]`. A vector is
+//! written as an object with `x` and `y` fields. The submodules handle optional
+//! vectors, vectors in a `Vec`, and fixed-size arrays of vectors.
+
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use windows_numerics::Vector2;
+
+/// The serialized form of a `Vector2`.
+#[derive(Serialize, Deserialize)]
+struct Point {
+    x: f32,
+    y: f32,
+}
+
+impl From<Vector2> for Point {
+    fn from(vector: Vector2) -> Self {
+        Self {
+            x: vector.X,
+            y: vector.Y,
+        }
+    }
+}
+
+impl From<Point> for Vector2 {
+    fn from(point: Point) -> Self {
+        Vector2 {
+            X: point.x,
+            Y: point.y,
+        }
+    }
+}
+
+pub(crate) fn serialize<S: Serializer>(vector: &Vector2, serializer: S) -> Result<S::Ok, S::Error> {
+    Point::from(*vector).serialize(serializer)
+}
+
+pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vector2, D::Error> {
+    Point::deserialize(deserializer).map(Vector2::from)
+}
+
+/// Serializes an `Option<Vector2>`.
+pub(crate) mod option {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(
+        vector: &Option<Vector2>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        vector.map(Point::from).serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Vector2>, D::Error> {
+        Ok(Option::<Point>::deserialize(deserializer)?.map(Vector2::from))
+    }
+}
+
+/// Serializes a `Vec<Vector2>`.
+pub(crate) mod vec {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(
+        vectors: &[Vector2],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(vectors.iter().map(|&vector| Point::from(vector)))
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Vector2>, D::Error> {
+        let points = Vec::<Point>::deserialize(deserializer)?;
+        Ok(points.into_iter().map(Vector2::from).collect())
+    }
+}
+
+/// Serializes a `[Vector2; N]`.
+pub(crate) mod array {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer, const N: usize>(
+        vectors: &[Vector2; N],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        super::vec::serialize(vectors, serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>, const N: usize>(
+        deserializer: D,
+    ) -> Result<[Vector2; N], D::Error> {
+        let vectors = super::vec::deserialize(deserializer)?;
+        let length = vectors.len();
+        vectors
+            .try_into()
+            .map_err(|_| D::Error::invalid_length(length, &format!("{N} points").as_str()))
+    }
+}
@@ -0,0 +1,85 @@
+use windows::Win32::Graphics::Direct2D::{Common::D2D_RECT_F, D2D1_ROUNDED_RECT};
+use windows_numerics::Vector2;
+
+use crate::core::render::color::Color;
+use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::rect::Rect;
+
+/// The maximum number of layers used to approximate a blurred shadow.
+const MAX_SHADOW_LAYERS: usize = 16;
+
+/// A soft drop shadow cast by a shape.
+///
+/// The `ID2D1HwndRenderTarget` used by default cannot host Direct2D effects, so
+/// the blur is approximated by stacking translucent rounded rectangles that grow
+/// outwards from the shape's bounds. The result is drawn beneath the shape and may
+/// extend outside its bounds by up to the blur radius plus the offset.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Shadow {
+    /// The offset of the shadow from the shape.
+    #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2"))]
+    pub offset: Vector2,
+    /// How far the shadow fades out beyond the shape's edges.
+    pub blur_radius: f32,
+    /// The color of the shadow at its most opaque.
+    pub color: Color,
+}
+
+impl Shadow {
+    /// Creates a new `Shadow`.
+    pub fn new(offset: Vector2, blur_radius: f32, color: Color) -> Self {
+        Self {
+            offset,
+            blur_radius,
+            color,
+        }
+    }
+
+    /// Draws the shadow for a rectangle with the given bounds.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for calling Direct2D drawing methods. The
+    /// caller must ensure that the `drawing_context` contains valid Direct2D resources.
+    pub fn draw_for_rect(&self, context: &DrawingContext, bounds: Rect) {
+        if self.color.a <= 0.0 {
+            return;
+        }
+
+        let shadow_bounds = Rect::new(
+            bounds.x + self.offset.X,
+            bounds.y + self.offset.Y,
+            bounds.width,
+            bounds.height,
+        );
+        let blur = self.blur_radius.max(0.0);
+        let layers = (blur.ceil() as usize).clamp(1, MAX_SHADOW_LAYERS);
+
+        // Each layer overlaps all the layers outside it, so pick the per-layer alpha
+        // such that the fully covered center reaches the requested alpha.
+        let layer_alpha = 1.0 - (1.0 - self.color.a.min(1.0)).powf(1.0 / layers as f32);
+        let layer_color = Color {
+            a: layer_alpha,
+            ..self.color
+        };
+
+        context.with_brush_color(&layer_color, |brush| {
+            for layer in (0..layers).rev() {
+                let spread = blur * (layer as f32 + 1.0) / layers as f32;
+                let rect = D2D_RECT_F {
+                    left: shadow_bounds.x - spread,
+                    top: shadow_bounds.y - spread,
+                    right: shadow_bounds.right() + spread,
+                    bottom: shadow_bounds.bottom() + spread,
+                };
+                let rounded = D2D1_ROUNDED_RECT {
+                    rect,
+                    radiusX: spread,
+                    radiusY: spread,
+                };
+                unsafe { context.render_target.FillRoundedRectangle(&rounded, brush) };
+            }
+        });
+    }
+}
@@ -0,0 +1,437 @@
+//! # Spatial Index
+//!
+//! This module provides the uniform grid a `SceneLayer` can use to find the objects
+//! near a point or inside a rectangle without checking every object.
+
+use std::collections::{HashMap, HashSet};
+use std::ops::RangeInclusive;
+
+use windows_numerics::Vector2;
+
+use crate::core::render::rect::Rect;
+use crate::core::render::scene::ObjectId;
+
+/// The number of columns and rows the indexed region is divided into.
+const GRID_SIZE: usize = 64;
+
+/// The position of an object in the draw order of its layer: its z-index and sequence
+/// number.
+pub(crate) type DrawKey = (i32, u64);
+
+/// The cells an object is listed in.
+struct CellRange {
+    columns: RangeInclusive<usize>,
+    rows: RangeInclusive<usize>,
+}
+
+/// What the index knows about one object.
+struct Entry {
+    key: DrawKey,
+    /// The object's bounds when it was indexed.
+    bounds: Option<Rect>,
+    /// The cells the object is listed in, or `None` if it is in `unindexed` instead.
+    cells: Option<CellRange>,
+}
+
+/// A uniform grid of cells covering a fixed region, listing the objects whose bounds
+/// overlap each cell.
+///
+/// Objects without bounds, or with bounds that extend outside the region, aren't
+/// listed in any cell. They are checked by every query, so the results are the same as
+/// checking every object, just faster when most objects lie inside the region.
+///
+/// The index doesn't notice when an object changes. The owning `SceneLayer` marks
+/// changed objects with `mark_dirty`, or the whole index with `mark_stale`, and
+/// re-inserts them before the next query.
+pub(crate) struct SpatialIndex {
+    region: Rect,
+    cells: Vec<Vec<ObjectId>>,
+    entries: HashMap<ObjectId, Entry>,
+    /// Objects that aren't listed in any cell.
+    unindexed: HashSet<ObjectId>,
+    /// Objects that changed since they were indexed.
+    dirty: HashSet<ObjectId>,
+    /// Whether every object must be re-indexed, such as after the draw order changed.
+    stale: bool,
+}
+
+impl SpatialIndex {
+    /// Creates a new, stale index covering `region`.
+    pub(crate) fn new(region: Rect) -> Self {
+        Self {
+            region,
+            cells: vec![Vec::new(); GRID_SIZE * GRID_SIZE],
+            entries: HashMap::new(),
+            unindexed: HashSet::new(),
+            dirty: HashSet::new(),
+            stale: true,
+        }
+    }
+
+    /// Returns the region covered by the grid.
+    pub(crate) fn region(&self) -> Rect {
+        self.region
+    }
+
+    /// Marks every object as needing to be re-indexed.
+    pub(crate) fn mark_stale(&mut self) {
+        self.stale = true;
+        self.dirty.clear();
+    }
+
+    /// Marks one object as added, removed, or changed since it was indexed.
+    pub(crate) fn mark_dirty(&mut self, id: ObjectId) {
+        if !self.stale {
+            self.dirty.insert(id);
+        }
+    }
+
+    /// Returns `true` if every object must be re-indexed with `rebuild`.
+    pub(crate) fn is_stale(&self) -> bool {
+        self.stale
+    }
+
+    /// Returns the objects marked with `mark_dirty`, clearing the marks.
+    pub(crate) fn take_dirty(&mut self) -> HashSet<ObjectId> {
+        std::mem::take(&mut self.dirty)
+    }
+
+    /// Returns the objects that had no bounds when they were indexed, such as text
+    /// that hadn't been laid out yet.
+    pub(crate) fn unbounded(&self) -> Vec<(ObjectId, DrawKey)> {
+        self.unindexed
+            .iter()
+            .filter_map(|id| {
+                let entry = &self.entries[id];
+                entry.bounds.is_none().then_some((*id, entry.key))
+            })
+            .collect()
+    }
+
+    /// Replaces the contents of the index with `objects`.
+    pub(crate) fn rebuild(
+        &mut self,
+        objects: impl IntoIterator<Item = (ObjectId, DrawKey, Option<Rect>)>,
+    ) {
+        for cell in &mut self.cells {
+            cell.clear();
+        }
+        self.entries.clear();
+        self.unindexed.clear();
+        self.dirty.clear();
+        self.stale = false;
+        for (id, key, bounds) in objects {
+            self.insert(id, key, bounds);
+        }
+    }
+
+    /// Adds an object to the index, replacing any previous entry for it.
+    pub(crate) fn insert(&mut self, id: ObjectId, key: DrawKey, bounds: Option<Rect>) {
+        self.remove(id);
+        let cells = bounds.and_then(|bounds| self.cell_range(&bounds));
+        match &cells {
+            Some(range) => {
+                for row in range.rows.clone() {
+                    for column in range.columns.clone() {
+                        self.cells[row * GRID_SIZE + column].push(id);
+                    }
+                }
+            }
+            None => {
+                self.unindexed.insert(id);
+            }
+        }
+        self.entries.insert(id, Entry { key, bounds, cells });
+    }
+
+    /// Removes an object from the index, if it is in it.
+    pub(crate) fn remove(&mut self, id: ObjectId) {
+        let Some(entry) = self.entries.remove(&id) else {
+            return;
+        };
+        match entry.cells {
+            Some(range) => {
+                for row in range.rows {
+                    for column in range.columns.clone() {
+                        self.cells[row * GRID_SIZE + column].retain(|other| *other != id);
+                    }
+                }
+            }
+            None => {
+                self.unindexed.remove(&id);
+            }
+        }
+    }
+
+    /// Returns the objects whose bounds contain `point`, in no particular order.
+    pub(crate) fn query_point(&self, point: Vector2) -> Vec<(DrawKey, ObjectId)> {
+        let cell = self
+            .cell_range(&Rect::new(point.X, point.Y, 0.0, 0.0))
+            .map(|range| &self.cells[range.rows.start() * GRID_SIZE + range.columns.start()]);
+        cell.into_iter()
+            .flatten()
+            .chain(&self.unindexed)
+            .filter_map(|id| {
+                let entry = &self.entries[id];
+                entry
+                    .bounds
+                    .is_some_and(|bounds| bounds.contains(point))
+                    .then_some((entry.key, *id))
+            })
+            .collect()
+    }
+
+    /// Returns the draw keys of the objects whose bounds intersect `rect`, along with
+    /// every object without bounds, in no particular order and possibly repeated.
+    pub(crate) fn query_rect(&self, rect: &Rect) -> Vec<DrawKey> {
+        let mut ids: Vec<&ObjectId> = self.unindexed.iter().collect();
+        if let Some(range) = self.clipped_cell_range(rect) {
+            for row in range.rows {
+                for column in range.columns.clone() {
+                    ids.extend(&self.cells[row * GRID_SIZE + column]);
+                }
+            }
+        }
+        ids.into_iter()
+            .filter_map(|id| {
+                let entry = &self.entries[id];
+                entry
+                    .bounds
+                    .is_none_or(|bounds| bounds.intersects(rect))
+                    .then_some(entry.key)
+            })
+            .collect()
+    }
+
+    /// Returns the cells covered by `bounds`, or `None` if it doesn't lie entirely
+    /// inside the region.
+    fn cell_range(&self, bounds: &Rect) -> Option<CellRange> {
+        let inside = bounds.x >= self.region.x
+            && bounds.y >= self.region.y
+            && bounds.right() <= self.region.right()
+            && bounds.bottom() <= self.region.bottom();
+        if inside {
+            self.clipped_cell_range(bounds)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the cells covered by the part of `rect` inside the region, or `None` if
+    /// it lies entirely outside.
+    fn clipped_cell_range(&self, rect: &Rect) -> Option<CellRange> {
+        if self.region.width <= 0.0 || self.region.height <= 0.0 || !rect.intersects(&self.region) {
+            return None;
+        }
+        let column = |x: f32| {
+            let cell = (x - self.region.x) / self.region.width * GRID_SIZE as f32;
+            (cell.max(0.0) as usize).min(GRID_SIZE - 1)
+        };
+        let row = |y: f32| {
+            let cell = (y - self.region.y) / self.region.height * GRID_SIZE as f32;
+            (cell.max(0.0) as usize).min(GRID_SIZE - 1)
+        };
+        Some(CellRange {
+            columns: column(rect.x)..=column(rect.right()),
+            rows: row(rect.y)..=row(rect.bottom()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use windows::core::Result;
+    use windows_numerics::Matrix3x2;
+
+    use super::*;
+    use crate::core::render::direct2d_context::Direct2DContext;
+    use crate::core::render::drawable::Drawable;
+    use crate::core::render::drawing_context::DrawingContext;
+    use crate::core::render::scene::{Layer, SceneLayer};
+    use crate::core::window::config::WindowConfig;
+
+    /// The region indexed by the tests, which makes every cell 10 DIPs square.
+    const REGION: Rect = Rect::new(0.0, 0.0, 640.0, 640.0);
+
+    /// An object with arbitrary bounds that logs its name when it is drawn.
+    struct Probe {
+        name: usize,
+        bounds: Option<Rect>,
+        drawn: Rc<RefCell<Vec<usize>>>,
+    }
+
+    impl Drawable for Probe {
+        fn draw(&self, _context: &DrawingContext) -> Result<()> {
+            self.drawn.borrow_mut().push(self.name);
+            Ok(())
+        }
+
+        fn bounds(&self) -> Option<Rect> {
+            self.bounds
+        }
+    }
+
+    /// A layer holding one probe for each of `bounds`, and the log its probes draw to.
+    struct TestLayer {
+        layer: SceneLayer,
+        ids: Vec<ObjectId>,
+        drawn: Rc<RefCell<Vec<usize>>>,
+    }
+
+    impl TestLayer {
+        fn new(bounds: &[Option<Rect>], indexed: bool) -> Self {
+            let drawn = Rc::new(RefCell::new(Vec::new()));
+            let mut layer = SceneLayer::new(Layer::Content);
+            if indexed {
+                layer.set_spatial_index(Some(REGION));
+            }
+            let ids = bounds
+                .iter()
+                .enumerate()
+                .map(|(name, &bounds)| {
+                    layer.add_object(Box::new(Probe {
+                        name,
+                        bounds,
+                        drawn: Rc::clone(&drawn),
+                    }))
+                })
+                .collect();
+            Self { layer, ids, drawn }
+        }
+
+        /// Changes the bounds of one probe the way an application would move it.
+        fn move_probe(&mut self, name: usize, bounds: Option<Rect>) {
+            self.layer
+                .get_object_mut::<Probe>(self.ids[name])
+                .unwrap()
+                .bounds = bounds;
+        }
+
+        /// Returns the names of the probes under `point`, front to back.
+        fn hit_test(&self, point: Vector2) -> Vec<usize> {
+            self.layer
+                .hit_test(point)
+                .into_iter()
+                .map(|id| self.ids.iter().position(|other| *other == id).unwrap())
+                .collect()
+        }
+
+        /// Returns the names of the probes drawn, in order, when `visible` is the
+        /// visible part of a 64 by 64 render target.
+        fn draw_culled(&self, context: &Direct2DContext, visible: Vector2) -> Vec<usize> {
+            self.drawn.borrow_mut().clear();
+            context
+                .render_offscreen(64, 64, 96.0, |context| {
+                    let offset = Matrix3x2::translation(-visible.X, -visible.Y);
+                    context.with_transform(&offset, || self.layer.draw_all(context))
+                })
+                .unwrap();
+            self.drawn.borrow().clone()
+        }
+    }
+
+    /// Bounds covering each case the index treats differently.
+    fn probe_bounds() -> Vec<Option<Rect>> {
+        vec![
+            // Inside a few cells.
+            Some(Rect::new(5.0, 5.0, 20.0, 20.0)),
+            // Exactly one cell, with every edge on a cell boundary.
+            Some(Rect::new(10.0, 10.0, 10.0, 10.0)),
+            // Without bounds, so never hit but always drawn.
+            None,
+            // Straddling the edge of the region.
+            Some(Rect::new(600.0, 600.0, 100.0, 100.0)),
+            // Entirely outside the region.
+            Some(Rect::new(-50.0, -50.0, 20.0, 20.0)),
+            // Exactly the region.
+            Some(REGION),
+        ]
+    }
+
+    /// Points inside, outside, and on the boundaries of cells and the region.
+    fn probe_points() -> Vec<Vector2> {
+        let coordinates = [
+            -50.0, -40.0, -30.0, -0.5, 0.0, 5.0, 9.99, 10.0, 15.0, 20.0, 25.0, 600.0, 640.0, 650.0,
+            700.0,
+        ];
+        coordinates
+            .iter()
+            .flat_map(|&x| coordinates.iter().map(move |&y| Vector2::new(x, y)))
+            .collect()
+    }
+
+    /// The top-left corners of 64 by 64 visible areas touching each probe and cell
+    /// boundary.
+    fn probe_views() -> Vec<Vector2> {
+        [
+            (0.0, 0.0),
+            (-100.0, -100.0),
+            (-94.0, -94.0),
+            (20.0, 20.0),
+            (25.0, 0.0),
+            (300.0, 300.0),
+            (576.0, 576.0),
+            (640.0, 640.0),
+            (660.0, 660.0),
+            (700.0, 700.0),
+        ]
+        .into_iter()
+        .map(|(x, y)| Vector2::new(x, y))
+        .collect()
+    }
+
+    fn assert_same_hits(linear: &TestLayer, indexed: &TestLayer) {
+        for point in probe_points() {
+            assert_eq!(
+                indexed.hit_test(point),
+                linear.hit_test(point),
+                "hit test at {point:?}"
+            );
+        }
+    }
+
+    fn assert_same_draws(linear: &TestLayer, indexed: &TestLayer) {
+        let context = Direct2DContext::new(&WindowConfig::default()).unwrap();
+        for view in probe_views() {
+            assert_eq!(
+                indexed.draw_culled(&context, view),
+                linear.draw_culled(&context, view),
+                "draw with the visible area at {view:?}"
+            );
+        }
+    }
+
+    /// Moves the probes into, out of, and around the region after they were indexed.
+    fn move_probes(layer: &mut TestLayer) {
+        layer.move_probe(0, Some(Rect::new(300.0, 300.0, 20.0, 20.0)));
+        layer.move_probe(1, Some(Rect::new(-30.0, 10.0, 40.0, 10.0)));
+        layer.move_probe(2, Some(Rect::new(20.0, 20.0, 10.0, 10.0)));
+        layer.move_probe(4, Some(Rect::new(0.0, 0.0, 10.0, 10.0)));
+        layer.move_probe(5, None);
+    }
+
+    #[test]
+    fn indexed_hit_test_matches_the_linear_search() {
+        let mut linear = TestLayer::new(&probe_bounds(), false);
+        let mut indexed = TestLayer::new(&probe_bounds(), true);
+        assert_same_hits(&linear, &indexed);
+
+        move_probes(&mut linear);
+        move_probes(&mut indexed);
+        assert_same_hits(&linear, &indexed);
+    }
+
+    #[test]
+    fn indexed_culling_draws_the_same_objects_as_the_linear_search() {
+        let mut linear = TestLayer::new(&probe_bounds(), false);
+        let mut indexed = TestLayer::new(&probe_bounds(), true);
+        assert_same_draws(&linear, &indexed);
+
+        move_probes(&mut linear);
+        move_probes(&mut indexed);
+        assert_same_draws(&linear, &indexed);
+    }
+}
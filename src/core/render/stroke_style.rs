@@ -0,0 +1,456 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+};
+
+use windows::{
+    core::*,
+    Win32::Graphics::Direct2D::{
+        ID2D1Factory, ID2D1Factory1, ID2D1StrokeStyle, D2D1_CAP_STYLE, D2D1_CAP_STYLE_FLAT,
+        D2D1_CAP_STYLE_ROUND, D2D1_CAP_STYLE_SQUARE, D2D1_DASH_STYLE, D2D1_DASH_STYLE_CUSTOM,
+        D2D1_DASH_STYLE_DASH, D2D1_DASH_STYLE_DASH_DOT, D2D1_DASH_STYLE_DOT, D2D1_DASH_STYLE_SOLID,
+        D2D1_LINE_JOIN, D2D1_LINE_JOIN_BEVEL, D2D1_LINE_JOIN_MITER, D2D1_LINE_JOIN_ROUND,
+        D2D1_STROKE_STYLE_PROPERTIES,
+    },
+};
+
+/// An error produced when creating a `DashPattern` from invalid lengths.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum DashPatternError {
+    /// The pattern had no lengths.
+    #[error("a dash pattern needs at least one length")]
+    Empty,
+    /// A length was negative, infinite, or NaN.
+    #[error("invalid dash length {length} at index {index}")]
+    InvalidLength {
+        /// The invalid length.
+        length: f32,
+        /// The index of the length in the pattern.
+        index: usize,
+    },
+    /// Every length was zero, so the pattern would never advance.
+    #[error("a dash pattern needs a length greater than zero")]
+    ZeroLength,
+}
+
+/// The alternating dash and gap lengths of a `StrokeStyle::Custom` pattern, in
+/// multiples of the stroke width.
+///
+/// The lengths are checked when the pattern is created, so drawing with it can't fail
+/// because of them.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "Vec<f32>", into = "Vec<f32>")
+)]
+pub struct DashPattern(Vec<f32>);
+
+impl DashPattern {
+    /// Creates a new `DashPattern` from alternating dash and gap lengths.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `dashes` is empty, if a length is
+    /// negative or not finite, or if every length is zero.
+    pub fn new(dashes: Vec<f32>) -> std::result::Result<Self, DashPatternError> {
+        if dashes.is_empty() {
+            return Err(DashPatternError::Empty);
+        }
+        if let Some((index, &length)) = dashes
+            .iter()
+            .enumerate()
+            .find(|(_, length)| !length.is_finite() || **length < 0.0)
+        {
+            return Err(DashPatternError::InvalidLength { length, index });
+        }
+        if dashes.iter().all(|&length| length == 0.0) {
+            return Err(DashPatternError::ZeroLength);
+        }
+        Ok(Self(dashes))
+    }
+
+    /// Returns the dash and gap lengths.
+    pub fn dashes(&self) -> &[f32] {
+        &self.0
+    }
+}
+
+impl TryFrom<Vec<f32>> for DashPattern {
+    type Error = DashPatternError;
+
+    fn try_from(dashes: Vec<f32>) -> std::result::Result<Self, Self::Error> {
+        Self::new(dashes)
+    }
+}
+
+impl From<DashPattern> for Vec<f32> {
+    fn from(pattern: DashPattern) -> Self {
+        pattern.0
+    }
+}
+
+/// The dash pattern used when stroking lines and shape outlines.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StrokeStyle {
+    /// A continuous stroke.
+    #[default]
+    Solid,
+    /// A stroke made of dashes.
+    Dash,
+    /// A stroke made of dots.
+    Dot,
+    /// A stroke of alternating dashes and dots.
+    DashDot,
+    /// A custom pattern of alternating dash and gap lengths.
+    ///
+    /// Lengths are expressed in multiples of the stroke width.
+    Custom(DashPattern),
+}
+
+impl StrokeStyle {
+    /// Creates a `StrokeStyle::Custom` from alternating dash and gap lengths, in
+    /// multiples of the stroke width.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the lengths aren't a valid `DashPattern`.
+    pub fn custom(dashes: Vec<f32>) -> std::result::Result<Self, DashPatternError> {
+        DashPattern::new(dashes).map(StrokeStyle::Custom)
+    }
+
+    /// Returns `true` if this is the solid style, which needs no stroke style object.
+    pub fn is_solid(&self) -> bool {
+        matches!(self, StrokeStyle::Solid)
+    }
+
+    fn dash_style(&self) -> D2D1_DASH_STYLE {
+        match self {
+            StrokeStyle::Solid => D2D1_DASH_STYLE_SOLID,
+            StrokeStyle::Dash => D2D1_DASH_STYLE_DASH,
+            StrokeStyle::Dot => D2D1_DASH_STYLE_DOT,
+            StrokeStyle::DashDot => D2D1_DASH_STYLE_DASH_DOT,
+            StrokeStyle::Custom(_) => D2D1_DASH_STYLE_CUSTOM,
+        }
+    }
+
+    /// Returns a hashable key identifying the pattern.
+    fn key(&self) -> Vec<u32> {
+        let mut key = vec![self.dash_style().0 as u32];
+        if let StrokeStyle::Custom(pattern) = self {
+            key.extend(pattern.dashes().iter().map(|dash| dash.to_bits()));
+        }
+        key
+    }
+}
+
+/// The shape drawn at the ends of open strokes and of each dash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LineCap {
+    /// The stroke ends exactly at the end point.
+    #[default]
+    Flat,
+    /// The stroke ends with a semicircle centered on the end point.
+    Round,
+    /// The stroke ends with a square extending half the stroke width past the end point.
+    Square,
+}
+
+impl From<LineCap> for D2D1_CAP_STYLE {
+    fn from(cap: LineCap) -> Self {
+        match cap {
+            LineCap::Flat => D2D1_CAP_STYLE_FLAT,
+            LineCap::Round => D2D1_CAP_STYLE_ROUND,
+            LineCap::Square => D2D1_CAP_STYLE_SQUARE,
+        }
+    }
+}
+
+/// The shape used where two segments of a stroke meet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LineJoin {
+    /// A sharp corner, beveled when it would exceed the miter limit.
+    #[default]
+    Miter,
+    /// A rounded corner.
+    Round,
+    /// A cut-off corner.
+    Bevel,
+}
+
+impl LineJoin {
+    /// Returns how far a stroke of `width` with these joins can reach past the points
+    /// of the figure it outlines.
+    ///
+    /// Miter joins reach up to the miter limit (`DEFAULT_MITER_LIMIT`) times half the
+    /// width at sharp corners. Other joins reach half the width, times √2 so square caps
+    /// on diagonal segments are covered too.
+    pub fn stroke_extent(self, width: f32) -> f32 {
+        let factor = match self {
+            LineJoin::Miter => DEFAULT_MITER_LIMIT.max(std::f32::consts::SQRT_2),
+            LineJoin::Round | LineJoin::Bevel => std::f32::consts::SQRT_2,
+        };
+        factor * width.max(0.0) / 2.0
+    }
+}
+
+impl From<LineJoin> for D2D1_LINE_JOIN {
+    fn from(join: LineJoin) -> Self {
+        match join {
+            LineJoin::Miter => D2D1_LINE_JOIN_MITER,
+            LineJoin::Round => D2D1_LINE_JOIN_ROUND,
+            LineJoin::Bevel => D2D1_LINE_JOIN_BEVEL,
+        }
+    }
+}
+
+/// The miter limit used unless another is given, as a multiple of half the stroke
+/// width.
+pub const DEFAULT_MITER_LIMIT: f32 = 10.0;
+
+/// A cached stroke style object and when it was last used.
+struct CachedStrokeStyle {
+    stroke_style: ID2D1StrokeStyle,
+    last_used: u64,
+}
+
+/// A cache of Direct2D stroke style objects, keyed by their pattern, caps, joins, and
+/// miter limit.
+///
+/// Stroke styles are device-independent resources, so the cache survives the loss
+/// of the render target and is owned by the `Direct2DContext`. It holds at most
+/// `MAX_STYLES` objects, evicting the least recently used one to make room for a new
+/// one.
+pub struct StrokeStyleCache {
+    styles: RefCell<HashMap<Vec<u32>, CachedStrokeStyle>>,
+    /// Counts the calls to `get`, to order the cached styles by their last use.
+    clock: Cell<u64>,
+}
+
+impl StrokeStyleCache {
+    /// The largest number of stroke style objects the cache holds.
+    pub const MAX_STYLES: usize = 64;
+
+    /// Creates a new, empty `StrokeStyleCache`.
+    pub fn new() -> Self {
+        Self {
+            styles: RefCell::new(HashMap::new()),
+            clock: Cell::new(0),
+        }
+    }
+
+    /// Returns the number of cached stroke style objects.
+    #[cfg(any(test, debug_assertions))]
+    pub fn len(&self) -> usize {
+        self.styles.borrow().len()
+    }
+
+    /// Returns `true` if no stroke style objects are cached.
+    #[cfg(any(test, debug_assertions))]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the stroke style object for the given dash pattern, caps, joins, and
+    /// miter limit, creating and caching it if necessary.
+    ///
+    /// Returns `None` for a solid stroke with flat caps, miter joins, and the default
+    /// miter limit, which Direct2D draws without a style object.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create the stroke style.
+    ///
+    /// # Safety
+    ///
+    /// This function contains an `unsafe` block for creating the stroke style. The
+    /// caller must provide a valid Direct2D factory.
+    pub fn get(
+        &self,
+        factory: &ID2D1Factory1,
+        style: &StrokeStyle,
+        cap: LineCap,
+        join: LineJoin,
+        miter_limit: f32,
+    ) -> Result<Option<ID2D1StrokeStyle>> {
+        if style.is_solid()
+            && cap == LineCap::Flat
+            && join == LineJoin::Miter
+            && miter_limit == DEFAULT_MITER_LIMIT
+        {
+            return Ok(None);
+        }
+
+        let mut key = style.key();
+        key.push(cap as u32);
+        key.push(join as u32);
+        key.push(miter_limit.to_bits());
+        let now = self.clock.get() + 1;
+        self.clock.set(now);
+        if let Some(cached) = self.styles.borrow_mut().get_mut(&key) {
+            cached.last_used = now;
+            return Ok(Some(cached.stroke_style.clone()));
+        }
+
+        let properties = D2D1_STROKE_STYLE_PROPERTIES {
+            startCap: cap.into(),
+            endCap: cap.into(),
+            dashCap: cap.into(),
+            lineJoin: join.into(),
+            miterLimit: miter_limit,
+            dashStyle: style.dash_style(),
+            dashOffset: 0.0,
+        };
+        let dashes = match style {
+            StrokeStyle::Custom(pattern) => Some(pattern.dashes()),
+            _ => None,
+        };
+
+        // `ID2D1Factory1` shadows `CreateStrokeStyle` with a version taking
+        // `D2D1_STROKE_STYLE_PROPERTIES1`, so call the base factory method explicitly.
+        let factory: &ID2D1Factory = factory;
+        let stroke_style = unsafe { factory.CreateStrokeStyle(&properties, dashes)? };
+
+        let mut styles = self.styles.borrow_mut();
+        if styles.len() >= Self::MAX_STYLES {
+            let oldest = styles
+                .iter()
+                .min_by_key(|(_, cached)| cached.last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                styles.remove(&oldest);
+            }
+        }
+        styles.insert(
+            key,
+            CachedStrokeStyle {
+                stroke_style: stroke_style.clone(),
+                last_used: now,
+            },
+        );
+        Ok(Some(stroke_style))
+    }
+}
+
+impl Default for StrokeStyleCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dash_pattern_accepts_dashes_and_gaps() {
+        let pattern = DashPattern::new(vec![4.0, 0.0, 1.0, 2.0]).unwrap();
+        assert_eq!(pattern.dashes(), &[4.0, 0.0, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn dash_pattern_rejects_invalid_lengths() {
+        assert_eq!(DashPattern::new(vec![]), Err(DashPatternError::Empty));
+        assert_eq!(
+            DashPattern::new(vec![2.0, -1.0]),
+            Err(DashPatternError::InvalidLength {
+                length: -1.0,
+                index: 1
+            })
+        );
+        assert!(matches!(
+            DashPattern::new(vec![f32::NAN]),
+            Err(DashPatternError::InvalidLength { index: 0, .. })
+        ));
+        assert!(matches!(
+            StrokeStyle::custom(vec![1.0, f32::INFINITY]),
+            Err(DashPatternError::InvalidLength { index: 1, .. })
+        ));
+        assert_eq!(
+            DashPattern::new(vec![0.0, 0.0]),
+            Err(DashPatternError::ZeroLength)
+        );
+    }
+
+    #[test]
+    fn stroke_extent_covers_miters_and_diagonal_square_caps() {
+        assert_eq!(LineJoin::Miter.stroke_extent(4.0), DEFAULT_MITER_LIMIT * 2.0);
+        assert_eq!(
+            LineJoin::Round.stroke_extent(4.0),
+            std::f32::consts::SQRT_2 * 2.0
+        );
+        assert_eq!(
+            LineJoin::Bevel.stroke_extent(4.0),
+            LineJoin::Round.stroke_extent(4.0)
+        );
+        assert_eq!(LineJoin::Miter.stroke_extent(-1.0), 0.0);
+    }
+
+    /// Returns the Direct2D factory shared by the thread's windows.
+    fn factory() -> ID2D1Factory1 {
+        crate::core::render::graphics_context::GraphicsContext::shared()
+            .unwrap()
+            .d2d_factory
+            .clone()
+    }
+
+    #[test]
+    fn cache_reuses_styles_with_the_same_key() {
+        let factory = factory();
+        let cache = StrokeStyleCache::new();
+        let get = |style: &StrokeStyle, cap| {
+            cache
+                .get(&factory, style, cap, LineJoin::Miter, DEFAULT_MITER_LIMIT)
+                .unwrap()
+        };
+
+        // Plain solid strokes need no style object.
+        assert!(get(&StrokeStyle::Solid, LineCap::Flat).is_none());
+        assert!(cache.is_empty());
+
+        let dash = get(&StrokeStyle::Dash, LineCap::Flat);
+        assert_eq!(get(&StrokeStyle::Dash, LineCap::Flat), dash);
+        assert_eq!(cache.len(), 1);
+
+        assert_ne!(get(&StrokeStyle::Dash, LineCap::Round), dash);
+        let custom = get(&StrokeStyle::custom(vec![3.0, 1.0]).unwrap(), LineCap::Flat);
+        let same = get(&StrokeStyle::custom(vec![3.0, 1.0]).unwrap(), LineCap::Flat);
+        assert_eq!(same, custom);
+        assert_eq!(cache.len(), 3);
+    }
+
+    #[test]
+    fn cache_evicts_the_least_recently_used_style() {
+        let factory = factory();
+        let cache = StrokeStyleCache::new();
+        let get = |miter_limit: f32| {
+            cache
+                .get(
+                    &factory,
+                    &StrokeStyle::Dash,
+                    LineCap::Flat,
+                    LineJoin::Miter,
+                    miter_limit,
+                )
+                .unwrap()
+        };
+
+        let first = get(1.0);
+        let second = get(2.0);
+        for miter_limit in 3..=StrokeStyleCache::MAX_STYLES {
+            get(miter_limit as f32);
+        }
+        assert_eq!(cache.len(), StrokeStyleCache::MAX_STYLES);
+
+        // Using the first style again makes the second the oldest, so it is evicted.
+        assert_eq!(get(1.0), first);
+        get(100.0);
+        assert_eq!(cache.len(), StrokeStyleCache::MAX_STYLES);
+        assert_eq!(get(1.0), first);
+        assert_ne!(get(2.0), second);
+        assert_eq!(cache.len(), StrokeStyleCache::MAX_STYLES);
+    }
+}
@@ -0,0 +1,200 @@
+use windows::{
+    Win32::Foundation::{E_POINTER, HMODULE, HWND},
+    Win32::Graphics::Direct2D::{
+        Common::{D2D1_ALPHA_MODE_IGNORE, D2D1_PIXEL_FORMAT},
+        D2D1_BITMAP_OPTIONS_CANNOT_DRAW, D2D1_BITMAP_OPTIONS_TARGET, D2D1_BITMAP_PROPERTIES1,
+        D2D1_DEVICE_CONTEXT_OPTIONS_NONE, D2DERR_RECREATE_TARGET, ID2D1DeviceContext,
+        ID2D1Factory1,
+    },
+    Win32::Graphics::Direct3D::{D3D_DRIVER_TYPE, D3D_DRIVER_TYPE_HARDWARE, D3D_DRIVER_TYPE_WARP},
+    Win32::Graphics::Direct3D11::{
+        D3D11_CREATE_DEVICE_BGRA_SUPPORT, D3D11_SDK_VERSION, D3D11CreateDevice, ID3D11Device,
+    },
+    Win32::Graphics::Dxgi::{
+        Common::{
+            DXGI_ALPHA_MODE_IGNORE, DXGI_FORMAT_B8G8R8A8_UNORM, DXGI_FORMAT_UNKNOWN,
+            DXGI_SAMPLE_DESC,
+        },
+        DXGI_ERROR_DEVICE_REMOVED, DXGI_ERROR_DEVICE_RESET, DXGI_PRESENT, DXGI_SCALING_STRETCH,
+        DXGI_SWAP_CHAIN_DESC1, DXGI_SWAP_CHAIN_FLAG, DXGI_SWAP_EFFECT_FLIP_SEQUENTIAL,
+        DXGI_USAGE_RENDER_TARGET_OUTPUT, IDXGIDevice, IDXGIFactory2, IDXGISurface, IDXGISwapChain1,
+    },
+    Win32::UI::HiDpi::GetDpiForWindow,
+    core::{Error, HRESULT, Interface, Result},
+};
+
+use crate::core::window::config::PresentMode;
+
+/// A Direct2D device context drawing into the back buffer of a DXGI swap chain.
+///
+/// This is the render target used with `RendererConfig::SwapChain`. Unlike an
+/// `ID2D1HwndRenderTarget`, the device context can host Direct2D effects and gives
+/// control over presentation. It is created on a Direct3D 11 device, or on the WARP
+/// software device if no hardware device is available.
+pub struct SwapChainTarget {
+    /// The Direct3D device the swap chain and device context were created on. Shared
+    /// textures drawn with the device context must be opened on it.
+    pub device: ID3D11Device,
+    /// The device context, which draws into the back buffer.
+    pub device_context: ID2D1DeviceContext,
+    /// The swap chain presented to the window.
+    pub swap_chain: IDXGISwapChain1,
+}
+
+impl SwapChainTarget {
+    /// Creates a device, a swap chain of `width` by `height` pixels for `hwnd`, and a
+    /// device context drawing into its back buffer at the window's DPI.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to create the Direct3D device,
+    /// the Direct2D device or device context, the swap chain, or the target bitmap.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for creating the device, the swap chain,
+    /// and the device context. The caller must ensure that the `hwnd` is a valid
+    /// window handle.
+    pub fn new(factory: &ID2D1Factory1, hwnd: HWND, width: u32, height: u32) -> Result<Self> {
+        let device = create_device(D3D_DRIVER_TYPE_HARDWARE)
+            .or_else(|_| create_device(D3D_DRIVER_TYPE_WARP))?;
+        let dxgi_device: IDXGIDevice = device.cast()?;
+
+        let device_context = unsafe {
+            let d2d_device = factory.CreateDevice(&dxgi_device)?;
+            d2d_device.CreateDeviceContext(D2D1_DEVICE_CONTEXT_OPTIONS_NONE)?
+        };
+        let dpi = unsafe { GetDpiForWindow(hwnd) } as f32;
+        unsafe { device_context.SetDpi(dpi, dpi) };
+
+        let description = DXGI_SWAP_CHAIN_DESC1 {
+            Width: width,
+            Height: height,
+            Format: DXGI_FORMAT_B8G8R8A8_UNORM,
+            SampleDesc: DXGI_SAMPLE_DESC {
+                Count: 1,
+                Quality: 0,
+            },
+            BufferUsage: DXGI_USAGE_RENDER_TARGET_OUTPUT,
+            BufferCount: 2,
+            Scaling: DXGI_SCALING_STRETCH,
+            SwapEffect: DXGI_SWAP_EFFECT_FLIP_SEQUENTIAL,
+            AlphaMode: DXGI_ALPHA_MODE_IGNORE,
+            ..Default::default()
+        };
+        let swap_chain = unsafe {
+            let adapter = dxgi_device.GetAdapter()?;
+            let dxgi_factory: IDXGIFactory2 = adapter.GetParent()?;
+            dxgi_factory.CreateSwapChainForHwnd(&device, hwnd, &description, None, None)?
+        };
+
+        let target = Self {
+            device,
+            device_context,
+            swap_chain,
+        };
+        target.attach_back_buffer()?;
+        Ok(target)
+    }
+
+    /// Resizes the swap chain's buffers to `width` by `height` pixels.
+    ///
+    /// A size of zero, such as while the window is minimized, is ignored.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to resize the buffers or to
+    /// recreate the target bitmap.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for detaching the target bitmap and
+    /// resizing the buffers.
+    pub fn resize(&self, width: u32, height: u32) -> Result<()> {
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+        // The buffers can only be resized once nothing refers to them.
+        unsafe {
+            self.device_context.SetTarget(None);
+            self.swap_chain.ResizeBuffers(
+                0,
+                width,
+                height,
+                DXGI_FORMAT_UNKNOWN,
+                DXGI_SWAP_CHAIN_FLAG(0),
+            )?;
+        }
+        self.attach_back_buffer()
+    }
+
+    /// Presents the back buffer to the window, waiting for the next vertical blank
+    /// with `PresentMode::VSync`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if presenting fails, such as
+    /// `DXGI_ERROR_DEVICE_REMOVED` when the device is lost.
+    pub fn present(&self, present_mode: PresentMode) -> Result<()> {
+        let sync_interval = match present_mode {
+            PresentMode::VSync => 1,
+            PresentMode::Immediate => 0,
+        };
+        unsafe { self.swap_chain.Present(sync_interval, DXGI_PRESENT(0)) }.ok()
+    }
+
+    /// Creates a bitmap for the swap chain's back buffer and makes it the device
+    /// context's target.
+    fn attach_back_buffer(&self) -> Result<()> {
+        let (mut dpi_x, mut dpi_y) = (0.0, 0.0);
+        unsafe { self.device_context.GetDpi(&mut dpi_x, &mut dpi_y) };
+        let properties = D2D1_BITMAP_PROPERTIES1 {
+            pixelFormat: D2D1_PIXEL_FORMAT {
+                format: DXGI_FORMAT_B8G8R8A8_UNORM,
+                alphaMode: D2D1_ALPHA_MODE_IGNORE,
+            },
+            dpiX: dpi_x,
+            dpiY: dpi_y,
+            bitmapOptions: D2D1_BITMAP_OPTIONS_TARGET | D2D1_BITMAP_OPTIONS_CANNOT_DRAW,
+            ..Default::default()
+        };
+        unsafe {
+            let surface: IDXGISurface = self.swap_chain.GetBuffer(0)?;
+            let bitmap = self
+                .device_context
+                .CreateBitmapFromDxgiSurface(&surface, Some(&properties))?;
+            self.device_context.SetTarget(&bitmap);
+        }
+        Ok(())
+    }
+}
+
+/// Returns `true` if `error` means the device was lost and the device-dependent
+/// resources must be recreated.
+pub fn is_device_lost(error: &Error) -> bool {
+    const DEVICE_LOST: [HRESULT; 3] = [
+        D2DERR_RECREATE_TARGET,
+        DXGI_ERROR_DEVICE_REMOVED,
+        DXGI_ERROR_DEVICE_RESET,
+    ];
+    DEVICE_LOST.contains(&error.code())
+}
+
+/// Creates a Direct3D 11 device of the given type that supports Direct2D.
+fn create_device(driver_type: D3D_DRIVER_TYPE) -> Result<ID3D11Device> {
+    let mut device = None;
+    unsafe {
+        D3D11CreateDevice(
+            None,
+            driver_type,
+            HMODULE::default(),
+            D3D11_CREATE_DEVICE_BGRA_SUPPORT,
+            None,
+            D3D11_SDK_VERSION,
+            Some(&mut device),
+            None,
+            None,
+        )?
+    };
+    device.ok_or_else(|| Error::from(E_POINTER))
+}
@@ -0,0 +1,232 @@
+use windows::{
+    Win32::Foundation::E_INVALIDARG,
+    Win32::Graphics::DirectWrite::{
+        DWRITE_PIXEL_GEOMETRY_RGB, DWRITE_RENDERING_MODE_DEFAULT, IDWriteFactory, IDWriteFactory1,
+        IDWriteRenderingParams,
+    },
+    core::{Error, Interface, Result},
+};
+
+/// An error produced when `TextRendering::Custom` settings are out of range.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum TextRenderingError {
+    /// The gamma was zero or less, above 256, or NaN.
+    #[error("gamma must be greater than 0.0 and at most 256.0, not {0}")]
+    Gamma(f32),
+    /// The ClearType enhanced contrast was negative or not finite.
+    #[error("enhanced contrast must be a finite value of at least 0.0, not {0}")]
+    EnhancedContrast(f32),
+    /// The grayscale enhanced contrast was negative or not finite.
+    #[error("grayscale enhanced contrast must be a finite value of at least 0.0, not {0}")]
+    GrayscaleEnhancedContrast(f32),
+    /// The ClearType level was outside 0.0 to 1.0, or NaN.
+    #[error("the ClearType level must be between 0.0 and 1.0, not {0}")]
+    ClearTypeLevel(f32),
+}
+
+/// How DirectWrite renders text: its gamma, contrast, and ClearType level.
+///
+/// The presets cover the common cases, so the DirectWrite settings only need to be
+/// known for `Custom`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextRendering {
+    /// The system's settings for the monitor, as tuned with the ClearType Text Tuner.
+    #[default]
+    Default,
+    /// Heavier, higher-contrast text for light text on a dark background, which looks
+    /// too thin with the default settings.
+    LightOnDark,
+    /// Explicit DirectWrite settings. Create them with `TextRendering::custom`, which
+    /// checks that they are in range.
+    Custom {
+        /// The gamma used for antialiasing, from just above 0.0 to 256.0. The system
+        /// default is usually 1.8; lower values make light text heavier.
+        gamma: f32,
+        /// The extra contrast applied to ClearType text, from 0.0 (none) upwards.
+        /// The system default is usually 0.5.
+        enhanced_contrast: f32,
+        /// The extra contrast applied to grayscale text, from 0.0 (none) upwards. This
+        /// needs Windows 8 or later and is ignored on earlier versions.
+        grayscale_enhanced_contrast: f32,
+        /// How much ClearType is used, from 0.0 (grayscale) to 1.0 (full ClearType).
+        cleartype_level: f32,
+    },
+}
+
+impl TextRendering {
+    /// Creates `TextRendering::Custom` settings.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `gamma` isn't greater than 0.0 and at most
+    /// 256.0, if either contrast is negative or not finite, or if `cleartype_level` is
+    /// outside 0.0 to 1.0.
+    pub fn custom(
+        gamma: f32,
+        enhanced_contrast: f32,
+        grayscale_enhanced_contrast: f32,
+        cleartype_level: f32,
+    ) -> std::result::Result<Self, TextRenderingError> {
+        let settings = TextRendering::Custom {
+            gamma,
+            enhanced_contrast,
+            grayscale_enhanced_contrast,
+            cleartype_level,
+        };
+        settings.validate()?;
+        Ok(settings)
+    }
+
+    /// Checks that the settings are in the ranges DirectWrite accepts. The presets
+    /// always are.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a `Custom` setting is out of range. See
+    /// `TextRendering::custom`.
+    pub fn validate(&self) -> std::result::Result<(), TextRenderingError> {
+        let TextRendering::Custom {
+            gamma,
+            enhanced_contrast,
+            grayscale_enhanced_contrast,
+            cleartype_level,
+        } = *self
+        else {
+            return Ok(());
+        };
+        if !(gamma > 0.0 && gamma <= 256.0) {
+            return Err(TextRenderingError::Gamma(gamma));
+        }
+        if !(enhanced_contrast.is_finite() && enhanced_contrast >= 0.0) {
+            return Err(TextRenderingError::EnhancedContrast(enhanced_contrast));
+        }
+        if !(grayscale_enhanced_contrast.is_finite() && grayscale_enhanced_contrast >= 0.0) {
+            return Err(TextRenderingError::GrayscaleEnhancedContrast(
+                grayscale_enhanced_contrast,
+            ));
+        }
+        if !(0.0..=1.0).contains(&cleartype_level) {
+            return Err(TextRenderingError::ClearTypeLevel(cleartype_level));
+        }
+        Ok(())
+    }
+
+    /// Returns the DirectWrite settings of the preset, or `None` for `Default`.
+    fn settings(&self) -> Option<(f32, f32, f32, f32)> {
+        match *self {
+            TextRendering::Default => None,
+            TextRendering::LightOnDark => Some((1.2, 1.0, 1.0, 1.0)),
+            TextRendering::Custom {
+                gamma,
+                enhanced_contrast,
+                grayscale_enhanced_contrast,
+                cleartype_level,
+            } => Some((
+                gamma,
+                enhanced_contrast,
+                grayscale_enhanced_contrast,
+                cleartype_level,
+            )),
+        }
+    }
+
+    /// Creates the rendering parameters to set on render targets with
+    /// `SetTextRenderingParams`, or returns `None` for `Default`, which leaves the
+    /// render target's own parameters in place.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the parameters are out of range, with the
+    /// `TextRenderingError` as its message, or if DirectWrite fails to create them.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for creating the rendering parameters.
+    pub(crate) fn create_params(
+        &self,
+        factory: &IDWriteFactory,
+    ) -> Result<Option<IDWriteRenderingParams>> {
+        // `Custom` can be built without `TextRendering::custom`, such as by
+        // deserializing it, and DirectWrite only reports `E_INVALIDARG`.
+        self.validate()
+            .map_err(|e| Error::new(E_INVALIDARG, e.to_string()))?;
+        let Some((gamma, contrast, grayscale_contrast, cleartype_level)) = self.settings() else {
+            return Ok(None);
+        };
+
+        // Grayscale contrast arrived alongside `IDWriteFactory1`.
+        let params = match factory.cast::<IDWriteFactory1>() {
+            Ok(factory) => unsafe {
+                factory
+                    .CreateCustomRenderingParams2(
+                        gamma,
+                        contrast,
+                        grayscale_contrast,
+                        cleartype_level,
+                        DWRITE_PIXEL_GEOMETRY_RGB,
+                        DWRITE_RENDERING_MODE_DEFAULT,
+                    )?
+                    .cast()?
+            },
+            Err(_) => unsafe {
+                factory.CreateCustomRenderingParams(
+                    gamma,
+                    contrast,
+                    cleartype_level,
+                    DWRITE_PIXEL_GEOMETRY_RGB,
+                    DWRITE_RENDERING_MODE_DEFAULT,
+                )?
+            },
+        };
+        Ok(Some(params))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_accepts_settings_in_range() {
+        assert_eq!(
+            TextRendering::custom(1.8, 0.5, 1.0, 1.0),
+            Ok(TextRendering::Custom {
+                gamma: 1.8,
+                enhanced_contrast: 0.5,
+                grayscale_enhanced_contrast: 1.0,
+                cleartype_level: 1.0,
+            })
+        );
+        assert!(TextRendering::custom(256.0, 0.0, 0.0, 0.0).is_ok());
+        assert_eq!(TextRendering::LightOnDark.validate(), Ok(()));
+    }
+
+    #[test]
+    fn custom_rejects_settings_out_of_range() {
+        assert_eq!(
+            TextRendering::custom(0.0, 0.5, 1.0, 1.0),
+            Err(TextRenderingError::Gamma(0.0))
+        );
+        assert_eq!(
+            TextRendering::custom(300.0, 0.5, 1.0, 1.0),
+            Err(TextRenderingError::Gamma(300.0))
+        );
+        assert!(matches!(
+            TextRendering::custom(f32::NAN, 0.5, 1.0, 1.0),
+            Err(TextRenderingError::Gamma(_))
+        ));
+        assert_eq!(
+            TextRendering::custom(1.8, -0.1, 1.0, 1.0),
+            Err(TextRenderingError::EnhancedContrast(-0.1))
+        );
+        assert_eq!(
+            TextRendering::custom(1.8, 0.5, f32::INFINITY, 1.0),
+            Err(TextRenderingError::GrayscaleEnhancedContrast(f32::INFINITY))
+        );
+        assert_eq!(
+            TextRendering::custom(1.8, 0.5, 1.0, 1.5),
+            Err(TextRenderingError::ClearTypeLevel(1.5))
+        );
+    }
+}
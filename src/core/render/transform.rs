@@ -0,0 +1,250 @@
+#[cfg(windows)]
+use std::cell::RefCell;
+
+#[cfg(windows)]
+use windows::core::Result;
+use windows_numerics::{Matrix3x2, Vector2};
+
+#[cfg(windows)]
+use crate::core::backend::Renderer;
+#[cfg(windows)]
+use crate::core::render::drawing_context::DrawingContext;
+#[cfg(windows)]
+use crate::core::render::rect::Rect;
+
+/// A per-object rotation and scale, applied around a pivot point.
+///
+/// The transform is composed with whatever transform is already active on the render
+/// target (for example from an enclosing container), so it only affects the object it
+/// is attached to. Scaling is applied before rotation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transform {
+    /// The clockwise rotation, in degrees.
+    pub rotation: f32,
+    /// The horizontal and vertical scale factors.
+    #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2"))]
+    pub scale: Vector2,
+    /// The pivot point, or `None` to pivot around the center of the object.
+    #[cfg_attr(feature = "serde", serde(with = "crate::core::render::serde_vector2::option"))]
+    pub origin: Option<Vector2>,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            rotation: 0.0,
+            scale: Vector2 { X: 1.0, Y: 1.0 },
+            origin: None,
+        }
+    }
+}
+
+impl Transform {
+    /// Creates a new `Transform` that rotates by `degrees` clockwise.
+    pub fn rotation(degrees: f32) -> Self {
+        Self {
+            rotation: degrees,
+            ..Self::default()
+        }
+    }
+
+    /// Creates a new `Transform` that scales by the given factors.
+    pub fn scale(x: f32, y: f32) -> Self {
+        Self {
+            scale: Vector2 { X: x, Y: y },
+            ..Self::default()
+        }
+    }
+
+    /// Sets the rotation, in degrees.
+    pub fn with_rotation(mut self, degrees: f32) -> Self {
+        self.rotation = degrees;
+        self
+    }
+
+    /// Sets the scale factors.
+    pub fn with_scale(mut self, x: f32, y: f32) -> Self {
+        self.scale = Vector2 { X: x, Y: y };
+        self
+    }
+
+    /// Sets the pivot point the rotation and scale are applied around.
+    pub fn with_origin(mut self, origin: Vector2) -> Self {
+        self.origin = Some(origin);
+        self
+    }
+
+    /// Returns `true` if the transform leaves objects unchanged.
+    pub fn is_identity(&self) -> bool {
+        self.rotation == 0.0 && self.scale.X == 1.0 && self.scale.Y == 1.0
+    }
+
+    /// Returns the matrix for this transform, pivoting around `default_origin` unless
+    /// an explicit origin is set.
+    pub fn to_matrix(&self, default_origin: Vector2) -> Matrix3x2 {
+        let origin = self.origin.unwrap_or(default_origin);
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        let m11 = self.scale.X * cos;
+        let m12 = self.scale.X * sin;
+        let m21 = -self.scale.Y * sin;
+        let m22 = self.scale.Y * cos;
+        Matrix3x2 {
+            M11: m11,
+            M12: m12,
+            M21: m21,
+            M22: m22,
+            M31: origin.X - (origin.X * m11 + origin.Y * m21),
+            M32: origin.Y - (origin.X * m12 + origin.Y * m22),
+        }
+    }
+}
+
+/// Runs `draw` with the object's transform, if any, composed onto the render target.
+///
+/// `center` is only evaluated when a non-identity transform without an explicit origin
+/// needs a pivot.
+#[cfg(windows)]
+pub(crate) fn draw_transformed(
+    context: &DrawingContext,
+    transform: Option<&Transform>,
+    center: impl FnOnce() -> Vector2,
+    draw: impl FnOnce() -> Result<()>,
+) -> Result<()> {
+    match transform {
+        Some(transform) if !transform.is_identity() => {
+            let local = transform.to_matrix(transform.origin.unwrap_or_else(center));
+            context.with_transform(&local, draw)
+        }
+        _ => draw(),
+    }
+}
+
+/// Runs `render` with the object's transform, if any, composed onto the renderer's
+/// transform, like `draw_transformed` does for a `DrawingContext`.
+#[cfg(windows)]
+pub(crate) fn render_transformed(
+    renderer: &mut dyn Renderer,
+    transform: Option<&Transform>,
+    center: impl FnOnce() -> Vector2,
+    render: impl FnOnce(&mut dyn Renderer),
+) {
+    match transform {
+        Some(transform) if !transform.is_identity() => {
+            let local = transform.to_matrix(transform.origin.unwrap_or_else(center));
+            renderer.with_transform(&local, render)
+        }
+        _ => render(renderer),
+    }
+}
+
+/// Returns the bounding box of an object with the given untransformed bounds after its
+/// transform, if any, is applied.
+///
+/// `center` is the same default pivot the object passes to `draw_transformed`.
+#[cfg(windows)]
+pub(crate) fn transformed_bounds(
+    transform: Option<&Transform>,
+    center: impl FnOnce() -> Vector2,
+    bounds: Rect,
+) -> Rect {
+    match transform {
+        Some(transform) if !transform.is_identity() => {
+            bounds.transform(&transform.to_matrix(transform.origin.unwrap_or_else(center)))
+        }
+        _ => bounds,
+    }
+}
+
+/// The transforms replaced by `DrawingContext::push_transform` that haven't been
+/// restored by `pop_transform` yet.
+///
+/// It is owned by the `Direct2DContext` and shared by every `DrawingContext`.
+#[cfg(windows)]
+pub struct TransformStack {
+    /// The render target transform before each unpopped push, innermost last.
+    saved: RefCell<Vec<Matrix3x2>>,
+}
+
+#[cfg(windows)]
+impl TransformStack {
+    /// Creates a new, empty `TransformStack`.
+    pub fn new() -> Self {
+        Self {
+            saved: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Returns the number of transforms pushed with `DrawingContext::push_transform`
+    /// that haven't been popped yet.
+    pub fn depth(&self) -> usize {
+        self.saved.borrow().len()
+    }
+
+    /// Saves the transform a push replaces.
+    pub(crate) fn push(&self, original: Matrix3x2) {
+        self.saved.borrow_mut().push(original);
+    }
+
+    /// Removes and returns the transform the innermost unpopped push replaced.
+    pub(crate) fn pop(&self) -> Option<Matrix3x2> {
+        self.saved.borrow_mut().pop()
+    }
+}
+
+#[cfg(windows)]
+impl Default for TransformStack {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the inverse of `matrix`, or `None` if it collapses the plane onto a line or
+/// a point and so can't be inverted.
+pub(crate) fn invert(matrix: &Matrix3x2) -> Option<Matrix3x2> {
+    let determinant = matrix.M11 * matrix.M22 - matrix.M12 * matrix.M21;
+    if determinant == 0.0 || !determinant.is_finite() {
+        return None;
+    }
+    Some(Matrix3x2 {
+        M11: matrix.M22 / determinant,
+        M12: -matrix.M12 / determinant,
+        M21: -matrix.M21 / determinant,
+        M22: matrix.M11 / determinant,
+        M31: (matrix.M21 * matrix.M32 - matrix.M22 * matrix.M31) / determinant,
+        M32: (matrix.M12 * matrix.M31 - matrix.M11 * matrix.M32) / determinant,
+    })
+}
+
+/// Returns `point` transformed by `matrix`.
+pub(crate) fn transform_point(matrix: &Matrix3x2, point: Vector2) -> Vector2 {
+    Vector2 {
+        X: point.X * matrix.M11 + point.Y * matrix.M21 + matrix.M31,
+        Y: point.X * matrix.M12 + point.Y * matrix.M22 + matrix.M32,
+    }
+}
+
+/// Returns the center of the bounding box of `points`, or the origin if there are none.
+#[cfg(windows)]
+pub(crate) fn bounds_center(points: impl IntoIterator<Item = Vector2>) -> Vector2 {
+    let mut points = points.into_iter();
+    let Some(first) = points.next() else {
+        return Vector2::default();
+    };
+    let (min, max) = points.fold((first, first), |(min, max), point| {
+        (
+            Vector2 {
+                X: min.X.min(point.X),
+                Y: min.Y.min(point.Y),
+            },
+            Vector2 {
+                X: max.X.max(point.X),
+                Y: max.Y.max(point.Y),
+            },
+        )
+    });
+    Vector2 {
+        X: (min.X + max.X) / 2.0,
+        Y: (min.Y + max.Y) / 2.0,
+    }
+}
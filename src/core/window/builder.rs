@@ -1,7 +1,11 @@
 use crate::app::App;
 use crate::core::event::event_handler::EventHandler;
+use crate::core::render::color::Color;
+use crate::core::render::objects::text_object::{FontStretch, FontStyle, FontWeight};
+use crate::core::render::text_rendering::TextRendering;
 use crate::core::window::config::WindowConfig;
 use super::Window;
+use std::path::PathBuf;
 use windows::core::Result;
 
 /// A builder for creating and configuring a `Window`.
@@ -59,11 +63,61 @@ impl WindowBuilder {
         self
     }
 
+    /// Sets the weight of the window's default font.
+    pub fn with_font_weight(mut self, weight: FontWeight) -> Self {
+        self.config.font_weight = weight;
+        self
+    }
+
+    /// Sets the slant of the window's default font.
+    pub fn with_font_style(mut self, style: FontStyle) -> Self {
+        self.config.font_style = style;
+        self
+    }
+
+    /// Sets the width of the window's default font.
+    pub fn with_font_stretch(mut self, stretch: FontStretch) -> Self {
+        self.config.font_stretch = stretch;
+        self
+    }
+
+    /// Adds a font file to load, so its families can be used as the font face name.
+    pub fn with_font_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.font_files.push(path.into());
+        self
+    }
+
+    /// Sets whether color fonts, such as emoji, are drawn in color.
+    pub fn with_color_fonts(mut self, color_fonts: bool) -> Self {
+        self.config.color_fonts = color_fonts;
+        self
+    }
+
+    /// Sets the locale used to select fonts and shape text, such as `ja-JP`.
+    pub fn with_locale(mut self, locale: &str) -> Self {
+        self.config.locale = Some(locale.to_string());
+        self
+    }
+
+    /// Sets how text is rendered, such as `TextRendering::LightOnDark` for light text on
+    /// a dark background.
+    pub fn with_text_rendering(mut self, text_rendering: TextRendering) -> Self {
+        self.config.text_rendering = text_rendering;
+        self
+    }
+
+    /// Sets the color the window is cleared to before the scene is drawn.
+    pub fn with_background_color(mut self, color: Color) -> Self {
+        self.config.background_color = color;
+        self
+    }
+
     /// Builds the window.
     ///
     /// # Errors
     ///
-    /// This function will return an error if it fails to create the window.
+    /// This function will return an error if it fails to create the window, or if a
+    /// font file is missing or is not a supported font.
     pub fn build<E: EventHandler + 'static>(&self, event_handler: E, app: App) -> Result<Box<Window<E>>> {
         Window::new(&self.config, event_handler, app)
     }
@@ -1,3 +1,61 @@
+use std::path::PathBuf;
+
+use crate::core::render::antialias::AntialiasMode;
+use crate::core::render::color::Color;
+use crate::core::render::objects::text_object::{FontStretch, FontStyle, FontWeight};
+use crate::core::render::text_rendering::TextRendering;
+
+/// How a window's contents are rasterized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RendererConfig {
+    /// Direct2D on the GPU, falling back to the CPU when no GPU is available.
+    #[default]
+    Direct2D,
+    /// The framework's own rasterizer, `SoftwareRenderer`, on the CPU without
+    /// Direct2D, presented to the window with GDI.
+    ///
+    /// This is slower, but works where the graphics driver is broken or unavailable,
+    /// such as some remote desktop sessions, and produces the same pixels on every
+    /// machine. A window falls back to it if the configured renderer can't be created.
+    ///
+    /// Only the scene is drawn, with `Scene::render`, so what that supports, such as
+    /// solid colors and the built-in bitmap font, is all that is shown. Event
+    /// handlers' `on_paint` isn't called, since it draws through Direct2D.
+    Software,
+    /// GDI, drawing into an offscreen bitmap with `GdiRenderer`.
+    ///
+    /// Like `Software`, this works without Direct2D and only draws the scene with
+    /// `Scene::render`, but GDI can use the installed fonts, so text is drawn in the
+    /// window's font. Edges aren't antialiased and translucent colors are drawn
+    /// opaque. A window falls back to it if Direct2D can't be initialized.
+    Gdi,
+    /// Direct2D on a device context drawing into a DXGI swap chain.
+    ///
+    /// Unlike the other options, the render target can host Direct2D effects, such as
+    /// the blur of `BlurRegion`, and presentation is under the framework's control.
+    /// If the device is lost, the device, swap chain, and device context are recreated
+    /// on the next paint.
+    SwapChain,
+    /// wgpu, drawing with `WgpuRenderer` on Direct3D 12 or Vulkan.
+    ///
+    /// This is experimental and needs the `wgpu` feature. Like `Software`, only the
+    /// scene is drawn, with `Scene::render`, and text is drawn with the built-in
+    /// bitmap font. A window falls back to `Direct2D` if no adapter supports it.
+    #[cfg(feature = "wgpu")]
+    Wgpu,
+}
+
+/// When a finished frame is shown on the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PresentMode {
+    /// Waits for the display's vertical blank, so frames never tear.
+    #[default]
+    VSync,
+    /// Shows frames as soon as they are drawn, for the lowest latency. Moving content
+    /// may tear.
+    Immediate,
+}
+
 /// Configuration for a window.
 ///
 /// This struct holds all the settings for a window, such as its title, size,
@@ -7,14 +65,61 @@ pub struct WindowConfig {
     pub title: String,
     /// The name of the window class.
     pub class_name: String,
-    /// The width of the window.
+    /// The width of the window, in DIPs. It is scaled to the monitor's DPI when the
+    /// window is created, so the window has the same physical size at any scale.
     pub width: i32,
-    /// The height of the window.
+    /// The height of the window, in DIPs.
     pub height: i32,
-    /// The font size for the window.
+    /// The font size for the window, in DIPs. DirectWrite scales it with the render
+    /// target's DPI, so it must not be scaled again.
     pub font_size: i32,
     /// The font face name for the window.
     pub font_face_name: String,
+    /// The weight of the window's default font.
+    pub font_weight: FontWeight,
+    /// The slant of the window's default font.
+    pub font_style: FontStyle,
+    /// The width of the window's default font.
+    pub font_stretch: FontStretch,
+    /// Font files to load in addition to the installed system fonts.
+    ///
+    /// The families in these files can be used as `font_face_name`, which lets an
+    /// application ship with a bundled font.
+    pub font_files: Vec<PathBuf>,
+    /// Whether color fonts, such as Segoe UI Emoji, are drawn in color.
+    ///
+    /// This has no effect on Windows versions that don't support color fonts.
+    pub color_fonts: bool,
+    /// How text is rendered, such as heavier for light text on a dark background.
+    pub text_rendering: TextRendering,
+    /// The locale used to select fonts and shape text, such as `ja-JP`, or `None` to
+    /// use the user's default locale.
+    pub locale: Option<String>,
+    /// How the window's contents are rasterized.
+    pub renderer: RendererConfig,
+    /// When frames are shown on the screen.
+    pub present_mode: PresentMode,
+    /// Whether the HWND render target keeps the previous frame's contents after
+    /// presenting, for applications that only redraw part of the window.
+    ///
+    /// Swap chain targets flip between buffers instead, so this has no effect with
+    /// `RendererConfig::SwapChain`.
+    pub retain_contents: bool,
+    /// How the edges of shapes are drawn, unless a drawable overrides it.
+    pub antialias_mode: AntialiasMode,
+    /// The color the window is cleared to before the scene is drawn.
+    ///
+    /// The window class's background brush has the same color, so a resized window
+    /// doesn't flash another color before it is repainted. It can be changed while the
+    /// window runs with `App::set_background_color`.
+    pub background_color: Color,
+    /// Whether shapes are aligned to device pixels, so thin lines and borders are
+    /// crisp instead of smeared over two pixels.
+    ///
+    /// Rectangles, lines, and grids round their edges and stroke widths to whole
+    /// device pixels at the window's DPI. This suits tables, grids, and separators, but
+    /// makes slowly moving shapes jump from pixel to pixel.
+    pub pixel_snap: bool,
 }
 
 impl Default for WindowConfig {
@@ -27,6 +132,19 @@ impl Default for WindowConfig {
             height: 600,
             font_size: 18,
             font_face_name: "MS Gothic".to_string(),
+            font_weight: FontWeight::Normal,
+            font_style: FontStyle::Normal,
+            font_stretch: FontStretch::Normal,
+            font_files: Vec::new(),
+            color_fonts: true,
+            text_rendering: TextRendering::default(),
+            locale: None,
+            renderer: RendererConfig::default(),
+            present_mode: PresentMode::default(),
+            retain_contents: false,
+            antialias_mode: AntialiasMode::default(),
+            background_color: Color::BLACK,
+            pixel_snap: false,
         }
     }
 }
\ No newline at end of file
@@ -19,15 +19,33 @@ use windows::{
     Win32::Foundation::{GetLastError, *},
     Win32::Graphics::Gdi::*,
     Win32::System::LibraryLoader::GetModuleHandleW,
+    Win32::UI::HiDpi::{
+        DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2, GetDpiForWindow, SetProcessDpiAwarenessContext,
+    },
     Win32::UI::WindowsAndMessaging::*,
 };
 
+#[cfg(feature = "wgpu")]
+use std::num::NonZeroIsize;
+
+#[cfg(feature = "wgpu")]
+use raw_window_handle::{
+    RawDisplayHandle, RawWindowHandle, Win32WindowHandle, WindowsDisplayHandle,
+};
+use windows_numerics::Matrix3x2;
+
 use self::wndproc_utils::wndproc;
-use crate::core::window::config::WindowConfig;
+use crate::core::window::config::{RendererConfig, WindowConfig};
 
 use crate::core::event::event_handler::EventHandler;
 use crate::app::App;
+use crate::core::backend::gdi::colorref;
+use crate::core::backend::{GdiRenderer, Renderer, SoftwareRenderer};
+#[cfg(feature = "wgpu")]
+use crate::core::backend::{WgpuError, WgpuRenderer};
+use crate::core::render::color::Color;
 use crate::core::render::direct2d_context::Direct2DContext;
+use crate::core::render::scene::Scene;
 
 /// Represents an application window.
 ///
@@ -37,6 +55,16 @@ use crate::core::render::direct2d_context::Direct2DContext;
 pub struct Window<E: EventHandler> {
     pub hwnd: HWND,
     pub d2d_context: Direct2DContext,
+    /// The framebuffer the window is drawn into with `RendererConfig::Software`, or
+    /// `None` if it is drawn with Direct2D.
+    pub software: Option<SoftwareRenderer>,
+    /// The bitmap the window is drawn into with `RendererConfig::Gdi`, or `None` if it
+    /// is drawn otherwise.
+    pub gdi: Option<GdiRenderer>,
+    /// The renderer the window is drawn with on `RendererConfig::Wgpu`, or `None` if it
+    /// is drawn otherwise.
+    #[cfg(feature = "wgpu")]
+    pub wgpu: Option<WgpuRenderer>,
     pub event_handler: E,
     pub app: App,
 }
@@ -45,14 +73,22 @@ impl<E: EventHandler + 'static> Window<E> {
     /// Creates a new application window.
     ///
     /// This function orchestrates the entire window setup process:
-    /// 1. Registers the window class with the operating system.
-    /// 2. Creates the `Window` struct on the heap using `Box::new`.
-    /// 3. Creates the actual Win32 window using `CreateWindowExW`.
+    /// 1. Makes the process per-monitor DPI aware, so Windows doesn't stretch its
+    ///    windows on high-DPI displays.
+    /// 2. Registers the window class with the operating system.
+    /// 3. Creates the `Window` struct on the heap using `Box::new`.
+    /// 4. Creates the actual Win32 window using `CreateWindowExW`.
     ///    - A raw pointer to the heap-allocated `Window` struct is passed as the
     ///      `lpParam` argument. This pointer is retrieved in `wndproc` during the
     ///      `WM_NCCREATE` message to associate the Rust struct with the `HWND`.
-    /// 4. Initializes device-dependent Direct2D resources.
-    /// 5. Shows and updates the window.
+    /// 5. Scales the window from the DIPs in `config` to the monitor's DPI.
+    /// 6. Creates the wgpu renderer with `RendererConfig::Wgpu`, falling back to
+    ///    `RendererConfig::Direct2D` if no adapter supports it.
+    /// 7. Initializes device-dependent Direct2D resources, falling back to
+    ///    `RendererConfig::Gdi` if the configured renderer fails, and to
+    ///    `RendererConfig::Software` if GDI fails too. Neither needs a device, so their
+    ///    bitmaps are only sized when painting.
+    /// 8. Shows and updates the window.
     ///
     /// The returned `Box<Self>` is the sole owner of the `Window` struct at this point.
     /// However, its lifetime will be managed by the `wndproc` and the message loop,
@@ -70,12 +106,22 @@ impl<E: EventHandler + 'static> Window<E> {
     /// the window, and showing and updating the window. The caller must ensure that
     /// it is safe to perform these operations.
     pub(super) fn new(config: &WindowConfig, event_handler: E, app: App) -> Result<Box<Self>> {
+        // This fails if the awareness was already set, such as by the manifest or an
+        // earlier window, which is fine.
+        unsafe {
+            let _ = SetProcessDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2);
+        }
+
         let instance = unsafe { GetModuleHandleW(None)? };
-        Self::register_class(instance.into(), &config.class_name)?;
+        Self::register_class(instance.into(), &config.class_name, config.background_color)?;
 
         let mut window = Box::new(Self {
             hwnd: HWND(std::ptr::null_mut()),
-            d2d_context: Direct2DContext::new(&config.font_face_name, config.font_size as f32)?,
+            d2d_context: Direct2DContext::new(config)?,
+            software: None,
+            gdi: None,
+            #[cfg(feature = "wgpu")]
+            wgpu: None,
             event_handler,
             app,
         });
@@ -98,7 +144,56 @@ impl<E: EventHandler + 'static> Window<E> {
         };
 
         window.hwnd = hwnd;
-        window.d2d_context.create_device_dependent_resources(hwnd)?;
+
+        // The window is created before its monitor is known, so its size in DIPs is
+        // only converted to pixels now.
+        let dpi = unsafe { GetDpiForWindow(hwnd) } as i32;
+        unsafe {
+            SetWindowPos(
+                hwnd,
+                None,
+                0,
+                0,
+                config.width * dpi / 96,
+                config.height * dpi / 96,
+                SWP_NOMOVE | SWP_NOZORDER | SWP_NOACTIVATE,
+            )?;
+        }
+
+        // Where Direct2D can't be used, such as with a broken driver, GDI still works,
+        // and the software renderer needs nothing but the CPU and a bitmap copy.
+        let mut renderer = window.d2d_context.renderer;
+        #[cfg(feature = "wgpu")]
+        if renderer == RendererConfig::Wgpu {
+            match window.create_wgpu_renderer(instance) {
+                Ok(wgpu) => window.wgpu = Some(wgpu),
+                Err(e) => {
+                    println!("Falling back to the Direct2D renderer: {:?}", e);
+                    renderer = RendererConfig::Direct2D;
+                    window.d2d_context.renderer = renderer;
+                }
+            }
+        }
+        if matches!(renderer, RendererConfig::Direct2D | RendererConfig::SwapChain) {
+            if let Err(e) = window.d2d_context.create_device_dependent_resources(hwnd) {
+                println!("Falling back to the GDI renderer: {:?}", e);
+                renderer = RendererConfig::Gdi;
+            }
+        }
+        if renderer == RendererConfig::Gdi {
+            let font_size = config.font_size as f32;
+            match GdiRenderer::new(0, 0, &config.font_face_name, font_size) {
+                Ok(gdi) => window.gdi = Some(gdi),
+                Err(e) => {
+                    println!("Falling back to the software renderer: {:?}", e);
+                    renderer = RendererConfig::Software;
+                }
+            }
+        }
+        if renderer == RendererConfig::Software {
+            window.software = Some(SoftwareRenderer::new(0, 0));
+        }
+        window.d2d_context.renderer = renderer;
 
         unsafe {
             let _ = ShowWindow(hwnd, SW_SHOW);
@@ -110,7 +205,33 @@ impl<E: EventHandler + 'static> Window<E> {
         Ok(window)
     }
 
-    /// Registers the window class.
+    /// Creates a `WgpuRenderer` drawing to the window, whose surface is sized when
+    /// painting.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the window hasn't been created, or if the
+    /// renderer can't be created for it.
+    ///
+    /// # Safety
+    ///
+    /// This function contains an `unsafe` block for creating the renderer's surface from
+    /// the window handle.
+    #[cfg(feature = "wgpu")]
+    fn create_wgpu_renderer(
+        &self,
+        instance: HMODULE,
+    ) -> std::result::Result<WgpuRenderer, WgpuError> {
+        let hwnd = NonZeroIsize::new(self.hwnd.0 as isize).ok_or(WgpuError::UnsupportedSurface)?;
+        let mut handle = Win32WindowHandle::new(hwnd);
+        handle.hinstance = NonZeroIsize::new(instance.0 as isize);
+        let display = RawDisplayHandle::Windows(WindowsDisplayHandle::new());
+        // The renderer is owned by the window, so it is dropped before the window
+        // handle is freed.
+        unsafe { WgpuRenderer::new(display, RawWindowHandle::Win32(handle), 0, 0) }
+    }
+
+    /// Registers the window class, with a background brush of `background_color`.
     ///
     /// # Errors
     ///
@@ -122,7 +243,11 @@ impl<E: EventHandler + 'static> Window<E> {
     /// This function contains `unsafe` blocks for loading the icon and cursor and
     /// registering the window class. The caller must ensure that it is safe to
     /// perform these operations.
-    fn register_class(instance: HINSTANCE, class_name: &str) -> Result<()> {
+    fn register_class(
+        instance: HINSTANCE,
+        class_name: &str,
+        background_color: Color,
+    ) -> Result<()> {
         let class_name_hstring = HSTRING::from(class_name);
 
         let wc = WNDCLASSEXW {
@@ -134,7 +259,7 @@ impl<E: EventHandler + 'static> Window<E> {
             hInstance: instance,
             hIcon: unsafe { LoadIconW(None, IDI_APPLICATION)? },
             hCursor: unsafe { LoadCursorW(None, IDC_ARROW)? },
-            hbrBackground: unsafe { HBRUSH(GetStockObject(BLACK_BRUSH).0) },
+            hbrBackground: background_brush(background_color),
             lpszMenuName: PCWSTR::null(),
             lpszClassName: PCWSTR::from_raw(class_name_hstring.as_ptr()),
             hIconSm: unsafe { LoadIconW(None, IDI_APPLICATION)? },
@@ -149,6 +274,156 @@ impl<E: EventHandler + 'static> Window<E> {
         Ok(())
     }
 
+    /// Changes the color the window is cleared to before the scene is drawn, and
+    /// repaints the window.
+    ///
+    /// The window class's background brush is replaced too, so the color shown while
+    /// the window is resized matches. Event handlers can change the color with
+    /// `App::set_background_color`.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for replacing the class background brush
+    /// and invalidating the window.
+    pub fn set_background_color(&mut self, color: Color) {
+        self.d2d_context.background_color = color;
+        unsafe {
+            let brush = background_brush(color);
+            let previous = SetClassLongPtrW(self.hwnd, GCLP_HBRBACKGROUND, brush.0 as isize);
+            if previous != 0 {
+                let _ = DeleteObject(HGDIOBJ(previous as *mut _));
+            }
+            let _ = InvalidateRect(Some(self.hwnd), None, false);
+        }
+    }
+
+    /// Saves the window's current frame as a PNG file at `path`.
+    ///
+    /// The window's render target can't be read back, so the frame is painted again
+    /// into an offscreen bitmap: the event handler's `on_paint` runs just as it does
+    /// for `WM_PAINT`, so the capture includes everything the handlers draw, not only
+    /// the scene. The bitmap has the render target's size in pixels and its DPI, so
+    /// the capture matches the window pixel for pixel on high-DPI displays too.
+    ///
+    /// `App::is_capturing_frame` is `true` during that `on_paint`, so handlers that
+    /// change the app when painting skip it: `AnimationHandler` doesn't advance the
+    /// scene, and `DiagnosticsHandler` keeps the statistics of the frame shown.
+    ///
+    /// With `RendererConfig::Software` or `RendererConfig::Gdi`, the scene is drawn into
+    /// the renderer's bitmap again and the bitmap is saved. Frames drawn with
+    /// `RendererConfig::Wgpu` can't be captured.
+    ///
+    /// Event handlers can ask for a capture with `App::request_capture`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the window renders with wgpu, if the
+    /// render target hasn't been created, or if it fails to paint, read back, or save
+    /// the frame.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for reading the size and DPI of the
+    /// render target.
+    pub fn capture_frame(&mut self, path: impl AsRef<std::path::Path>) -> Result<()> {
+        #[cfg(feature = "wgpu")]
+        if self.wgpu.is_some() {
+            return Err(Error::new(E_NOTIMPL, "frames drawn with wgpu can't be captured"));
+        }
+        self.render_scene_frame()?;
+        if let Some(renderer) = &self.software {
+            return self.d2d_context.save_png(
+                path,
+                renderer.width(),
+                renderer.height(),
+                renderer.pixels(),
+            );
+        }
+        if let Some(renderer) = &self.gdi {
+            return self.d2d_context.save_png(
+                path,
+                renderer.width(),
+                renderer.height(),
+                &renderer.pixels(),
+            );
+        }
+
+        let Some(render_target) = &self.d2d_context.render_target else {
+            return Err(Error::new(E_FAIL, "the render target has not been created"));
+        };
+        let (mut dpi_x, mut dpi_y) = (0.0, 0.0);
+        let size = unsafe {
+            render_target.GetDpi(&mut dpi_x, &mut dpi_y);
+            render_target.GetPixelSize()
+        };
+
+        self.app.set_capturing_frame(true);
+        let pixels = self
+            .d2d_context
+            .render_offscreen(size.width, size.height, dpi_x, |drawing_context| {
+                self.event_handler.on_paint(&mut self.app, drawing_context);
+                Ok(())
+            });
+        self.app.set_capturing_frame(false);
+        self.d2d_context.save_png(path, size.width, size.height, &pixels?)
+    }
+
+    /// Returns `true` if the window is drawn by Direct2D, rather than by the software,
+    /// GDI, or wgpu renderer.
+    pub fn draws_with_direct2d(&self) -> bool {
+        let direct2d = self.software.is_none() && self.gdi.is_none();
+        #[cfg(feature = "wgpu")]
+        let direct2d = direct2d && self.wgpu.is_none();
+        direct2d
+    }
+
+    /// Draws the scene with the software, GDI, or wgpu renderer, if the window renders
+    /// with one. The wgpu renderer only records the frame, which `WgpuRenderer::present`
+    /// draws.
+    ///
+    /// The bitmap or surface is sized to the client area and cleared to the background
+    /// color, and the scene is scaled from DIPs to the window's DPI, as Direct2D does.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it fails to get the client rect or to
+    /// resize the GDI bitmap.
+    ///
+    /// # Safety
+    ///
+    /// This function contains `unsafe` blocks for getting the client rect and DPI.
+    pub(crate) fn render_scene_frame(&mut self) -> Result<()> {
+        if self.draws_with_direct2d() {
+            return Ok(());
+        }
+        let mut rect = RECT::default();
+        unsafe { GetClientRect(self.hwnd, &mut rect)? };
+        let width = (rect.right - rect.left).max(0) as u32;
+        let height = (rect.bottom - rect.top).max(0) as u32;
+        let scale = unsafe { GetDpiForWindow(self.hwnd) } as f32 / 96.0;
+        let background_color = self.d2d_context.background_color;
+
+        #[cfg(feature = "wgpu")]
+        if let Some(wgpu) = &mut self.wgpu {
+            wgpu.resize(width, height);
+            draw_scene(wgpu, &self.app.scene, background_color, scale);
+            return Ok(());
+        }
+        let renderer: &mut dyn Renderer = match (&mut self.software, &mut self.gdi) {
+            (Some(software), _) => {
+                software.resize(width, height);
+                software
+            }
+            (None, Some(gdi)) => {
+                gdi.resize(width, height)?;
+                gdi
+            }
+            (None, None) => return Ok(()),
+        };
+        draw_scene(renderer, &self.app.scene, background_color, scale);
+        Ok(())
+    }
+
     /// Runs the application by starting the message loop.
     ///
     /// # Errors
@@ -170,3 +445,25 @@ impl<E: EventHandler + 'static> Window<E> {
         Ok(())
     }
 }
+
+/// Clears `renderer` to `background_color` and draws `scene` scaled by `scale`, from
+/// DIPs to pixels.
+fn draw_scene(renderer: &mut dyn Renderer, scene: &Scene, background_color: Color, scale: f32) {
+    renderer.clear(background_color);
+    renderer.set_transform(Matrix3x2 {
+        M11: scale,
+        M22: scale,
+        ..Matrix3x2::identity()
+    });
+    scene.render(renderer);
+    renderer.set_transform(Matrix3x2::identity());
+}
+
+/// Creates a GDI brush of `color` for the window class background. Alpha is ignored.
+///
+/// # Safety
+///
+/// This function contains an `unsafe` block for creating the brush.
+fn background_brush(color: Color) -> HBRUSH {
+    unsafe { CreateSolidBrush(colorref(color)) }
+}
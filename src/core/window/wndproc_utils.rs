@@ -2,11 +2,20 @@ use crate::core::event::event_handler::EventHandler;
 use crate::core::event::key_id::KeyId;
 use crate::core::window::Window;
 use windows::{
+    core::{Error, Result},
     Win32::Foundation::*,
-    Win32::Graphics::Direct2D::Common::*,
+    Win32::Graphics::Gdi::{
+        BeginPaint, EndPaint, InvalidateRect, ScreenToClient, StretchDIBits, ValidateRect,
+        BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS, HDC, PAINTSTRUCT, SRCCOPY,
+    },
+    Win32::UI::HiDpi::GetDpiForWindow,
+    Win32::UI::Input::KeyboardAndMouse::{ReleaseCapture, SetCapture},
     Win32::UI::WindowsAndMessaging::*,
 };
+use crate::core::backend::SoftwareRenderer;
 use crate::core::render::drawing_context::DrawingContext;
+use crate::core::render::swap_chain::is_device_lost;
+use windows_numerics::Vector2;
 
 /// The main window procedure (`wndproc`) for the application.
 ///
@@ -48,59 +57,125 @@ pub extern "system" fn wndproc<E: EventHandler + 'static>(
 
     let window = unsafe { &mut *window };
 
-    match message {
+    let result = match message {
+        WM_PAINT if !window.draws_with_direct2d() => {
+            if let Err(e) = paint_scene_frame(window, hwnd) {
+                println!("Failed to paint the frame: {:?}", e);
+            }
+            let _ = unsafe { ValidateRect(Some(hwnd), None) };
+            LRESULT(0)
+        }
         WM_PAINT => {
-            if let (Some(render_target), Some(brush), Some(text_format)) = (
-                &window.d2d_context.render_target,
-                &window.d2d_context.brush,
-                &window.d2d_context.text_format,
-            ) {
-                let drawing_context = DrawingContext {
-                    render_target,
-                    brush,
-                    text_format,
-                    dwrite_factory: &window.d2d_context.dwrite_factory,
+            // If the device is lost while painting, the resources are recreated and the
+            // frame is painted again right away, so no blank frame is shown. Only if
+            // that fails too is the window left invalid to try again later.
+            let mut device_lost = false;
+            for _ in 0..2 {
+                ensure_device_resources(window, hwnd);
+                device_lost = match paint_frame(window) {
+                    Ok(()) => false,
+                    Err(e) if is_device_lost(&e) => true,
+                    Err(e) => {
+                        println!("EndDraw failed: {:?}", e);
+                        false
+                    }
                 };
-
-                window
-                    .event_handler
-                    .on_paint(&mut window.app, &drawing_context);
+                if !device_lost {
+                    break;
+                }
+                window.d2d_context.release_device_dependent_resources();
+                window.event_handler.on_device_lost(&mut window.app);
+            }
+            // Mark the window as painted so Windows stops sending `WM_PAINT` until
+            // something invalidates it again.
+            let _ = unsafe { ValidateRect(Some(hwnd), None) };
+            if device_lost {
+                let _ = unsafe { InvalidateRect(Some(hwnd), None, false) };
             }
             LRESULT(0)
         }
         WM_SIZE => {
+            // The render target is sized in pixels, but handlers get the size in DIPs.
             let width = (lparam.0 & 0xFFFF) as i32;
             let height = ((lparam.0 >> 16) & 0xFFFF) as i32;
+            if let Err(e) = window.d2d_context.resize(width as u32, height as u32) {
+                println!("Failed to resize the render target: {:?}", e);
+            }
+            let dpi = window_dpi(hwnd);
+            let size = Vector2::new(to_dips(width, dpi), to_dips(height, dpi));
+            window.app.set_window_size(size);
+            let (width, height) = rounded(size);
             window
                 .event_handler
                 .on_resize(&mut window.app, width, height);
-            if let Some(render_target) = &window.d2d_context.render_target {
-                let new_size = D2D_SIZE_U {
-                    width: width as u32,
-                    height: height as u32,
-                };
-                unsafe { render_target.Resize(&new_size).ok() };
-            }
+            LRESULT(0)
+        }
+        WM_DPICHANGED => {
+            // The window moved to a monitor with a different scale. Draw at the new DPI
+            // and take the size Windows suggests, which keeps the window's size in
+            // DIPs; the resulting `WM_SIZE` resizes the render target.
+            let dpi = ((wparam.0 >> 16) & 0xFFFF) as f32;
+            window.d2d_context.set_dpi(dpi);
+            let suggested = unsafe { &*(lparam.0 as *const RECT) };
+            let _ = unsafe {
+                SetWindowPos(
+                    hwnd,
+                    None,
+                    suggested.left,
+                    suggested.top,
+                    suggested.right - suggested.left,
+                    suggested.bottom - suggested.top,
+                    SWP_NOZORDER | SWP_NOACTIVATE,
+                )
+            };
             LRESULT(0)
         }
         WM_MOUSEMOVE => {
-            let x = (lparam.0 & 0xFFFF) as i32;
-            let y = ((lparam.0 >> 16) & 0xFFFF) as i32;
+            let (x, y) = record_mouse_message(window, hwnd, wparam, lparam);
             window.event_handler.on_mouse_move(&mut window.app, x, y);
             LRESULT(0)
         }
         WM_LBUTTONDOWN => {
-            let x = (lparam.0 & 0xFFFF) as i32;
-            let y = ((lparam.0 >> 16) & 0xFFFF) as i32;
+            let (x, y) = record_mouse_message(window, hwnd, wparam, lparam);
             window.event_handler.on_lbutton_down(&mut window.app, x, y);
             LRESULT(0)
         }
         WM_LBUTTONUP => {
-            let x = (lparam.0 & 0xFFFF) as i32;
-            let y = ((lparam.0 >> 16) & 0xFFFF) as i32;
+            let (x, y) = record_mouse_message(window, hwnd, wparam, lparam);
             window.event_handler.on_lbutton_up(&mut window.app, x, y);
             LRESULT(0)
         }
+        WM_MBUTTONDOWN => {
+            let (x, y) = record_mouse_message(window, hwnd, wparam, lparam);
+            window.event_handler.on_mbutton_down(&mut window.app, x, y);
+            LRESULT(0)
+        }
+        WM_MBUTTONUP => {
+            let (x, y) = record_mouse_message(window, hwnd, wparam, lparam);
+            window.event_handler.on_mbutton_up(&mut window.app, x, y);
+            LRESULT(0)
+        }
+        WM_CAPTURECHANGED => {
+            window.event_handler.on_capture_lost(&mut window.app);
+            LRESULT(0)
+        }
+        WM_MOUSEWHEEL => {
+            // Unlike other mouse messages, the position is in screen coordinates, and
+            // the delta is signed.
+            window.app.set_mouse_key_state(wparam.0 & 0xFFFF);
+            let delta = ((wparam.0 >> 16) & 0xFFFF) as i16 as i32;
+            let (x, y) = signed_coordinates(lparam);
+            let mut point = POINT { x, y };
+            let _ = unsafe { ScreenToClient(hwnd, &mut point) };
+            let dpi = window_dpi(hwnd);
+            let position = Vector2::new(to_dips(point.x, dpi), to_dips(point.y, dpi));
+            window.app.set_mouse_position(position);
+            let (x, y) = rounded(position);
+            window
+                .event_handler
+                .on_mouse_wheel(&mut window.app, x, y, delta);
+            LRESULT(0)
+        }
         WM_KEYDOWN => {
             let key = KeyId::from_vkey(wparam.0 as u16);
             window.event_handler.on_key_down(&mut window.app, key);
@@ -111,6 +186,10 @@ pub extern "system" fn wndproc<E: EventHandler + 'static>(
             window.event_handler.on_key_up(&mut window.app, key);
             LRESULT(0)
         }
+        WM_TIMER => {
+            window.event_handler.on_timer(&mut window.app, wparam.0);
+            LRESULT(0)
+        }
         WM_DESTROY => {
             window.event_handler.on_destroy(&mut window.app);
             unsafe { PostQuitMessage(0) };
@@ -121,7 +200,8 @@ pub extern "system" fn wndproc<E: EventHandler + 'static>(
             if ptr != 0 {
                 let _ = unsafe { Box::from_raw(ptr as *mut Window<E>) };
             }
-            LRESULT(0)
+            // The window has been freed, so it must not be touched below.
+            return LRESULT(0);
         }
         _ => {
             if let Some(result) =
@@ -129,9 +209,275 @@ pub extern "system" fn wndproc<E: EventHandler + 'static>(
                     .event_handler
                     .handle_message(&mut window.app, message, wparam, lparam)
             {
-                return LRESULT(result);
+                LRESULT(result)
+            } else {
+                unsafe { DefWindowProcW(hwnd, message, wparam, lparam) }
             }
-            unsafe { DefWindowProcW(hwnd, message, wparam, lparam) }
         }
+    };
+
+    // Switch to a new background color if a handler asked for one. This repaints the
+    // window.
+    if let Some(color) = window.app.take_background_color_request() {
+        window.set_background_color(color);
+    }
+
+    // Capture or release the mouse if a handler asked to. Releasing it sends
+    // `WM_CAPTURECHANGED`, which tells the handlers.
+    match window.app.take_mouse_capture_request() {
+        Some(true) => {
+            unsafe { SetCapture(hwnd) };
+        }
+        Some(false) => {
+            let _ = unsafe { ReleaseCapture() };
+        }
+        None => {}
+    }
+
+    // Schedule a repaint if a handler asked for one while processing the message.
+    if window.app.take_redraw_request() {
+        let _ = unsafe { InvalidateRect(Some(hwnd), None, false) };
+    }
+
+    // Start any timers a handler asked for. Intervals are clamped by Windows.
+    for (id, interval) in window.app.take_timer_requests() {
+        let milliseconds = interval.as_millis().min(u32::MAX as u128) as u32;
+        unsafe { SetTimer(Some(hwnd), id, milliseconds, None) };
+    }
+
+    // Save any captures a handler asked for.
+    for path in window.app.take_capture_requests() {
+        if let Err(e) = window.capture_frame(&path) {
+            println!("Failed to capture the frame: {:?}", e);
+        }
+    }
+
+    result
+}
+
+/// Recreates the device-dependent resources if they were released after the device
+/// was lost, and tells the event handler once they are back.
+fn ensure_device_resources<E: EventHandler>(window: &mut Window<E>, hwnd: HWND) {
+    if window.d2d_context.render_target.is_some() {
+        return;
+    }
+    match window.d2d_context.create_device_dependent_resources(hwnd) {
+        Ok(()) => window.event_handler.on_device_restored(&mut window.app),
+        Err(e) => println!("Failed to recreate device resources: {:?}", e),
+    }
+}
+
+/// Paints one frame: opens it, lets the event handler draw, and presents it.
+///
+/// Nothing is painted if the device-dependent resources don't exist.
+///
+/// # Errors
+///
+/// This function will return an error if `EndDraw` or presenting fails, such as
+/// `D2DERR_RECREATE_TARGET` when the device is lost.
+///
+/// # Safety
+///
+/// This function contains `unsafe` blocks for opening and closing the frame.
+fn paint_frame<E: EventHandler>(window: &mut Window<E>) -> Result<()> {
+    let (Some(render_target), Some(brush), Some(text_format)) = (
+        &window.d2d_context.render_target,
+        &window.d2d_context.brush,
+        &window.d2d_context.text_format,
+    ) else {
+        return Ok(());
+    };
+    let drawing_context = DrawingContext {
+        render_target,
+        brush,
+        brushes: &window.d2d_context.brushes,
+        text_format,
+        dwrite_factory: &window.d2d_context.graphics.dwrite_factory,
+        d2d_factory: &window.d2d_context.graphics.d2d_factory,
+        stroke_styles: &window.d2d_context.stroke_styles,
+        layers: &window.d2d_context.layers,
+        transforms: &window.d2d_context.transforms,
+        wic_factory: &window.d2d_context.graphics.wic_factory,
+        text_options: window.d2d_context.text_options,
+        background_color: window.d2d_context.background_color,
+        d3d_device: window
+            .d2d_context
+            .swap_chain
+            .as_ref()
+            .map(|swap_chain| &swap_chain.device),
+        pixel_snap: window.d2d_context.pixel_snap,
+    };
+
+    // The frame is open for the whole of `on_paint`, so handlers after the
+    // `RenderEventHandler` can draw on top of the scene.
+    unsafe { render_target.BeginDraw() };
+    window
+        .event_handler
+        .on_paint(&mut window.app, &drawing_context);
+    drawing_context.end_frame();
+    unsafe { render_target.EndDraw(None, None) }?;
+    window.d2d_context.present()
+}
+
+/// Paints one frame with the software, GDI, or wgpu renderer: draws the scene into
+/// its bitmap and copies the bitmap to the window, or presents the wgpu frame.
+///
+/// # Errors
+///
+/// This function will return an error if the scene can't be drawn, the bitmap can't
+/// be copied to the window, or the wgpu frame can't be presented.
+///
+/// # Safety
+///
+/// This function contains `unsafe` blocks for opening and closing the paint.
+fn paint_scene_frame<E: EventHandler>(window: &mut Window<E>, hwnd: HWND) -> Result<()> {
+    window.render_scene_frame()?;
+    // wgpu presents to the window's surface itself, without a device context.
+    #[cfg(feature = "wgpu")]
+    if let Some(wgpu) = &mut window.wgpu {
+        return wgpu.present().map_err(|e| Error::new(E_FAIL, e.to_string()));
+    }
+    let mut paint = PAINTSTRUCT::default();
+    let hdc = unsafe { BeginPaint(hwnd, &mut paint) };
+    let presented = match (&window.software, &window.gdi) {
+        (Some(software), _) => present_framebuffer(hdc, software),
+        (None, Some(gdi)) => gdi.present(hdc),
+        (None, None) => Ok(()),
+    };
+    let _ = unsafe { EndPaint(hwnd, &paint) };
+    presented
+}
+
+/// Copies the software renderer's framebuffer to `hdc` with `StretchDIBits`.
+///
+/// # Errors
+///
+/// This function will return an error if the copy fails.
+///
+/// # Safety
+///
+/// This function contains an `unsafe` block for copying the framebuffer.
+fn present_framebuffer(hdc: HDC, renderer: &SoftwareRenderer) -> Result<()> {
+    let (width, height) = (renderer.width() as i32, renderer.height() as i32);
+    if width == 0 || height == 0 {
+        return Ok(());
+    }
+
+    // GDI takes BGR pixels. The background is opaque, so alpha is dropped.
+    let bgrx: Vec<u8> = renderer
+        .pixels()
+        .chunks_exact(4)
+        .flat_map(|pixel| [pixel[2], pixel[1], pixel[0], 0])
+        .collect();
+    let info = BITMAPINFO {
+        bmiHeader: BITMAPINFOHEADER {
+            biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: width,
+            // A negative height makes the rows top-down, as in the framebuffer.
+            biHeight: -height,
+            biPlanes: 1,
+            biBitCount: 32,
+            biCompression: BI_RGB.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let copied = unsafe {
+        StretchDIBits(
+            hdc,
+            0,
+            0,
+            width,
+            height,
+            0,
+            0,
+            width,
+            height,
+            Some(bgrx.as_ptr().cast()),
+            &info,
+            DIB_RGB_COLORS,
+            SRCCOPY,
+        )
+    };
+    if copied == 0 {
+        return Err(Error::new(E_FAIL, "StretchDIBits failed"));
+    }
+    Ok(())
+}
+
+/// Records the key state and exact position of a mouse message in the `App`, and
+/// returns the position rounded to whole DIPs for the event handler.
+fn record_mouse_message<E: EventHandler>(
+    window: &mut Window<E>,
+    hwnd: HWND,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> (i32, i32) {
+    let position = client_position(lparam, window_dpi(hwnd));
+    window.app.set_mouse_key_state(wparam.0);
+    window.app.set_mouse_position(position);
+    rounded(position)
+}
+
+/// Returns the client coordinates of a mouse message's `lparam`, in DIPs.
+///
+/// The coordinates are negative when the mouse is captured and the cursor is left of
+/// or above the client area.
+fn client_position(lparam: LPARAM, dpi: f32) -> Vector2 {
+    let (x, y) = signed_coordinates(lparam);
+    Vector2::new(to_dips(x, dpi), to_dips(y, dpi))
+}
+
+/// Returns the signed x and y coordinates packed into the low and high words of a
+/// mouse message's `lparam` (`GET_X_LPARAM` and `GET_Y_LPARAM`).
+fn signed_coordinates(lparam: LPARAM) -> (i32, i32) {
+    let x = (lparam.0 & 0xFFFF) as i16 as i32;
+    let y = ((lparam.0 >> 16) & 0xFFFF) as i16 as i32;
+    (x, y)
+}
+
+/// Rounds a position or size in DIPs to whole DIPs, as event handlers receive them.
+fn rounded(point: Vector2) -> (i32, i32) {
+    (point.X.round() as i32, point.Y.round() as i32)
+}
+
+/// Returns the DPI of the monitor `hwnd` is on.
+///
+/// Unlike the render target's DPI, this is known as soon as the window exists, so
+/// the `WM_SIZE` messages sent while it is created are converted correctly too.
+fn window_dpi(hwnd: HWND) -> f32 {
+    unsafe { GetDpiForWindow(hwnd) as f32 }
+}
+
+/// Converts a length in physical pixels to DIPs at `dpi`, without rounding.
+fn to_dips(pixels: i32, dpi: f32) -> f32 {
+    pixels as f32 * 96.0 / dpi
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Packs client coordinates into an `lparam`, as `MAKELPARAM` does.
+    fn lparam(x: i16, y: i16) -> LPARAM {
+        LPARAM(((y as u16 as isize) << 16) | x as u16 as isize)
+    }
+
+    #[test]
+    fn client_position_sign_extends_coordinates_outside_the_client_area() {
+        let position = client_position(lparam(-5, -20), 96.0);
+
+        assert_eq!(position, Vector2::new(-5.0, -20.0));
+    }
+
+    #[test]
+    fn client_position_keeps_fractional_dips() {
+        // At 150%, 3 pixels are 2 DIPs and 1 pixel is two thirds of one.
+        let position = client_position(lparam(3, 1), 144.0);
+
+        assert_eq!(position.X, 2.0);
+        assert!((position.Y - 2.0 / 3.0).abs() < 1e-6);
+        assert_eq!(rounded(position), (2, 1));
     }
 }
@@ -10,6 +10,9 @@
 //! - `app`: Contains the central application state.
 //! - `core`: Encapsulates window creation, events, and rendering.
 //!
+//! Only `core::backend` and the types it uses, `Color`, `Rect`, and `Transform`, are
+//! available on platforms other than Windows.
+//!
 //! ## Getting Started
 //!
 //! To create a new `my_gui` application, you use the `WindowBuilder` to configure and
@@ -38,6 +41,6 @@
 //! }
 //! ```
 
-pub mod app;
 #[cfg(windows)]
+pub mod app;
 pub mod core;
\ No newline at end of file